@@ -0,0 +1,86 @@
+//! Startup cost of a scan of a tiny tree: the walk itself plus refreshing the
+//! scan cache, which every `scan` does. Run with `cargo bench`.
+//!
+//! Uses plain `Instant` timing rather than a benchmark framework, so it needs
+//! no extra dependencies; compare the per-iteration times across changes.
+
+use std::fs;
+use std::hint::black_box;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use dev_sweep::config::DevSweepConfig;
+use dev_sweep::scancache::{CachedProject, ScanCache};
+use dev_sweep::scanner::{ProjectKind, ScanOptions, ScannedProject, scan_directory_with};
+
+const ITERATIONS: u32 = 200;
+
+/// Projects from earlier scans of other roots, as a cache that has been in use a while holds.
+const CACHED_PROJECTS: usize = 5_000;
+
+fn tiny_tree() -> PathBuf {
+    let root = std::env::temp_dir().join("dev_sweep_bench_scan_startup");
+    let _ = fs::remove_dir_all(&root);
+    for name in ["api", "web"] {
+        let project = root.join(name);
+        fs::create_dir_all(project.join("target/debug")).unwrap();
+        fs::write(project.join("Cargo.toml"), "[package]").unwrap();
+        fs::write(project.join("target/debug/app"), "binary").unwrap();
+    }
+    root
+}
+
+fn warm_cache() -> ScanCache {
+    let mut projects: Vec<CachedProject> = (0..CACHED_PROJECTS)
+        .map(|i| CachedProject {
+            path: PathBuf::from(format!("/elsewhere/group{}/project{i}", i % 50)),
+            name: format!("project{i}"),
+            kind: ProjectKind::Rust,
+            other_kinds: Vec::new(),
+            bytes: i as u64,
+            last_modified: None,
+            targets: Vec::new(),
+        })
+        .collect();
+    // As saved by `ScanCache::update`.
+    projects.sort_by(|a, b| a.path.cmp(&b.path));
+    ScanCache {
+        timestamp: None,
+        projects,
+    }
+}
+
+fn time(label: &str, mut run: impl FnMut()) {
+    run();
+    let started = Instant::now();
+    for _ in 0..ITERATIONS {
+        run();
+    }
+    let per_iteration: Duration = started.elapsed() / ITERATIONS;
+    println!("{label:<28} {per_iteration:>12.2?} per iteration");
+}
+
+fn scan(root: &Path) -> Vec<ScannedProject> {
+    scan_directory_with(root, &ScanOptions::default(), &DevSweepConfig::default()).unwrap()
+}
+
+fn main() {
+    let root = tiny_tree();
+    let roots = vec![root.clone()];
+    let projects = scan(&root);
+    let mut cache = warm_cache();
+
+    time("scan tiny tree", || {
+        black_box(scan(&root));
+    });
+    time("diff against scan cache", || {
+        black_box(cache.diff(&roots, &projects, true));
+    });
+    // Re-recording the same scan leaves the cache as it was, so one can be reused.
+    time("update scan cache", || {
+        cache.update(&roots, &projects, true);
+        black_box(&cache);
+    });
+
+    fs::remove_dir_all(&root).unwrap();
+}
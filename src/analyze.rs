@@ -0,0 +1,75 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::filter::apply_rebuild_costs;
+use crate::scanner::{ProjectKind, RebuildCost, ScannedProject};
+
+/// How much effort rebuilding each [`RebuildCost`] is worth, by default, in
+/// units of a cheap rebuild. Overridden by `rebuild_effort` in the config.
+pub const DEFAULT_REBUILD_EFFORT: &[(RebuildCost, f64)] = &[
+    (RebuildCost::Cheap, 1.0),
+    (RebuildCost::Moderate, 3.0),
+    (RebuildCost::Expensive, 10.0),
+];
+
+/// The effort of rebuilding `cost`: the one set in `overrides`, if it's a
+/// positive number, or the default.
+pub fn rebuild_effort(cost: RebuildCost, overrides: &BTreeMap<RebuildCost, f64>) -> f64 {
+    overrides
+        .get(&cost)
+        .copied()
+        .filter(|effort| effort.is_finite() && *effort > 0.0)
+        .or_else(|| {
+            DEFAULT_REBUILD_EFFORT
+                .iter()
+                .find(|(c, _)| *c == cost)
+                .map(|(_, effort)| *effort)
+        })
+        .unwrap_or(1.0)
+}
+
+/// A project ranked by `analyze`.
+#[derive(Debug, Clone, Serialize)]
+pub struct Candidate {
+    #[serde(flatten)]
+    pub project: ScannedProject,
+    /// What rebuilding the project's artifacts would take (see [`rebuild_effort`]).
+    pub effort: f64,
+    /// Reclaimable megabytes per unit of effort: the higher, the better a
+    /// project is to clean.
+    pub score: f64,
+}
+
+/// Score every project by how much it frees for the effort of rebuilding it,
+/// best candidate first (ties broken by path).
+///
+/// Each project is labelled with its rebuild cost as `--estimate-rebuild-cost`
+/// would, with `rebuild_costs` overriding the built-in labels per kind, and
+/// that cost's effort comes from `efforts` (see [`rebuild_effort`]).
+pub fn rank_by_effort(
+    mut projects: Vec<ScannedProject>,
+    rebuild_costs: &BTreeMap<ProjectKind, RebuildCost>,
+    efforts: &BTreeMap<RebuildCost, f64>,
+) -> Vec<Candidate> {
+    apply_rebuild_costs(&mut projects, rebuild_costs);
+    let mut candidates: Vec<Candidate> = projects
+        .into_iter()
+        .map(|project| {
+            let cost = project.rebuild_cost.unwrap_or(RebuildCost::Moderate);
+            let effort = rebuild_effort(cost, efforts);
+            let megabytes = project.total_cleanable_bytes as f64 / (1024.0 * 1024.0);
+            Candidate {
+                project,
+                effort,
+                score: megabytes / effort,
+            }
+        })
+        .collect();
+    candidates.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| a.project.path.cmp(&b.project.path))
+    });
+    candidates
+}
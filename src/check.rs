@@ -0,0 +1,134 @@
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::scanner::ScannedProject;
+use crate::util::format_bytes;
+
+/// Outcome of `dev-sweep check`, from best to worst.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Ok,
+    Warning,
+    Critical,
+    /// The check couldn't run, e.g. the scan failed.
+    Unknown,
+}
+
+impl CheckStatus {
+    /// The exit code a Nagios-style plugin reports this status with:
+    /// 0 OK, 1 WARNING, 2 CRITICAL, 3 UNKNOWN.
+    pub fn exit_code(self) -> i32 {
+        match self {
+            Self::Ok => 0,
+            Self::Warning => 1,
+            Self::Critical => 2,
+            Self::Unknown => 3,
+        }
+    }
+}
+
+impl fmt::Display for CheckStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Ok => "OK",
+            Self::Warning => "WARNING",
+            Self::Critical => "CRITICAL",
+            Self::Unknown => "UNKNOWN",
+        };
+        f.write_str(name)
+    }
+}
+
+/// How much reclaimable space `check` tolerates, in bytes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct CheckThresholds {
+    /// `--warn`: reclaimable space at or above this is a warning.
+    #[serde(rename = "warn_bytes")]
+    pub warn: Option<u64>,
+    /// `--crit`: reclaimable space at or above this is critical.
+    #[serde(rename = "crit_bytes")]
+    pub crit: Option<u64>,
+}
+
+impl CheckThresholds {
+    /// The status for `reclaimable` bytes: critical once it reaches `crit`,
+    /// a warning once it reaches `warn`, OK below both.
+    pub fn status(&self, reclaimable: u64) -> CheckStatus {
+        if self.crit.is_some_and(|crit| reclaimable >= crit) {
+            CheckStatus::Critical
+        } else if self.warn.is_some_and(|warn| reclaimable >= warn) {
+            CheckStatus::Warning
+        } else {
+            CheckStatus::Ok
+        }
+    }
+}
+
+/// Result of `dev-sweep check`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CheckReport {
+    pub status: CheckStatus,
+    /// Reclaimable space across every project found.
+    pub reclaimable_bytes: u64,
+    /// How many projects have something to reclaim.
+    pub projects: usize,
+    #[serde(flatten)]
+    pub thresholds: CheckThresholds,
+}
+
+impl CheckReport {
+    /// Total up `projects` and compare the result against `thresholds`.
+    pub fn new(projects: &[ScannedProject], thresholds: CheckThresholds) -> Self {
+        let reclaimable_bytes = projects.iter().map(|p| p.total_cleanable_bytes).sum();
+        CheckReport {
+            status: thresholds.status(reclaimable_bytes),
+            reclaimable_bytes,
+            projects: projects.len(),
+            thresholds,
+        }
+    }
+
+    /// The one-line plugin output: status, a readable summary, and after the
+    /// `|` the total as Nagios performance data (`label=value;warn;crit;min;`).
+    pub fn status_line(&self) -> String {
+        let mut limits = Vec::new();
+        if let Some(warn) = self.thresholds.warn {
+            limits.push(format!("warn at {}", format_bytes(warn)));
+        }
+        if let Some(crit) = self.thresholds.crit {
+            limits.push(format!("crit at {}", format_bytes(crit)));
+        }
+        let noun = if self.projects == 1 {
+            "project"
+        } else {
+            "projects"
+        };
+        let threshold = |t: Option<u64>| t.map(|b| b.to_string()).unwrap_or_default();
+        format!(
+            "DEV-SWEEP {} - {} reclaimable in {} {} ({}) | reclaimable={}B;{};{};0;",
+            self.status,
+            format_bytes(self.reclaimable_bytes),
+            self.projects,
+            noun,
+            limits.join(", "),
+            self.reclaimable_bytes,
+            threshold(self.thresholds.warn),
+            threshold(self.thresholds.crit),
+        )
+    }
+}
+
+/// Error returned once `check` has printed a status other than OK, so the
+/// process can exit with that status's [`CheckStatus::exit_code`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CheckAlert(pub CheckStatus);
+
+impl fmt::Display for CheckAlert {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "check status {}", self.0)
+    }
+}
+
+impl std::error::Error for CheckAlert {}
@@ -0,0 +1,51 @@
+use std::path::PathBuf;
+
+use walkdir::{DirEntry, WalkDir};
+
+use crate::scanner::ScannedProject;
+
+/// Whether a file named `name` shows an editor has a file open:
+///
+/// - Vim and Neovim swap files: `.name.swp`, `.name.swo`, `.name.swn`
+/// - Emacs lock files (`.#name`) and auto-saves of unsaved buffers (`#name#`)
+pub fn is_editor_marker(name: &str) -> bool {
+    let vim_swap = name.starts_with('.')
+        && [".swp", ".swo", ".swn"]
+            .iter()
+            .any(|ext| name.len() > ext.len() + 1 && name.ends_with(ext));
+    let emacs_lock = name.starts_with(".#");
+    let emacs_autosave = name.len() > 2 && name.starts_with('#') && name.ends_with('#');
+    vim_swap || emacs_lock || emacs_autosave
+}
+
+/// Look for a sign that the project is open in an editor: a file matching
+/// [`is_editor_marker`], or (on Unix) a socket such as a language server's.
+///
+/// The project's clean targets and `.git` aren't searched, since what's in
+/// them says nothing about the sources being edited. Returns the first marker
+/// found.
+pub fn find_editor_activity(project: &ScannedProject) -> Option<PathBuf> {
+    let skipped = |entry: &DirEntry| {
+        entry.depth() > 0
+            && (entry.file_name() == ".git"
+                || project.clean_targets.iter().any(|t| t.path == entry.path()))
+    };
+    WalkDir::new(&project.path)
+        .follow_links(false)
+        .into_iter()
+        .filter_entry(|e| !skipped(e))
+        .filter_map(|e| e.ok())
+        .find(|e| is_editor_marker(&e.file_name().to_string_lossy()) || is_socket(e))
+        .map(|e| e.into_path())
+}
+
+#[cfg(unix)]
+fn is_socket(entry: &DirEntry) -> bool {
+    use std::os::unix::fs::FileTypeExt;
+    entry.file_type().is_socket()
+}
+
+#[cfg(not(unix))]
+fn is_socket(_entry: &DirEntry) -> bool {
+    false
+}
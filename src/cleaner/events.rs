@@ -0,0 +1,132 @@
+use std::path::Path;
+
+use serde::Serialize;
+
+use super::CleanResult;
+use crate::scanner::ScannedProject;
+
+/// One line of `clean --format events` output, serialized as a JSON object
+/// tagged with its `event` name.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "lowercase")]
+pub enum CleanEvent<'a> {
+    /// Emitted once, before anything is removed.
+    Start {
+        projects: usize,
+        targets: usize,
+        bytes_estimated: u64,
+        dry_run: bool,
+    },
+    /// A project had at least one target removed (or would have, for a dry run).
+    Cleaned {
+        project: &'a str,
+        path: &'a Path,
+        targets_cleaned: usize,
+        bytes_freed: u64,
+    },
+    /// A project or one of its targets was deliberately left alone.
+    Skipped {
+        project: &'a str,
+        path: &'a Path,
+        reason: String,
+    },
+    /// A target couldn't be archived or removed.
+    Error {
+        project: &'a str,
+        path: &'a Path,
+        error: &'a str,
+    },
+    /// Emitted once, after every project was handled or the clean stopped early.
+    Done {
+        projects_cleaned: usize,
+        targets_cleaned: usize,
+        bytes_freed: u64,
+        errors: usize,
+        /// Ctrl-C stopped the clean before every project was handled.
+        interrupted: bool,
+        /// The `--time-budget` ran out before every project was handled.
+        time_budget_exhausted: bool,
+    },
+}
+
+impl<'a> CleanEvent<'a> {
+    /// The `start` event for cleaning `projects`.
+    pub fn start(projects: &[&ScannedProject], dry_run: bool) -> Self {
+        CleanEvent::Start {
+            projects: projects.len(),
+            targets: projects.iter().map(|p| p.clean_targets.len()).sum(),
+            bytes_estimated: projects.iter().map(|p| p.total_cleanable_bytes).sum(),
+            dry_run,
+        }
+    }
+
+    /// The events describing how `project` was cleaned: skips first, then
+    /// errors, then `cleaned` if anything was removed.
+    pub fn for_result(project: &'a ScannedProject, result: &'a CleanResult) -> Vec<Self> {
+        let project_name = result.project_name.as_str();
+        let mut events = Vec::new();
+        if let Some(ref reason) = result.skipped_git {
+            events.push(CleanEvent::Skipped {
+                project: project_name,
+                path: &project.path,
+                reason: reason.clone(),
+            });
+        }
+        if let Some(ref marker) = result.skipped_in_use {
+            events.push(CleanEvent::Skipped {
+                project: project_name,
+                path: &project.path,
+                reason: format!("in use ({})", marker.display()),
+            });
+        }
+        for path in &result.skipped_unsafe {
+            events.push(CleanEvent::Skipped {
+                project: project_name,
+                path,
+                reason: "protected".to_string(),
+            });
+        }
+        for path in &result.skipped_stale {
+            events.push(CleanEvent::Skipped {
+                project: project_name,
+                path,
+                reason: "changed since the scan".to_string(),
+            });
+        }
+        for path in &result.skipped_active {
+            events.push(CleanEvent::Skipped {
+                project: project_name,
+                path,
+                reason: "recently modified".to_string(),
+            });
+        }
+        for error in &result.errors {
+            events.push(CleanEvent::Error {
+                project: project_name,
+                path: &project.path,
+                error,
+            });
+        }
+        if result.targets_cleaned > 0 {
+            events.push(CleanEvent::Cleaned {
+                project: project_name,
+                path: &project.path,
+                targets_cleaned: result.targets_cleaned,
+                bytes_freed: result.bytes_freed,
+            });
+        }
+        events
+    }
+
+    /// The `done` event totalling `results`.
+    pub fn done(results: &[CleanResult], interrupted: bool, time_budget_exhausted: bool) -> Self {
+        CleanEvent::Done {
+            projects_cleaned: results.iter().filter(|r| r.targets_cleaned > 0).count(),
+            targets_cleaned: results.iter().map(|r| r.targets_cleaned).sum(),
+            bytes_freed: results.iter().map(|r| r.bytes_freed).sum(),
+            errors: results.iter().map(|r| r.errors.len()).sum(),
+            interrupted,
+            time_budget_exhausted,
+        }
+    }
+}
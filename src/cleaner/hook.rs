@@ -0,0 +1,58 @@
+use std::io;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use anyhow::{Context, Result};
+
+use crate::readonly::ensure_writable;
+
+/// Replace each `{path}` in a hook command with `path`, quoted for the shell
+/// so paths with spaces or quotes come through as one argument.
+pub fn expand_hook(command: &str, path: &Path) -> String {
+    command.replace("{path}", &shell_quote(&path.display().to_string()))
+}
+
+/// Run a `pre_clean_cmd` or `post_clean_cmd` for the project at `path`.
+///
+/// The command goes through the shell (`sh -c`, or `cmd /C` on Windows) with
+/// the project as its working directory. Its output is sent to stderr, so it
+/// can't mix into JSON or event output on stdout. Fails if the command can't
+/// be started or exits unsuccessfully.
+pub fn run_hook(name: &str, command: &str, path: &Path) -> Result<()> {
+    let expanded = expand_hook(command, path);
+    ensure_writable(&format!("run {name} `{expanded}`"))?;
+    let status = shell(&expanded)
+        .current_dir(path)
+        .stdin(Stdio::null())
+        .stdout(io::stderr())
+        .status()
+        .with_context(|| format!("Failed to run {name} `{expanded}`"))?;
+    if !status.success() {
+        anyhow::bail!("{name} `{expanded}` failed ({status})");
+    }
+    Ok(())
+}
+
+#[cfg(not(windows))]
+pub(crate) fn shell(command: &str) -> Command {
+    let mut shell = Command::new("sh");
+    shell.arg("-c").arg(command);
+    shell
+}
+
+#[cfg(windows)]
+pub(crate) fn shell(command: &str) -> Command {
+    let mut shell = Command::new("cmd");
+    shell.arg("/C").arg(command);
+    shell
+}
+
+#[cfg(not(windows))]
+pub(crate) fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+#[cfg(windows)]
+pub(crate) fn shell_quote(s: &str) -> String {
+    format!("\"{s}\"")
+}
@@ -0,0 +1,117 @@
+use std::fmt;
+use std::sync::atomic::AtomicBool;
+#[cfg(any(unix, windows))]
+use std::sync::atomic::Ordering;
+
+#[cfg(windows)]
+use windows_sys::Win32::System::Console::{CTRL_BREAK_EVENT, CTRL_C_EVENT, SetConsoleCtrlHandler};
+#[cfg(windows)]
+use windows_sys::core::BOOL;
+
+/// Set by the Ctrl-C handler while an [`InterruptGuard`] is alive.
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// Exit code after a clean was interrupted: 128 + SIGINT, as shells report it.
+pub const INTERRUPTED_EXIT_CODE: i32 = 130;
+
+/// Error returned once an interrupted clean has reported what it did, so the
+/// process can exit with [`INTERRUPTED_EXIT_CODE`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Interrupted;
+
+impl fmt::Display for Interrupted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Interrupted; the remaining targets were left in place")
+    }
+}
+
+impl std::error::Error for Interrupted {}
+
+/// The flag the Ctrl-C handler sets, for [`CleanOptions::interrupt`].
+///
+/// [`CleanOptions::interrupt`]: super::CleanOptions::interrupt
+pub fn interrupt_flag() -> &'static AtomicBool {
+    &INTERRUPTED
+}
+
+/// While alive, Ctrl-C sets [`interrupt_flag`] instead of killing the process,
+/// so a clean can finish the target it is removing and stop before the next.
+/// Dropping the guard restores the previous behavior, so prompts shown
+/// afterwards can be cancelled as usual.
+///
+/// On Unix this is a `SIGINT` handler; on Windows, a console control handler
+/// for Ctrl-C and Ctrl-Break.
+#[must_use = "Ctrl-C is only caught while the guard is alive"]
+pub struct InterruptGuard {
+    /// The `SIGINT` disposition to put back on drop.
+    #[cfg(unix)]
+    previous: libc::sigaction,
+}
+
+impl InterruptGuard {
+    #[cfg(unix)]
+    pub fn install() -> Self {
+        // SAFETY: both structs are plain C data, zeroed is a valid empty
+        // `sigaction`, and the handler only stores to an atomic, which is
+        // async-signal-safe.
+        unsafe {
+            let mut action: libc::sigaction = std::mem::zeroed();
+            action.sa_sigaction = on_sigint as *const () as libc::sighandler_t;
+            // Restart interrupted system calls, so the removal in progress
+            // doesn't fail with EINTR.
+            action.sa_flags = libc::SA_RESTART;
+            libc::sigemptyset(&mut action.sa_mask);
+            let mut previous: libc::sigaction = std::mem::zeroed();
+            libc::sigaction(libc::SIGINT, &action, &mut previous);
+            InterruptGuard { previous }
+        }
+    }
+
+    #[cfg(windows)]
+    pub fn install() -> Self {
+        // SAFETY: the handler is a plain function that lives for the whole
+        // process and only stores to an atomic.
+        unsafe {
+            SetConsoleCtrlHandler(Some(on_console_ctrl), 1);
+        }
+        InterruptGuard {}
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    pub fn install() -> Self {
+        InterruptGuard {}
+    }
+}
+
+impl Drop for InterruptGuard {
+    fn drop(&mut self) {
+        // SAFETY: puts back the disposition saved by `install`.
+        #[cfg(unix)]
+        unsafe {
+            libc::sigaction(libc::SIGINT, &self.previous, std::ptr::null_mut());
+        }
+        // SAFETY: removes the handler added by `install`.
+        #[cfg(windows)]
+        unsafe {
+            SetConsoleCtrlHandler(Some(on_console_ctrl), 0);
+        }
+    }
+}
+
+#[cfg(unix)]
+extern "C" fn on_sigint(_: libc::c_int) {
+    INTERRUPTED.store(true, Ordering::SeqCst);
+}
+
+/// Runs on a thread of its own; returning nonzero stops Windows from ending
+/// the process.
+#[cfg(windows)]
+unsafe extern "system" fn on_console_ctrl(ctrl_type: u32) -> BOOL {
+    match ctrl_type {
+        CTRL_C_EVENT | CTRL_BREAK_EVENT => {
+            INTERRUPTED.store(true, Ordering::SeqCst);
+            1
+        }
+        _ => 0,
+    }
+}
@@ -1,43 +1,339 @@
+use std::fmt;
 use std::fs;
-use std::path::Path;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant, SystemTime};
 
 use anyhow::{Context, Result};
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use tracing::field::Empty;
+use tracing::{debug, debug_span, info, info_span, warn};
 
-use crate::scanner::ScannedProject;
+use crate::git::{self, GitStatus, working_tree_status};
+use crate::readonly::ensure_writable;
+use crate::scanner::SizeMode;
+use crate::scanner::walk::{dir_size_with, has_marker};
+use crate::scanner::{CleanTarget, DEFAULT_TEMP_OLDER_THAN, ProjectKind, ScannedProject};
+use crate::util::{format_bytes, long_path};
+
+pub mod editors;
+pub mod events;
+pub mod hook;
+pub mod interrupt;
+pub mod prune;
+pub mod quarantine;
+
+pub use editors::{find_editor_activity, is_editor_marker};
+pub use events::CleanEvent;
+pub use hook::{expand_hook, run_hook};
+pub use interrupt::{INTERRUPTED_EXIT_CODE, InterruptGuard, Interrupted, interrupt_flag};
+pub use prune::{PruneResult, find_empty_dirs, prune_empty_dirs};
+pub use quarantine::{
+    PurgeResult, QUARANTINE_DIR_NAME, QuarantineEntry, purge_quarantine, quarantine_batch_dir,
+    quarantine_index_path, quarantine_target,
+};
+
+/// Options controlling how targets are removed.
+#[derive(Debug, Clone, Default)]
+pub struct CleanOptions {
+    /// Only report what *would* be cleaned without deleting anything.
+    pub dry_run: bool,
+    /// Archive each target as a `.tar.gz` into this directory before removing it.
+    pub archive_dir: Option<PathBuf>,
+    /// Move targets into this directory instead of removing them (see
+    /// [`quarantine_target`]).
+    pub quarantine_dir: Option<PathBuf>,
+    /// Skip targets containing anything modified within this window (likely an
+    /// in-progress build). `None` disables the check.
+    pub active_window: Option<Duration>,
+    /// Skip projects with uncommitted changes in their git working tree.
+    pub require_git_clean: bool,
+    /// With `require_git_clean`, also skip projects that aren't under git.
+    pub strict: bool,
+    /// Skip projects that look open in an editor (see [`find_editor_activity`]).
+    pub exclude_active_editors: bool,
+    /// Only remove targets on the [`SAFE_TARGET_NAMES`] allowlist.
+    ///
+    /// [`SAFE_TARGET_NAMES`]: crate::scanner::SAFE_TARGET_NAMES
+    pub safe_mode: bool,
+    /// Stop before the next target once this is set (see [`InterruptGuard`]).
+    /// The target being removed at the time is always finished.
+    pub interrupt: Option<&'static AtomicBool>,
+    /// Shell command run in each project before its targets are removed, with
+    /// `{path}` replaced by the project path. If it fails, the project is left
+    /// alone. Not run for dry runs, nor when every target is skipped.
+    pub pre_clean_cmd: Option<String>,
+    /// Shell command run in each project after its targets were removed, with
+    /// `{path}` substituted. A failure is only reported as a warning. Only run
+    /// when `pre_clean_cmd` would have been.
+    pub post_clean_cmd: Option<String>,
+    /// How the bytes freed are measured; should match the scan's.
+    pub size_mode: SizeMode,
+    /// How long nothing in a temp directory entry must have been modified for
+    /// it to be removed (default [`DEFAULT_TEMP_OLDER_THAN`]). Checked again
+    /// even with `active_window` off.
+    pub temp_older_than: Option<Duration>,
+    /// Start no further project once this long has passed since cleaning
+    /// began. The project being cleaned at the time is always finished, and
+    /// the first one is always started.
+    pub time_budget: Option<Duration>,
+}
+
+impl CleanOptions {
+    /// Whether cleaning was asked to stop.
+    pub fn interrupted(&self) -> bool {
+        self.interrupt.is_some_and(|f| f.load(Ordering::SeqCst))
+    }
+
+    /// Whether a clean of `total` projects that produced `results` stopped
+    /// early because [`time_budget`](Self::time_budget) ran out.
+    pub fn ran_out_of_time(&self, results: &[CleanResult], total: usize) -> bool {
+        self.time_budget.is_some() && !self.interrupted() && results.len() < total
+    }
+}
 
 /// Result of a clean operation on a single project.
 #[derive(Debug)]
 pub struct CleanResult {
     pub project_name: String,
     pub targets_cleaned: usize,
+    /// Bytes actually freed, measured just before each target was removed.
+    /// For a dry run this is the scan-time estimate.
     pub bytes_freed: u64,
+    /// What the scan estimated the cleaned targets would free.
+    pub bytes_estimated: u64,
     pub errors: Vec<String>,
+    /// Archives written before removal (empty unless archiving was requested).
+    pub archives: Vec<PathBuf>,
+    /// Targets moved into quarantine instead of being removed.
+    pub quarantined: Vec<QuarantineEntry>,
+    /// Targets left alone because they were modified too recently.
+    pub skipped_active: Vec<PathBuf>,
+    /// Targets that couldn't be removed for lack of permission (EACCES/EPERM).
+    pub permission_denied: Vec<PathBuf>,
+    /// Why the whole project was left alone by [`CleanOptions::require_git_clean`].
+    pub skipped_git: Option<String>,
+    /// The editor file that left the whole project alone as in use, with
+    /// [`CleanOptions::exclude_active_editors`].
+    pub skipped_in_use: Option<PathBuf>,
+    /// Targets left alone because they are protected or, in safe mode, not allowlisted.
+    pub skipped_unsafe: Vec<PathBuf>,
+    /// Targets left alone because they no longer match the scan (see [`stale_reason`]).
+    pub skipped_stale: Vec<PathBuf>,
+    /// Problems that didn't stop the clean, such as a failing `post_clean_cmd`.
+    pub warnings: Vec<String>,
+}
+
+/// Exit code after a clean in which some targets couldn't be removed.
+pub const CLEAN_FAILED_EXIT_CODE: i32 = 3;
+
+/// Error returned once a clean that failed to remove some targets has reported
+/// what it did, so the process can exit with [`CLEAN_FAILED_EXIT_CODE`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CleanFailed {
+    /// How many targets couldn't be archived or removed.
+    pub failures: usize,
+}
+
+impl fmt::Display for CleanFailed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let noun = if self.failures == 1 { "target" } else { "targets" };
+        write!(f, "{} {} could not be cleaned", self.failures, noun)
+    }
 }
 
+impl std::error::Error for CleanFailed {}
+
 /// Clean the specified targets from a project.
 ///
 /// If `dry_run` is true, only reports what *would* be cleaned without deleting anything.
 pub fn clean_project(project: &ScannedProject, dry_run: bool) -> Result<CleanResult> {
+    clean_project_with(
+        project,
+        &CleanOptions {
+            dry_run,
+            ..Default::default()
+        },
+    )
+}
+
+/// Clean the specified targets from a project using the given options.
+///
+/// When archiving, a target is only removed once its archive has been written
+/// successfully; a failed archive is reported as an error and the target is kept.
+pub fn clean_project_with(project: &ScannedProject, options: &CleanOptions) -> Result<CleanResult> {
+    let span = info_span!(
+        "clean",
+        path = %project.path.display(),
+        dry_run = options.dry_run,
+        targets = Empty,
+        bytes = Empty,
+    );
+    let _entered = span.enter();
     let mut result = CleanResult {
         project_name: project.name.clone(),
         targets_cleaned: 0,
         bytes_freed: 0,
+        bytes_estimated: 0,
         errors: Vec::new(),
+        archives: Vec::new(),
+        quarantined: Vec::new(),
+        skipped_active: Vec::new(),
+        permission_denied: Vec::new(),
+        skipped_git: None,
+        skipped_in_use: None,
+        skipped_unsafe: Vec::new(),
+        skipped_stale: Vec::new(),
+        warnings: Vec::new(),
     };
 
+    // `git gc` only touches the object store, so the work tree's state doesn't
+    // matter (and a `.git` directory has no work tree to check).
+    if options.require_git_clean && project.kind != ProjectKind::GitGc {
+        let skipped = match working_tree_status(&project.path) {
+            Ok(GitStatus::Clean) => None,
+            Ok(GitStatus::NotARepo) if !options.strict => None,
+            Ok(GitStatus::NotARepo) => Some("not a git repository".to_string()),
+            Ok(GitStatus::Dirty) => Some("uncommitted changes".to_string()),
+            Err(e) => Some(format!("{e:#}")),
+        };
+        if let Some(reason) = skipped {
+            info!(%reason, "project skipped");
+            result.skipped_git = Some(reason);
+            return Ok(result);
+        }
+    }
+
+    if options.exclude_active_editors
+        && project.kind != ProjectKind::GitGc
+        && let Some(marker) = find_editor_activity(project)
+    {
+        info!(marker = %marker.display(), "project skipped: open in an editor");
+        result.skipped_in_use = Some(marker);
+        return Ok(result);
+    }
+
+    // The hooks only run once a target has passed the checks below, so a
+    // project whose targets are all skipped is left untouched.
+    let mut hooks_ran = false;
+    let project_moved = !project_still_detected(project);
     for target in &project.clean_targets {
-        if dry_run {
+        if options.interrupted() {
+            break;
+        }
+
+        let stale = if project_moved {
+            Some("project no longer there")
+        } else {
+            stale_reason(target)
+        };
+        if let Some(reason) = stale {
+            warn!(path = %target.path.display(), reason, "target skipped: changed since the scan");
+            result.skipped_stale.push(target.path.clone());
+            continue;
+        }
+
+        if target.protected || (options.safe_mode && !target.is_safe()) {
+            debug!(path = %target.path.display(), "target skipped: protected");
+            result.skipped_unsafe.push(target.path.clone());
+            continue;
+        }
+
+        let window = if project.kind == ProjectKind::Temp {
+            let temp = options.temp_older_than.unwrap_or(DEFAULT_TEMP_OLDER_THAN);
+            Some(options.active_window.map_or(temp, |w| w.max(temp)))
+        } else {
+            options.active_window
+        };
+        if let Some(window) = window
+            && modified_within(&target.path, window)
+        {
+            debug!(path = %target.path.display(), "target skipped: recently modified");
+            result.skipped_active.push(target.path.clone());
+            continue;
+        }
+
+        if options.dry_run {
             result.targets_cleaned += 1;
             result.bytes_freed += target.size_bytes;
+            result.bytes_estimated += target.size_bytes;
             continue;
         }
 
+        if !hooks_ran {
+            hooks_ran = true;
+            if let Some(ref command) = options.pre_clean_cmd
+                && let Err(e) = run_hook("pre_clean_cmd", command, &project.path)
+            {
+                warn!(error = %format!("{e:#}"), "project skipped: pre-clean hook failed");
+                result.errors.push(format!("{e:#}"));
+                return Ok(result);
+            }
+        }
+
+        // Objects are compacted in place by git, never deleted or archived.
+        if project.kind == ProjectKind::GitGc {
+            let before = dir_size_with(&target.path, options.size_mode).unwrap_or(0);
+            let repo = project.path.parent().unwrap_or(&project.path);
+            match git::gc(repo) {
+                Ok(()) => {
+                    let after = dir_size_with(&target.path, options.size_mode).unwrap_or(before);
+                    result.targets_cleaned += 1;
+                    result.bytes_freed += before.saturating_sub(after);
+                    result.bytes_estimated += target.size_bytes;
+                }
+                Err(e) => {
+                    warn!(error = %format!("{e:#}"), "git gc failed");
+                    result.errors.push(format!("{e:#}"));
+                }
+            }
+            continue;
+        }
+
+        if let Some(ref archive_dir) = options.archive_dir {
+            match archive_target(project, target, archive_dir) {
+                Ok(archive) => result.archives.push(archive),
+                Err(e) => {
+                    result.errors.push(format!("{e:#}"));
+                    continue;
+                }
+            }
+        }
+
+        if let Some(ref batch_dir) = options.quarantine_dir {
+            match quarantine_target(project, target, batch_dir, options.size_mode) {
+                Ok(entry) => {
+                    result.targets_cleaned += 1;
+                    result.bytes_freed += entry.size_bytes;
+                    result.bytes_estimated += target.size_bytes;
+                    result.quarantined.push(entry);
+                }
+                Err(e) => {
+                    warn!(error = %format!("{e:#}"), "quarantine failed");
+                    result.errors.push(format!("{e:#}"));
+                }
+            }
+            continue;
+        }
+
+        // Sizes can drift between scan and clean (a build ran, a cache was
+        // pruned), so measure what is really there right before removing it.
+        let measured = dir_size_with(&target.path, options.size_mode).unwrap_or(target.size_bytes);
+        let _remove =
+            debug_span!("remove", path = %target.path.display(), bytes = measured).entered();
         match remove_dir_all(&target.path) {
             Ok(()) => {
                 result.targets_cleaned += 1;
-                result.bytes_freed += target.size_bytes;
+                result.bytes_freed += measured;
+                result.bytes_estimated += target.size_bytes;
             }
             Err(e) => {
+                warn!(error = %format!("{e:#}"), "removal failed");
+                if is_permission_denied(&e) {
+                    result.permission_denied.push(target.path.clone());
+                }
                 result.errors.push(format!(
                     "Failed to remove {}: {}",
                     target.path.display(),
@@ -47,32 +343,322 @@ pub fn clean_project(project: &ScannedProject, dry_run: bool) -> Result<CleanRes
         }
     }
 
+    if hooks_ran
+        && let Some(ref command) = options.post_clean_cmd
+        && let Err(e) = run_hook("post_clean_cmd", command, &project.path)
+    {
+        warn!(error = %format!("{e:#}"), "post-clean hook failed");
+        result.warnings.push(format!("{e:#}"));
+    }
+
+    span.record("targets", result.targets_cleaned);
+    span.record("bytes", result.bytes_freed);
     Ok(result)
 }
 
+/// Why `target` no longer looks like the directory the scan found, if it
+/// doesn't: it's gone (say, the project was renamed or moved since), or
+/// something other than a directory is in its place. A core dump or a kept
+/// large file, the targets that are files, only has to still be a file.
+///
+/// Only the target's own metadata is read, so the check is cheap.
+pub fn stale_reason(target: &CleanTarget) -> Option<&'static str> {
+    match fs::symlink_metadata(long_path(&target.path)) {
+        Err(_) => Some("no longer exists"),
+        Ok(meta) if meta.file_type().is_symlink() => Some("replaced by a symlink"),
+        Ok(meta) if (target.runtime_data || target.large_file) && meta.is_file() => None,
+        Ok(meta) if !meta.is_dir() => Some("no longer a directory"),
+        Ok(_) => None,
+    }
+}
+
+/// Whether the project root still has a marker file of its kind, so that a
+/// different project moved into its place isn't cleaned from stale results.
+///
+/// Global caches and relocated build output (a target that is the project
+/// itself) have no markers and always pass.
+fn project_still_detected(project: &ScannedProject) -> bool {
+    project.kind.marker_files().is_empty()
+        || project.clean_targets.iter().any(|t| t.path == project.path)
+        || has_marker(&project.path, project.kind)
+}
+
+/// Whether an error was caused by a permission check (EACCES or EPERM).
+fn is_permission_denied(error: &anyhow::Error) -> bool {
+    error
+        .chain()
+        .filter_map(|cause| cause.downcast_ref::<std::io::Error>())
+        .any(|e| e.kind() == std::io::ErrorKind::PermissionDenied)
+}
+
+/// The command a user can run with elevated privileges to remove `paths`.
+///
+/// dev-sweep never escalates on its own; this is only shown as a hint.
+#[cfg(unix)]
+pub fn elevated_remove_command(paths: &[&PathBuf]) -> String {
+    let quoted: Vec<String> = paths
+        .iter()
+        .map(|p| format!("'{}'", p.display().to_string().replace('\'', r"'\''")))
+        .collect();
+    format!("sudo rm -rf -- {}", quoted.join(" "))
+}
+
+/// The command a user can run with elevated privileges to remove `paths`.
+///
+/// dev-sweep never escalates on its own; this is only shown as a hint, to be run
+/// from an administrator prompt.
+#[cfg(not(unix))]
+pub fn elevated_remove_command(paths: &[&PathBuf]) -> String {
+    let quoted: Vec<String> = paths.iter().map(|p| format!("\"{}\"", p.display())).collect();
+    format!("rmdir /s /q {}", quoted.join(" "))
+}
+
 /// Remove a directory and all its contents.
 ///
-/// This is a wrapper around `fs::remove_dir_all` with better error context.
+/// This is a wrapper around `fs::remove_dir_all` with better error context, that
+/// also removes trees nested deeper than Windows' `MAX_PATH` (see [`long_path`]).
+/// A target that is a single file (a core dump) is removed as a file.
 fn remove_dir_all(path: &Path) -> Result<()> {
-    fs::remove_dir_all(path)
+    ensure_writable(&format!("remove {}", path.display()))?;
+    if fs::symlink_metadata(long_path(path)).is_ok_and(|m| m.is_file()) {
+        fs::remove_file(long_path(path))
+            .with_context(|| format!("Failed to remove file: {}", path.display()))?;
+        return Ok(());
+    }
+    fs::remove_dir_all(long_path(path))
         .with_context(|| format!("Failed to remove directory: {}", path.display()))?;
     Ok(())
 }
 
 /// Clean multiple projects and return results.
-pub fn clean_projects(
+pub fn clean_projects(projects: &[&ScannedProject], dry_run: bool) -> Vec<CleanResult> {
+    clean_projects_with(
+        projects,
+        &CleanOptions {
+            dry_run,
+            ..Default::default()
+        },
+    )
+}
+
+/// Clean multiple projects using the given options and return results.
+pub fn clean_projects_with(
     projects: &[&ScannedProject],
-    dry_run: bool,
+    options: &CleanOptions,
 ) -> Vec<CleanResult> {
+    clean_projects_with_progress(projects, options, |_, _| {})
+}
+
+/// Like [`clean_projects_with`], calling `on_result` with each project and its
+/// result as soon as that project is done, for live progress reporting.
+///
+/// Once [`CleanOptions::interrupt`] is set or the [`CleanOptions::time_budget`]
+/// is used up, no further projects are started, so there may be fewer results
+/// than projects. The budget never stops the first project.
+pub fn clean_projects_with_progress(
+    projects: &[&ScannedProject],
+    options: &CleanOptions,
+    mut on_result: impl FnMut(&ScannedProject, &CleanResult),
+) -> Vec<CleanResult> {
+    let started = Instant::now();
     projects
         .iter()
-        .map(|p| {
-            clean_project(p, dry_run).unwrap_or_else(|e| CleanResult {
+        .enumerate()
+        .take_while(|&(i, _)| {
+            !options.interrupted()
+                && (i == 0
+                    || options
+                        .time_budget
+                        .is_none_or(|budget| started.elapsed() < budget))
+        })
+        .map(|(_, p)| {
+            let result = clean_project_with(p, options).unwrap_or_else(|e| CleanResult {
                 project_name: p.name.clone(),
                 targets_cleaned: 0,
                 bytes_freed: 0,
+                bytes_estimated: 0,
                 errors: vec![e.to_string()],
-            })
+                archives: Vec::new(),
+                quarantined: Vec::new(),
+                skipped_active: Vec::new(),
+                permission_denied: Vec::new(),
+                skipped_git: None,
+                skipped_in_use: None,
+                skipped_unsafe: Vec::new(),
+                skipped_stale: Vec::new(),
+                warnings: Vec::new(),
+            });
+            on_result(p, &result);
+            result
         })
         .collect()
 }
+
+/// Measured bytes freed differing from the scan estimate by more than this
+/// fraction of the estimate is called out in the clean summary.
+pub const FREED_DRIFT_THRESHOLD: f64 = 0.05;
+
+/// How far the bytes actually freed drifted from the scan estimate, or `None`
+/// if it's within [`FREED_DRIFT_THRESHOLD`].
+pub fn freed_drift(estimated: u64, freed: u64) -> Option<i128> {
+    let delta = freed as i128 - estimated as i128;
+    let allowed = estimated as f64 * FREED_DRIFT_THRESHOLD;
+    (delta.unsigned_abs() as f64 > allowed).then_some(delta)
+}
+
+/// Whether anything under `path` was modified within the last `window`.
+///
+/// Used to avoid deleting a build directory while a build is writing to it.
+/// Symlinks are not followed; entries whose time can't be read are ignored.
+pub fn modified_within(path: &Path, window: Duration) -> bool {
+    let cutoff = SystemTime::now()
+        .checked_sub(window)
+        .unwrap_or(SystemTime::UNIX_EPOCH);
+
+    walkdir::WalkDir::new(long_path(path))
+        .follow_links(false)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter_map(|e| e.metadata().ok()?.modified().ok())
+        .any(|modified| modified > cutoff)
+}
+
+// ── Archiving ───────────────────────────────────────────────────────────────
+
+/// Compress a single target into `archive_dir` as `<project>-<target>-<timestamp>.tar.gz`.
+///
+/// An existing archive is never overwritten: when two projects of the same
+/// name are archived within a second, the later one gets a `-2`, `-3`, …
+/// suffix. Entries are stored relative to the target's name, so extracting the archive
+/// inside the project root restores the directory in place.
+pub fn archive_target(
+    project: &ScannedProject,
+    target: &CleanTarget,
+    archive_dir: &Path,
+) -> Result<PathBuf> {
+    ensure_writable(&format!("archive {}", target.path.display()))?;
+    fs::create_dir_all(archive_dir).with_context(|| {
+        format!(
+            "Failed to create archive directory: {}",
+            archive_dir.display()
+        )
+    })?;
+
+    let name = archive_file_name(&project.name, &target.name, chrono::Local::now());
+    let (archive_path, file) = create_archive_file(archive_dir, &name)?;
+
+    let write = || -> Result<()> {
+        let mut builder = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+        builder.follow_symlinks(false);
+        if target.path.is_file() {
+            builder.append_path_with_name(&target.path, &target.name)?;
+        } else {
+            builder.append_dir_all(&target.name, &target.path)?;
+        }
+        builder.into_inner()?.finish()?;
+        Ok(())
+    };
+
+    if let Err(e) = write() {
+        // Never leave a truncated archive behind that looks like a valid backup.
+        let _ = fs::remove_file(&archive_path);
+        return Err(e.context(format!(
+            "Failed to archive {} to {}",
+            target.path.display(),
+            archive_path.display()
+        )));
+    }
+
+    Ok(archive_path)
+}
+
+/// Create a new, empty archive file in `archive_dir` called `name`, or
+/// `name` with a counter before the extension if that is taken.
+fn create_archive_file(archive_dir: &Path, name: &str) -> Result<(PathBuf, fs::File)> {
+    let stem = name.strip_suffix(".tar.gz").unwrap_or(name);
+    let mut path = archive_dir.join(name);
+    let mut attempt = 1;
+    loop {
+        match fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+        {
+            Ok(file) => return Ok((path, file)),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+                attempt += 1;
+                path = archive_dir.join(format!("{stem}-{attempt}.tar.gz"));
+            }
+            Err(e) => {
+                return Err(e)
+                    .with_context(|| format!("Failed to create archive: {}", path.display()));
+            }
+        }
+    }
+}
+
+/// Build the archive file name for a target, e.g. `my-app-target-20250101-120000.tar.gz`.
+///
+/// Path separators in nested target names (like `src/__pycache__`) are replaced
+/// so the archive always lands directly in the destination directory.
+pub fn archive_file_name(
+    project_name: &str,
+    target_name: &str,
+    when: chrono::DateTime<chrono::Local>,
+) -> String {
+    let sanitize = |s: &str| s.replace(['/', '\\'], "_");
+    format!(
+        "{}-{}-{}.tar.gz",
+        sanitize(project_name),
+        sanitize(target_name),
+        when.format("%Y%m%d-%H%M%S")
+    )
+}
+
+/// Ensure the archive destination has room for `needed` bytes.
+///
+/// The estimate is the uncompressed size of the selected targets, so this is
+/// deliberately conservative. The directory (or its nearest existing ancestor)
+/// is checked; platforms where free space can't be queried are not blocked.
+pub fn check_archive_space(archive_dir: &Path, needed: u64) -> Result<()> {
+    let existing = archive_dir
+        .ancestors()
+        .find(|p| p.exists())
+        .unwrap_or(archive_dir);
+
+    if let Some(available) = available_space(existing)
+        && available < needed
+    {
+        anyhow::bail!(
+            "Not enough space in {}: need ~{}, only {} available",
+            archive_dir.display(),
+            format_bytes(needed),
+            format_bytes(available)
+        );
+    }
+
+    Ok(())
+}
+
+/// Free space available to unprivileged users on the filesystem containing `path`.
+#[cfg(unix)]
+pub fn available_space(path: &Path) -> Option<u64> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    // SAFETY: `c_path` is a valid NUL-terminated string and `stat` is a valid out-pointer.
+    let rc = unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) };
+    if rc != 0 {
+        return None;
+    }
+    #[allow(clippy::unnecessary_cast)]
+    Some(stat.f_bavail as u64 * stat.f_frsize as u64)
+}
+
+/// Free space available to unprivileged users on the filesystem containing `path`.
+#[cfg(not(unix))]
+pub fn available_space(_path: &Path) -> Option<u64> {
+    None
+}
@@ -0,0 +1,107 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use walkdir::WalkDir;
+
+use crate::config::DevSweepConfig;
+use crate::readonly::ensure_writable;
+use crate::scanner::ScanOptions;
+use crate::scanner::walk::should_visit;
+
+/// Result of pruning empty directories.
+#[derive(Debug, Default)]
+pub struct PruneResult {
+    /// Directories removed (or that would be, for a dry run).
+    pub pruned: Vec<PathBuf>,
+    pub errors: Vec<String>,
+}
+
+/// Find directories under `root` that hold nothing but other empty directories.
+///
+/// The walk skips the same directories a scan does (hidden ones such as `.git`,
+/// and artifact or dependency dirs like `node_modules`), plus anything under the
+/// config's `ignore_paths`, and honors `max_depth` and `one_file_system`. A
+/// directory with any file, symlink, or skipped subdirectory in it is never
+/// listed, and neither is `root` itself.
+///
+/// Results are deepest first, so removing them in order never hits a directory
+/// whose children are still there.
+pub fn find_empty_dirs(
+    root: &Path,
+    options: &ScanOptions,
+    config: &DevSweepConfig,
+) -> Vec<PathBuf> {
+    let ignored: HashSet<PathBuf> = config
+        .ignore_paths
+        .iter()
+        .filter_map(|p| fs::canonicalize(p).ok())
+        .collect();
+
+    let mut walker = WalkDir::new(root)
+        .follow_links(false)
+        .same_file_system(options.one_file_system);
+    if let Some(depth) = options.max_depth {
+        walker = walker.max_depth(depth);
+    }
+
+    let visit = |entry: &walkdir::DirEntry| {
+        should_visit(entry)
+            && fs::canonicalize(entry.path()).map_or(true, |c| !ignored.contains(&c))
+    };
+
+    let dirs: Vec<PathBuf> = walker
+        .into_iter()
+        .filter_entry(visit)
+        .filter_map(|e| e.ok())
+        .filter(|e| e.depth() > 0 && e.file_type().is_dir())
+        .map(|e| e.into_path())
+        .collect();
+
+    // The walk lists every directory before its contents, so going backwards
+    // settles all of a directory's children before the directory itself.
+    let mut empty: HashSet<PathBuf> = HashSet::new();
+    let mut found = Vec::new();
+    for dir in dirs.into_iter().rev() {
+        let Ok(children) = fs::read_dir(&dir) else {
+            continue;
+        };
+        let only_empty_dirs = children
+            .map(|child| child.map(|c| empty.contains(&c.path())))
+            .all(|is_empty| is_empty.unwrap_or(false));
+        if only_empty_dirs {
+            empty.insert(dir.clone());
+            found.push(dir);
+        }
+    }
+    found
+}
+
+/// Remove the directories found by [`find_empty_dirs`], in order.
+///
+/// Each removal is non-recursive, so a directory that gained content since it
+/// was found fails with an error instead of being deleted. `root` is never
+/// removed, whatever `dirs` contains.
+pub fn prune_empty_dirs(root: &Path, dirs: &[PathBuf], dry_run: bool) -> PruneResult {
+    let mut result = PruneResult::default();
+    for dir in dirs {
+        if dir == root {
+            continue;
+        }
+        if dry_run {
+            result.pruned.push(dir.clone());
+            continue;
+        }
+        if let Err(e) = ensure_writable(&format!("remove {}", dir.display())) {
+            result.errors.push(e.to_string());
+            continue;
+        }
+        match fs::remove_dir(dir) {
+            Ok(()) => result.pruned.push(dir.clone()),
+            Err(e) => result
+                .errors
+                .push(format!("Failed to remove {}: {}", dir.display(), e)),
+        }
+    }
+    result
+}
@@ -0,0 +1,193 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::readonly::ensure_writable;
+use crate::scanner::walk::dir_size_with;
+use crate::scanner::{CleanTarget, ScannedProject, SizeMode};
+use crate::util::long_path;
+
+/// Directory created under the scan root for `clean --quarantine` when the
+/// config doesn't set `quarantine_dir`. It's hidden, so scans never walk into it.
+pub const QUARANTINE_DIR_NAME: &str = ".dev-sweep-quarantine";
+
+/// A target moved aside by `clean --quarantine`, as recorded in the index.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuarantineEntry {
+    /// When the target was moved.
+    pub quarantined_at: DateTime<Local>,
+    /// Where the target was, so it can be moved back by hand.
+    pub original: PathBuf,
+    /// Where the target is now.
+    pub location: PathBuf,
+    /// Size in bytes when it was moved.
+    pub size_bytes: u64,
+}
+
+/// Result of [`purge_quarantine`].
+#[derive(Debug, Default)]
+pub struct PurgeResult {
+    /// Entries removed (or that would be, for a dry run).
+    pub purged: Vec<QuarantineEntry>,
+    /// Entries left in quarantine because they are too recent.
+    pub kept: usize,
+    pub errors: Vec<String>,
+}
+
+/// Default location of the quarantine index (~/.local/share/dev-sweep/quarantine.jsonl).
+pub fn quarantine_index_path() -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("~/.local/share"))
+        .join("dev-sweep")
+        .join("quarantine.jsonl")
+}
+
+/// The directory one clean run quarantines into: a subdirectory of `base`
+/// named for when the run started, e.g. `20250101-120000`.
+pub fn quarantine_batch_dir(base: &Path, when: DateTime<Local>) -> PathBuf {
+    base.join(when.format("%Y%m%d-%H%M%S").to_string())
+}
+
+/// Move `target` into `batch_dir` as `<project>-<target>` (with a numeric
+/// suffix if that name is taken), instead of deleting it.
+///
+/// The move is a rename, so `batch_dir` has to be on the same filesystem as
+/// the target; otherwise it fails and the target stays where it is.
+pub fn quarantine_target(
+    project: &ScannedProject,
+    target: &CleanTarget,
+    batch_dir: &Path,
+    mode: SizeMode,
+) -> Result<QuarantineEntry> {
+    ensure_writable(&format!("quarantine {}", target.path.display()))?;
+    fs::create_dir_all(batch_dir).with_context(|| {
+        format!(
+            "Failed to create quarantine directory: {}",
+            batch_dir.display()
+        )
+    })?;
+
+    let sanitize = |s: &str| s.replace(['/', '\\'], "_");
+    let name = format!("{}-{}", sanitize(&project.name), sanitize(&target.name));
+    let mut location = batch_dir.join(&name);
+    for n in 2.. {
+        if !location.exists() {
+            break;
+        }
+        location = batch_dir.join(format!("{name}-{n}"));
+    }
+
+    let size_bytes = dir_size_with(&target.path, mode).unwrap_or(target.size_bytes);
+    fs::rename(long_path(&target.path), long_path(&location)).with_context(|| {
+        format!(
+            "Failed to move {} into quarantine at {} (it must be on the same filesystem)",
+            target.path.display(),
+            location.display()
+        )
+    })?;
+
+    Ok(QuarantineEntry {
+        quarantined_at: Local::now(),
+        original: target.path.clone(),
+        location,
+        size_bytes,
+    })
+}
+
+/// Append entries to the index at `path`, one JSON object per line.
+pub fn append_index(path: &Path, entries: &[QuarantineEntry]) -> Result<()> {
+    if entries.is_empty() {
+        return Ok(());
+    }
+    ensure_writable("write the quarantine index")?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Failed to open quarantine index: {}", path.display()))?;
+    for entry in entries {
+        writeln!(file, "{}", serde_json::to_string(entry)?)?;
+    }
+    Ok(())
+}
+
+/// Read every entry from the index at `path`.
+///
+/// A missing index is empty, and lines that can't be parsed are skipped.
+pub fn read_index(path: &Path) -> Vec<QuarantineEntry> {
+    fs::read_to_string(path)
+        .map(|contents| {
+            contents
+                .lines()
+                .filter_map(|line| serde_json::from_str(line).ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Permanently remove what the index at `path` lists as quarantined before
+/// `cutoff` (everything, if `None`), then rewrite the index without it.
+///
+/// Entries whose quarantined copy is already gone are dropped from the index
+/// without being reported. A copy that can't be removed stays listed, so a
+/// later purge can try again. Emptied batch directories are removed too.
+pub fn purge_quarantine(
+    path: &Path,
+    cutoff: Option<DateTime<Local>>,
+    dry_run: bool,
+) -> Result<PurgeResult> {
+    let mut result = PurgeResult::default();
+    let mut remaining = Vec::new();
+
+    for entry in read_index(path) {
+        if fs::symlink_metadata(long_path(&entry.location)).is_err() {
+            continue;
+        }
+        if cutoff.is_some_and(|cutoff| entry.quarantined_at >= cutoff) {
+            result.kept += 1;
+            remaining.push(entry);
+            continue;
+        }
+        if dry_run {
+            result.purged.push(entry);
+            continue;
+        }
+        // A quarantined core dump is a file, not a directory.
+        match super::remove_dir_all(&entry.location) {
+            Ok(()) => {
+                if let Some(batch) = entry.location.parent() {
+                    // Only succeeds once the batch is empty.
+                    let _ = fs::remove_dir(batch);
+                }
+                result.purged.push(entry);
+            }
+            Err(e) => {
+                result.errors.push(format!(
+                    "Failed to remove {}: {:#}",
+                    entry.location.display(),
+                    e
+                ));
+                remaining.push(entry);
+            }
+        }
+    }
+
+    if !dry_run && path.exists() {
+        ensure_writable("write the quarantine index")?;
+        let mut contents = String::new();
+        for entry in &remaining {
+            contents.push_str(&serde_json::to_string(entry)?);
+            contents.push('\n');
+        }
+        fs::write(path, contents)
+            .with_context(|| format!("Failed to write quarantine index: {}", path.display()))?;
+    }
+    Ok(result)
+}
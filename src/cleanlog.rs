@@ -0,0 +1,107 @@
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::cleaner::CleanResult;
+use crate::readonly::ensure_writable;
+use crate::scanner::{ProjectKind, ScannedProject};
+
+/// One project cleaned by a real (non-dry-run) clean.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CleanLogEntry {
+    /// When the clean finished.
+    pub timestamp: DateTime<Local>,
+    /// The project root.
+    pub project: PathBuf,
+    /// The detected project kind.
+    pub kind: ProjectKind,
+    /// Targets that were removed.
+    pub targets: Vec<PathBuf>,
+    /// Bytes freed.
+    pub bytes_freed: u64,
+}
+
+/// Default location of the clean log (~/.local/share/dev-sweep/clean-log.jsonl).
+pub fn clean_log_path() -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("~/.local/share"))
+        .join("dev-sweep")
+        .join("clean-log.jsonl")
+}
+
+/// Build log entries for the projects a clean actually removed something from.
+///
+/// `projects` and `results` are paired index-for-index, as returned by
+/// [`crate::cleaner::clean_projects_with`].
+pub fn entries_for(projects: &[&ScannedProject], results: &[CleanResult]) -> Vec<CleanLogEntry> {
+    let timestamp = Local::now();
+    projects
+        .iter()
+        .zip(results)
+        .filter(|(_, r)| r.targets_cleaned > 0)
+        .map(|(p, r)| CleanLogEntry {
+            timestamp,
+            project: p.path.clone(),
+            kind: p.kind,
+            targets: p
+                .clean_targets
+                .iter()
+                .filter(|t| !r.skipped_active.contains(&t.path) && !t.path.exists())
+                .map(|t| t.path.clone())
+                .collect(),
+            bytes_freed: r.bytes_freed,
+        })
+        .collect()
+}
+
+/// Append entries to the log at `path`, one JSON object per line.
+pub fn append(path: &Path, entries: &[CleanLogEntry]) -> Result<()> {
+    if entries.is_empty() {
+        return Ok(());
+    }
+    ensure_writable("write the clean log")?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Failed to open clean log: {}", path.display()))?;
+    for entry in entries {
+        writeln!(file, "{}", serde_json::to_string(entry)?)?;
+    }
+    Ok(())
+}
+
+/// Read every entry from the log at `path`.
+///
+/// A missing log is empty; lines that can't be parsed are skipped so one bad
+/// write never hides the rest of the history.
+pub fn read(path: &Path) -> Vec<CleanLogEntry> {
+    fs::read_to_string(path)
+        .map(|contents| {
+            contents
+                .lines()
+                .filter_map(|line| serde_json::from_str(line).ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// The most recent clean time of each project in `entries`.
+pub fn last_cleaned(entries: &[CleanLogEntry]) -> HashMap<PathBuf, DateTime<Local>> {
+    let mut last: HashMap<PathBuf, DateTime<Local>> = HashMap::new();
+    for entry in entries {
+        let time = last.entry(entry.project.clone()).or_insert(entry.timestamp);
+        if entry.timestamp > *time {
+            *time = entry.timestamp;
+        }
+    }
+    last
+}
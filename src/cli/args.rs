@@ -1,6 +1,11 @@
 use std::path::PathBuf;
 
-use clap::{Parser, Subcommand};
+use clap::{ArgGroup, Parser, Subcommand, ValueEnum};
+
+use crate::filter::SortKey;
+use crate::logging::LogFormat;
+use crate::scanner::ProjectKind;
+use crate::tui::colors::ColorMode;
 
 /// CLI argument definitions for dev-sweep.
 #[derive(Parser)]
@@ -26,30 +31,344 @@ pub struct Cli {
     #[arg(short = 'd', long, global = true)]
     pub max_depth: Option<usize>,
 
+    /// Only detect projects at least this many levels below the scan root
+    #[arg(long, global = true)]
+    pub min_depth: Option<usize>,
+
     /// Only show projects older than this (e.g. "30d", "3m", "1y")
     #[arg(short, long, global = true)]
     pub older_than: Option<String>,
 
+    /// Only show projects with at least this much to reclaim (e.g. "100MB");
+    /// 0 shows everything, overriding exclude_under_size in the config
+    #[arg(long, value_name = "SIZE", global = true)]
+    pub min_size: Option<String>,
+
+    /// Leave out the N most recently modified projects of each kind
+    #[arg(long, visible_alias = "preserve-recent", value_name = "N", global = true)]
+    pub keep_recent: Option<usize>,
+
+    /// Only show projects of these kinds (e.g. "Rust", "Node"); repeatable or comma-separated
+    #[arg(short, long = "kind", value_name = "KIND", value_delimiter = ',', global = true)]
+    pub kinds: Vec<String>,
+
+    /// Only show projects whose name or path contains this text (case-insensitive)
+    #[arg(long, visible_alias = "grep", value_name = "TEXT", global = true)]
+    pub filter: Option<String>,
+
+    /// Only show projects whose name or path matches this regex (case-insensitive)
+    #[arg(long, value_name = "PATTERN", global = true)]
+    pub regex: Option<String>,
+
+    /// Also report a machine-wide cache shared by all projects (e.g. "Julia"); repeatable
+    #[arg(long = "global-cache", value_name = "KIND", global = true)]
+    pub global_caches: Vec<ProjectKind>,
+
+    /// Never clean targets matching this glob (e.g. "target/release", "*.egg-info"); repeatable
+    #[arg(long = "exclude-target", value_name = "PATTERN", global = true)]
+    pub exclude_targets: Vec<String>,
+
+    /// Only offer clean targets whose newest file is older than this (e.g. "60d"),
+    /// dropping projects left with none
+    #[arg(long, value_name = "AGE", global = true)]
+    pub target_older_than: Option<String>,
+
+    /// Keep files larger than this (e.g. "500MB") out of what gets sized and cleaned,
+    /// listing them as kept large files
+    #[arg(long, value_name = "SIZE", global = true)]
+    pub skip_files_over: Option<String>,
+
+    /// Only show projects never cleaned, or whose artifacts came back since their last clean
+    #[arg(long, global = true)]
+    pub since_clean: bool,
+
+    /// Only show projects whose reclaimable size changed since the last scan, with the change
+    #[arg(long, global = true)]
+    pub since_last: bool,
+
+    /// Record each project's largest reclaimable size seen so far, and show it next to
+    /// the current size
+    #[arg(long, global = true)]
+    pub record_max: bool,
+
+    /// Only walk directories modified since this file was; projects under the
+    /// others are taken from the last scan's cache (a fast, coarse incremental scan)
+    #[arg(long, value_name = "PATH", global = true)]
+    pub changed_since_file: Option<PathBuf>,
+
+    /// Save progress after each top-level directory, and pick up where an
+    /// interrupted --resume scan of the same root stopped
+    #[arg(long, global = true)]
+    pub resume: bool,
+
+    /// With --resume, walk the biggest top-level directories first (by a quick
+    /// size estimate), so an interrupted scan has checkpointed them
+    #[arg(long, global = true, requires = "resume")]
+    pub largest_first: bool,
+
+    /// Order results by size (default), age, name, or path; ties are broken by path
+    #[arg(long, value_enum, default_value_t, global = true)]
+    pub sort: SortKey,
+
+    /// Stay on the scan root's filesystem, skipping other mounts (like `du -x`)
+    #[arg(
+        short = 'x',
+        long,
+        visible_alias = "follow-to-mount-boundary",
+        global = true
+    )]
+    pub one_file_system: bool,
+
+    /// Also offer regenerable IDE caches (.vs, .idea/caches, .vscode/ipch) for cleaning
+    #[arg(long, global = true)]
+    pub ide_caches: bool,
+
+    /// Also offer log directories (logs/, log/) and core dumps in each project;
+    /// these are runtime data, not build output, and won't come back
+    #[arg(long, global = true)]
+    pub include_logs: bool,
+
+    /// Also report git repositories with loose objects to compact; cleaning
+    /// them runs `git gc --prune=now` instead of deleting anything
+    #[arg(long, global = true)]
+    pub git_gc: bool,
+
+    /// Report each Cargo, npm, Yarn, or pnpm workspace as one project, with its
+    /// members' targets, instead of one project per member
+    #[arg(long, global = true)]
+    pub follow_project_references: bool,
+
+    /// Also report directories in the OS temp directory that nothing was modified
+    /// in for temp_older_than (default 7d); only those are ever cleaned
+    #[arg(long, global = true)]
+    pub include_temp: bool,
+
+    /// Only ever clean well-known artifact directories (target, node_modules, build, ...);
+    /// other targets are listed but marked protected
+    #[arg(long, global = true)]
+    pub safe: bool,
+
+    /// Time each top-level directory of the scan and print the slowest afterwards
+    #[arg(long, global = true)]
+    pub profile_scan: bool,
+
+    /// How many of the slowest directories --profile-scan reports
+    #[arg(long, value_name = "N", default_value_t = 10, global = true)]
+    pub profile_top: usize,
+
+    /// Fail instead of silently skipping unknown kinds, unreadable directories,
+    /// unresolvable ignore paths, or targets that can't be cleaned
+    #[arg(long, global = true)]
+    pub strict: bool,
+
     /// Output results as JSON
     #[arg(long, global = true)]
     pub json: bool,
+
+    /// Output results as JSON on one line, without whitespace (implies --json)
+    #[arg(long, global = true)]
+    pub json_compact: bool,
+
+    /// Show extra detail, such as each project's largest file and what --max-depth cut off
+    #[arg(short, long, global = true)]
+    pub verbose: bool,
+
+    /// Before the results, list every detected project with the filters it
+    /// passed or failed and whether it was kept
+    #[arg(long, global = true)]
+    pub explain_filters: bool,
+
+    /// Leave out the table's totals footer and color legend
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    pub quiet: bool,
+
+    /// Show one dense line per project (name, short type, size) instead of the full table
+    #[arg(long, global = true)]
+    pub compact: bool,
+
+    /// Leave the grand total out of the table, headline, and summary (JSON keeps it)
+    #[arg(long, global = true)]
+    pub no_total: bool,
+
+    /// Print the grand total as an exact number of bytes instead of e.g. "1.5 GB"
+    #[arg(long, global = true, conflicts_with = "no_total")]
+    pub total_bytes: bool,
+
+    /// Label each project with how costly its artifacts are to rebuild
+    /// (cheap, moderate, or expensive) in the table and JSON
+    #[arg(long, global = true)]
+    pub estimate_rebuild_cost: bool,
+
+    /// When to color the output: auto colors a terminal unless NO_COLOR is set
+    #[arg(long, value_enum, value_name = "WHEN", default_value_t, global = true)]
+    pub color: ColorMode,
+
+    /// Don't color the output (the same as --color=never)
+    #[arg(long, global = true, conflicts_with = "color")]
+    pub no_color: bool,
+
+    /// Print plain ASCII, without colors, in place of symbols and box drawing.
+    /// On by default when TERM is "dumb" or the locale isn't UTF-8
+    #[arg(long, global = true)]
+    pub ascii: bool,
+
+    /// Log scans and cleans to stderr in this format; levels come from RUST_LOG
+    /// (default "dev_sweep=info"). Without it, logs are only written when RUST_LOG is set
+    #[arg(long, value_enum, value_name = "FORMAT", global = true)]
+    pub log_format: Option<LogFormat>,
 }
 
 #[derive(Subcommand)]
 pub enum Commands {
     /// Scan for projects and show what can be cleaned (default)
-    Scan,
+    Scan {
+        /// How to present the results (html writes a standalone report to stdout)
+        #[arg(long, value_enum, default_value_t)]
+        format: ScanFormat,
+        /// Print only the project paths, each followed by a NUL byte (for xargs -0)
+        #[arg(long, visible_alias = "output-null-separated")]
+        print_paths0: bool,
+        /// With --print-paths0, print the paths of the targets a clean would
+        /// remove instead of the projects
+        #[arg(long, requires = "print_paths0")]
+        targets: bool,
+        /// Guarantee nothing on disk is changed: no removals, archives, or
+        /// scan cache update; any write fails instead
+        #[arg(long)]
+        read_only: bool,
+    },
     /// Interactively select and clean projects
     Clean {
         /// Clean all found projects without prompting
         #[arg(short, long)]
         all: bool,
+        /// Don't ask for confirmation before cleaning
+        #[arg(short, long)]
+        yes: bool,
+        /// Always ask for confirmation, even below the configured confirm_threshold
+        #[arg(long, conflicts_with = "yes")]
+        no_auto: bool,
+        /// With --all, confirm by typing the size being freed (e.g. "12.5 GB")
+        /// instead of answering y/N
+        #[arg(long, requires = "all", conflicts_with = "yes")]
+        confirm_phrase: bool,
         /// Show what would be cleaned without actually deleting
         #[arg(long)]
         dry_run: bool,
+        /// Archive each target as a .tar.gz into this directory before deleting it
+        #[arg(long, value_name = "DIR")]
+        archive: Option<PathBuf>,
+        /// Move targets into a timestamped quarantine directory instead of
+        /// deleting them, until purge-quarantine removes them
+        #[arg(long, conflicts_with = "archive")]
+        quarantine: bool,
+        /// Remove every target with this name (e.g. node_modules) across all projects
+        #[arg(long, value_name = "NAME", conflicts_with = "all")]
+        target: Option<String>,
+        /// Clean every project of the config's auto_clean_kinds without prompting,
+        /// leaving other kinds alone (for unattended runs)
+        #[arg(long, conflicts_with_all = ["all", "target", "no_auto"])]
+        auto: bool,
+        /// Order to clean selected projects in: size (largest first, the default),
+        /// age (oldest first), or a kind priority list such as Node,Rust
+        #[arg(long, value_name = "ORDER")]
+        clean_order: Option<String>,
+        /// Start no further projects once this long has passed (e.g. "90s", "2m",
+        /// "1h"); the largest are cleaned first, and the rest are left for another run
+        #[arg(long, value_name = "DURATION")]
+        time_budget: Option<String>,
+        /// Skip targets with files modified within this many seconds (likely an active build)
+        #[arg(long, value_name = "SECS", default_value_t = 60)]
+        exclude_newer_subtree: u64,
+        /// Clean targets even if they were modified recently
+        #[arg(long)]
+        force: bool,
+        /// Skip projects whose git working tree has uncommitted changes
+        /// (with --strict, also projects not under git)
+        #[arg(long)]
+        require_git_clean: bool,
+        /// Skip projects that look open in an editor (swap or lock files,
+        /// sockets), reporting them as in use
+        #[arg(long)]
+        exclude_active_editors: bool,
+        /// Afterwards, also remove empty directories under the scan root
+        #[arg(long)]
+        prune_empty: bool,
+        /// Exit successfully even if some targets couldn't be removed (ignored with --strict)
+        #[arg(long)]
+        ignore_errors: bool,
+        /// How to report progress and results (events needs --all or --target)
+        #[arg(long, value_enum, default_value_t)]
+        format: CleanFormat,
     },
     /// Show a quick summary of reclaimable space
-    Summary,
+    Summary {
+        /// Also break reclaimable space down by directory, aggregated up the hierarchy
+        #[arg(long, visible_alias = "depth-report")]
+        tree: bool,
+        /// How many directory levels below the scan root the tree shows
+        #[arg(long, value_name = "N", default_value_t = 3, requires = "tree")]
+        tree_depth: usize,
+        /// Also report node_modules (and similar) installed from identical lockfiles,
+        /// and how much a shared store such as pnpm's would save
+        #[arg(long)]
+        duplication: bool,
+        /// Also break reclaimable space down by the user owning each project (Unix only)
+        #[arg(long)]
+        by_owner: bool,
+        /// Also break reclaimable space down by the filesystem each project is on
+        /// (its mount point, or drive on Windows), with the space free on each
+        #[arg(long)]
+        by_filesystem: bool,
+    },
+    /// List the N biggest projects by reclaimable size, one line each
+    Top {
+        /// How many projects to list
+        #[arg(value_name = "N", default_value_t = 10)]
+        count: usize,
+    },
+    /// Rank projects by reclaimable size per rebuild effort, to see which are
+    /// the best to clean first
+    Analyze {
+        /// How many projects to list
+        #[arg(value_name = "N", default_value_t = 10)]
+        count: usize,
+    },
+    /// Report total reclaimable space against thresholds for monitoring: prints one
+    /// status line and exits 0 (OK), 1 (WARNING), 2 (CRITICAL), or 3 (UNKNOWN)
+    #[command(group(ArgGroup::new("thresholds").required(true).multiple(true)))]
+    Check {
+        /// Warn (exit 1) once reclaimable space reaches SIZE, e.g. "50G"
+        #[arg(long, value_name = "SIZE", group = "thresholds")]
+        warn: Option<String>,
+        /// Go critical (exit 2) once reclaimable space reaches SIZE, e.g. "100G"
+        #[arg(long, value_name = "SIZE", group = "thresholds")]
+        crit: Option<String>,
+    },
+    /// Permanently remove targets moved aside by clean --quarantine (with
+    /// --older-than, only those quarantined longer ago than that)
+    PurgeQuarantine {
+        /// Purge everything in quarantine without prompting
+        #[arg(short, long)]
+        all: bool,
+        /// Don't ask for confirmation before purging
+        #[arg(short, long)]
+        yes: bool,
+        /// Show what would be removed without removing it
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// List the supported project kinds with their marker files and targets
+    Kinds,
+    /// Combine `scan --json` or `summary --json` reports (e.g. from several
+    /// machines) into one summary, without scanning
+    Merge {
+        /// Further reports to combine (the first one is given as PATH)
+        #[arg(index = 2, value_name = "REPORT")]
+        reports: Vec<PathBuf>,
+    },
+    /// Build a throwaway sandbox, scan and clean it, and report what works on this machine
+    Selftest,
     /// Manage dev-sweep configuration
     Config {
         /// Show the current config
@@ -58,5 +377,56 @@ pub enum Commands {
         /// Reset config to defaults
         #[arg(long)]
         reset: bool,
+        /// Print only the config file's path, for scripts
+        #[arg(long = "path", conflicts_with_all = ["show", "reset", "edit"])]
+        print_path: bool,
+        /// Open the config file in $VISUAL or $EDITOR, creating it from the
+        /// defaults if it doesn't exist
+        #[arg(long, conflicts_with_all = ["show", "reset"])]
+        edit: bool,
+        /// With --reset, show what would change instead of writing the file
+        #[arg(long, requires = "reset")]
+        dry_run: bool,
+        /// Save a copy of the config file under NAME, to go back to with --restore
+        #[arg(
+            long,
+            value_name = "NAME",
+            conflicts_with_all = ["show", "reset", "print_path", "edit", "restore", "list_snapshots"]
+        )]
+        snapshot: Option<String>,
+        /// Replace the config file with the snapshot saved under NAME
+        #[arg(
+            long,
+            value_name = "NAME",
+            conflicts_with_all = ["show", "reset", "print_path", "edit", "list_snapshots"]
+        )]
+        restore: Option<String>,
+        /// List the saved config snapshots and when each was taken
+        #[arg(long, conflicts_with_all = ["show", "reset", "print_path", "edit"])]
+        list_snapshots: bool,
     },
 }
+
+/// How `scan` presents what it found.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum ScanFormat {
+    /// A table for the terminal
+    #[default]
+    Text,
+    /// A JSON report (the same as --json)
+    Json,
+    /// A self-contained HTML page with a sortable table, for sharing
+    Html,
+}
+
+/// How `clean` reports what it did.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum CleanFormat {
+    /// Tables and a summary for people
+    #[default]
+    Text,
+    /// One JSON report at the end (the same as --json)
+    Json,
+    /// One JSON event per line (NDJSON) as each project is cleaned
+    Events,
+}
@@ -1,83 +1,332 @@
-use std::collections::HashMap;
-use std::path::Path;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 
-use anyhow::Result;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use serde::Serialize;
 
-use crate::cleaner::clean_projects;
-use crate::config::DevSweepConfig;
-use crate::scanner::{ScannedProject, scan_directory};
-use crate::tui::colors::{blue, cyan, dim, green, red_bold, yellow_bold};
-use crate::tui::display::{confirm, multi_select, print_clean_summary, print_results_table};
-use crate::util::{format_bytes, parse_age};
+use crate::analyze::rank_by_effort;
+use crate::check::{CheckAlert, CheckReport, CheckStatus, CheckThresholds};
+use crate::cleaner::{
+    CleanEvent, CleanFailed, CleanOptions, CleanResult, InterruptGuard, Interrupted, PruneResult,
+    QuarantineEntry, available_space, check_archive_space, clean_projects_with,
+    clean_projects_with_progress, find_empty_dirs, prune_empty_dirs, purge_quarantine, quarantine,
+    quarantine_index_path,
+};
+use crate::cleanlog::{self, clean_log_path, entries_for, last_cleaned};
+use crate::cli::{CleanFormat, ScanFormat};
+use crate::config::{
+    ConfigSource, DEFAULT_LARGE_FILE_FRACTION, DevSweepConfig, LayeredConfig, SnapshotAction,
+    edit_config, editor_command, list_snapshots, restore_snapshot, save_snapshot,
+};
+use crate::duplication::find_duplicate_dependencies;
+use crate::filter::{
+    CleanOrder, FilterCheck, ProjectFilter, SortKey, age_cutoff, apply_filters, apply_kind_aliases,
+    apply_rebuild_costs, build_path_tree, build_regex, decide_filters, drop_recent_targets,
+    exclude_targets, filter_by_target, group_by_filesystem, group_by_kind, group_by_owner,
+    is_older_than, kinds_present, matches_regex, matches_substring, most_recent_per_kind,
+    order_for_cleaning, protect_last_builds, protect_unsafe_targets, regrown_since_clean,
+    sort_projects,
+};
+use crate::kinds::list_kinds;
+use crate::merge::{JSON_SCHEMA_VERSION, merge_reports, read_report};
+use crate::peaks::{self, peak_history_path};
+use crate::readonly::is_read_only;
+use crate::scancache::{self, scan_cache_path};
+use crate::scanner::{ProjectKind, ScanOptions, ScannedProject, scan_directory_with};
+use crate::selftest::run_selftest;
+use crate::tui::colors::{
+    blue, bold, cyan, dim, green, green_bold, red, red_bold, yellow, yellow_bold,
+};
+use crate::tui::display::{
+    DELETION_PLAN_LIMIT, DUPLICATION_LIMIT, PathList, TotalFormat, confirm, confirm_phrase,
+    confirm_phrase_stderr, confirm_stderr, format_auto_clean_summary, format_clean_progress,
+    format_config_layers, format_config_snapshots, format_duplication, format_effort_ranking,
+    format_freed_estimate, format_interrupted, format_kinds_table, format_line_diff,
+    format_path_tree, format_peak_sizes, format_permission_hint, format_quarantine_note,
+    format_scan_changes, format_since_clean, format_target_results, format_time_budget_exhausted,
+    format_top_projects, format_total, multi_select, null_separated_paths, print_clean_summary,
+    print_deletion_plan, print_filter_decisions, print_largest_files, print_preserved_recent,
+    print_results_table,
+};
+use crate::tui::glyphs;
+use crate::tui::html::render_html_report;
+use crate::util::{
+    LineChange, common_ancestor, diff_lines, format_age, format_bytes, hostname, pad_left,
+    shorten_path, to_json, user_name,
+};
+
+/// What to scan and which results to keep, shared by every scanning command.
+#[derive(Debug, Clone, Default)]
+pub struct ScanArgs {
+    /// Directories to scan (at least one), none of them inside another.
+    pub paths: Vec<PathBuf>,
+    /// Options passed through to the scanner.
+    pub options: ScanOptions,
+    /// Only keep projects older than this age string (e.g. "30d").
+    pub older_than: Option<String>,
+    /// Only keep projects with at least this many reclaimable bytes.
+    pub min_size: Option<u64>,
+    /// Leave out the N most recently modified projects of each kind.
+    pub keep_recent: Option<usize>,
+    /// Only keep projects of these kinds (names as typed by the user).
+    pub kinds: Vec<String>,
+    /// Only keep projects whose name or path contains this, ignoring case.
+    pub filter: Option<String>,
+    /// Only keep projects whose name or path matches this regex, ignoring case.
+    pub regex: Option<String>,
+    /// Globs naming clean targets to drop from every project.
+    pub exclude_targets: Vec<String>,
+    /// Only keep clean targets older than this age string (e.g. "60d").
+    pub target_older_than: Option<String>,
+    /// Mark targets not on the safe-mode allowlist as protected (`--safe`).
+    pub safe: bool,
+    /// Only keep projects never cleaned, or whose artifacts came back since.
+    pub since_clean: bool,
+    /// Compare against the cached previous scan (`--since-last`).
+    pub since_last: bool,
+    /// Update the peak history and compare each project with its peak
+    /// (`--record-max`).
+    pub record_max: bool,
+    /// Display order of the results.
+    pub sort: SortKey,
+    /// Show per-project details (such as the largest file) below the table.
+    pub verbose: bool,
+    /// Print which filters each detected project passed (`--explain-filters`).
+    pub explain_filters: bool,
+    /// Leave out the totals footer and color legend below the table.
+    pub quiet: bool,
+    /// Show one dense line per project instead of the full table.
+    pub compact: bool,
+    /// How grand totals are printed, if at all (`--no-total`, `--total-bytes`).
+    pub totals: TotalFormat,
+    /// Label each project with how costly its artifacts are to rebuild.
+    pub estimate_rebuild_cost: bool,
+}
 
 // ── Commands ────────────────────────────────────────────────────────────────
 
 pub fn cmd_scan(
-    path: &Path,
-    max_depth: Option<usize>,
-    older_than: Option<&str>,
-    json: bool,
+    args: &ScanArgs,
+    format: ScanFormat,
+    paths0: Option<PathList>,
     config: &DevSweepConfig,
 ) -> Result<()> {
-    let mut projects = scan_directory(path, max_depth, config)?;
-    filter_by_age(&mut projects, older_than)?;
-    sort_by_size(&mut projects);
+    if format == ScanFormat::Html && args.since_last {
+        anyhow::bail!("--format html can't be combined with --since-last");
+    }
+    if paths0.is_some() && (format != ScanFormat::Text || args.since_last) {
+        anyhow::bail!("--print-paths0 can't be combined with --format, --json, or --since-last");
+    }
+    let json = format == ScanFormat::Json;
+    let read_only = is_read_only();
+    if read_only {
+        // On stderr, so it's seen whatever the output format.
+        eprintln!(
+            "  {} Read-only scan: nothing will be deleted, archived, or cached.",
+            cyan("🔒")
+        );
+    }
+    let mut projects = scan_projects(args, config)?;
+    sort_projects(&mut projects, args.sort);
 
-    if json {
-        println!("{}", serde_json::to_string_pretty(&projects)?);
+    // Every scan refreshes the cache that `--since-last` compares against,
+    // except a read-only one, which only reads it.
+    let cache_path = scan_cache_path();
+    let mut cache = scancache::load(&cache_path);
+    let complete = !args.is_filtered();
+    let changes = args
+        .since_last
+        .then(|| cache.diff(&args.paths, &projects, complete));
+    let previous_scan = cache.timestamp;
+    if !read_only {
+        cache.update(&args.paths, &projects, complete);
+        if let Err(e) = scancache::save(&cache_path, &cache) {
+            eprintln!("  {} Could not update scan cache: {:#}", yellow("⚠"), e);
+        }
+    }
+    // Peaks are recorded before any output, so every form of the scan,
+    // --since-last included, keeps the history growing.
+    let peaks = args.record_max.then(|| {
+        let path = peak_history_path();
+        let mut history = peaks::load(&path);
+        if !read_only {
+            history.record(&projects, Local::now());
+            if let Err(e) = peaks::save(&path, &history) {
+                eprintln!("  {} Could not update peak history: {:#}", yellow("⚠"), e);
+            }
+        }
+        history
+    });
+
+    if let Some(changes) = changes {
+        if json {
+            let values: Vec<_> = changes
+                .iter()
+                .map(|c| {
+                    let mut value = serde_json::to_value(c)?;
+                    value["delta_bytes"] = serde_json::to_value(c.delta() as i64)?;
+                    Ok(value)
+                })
+                .collect::<Result<_>>()?;
+            print_json_list("changes", &values, &args.paths)?;
+        } else {
+            let since = match previous_scan {
+                Some(when) => format!("since the last scan ({})", when.format("%Y-%m-%d %H:%M")),
+                None => "(no previous scan cached)".to_string(),
+            };
+            println!("\n  {} {}\n", bold("Changes"), dim(&since));
+            if changes.is_empty() {
+                println!("  {} Nothing changed.\n", blue("ℹ"));
+            } else {
+                for line in format_scan_changes(&changes) {
+                    println!("{line}");
+                }
+                println!();
+            }
+        }
+        return Ok(());
+    }
+
+    if let Some(list) = paths0 {
+        let mut out = io::stdout().lock();
+        out.write_all(&null_separated_paths(&projects, list))?;
+        out.flush()?;
+        return Ok(());
+    }
+
+    if format == ScanFormat::Html {
+        print!("{}", render_html_report(&projects, &args.paths, Local::now()));
+        return Ok(());
+    }
+
+    let last_cleaned = if args.since_clean {
+        last_cleaned(&cleanlog::read(&clean_log_path()))
     } else {
-        print_results_table(&projects);
+        HashMap::new()
+    };
+
+    if json && (args.since_clean || peaks.is_some()) {
+        let mut values = Vec::with_capacity(projects.len());
+        for p in &projects {
+            let mut value = serde_json::to_value(p)?;
+            if args.since_clean {
+                value["last_cleaned"] = serde_json::to_value(last_cleaned.get(&p.path))?;
+            }
+            if let Some(ref history) = peaks {
+                let peak = history.peak(p);
+                value["max_bytes"] = serde_json::to_value(peak.map(|peak| peak.bytes))?;
+                value["max_recorded_at"] =
+                    serde_json::to_value(peak.map(|peak| peak.recorded_at))?;
+            }
+            values.push(value);
+        }
+        print_json_list("projects", &values, &args.paths)?;
+    } else if json {
+        print_json_list("projects", &projects, &args.paths)?;
+    } else {
+        print_results_table(&projects, !args.quiet, args.compact, args.totals);
+        if args.verbose {
+            print_largest_files(&projects, large_file_fraction(config));
+        }
+        if args.since_clean && !projects.is_empty() {
+            println!("  {}", bold("Since last clean:"));
+            for line in format_since_clean(&projects, &last_cleaned) {
+                println!("{line}");
+            }
+            println!();
+        }
+        if let Some(ref history) = peaks
+            && !projects.is_empty()
+        {
+            println!("  {}", bold("Compared with peak size:"));
+            for line in format_peak_sizes(&projects, history) {
+                println!("{line}");
+            }
+            println!();
+        }
     }
 
     Ok(())
 }
 
+/// Clean the projects picked from the scan results, or all of them with `all`.
+///
+/// Returns whether the clean went ahead: `false` when the confirmation was
+/// declined, `true` otherwise (even with nothing found or selected).
 pub fn cmd_clean(
-    path: &Path,
-    max_depth: Option<usize>,
-    older_than: Option<&str>,
+    args: &ScanArgs,
     all: bool,
-    dry_run: bool,
-    json: bool,
+    options: &CleanOptions,
+    order: &CleanOrder,
+    confirm: Confirmation,
+    format: CleanFormat,
     config: &DevSweepConfig,
-) -> Result<()> {
-    let mut projects = scan_directory(path, max_depth, config)?;
-    filter_by_age(&mut projects, older_than)?;
-    sort_by_size(&mut projects);
+) -> Result<bool> {
+    let dry_run = options.dry_run;
+    let text = format == CleanFormat::Text;
+    if format == CleanFormat::Events && !all {
+        anyhow::bail!("--format events can't prompt for a selection; add --all or --target");
+    }
+    let mut projects = scan_projects(args, config)?;
+    sort_projects(&mut projects, args.sort);
 
-    if projects.is_empty() {
+    if projects.is_empty() && text {
         println!(
             "\n  {} No projects with cleanable artifacts found.\n",
             blue("ℹ")
         );
-        return Ok(());
+        return Ok(true);
     }
 
-    print_results_table(&projects);
+    if text {
+        print_results_table(&projects, !args.quiet, args.compact, args.totals);
+        if args.verbose {
+            print_largest_files(&projects, large_file_fraction(config));
+        }
+    }
+
+    let selected_projects: Vec<&ScannedProject> = if projects.is_empty() {
+        Vec::new()
+    } else if all {
+        let total: u64 = projects.iter().map(|p| p.total_cleanable_bytes).sum();
+        if let Some(ref archive_dir) = options.archive_dir {
+            check_archive_space(archive_dir, total)?;
+        }
+
+        let mut selected: Vec<&ScannedProject> = projects.iter().collect();
+        order_for_cleaning(&mut selected, order);
+        if text {
+            print_plan(&selected, dry_run);
+        }
 
-    let selected_projects: Vec<&ScannedProject> = if all {
         if !dry_run {
-            let total: u64 = projects.iter().map(|p| p.total_cleanable_bytes).sum();
-            let confirmed = confirm(&format!(
+            let prompt = format!(
                 "Clean ALL {} projects? This will free {} and cannot be undone!",
                 projects.len(),
                 format_bytes(total),
-            ))?;
-
+            );
+            let confirmed = if confirm.phrase_above.is_some_and(|above| total > above) {
+                confirm_clean_phrase(&prompt, &format_bytes(total), format)?
+            } else {
+                confirm_clean(&prompt, total, confirm.policy, format)?
+            };
             if !confirmed {
-                println!("  {} Aborted.\n", red_bold("✗"));
-                return Ok(());
+                return Ok(false);
             }
         }
-        projects.iter().collect()
+        selected
     } else {
         let items: Vec<String> = projects
             .iter()
             .map(|p| {
                 format!(
-                    "{} ({}) — {} [{}]",
+                    "{} ({}) {} {} [{}]",
                     p.name,
-                    p.kind,
+                    p.display_kind(),
+                    glyphs::plain("—"),
                     format_bytes(p.total_cleanable_bytes),
                     p.clean_targets
                         .iter()
@@ -92,107 +341,748 @@ pub fn cmd_clean(
 
         if selections.is_empty() {
             println!("  {} Nothing selected.\n", blue("ℹ"));
-            return Ok(());
+            return Ok(true);
+        }
+
+        let mut selected: Vec<&ScannedProject> = selections.iter().map(|&i| &projects[i]).collect();
+        order_for_cleaning(&mut selected, order);
+        let sel_total: u64 = selected.iter().map(|p| p.total_cleanable_bytes).sum();
+        if let Some(ref archive_dir) = options.archive_dir {
+            check_archive_space(archive_dir, sel_total)?;
+        }
+
+        if text {
+            print_plan(&selected, dry_run);
         }
 
         if !dry_run {
-            let sel_total: u64 = selections
-                .iter()
-                .map(|&i| projects[i].total_cleanable_bytes)
-                .sum();
-            let confirmed = confirm(&format!(
+            let prompt = format!(
                 "Clean {} projects? This will free {}.",
                 selections.len(),
                 format_bytes(sel_total),
-            ))?;
-            if !confirmed {
-                println!("  {} Aborted.\n", red_bold("✗"));
-                return Ok(());
+            );
+            if !confirm_clean(&prompt, sel_total, confirm.policy, format)? {
+                return Ok(false);
             }
         }
 
-        selections.iter().map(|&i| &projects[i]).collect()
+        selected
     };
 
-    let action = if dry_run { "Would clean" } else { "Cleaning" };
-    println!(
-        "\n  {} {} {} projects...\n",
-        dim("→"),
-        action,
-        cyan(&selected_projects.len().to_string()),
-    );
+    if text {
+        let action = if dry_run { "Would clean" } else { "Cleaning" };
+        println!(
+            "\n  {} {} {} projects...\n",
+            dim("→"),
+            action,
+            cyan(&selected_projects.len().to_string()),
+        );
+    }
 
-    let results = clean_projects(&selected_projects, dry_run);
+    let results = clean_reporting(&selected_projects, options, format, "projects");
+    let interrupted = options.interrupted();
+    let remaining = &selected_projects[results.len()..];
+    let out_of_time = options.ran_out_of_time(&results, selected_projects.len());
+    if !dry_run {
+        record_cleans(&selected_projects, &results);
+    }
 
-    if json {
+    if format == CleanFormat::Json {
         let summary = serde_json::json!({
             "dry_run": dry_run,
+            "interrupted": interrupted,
+            "time_budget_exhausted": out_of_time,
+            "bytes_remaining": remaining.iter().map(|p| p.total_cleanable_bytes).sum::<u64>(),
             "projects_cleaned": results.len(),
             "total_bytes_freed": results.iter().map(|r| r.bytes_freed).sum::<u64>(),
+            "total_bytes_estimated": results.iter().map(|r| r.bytes_estimated).sum::<u64>(),
             "errors": results.iter().flat_map(|r| r.errors.clone()).collect::<Vec<_>>(),
+            "archives": results.iter().flat_map(|r| r.archives.clone()).collect::<Vec<_>>(),
+            "quarantined": results.iter().flat_map(|r| r.quarantined.clone()).collect::<Vec<_>>(),
+            "skipped_active": results
+                .iter()
+                .flat_map(|r| r.skipped_active.clone())
+                .collect::<Vec<_>>(),
+            "permission_denied": results
+                .iter()
+                .flat_map(|r| r.permission_denied.clone())
+                .collect::<Vec<_>>(),
+            "skipped_unsafe": results
+                .iter()
+                .flat_map(|r| r.skipped_unsafe.clone())
+                .collect::<Vec<_>>(),
+            "skipped_stale": results
+                .iter()
+                .flat_map(|r| r.skipped_stale.clone())
+                .collect::<Vec<_>>(),
+            "skipped_git": results
+                .iter()
+                .filter_map(|r| {
+                    let reason = r.skipped_git.as_ref()?;
+                    Some(serde_json::json!({ "project": r.project_name, "reason": reason }))
+                })
+                .collect::<Vec<_>>(),
+            "skipped_in_use": results
+                .iter()
+                .filter_map(|r| {
+                    let marker = r.skipped_in_use.as_ref()?;
+                    Some(serde_json::json!({ "project": r.project_name, "marker": marker }))
+                })
+                .collect::<Vec<_>>(),
+            "warnings": results
+                .iter()
+                .flat_map(|r| {
+                    r.warnings.iter().map(|warning| {
+                        serde_json::json!({ "project": r.project_name, "warning": warning })
+                    })
+                })
+                .collect::<Vec<_>>(),
         });
-        println!("{}", serde_json::to_string_pretty(&summary)?);
-    } else {
+        print_json(summary, &args.paths)?;
+    } else if text {
+        print_clean_summary(&results, dry_run);
+        if interrupted {
+            let total = selected_projects.len();
+            println!("{}\n", format_interrupted(&results, total, "projects"));
+        } else if out_of_time {
+            let note = format_time_budget_exhausted(&results, remaining, "projects");
+            println!("{note}\n");
+        }
+    }
+
+    if interrupted {
+        return Err(Interrupted.into());
+    }
+    let failures: usize = results.iter().map(|r| r.errors.len()).sum();
+    if failures > 0 {
+        return Err(CleanFailed { failures }.into());
+    }
+
+    Ok(true)
+}
+
+/// Clean every target called `target_name` across all scanned projects in one batch.
+///
+/// Unlike [`cmd_clean`], no projects are selected: all matching targets are
+/// listed, confirmed once for their combined size, and reported individually.
+/// Returns whether the clean went ahead, as [`cmd_clean`] does.
+pub fn cmd_clean_target(
+    args: &ScanArgs,
+    target_name: &str,
+    options: &CleanOptions,
+    order: &CleanOrder,
+    confirm: ConfirmPolicy,
+    format: CleanFormat,
+    config: &DevSweepConfig,
+) -> Result<bool> {
+    let dry_run = options.dry_run;
+    let text = format == CleanFormat::Text;
+    let mut projects = scan_projects(args, config)?;
+    filter_by_target(&mut projects, target_name);
+    sort_projects(&mut projects, SortKey::Path);
+
+    if projects.is_empty() {
+        if format == CleanFormat::Events {
+            clean_reporting(&[], options, format, "targets");
+            return Ok(true);
+        }
+        println!("\n  {} No '{}' targets found.\n", blue("ℹ"), target_name);
+        return Ok(true);
+    }
+
+    // One single-target project per entry, so every target gets its own result.
+    let batch: Vec<ScannedProject> = projects
+        .iter()
+        .flat_map(|p| {
+            p.clean_targets.iter().map(|t| ScannedProject {
+                clean_targets: vec![t.clone()],
+                total_cleanable_bytes: t.size_bytes,
+                ..p.clone()
+            })
+        })
+        .collect();
+    let total: u64 = batch.iter().map(|p| p.total_cleanable_bytes).sum();
+    let kinds = kinds_present(&projects);
+
+    if text && kinds.len() > 1 {
+        let names: Vec<String> = kinds.iter().map(|k| k.to_string()).collect();
+        println!(
+            "\n  {} '{}' matches targets in {} project types: {}. Use {} to narrow.",
+            yellow("⚠"),
+            target_name,
+            kinds.len(),
+            names.join(", "),
+            green("--kind"),
+        );
+    }
+
+    if let Some(ref archive_dir) = options.archive_dir {
+        check_archive_space(archive_dir, total)?;
+    }
+
+    if text {
+        let selected: Vec<&ScannedProject> = projects.iter().collect();
+        print_plan(&selected, dry_run);
+    }
+
+    if !dry_run {
+        let prompt = format!(
+            "Remove {} '{}' targets across {} projects? This will free {}.",
+            batch.len(),
+            target_name,
+            projects.len(),
+            format_bytes(total),
+        );
+        if !confirm_clean(&prompt, total, confirm, format)? {
+            return Ok(false);
+        }
+    }
+
+    let mut refs: Vec<&ScannedProject> = batch.iter().collect();
+    order_for_cleaning(&mut refs, order);
+    let results = clean_reporting(&refs, options, format, "targets");
+    let interrupted = options.interrupted();
+    let remaining = &refs[results.len()..];
+    let out_of_time = options.ran_out_of_time(&results, refs.len());
+    if !dry_run {
+        record_cleans(&refs, &results);
+    }
+    let freed: u64 = results.iter().map(|r| r.bytes_freed).sum();
+    let failures: usize = results.iter().map(|r| r.errors.len()).sum();
+
+    if format == CleanFormat::Json {
+        let targets: Vec<_> = batch
+            .iter()
+            .zip(&results)
+            .map(|(p, r)| {
+                serde_json::json!({
+                    "project": p.name,
+                    "kind": p.kind,
+                    "kind_alias": p.kind_alias,
+                    "path": p.clean_targets[0].path,
+                    "size_bytes": p.total_cleanable_bytes,
+                    "bytes_freed": r.bytes_freed,
+                    "cleaned": r.targets_cleaned > 0,
+                    "skipped_active": !r.skipped_active.is_empty(),
+                    "skipped_git": r.skipped_git,
+                    "skipped_in_use": r.skipped_in_use,
+                    "skipped_unsafe": !r.skipped_unsafe.is_empty(),
+                    "skipped_stale": !r.skipped_stale.is_empty(),
+                    "permission_denied": !r.permission_denied.is_empty(),
+                    "error": r.errors.first(),
+                    "warning": r.warnings.first(),
+                    "archive": r.archives.first(),
+                    "quarantine": r.quarantined.first().map(|q| &q.location),
+                })
+            })
+            .collect();
+        let summary = serde_json::json!({
+            "dry_run": dry_run,
+            "interrupted": interrupted,
+            "time_budget_exhausted": out_of_time,
+            "bytes_remaining": remaining.iter().map(|p| p.total_cleanable_bytes).sum::<u64>(),
+            "target": target_name,
+            "kinds": kinds,
+            "targets": targets,
+            "total_bytes_freed": freed,
+            "total_bytes_estimated": results.iter().map(|r| r.bytes_estimated).sum::<u64>(),
+        });
+        print_json(summary, &args.paths)?;
+    } else if text {
+        println!();
+        for line in format_target_results(&batch, &results, dry_run) {
+            println!("{line}");
+        }
+        let verb = if dry_run { "would be freed" } else { "freed" };
+        let cleaned: usize = results.iter().map(|r| r.targets_cleaned).sum();
+        println!(
+            "\n  {} {} {} from {} of {} '{}' targets.",
+            bold(if dry_run { "🔍" } else { "🧹" }),
+            yellow_bold(&format_bytes(freed)),
+            verb,
+            cyan(&cleaned.to_string()),
+            cyan(&batch.len().to_string()),
+            target_name,
+        );
+        if !dry_run && cleaned > 0 {
+            println!("{}", format_freed_estimate(&results));
+        }
+        if let Some(note) = format_quarantine_note(&results) {
+            println!("{note}");
+        }
+        if let Some(hint) = format_permission_hint(&results) {
+            println!("{hint}");
+        }
+        if interrupted {
+            println!("{}", format_interrupted(&results, batch.len(), "targets"));
+        } else if out_of_time {
+            let note = format_time_budget_exhausted(&results, remaining, "targets");
+            println!("{note}");
+        }
+        println!();
+    }
+
+    if interrupted {
+        return Err(Interrupted.into());
+    }
+    if failures > 0 {
+        return Err(CleanFailed { failures }.into());
+    }
+
+    Ok(true)
+}
+
+/// Clean every project whose kind is in `auto_kinds` (the config's
+/// `auto_clean_kinds`) without prompting, for `clean --auto` on unattended
+/// machines. Projects of other kinds are neither listed nor touched, and so
+/// are polyglot projects with any kind not in the list.
+pub fn cmd_clean_auto(
+    args: &ScanArgs,
+    auto_kinds: &[ProjectKind],
+    options: &CleanOptions,
+    order: &CleanOrder,
+    format: CleanFormat,
+    config: &DevSweepConfig,
+) -> Result<()> {
+    if auto_kinds.is_empty() {
+        anyhow::bail!(
+            "--auto cleans the kinds listed in auto_clean_kinds, but the config lists none"
+        );
+    }
+    let dry_run = options.dry_run;
+    let text = format == CleanFormat::Text;
+    let mut projects = scan_projects(args, config)?;
+    projects.retain(|p| p.kinds().all(|k| auto_kinds.contains(&k)));
+    sort_projects(&mut projects, SortKey::Path);
+
+    let kind_names: Vec<String> = auto_kinds.iter().map(|k| k.to_string()).collect();
+    if projects.is_empty() && text {
+        println!(
+            "\n  {} No projects of the auto-clean kinds ({}) found.\n",
+            blue("ℹ"),
+            kind_names.join(", ")
+        );
+        return Ok(());
+    }
+
+    let mut selected: Vec<&ScannedProject> = projects.iter().collect();
+    order_for_cleaning(&mut selected, order);
+    if let Some(ref archive_dir) = options.archive_dir {
+        let total: u64 = projects.iter().map(|p| p.total_cleanable_bytes).sum();
+        check_archive_space(archive_dir, total)?;
+    }
+    if text {
+        print_plan(&selected, dry_run);
+    }
+
+    let results = clean_reporting(&selected, options, format, "projects");
+    let interrupted = options.interrupted();
+    let remaining = &selected[results.len()..];
+    let out_of_time = options.ran_out_of_time(&results, selected.len());
+    if !dry_run {
+        record_cleans(&selected, &results);
+    }
+
+    if format == CleanFormat::Json {
+        let cleaned: Vec<_> = selected
+            .iter()
+            .zip(&results)
+            .map(|(p, r)| {
+                serde_json::json!({
+                    "project": p.name,
+                    "kind": p.kind,
+                    "kind_alias": p.kind_alias,
+                    "path": p.path,
+                    "targets_cleaned": r.targets_cleaned,
+                    "bytes_freed": r.bytes_freed,
+                    "errors": r.errors,
+                    "warnings": r.warnings,
+                    "skipped_git": r.skipped_git,
+                    "skipped_in_use": r.skipped_in_use,
+                    "quarantined": r.quarantined,
+                })
+            })
+            .collect();
+        let summary = serde_json::json!({
+            "dry_run": dry_run,
+            "interrupted": interrupted,
+            "time_budget_exhausted": out_of_time,
+            "bytes_remaining": remaining.iter().map(|p| p.total_cleanable_bytes).sum::<u64>(),
+            "auto_clean_kinds": auto_kinds,
+            "projects": cleaned,
+            "total_bytes_freed": results.iter().map(|r| r.bytes_freed).sum::<u64>(),
+            "total_bytes_estimated": results.iter().map(|r| r.bytes_estimated).sum::<u64>(),
+        });
+        print_json(summary, &args.paths)?;
+    } else if text {
+        for line in format_auto_clean_summary(&selected, &results, dry_run) {
+            println!("{line}");
+        }
         print_clean_summary(&results, dry_run);
+        if interrupted {
+            println!("{}\n", format_interrupted(&results, selected.len(), "projects"));
+        } else if out_of_time {
+            let note = format_time_budget_exhausted(&results, remaining, "projects");
+            println!("{note}\n");
+        }
+    }
+
+    if interrupted {
+        return Err(Interrupted.into());
+    }
+    let failures: usize = results.iter().map(|r| r.errors.len()).sum();
+    if failures > 0 {
+        return Err(CleanFailed { failures }.into());
+    }
+
+    Ok(())
+}
+
+/// When `clean` asks before removing anything.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ConfirmPolicy {
+    /// Always ask (the default, and `--no-auto`).
+    #[default]
+    Always,
+    /// Only ask when more than this many bytes would be freed (`confirm_threshold`).
+    Above(u64),
+    /// Never ask (`--yes`).
+    Never,
+}
+
+/// How `clean` confirms a clean of the projects picked or, with `--all`, of
+/// everything.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Confirmation {
+    /// Whether to ask at all.
+    pub policy: ConfirmPolicy,
+    /// Have an `--all` clean freeing more than this many bytes confirmed by
+    /// typing the size out (`--confirm-phrase`, `confirm_phrase_above`).
+    pub phrase_above: Option<u64>,
+}
+
+impl ConfirmPolicy {
+    /// Whether a clean freeing `bytes` needs confirming.
+    pub fn asks_for(self, bytes: u64) -> bool {
+        match self {
+            ConfirmPolicy::Always => true,
+            ConfirmPolicy::Above(threshold) => bytes > threshold,
+            ConfirmPolicy::Never => false,
+        }
+    }
+}
+
+impl ScanArgs {
+    /// Whether any result filter narrows the scan below everything found.
+    pub fn is_filtered(&self) -> bool {
+        self.older_than.is_some()
+            || self.min_size.is_some()
+            || self.keep_recent.is_some()
+            || !self.kinds.is_empty()
+            || self.filter.is_some()
+            || self.regex.is_some()
+            || !self.exclude_targets.is_empty()
+            || self.target_older_than.is_some()
+            || self.since_clean
+    }
+}
+
+/// Remove empty directories under the scan root, after confirming unless `all`.
+/// With `json`, the prompt and report go to stderr: this runs after a clean,
+/// whose own JSON report has stdout.
+pub fn cmd_prune_empty(
+    args: &ScanArgs,
+    all: bool,
+    dry_run: bool,
+    json: bool,
+    config: &DevSweepConfig,
+) -> Result<()> {
+    let found: Vec<(&PathBuf, Vec<PathBuf>)> = args
+        .paths
+        .iter()
+        .map(|root| (root, find_empty_dirs(root, &args.options, config)))
+        .collect();
+    let dirs: Vec<&PathBuf> = found.iter().flat_map(|(_, dirs)| dirs).collect();
+
+    if dirs.is_empty() {
+        if json {
+            let summary = serde_json::json!({ "dry_run": dry_run, "pruned": [], "errors": [] });
+            eprint_json(summary, &args.paths)?;
+        } else {
+            println!("  {} No empty directories to prune.\n", blue("ℹ"));
+        }
+        return Ok(());
+    }
+
+    if !json {
+        println!(
+            "\n  {} {} empty directories under {}:",
+            dim("→"),
+            cyan(&dirs.len().to_string()),
+            display_roots(&args.paths),
+        );
+        for dir in dirs.iter().take(DELETION_PLAN_LIMIT) {
+            println!("    {} {}", blue("•"), dir.display());
+        }
+        if dirs.len() > DELETION_PLAN_LIMIT {
+            println!(
+                "    {}",
+                dim(&format!("… and {} more", dirs.len() - DELETION_PLAN_LIMIT))
+            );
+        }
+        println!();
+    }
+
+    if !dry_run && !all {
+        let prompt = format!("Remove {} empty directories?", dirs.len());
+        if json && !confirm_stderr(&prompt)? {
+            eprintln!("  {} Aborted.\n", red_bold("✗"));
+            return Ok(());
+        }
+        if !json && !confirm(&prompt)? {
+            println!("  {} Aborted.\n", red_bold("✗"));
+            return Ok(());
+        }
+    }
+
+    let mut result = PruneResult::default();
+    for (root, dirs) in &found {
+        let pruned = prune_empty_dirs(root, dirs, dry_run);
+        result.pruned.extend(pruned.pruned);
+        result.errors.extend(pruned.errors);
+    }
+
+    if json {
+        let summary = serde_json::json!({
+            "dry_run": dry_run,
+            "pruned": result.pruned,
+            "errors": result.errors,
+        });
+        eprint_json(summary, &args.paths)?;
+    } else {
+        let verb = if dry_run { "Would prune" } else { "Pruned" };
+        println!(
+            "  {} {} {} empty directories.",
+            bold(if dry_run { "🔍" } else { "🧹" }),
+            verb,
+            cyan(&result.pruned.len().to_string()),
+        );
+        for error in &result.errors {
+            println!("    {} {}", red("✗"), error);
+        }
+        println!();
+    }
+
+    if args.options.strict && !result.errors.is_empty() {
+        anyhow::bail!(
+            "{} directories could not be pruned (strict mode)",
+            result.errors.len()
+        );
     }
 
     Ok(())
 }
 
+/// Permanently remove quarantined targets: those moved before `cutoff`, or
+/// all of them, once `confirm` allows.
+pub fn cmd_purge_quarantine(
+    cutoff: Option<DateTime<Local>>,
+    confirm: ConfirmPolicy,
+    dry_run: bool,
+    json: bool,
+) -> Result<()> {
+    let index = quarantine_index_path();
+    if !dry_run {
+        let pending = purge_quarantine(&index, cutoff, true)?;
+        let total: u64 = pending.purged.iter().map(|e| e.size_bytes).sum();
+        let prompt = format!(
+            "Permanently remove {} quarantined targets? This will free {}.",
+            pending.purged.len(),
+            format_bytes(total),
+        );
+        let format = if json {
+            CleanFormat::Json
+        } else {
+            CleanFormat::Text
+        };
+        if !pending.purged.is_empty() && !confirm_clean(&prompt, total, confirm, format)? {
+            return Ok(());
+        }
+    }
+    let result = purge_quarantine(&index, cutoff, dry_run)?;
+    let bytes: u64 = result.purged.iter().map(|e| e.size_bytes).sum();
+
+    if json {
+        let summary = serde_json::json!({
+            "dry_run": dry_run,
+            "purged": result.purged,
+            "kept": result.kept,
+            "total_bytes_freed": bytes,
+            "errors": result.errors,
+        });
+        // The quarantine directories purged from, each holding one batch per run.
+        let mut roots: Vec<PathBuf> = result
+            .purged
+            .iter()
+            .filter_map(|e| e.location.parent()?.parent().map(PathBuf::from))
+            .collect();
+        roots.sort();
+        roots.dedup();
+        print_json(summary, &roots)?;
+    } else if result.purged.is_empty() && result.errors.is_empty() {
+        println!("  {} Nothing in quarantine to purge.", blue("ℹ"));
+        if result.kept > 0 {
+            println!(
+                "  {} {} quarantined targets are too recent; lower {} to include them.",
+                dim("→"),
+                cyan(&result.kept.to_string()),
+                green("--older-than")
+            );
+        }
+        println!();
+    } else {
+        println!();
+        for entry in &result.purged {
+            println!(
+                "    {} {} {}",
+                blue("•"),
+                entry.original.display(),
+                dim(&format!(
+                    "({}, quarantined {})",
+                    format_bytes(entry.size_bytes),
+                    format_age(Local::now() - entry.quarantined_at)
+                )),
+            );
+        }
+        let verb = if dry_run { "would be freed" } else { "freed" };
+        println!(
+            "\n  {} {} {} from {} quarantined targets.",
+            bold(if dry_run { "🔍" } else { "🧹" }),
+            yellow_bold(&format_bytes(bytes)),
+            verb,
+            cyan(&result.purged.len().to_string()),
+        );
+        if result.kept > 0 {
+            println!(
+                "  {} {} more are too recent to purge.",
+                dim("→"),
+                cyan(&result.kept.to_string())
+            );
+        }
+        for error in &result.errors {
+            println!("    {} {}", red("✗"), error);
+        }
+        println!();
+    }
+
+    if !result.errors.is_empty() {
+        return Err(CleanFailed {
+            failures: result.errors.len(),
+        }
+        .into());
+    }
+    Ok(())
+}
+
 pub fn cmd_summary(
-    path: &Path,
-    max_depth: Option<usize>,
-    older_than: Option<&str>,
+    args: &ScanArgs,
+    tree_depth: Option<usize>,
+    duplication: bool,
+    by_owner: bool,
+    by_filesystem: bool,
     json: bool,
     config: &DevSweepConfig,
 ) -> Result<()> {
-    let mut projects = scan_directory(path, max_depth, config)?;
-    filter_by_age(&mut projects, older_than)?;
+    if by_owner && !cfg!(unix) {
+        anyhow::bail!("--by-owner needs Unix file ownership (user IDs), which this platform lacks");
+    }
+    let projects = scan_projects(args, config)?;
+    let duplication = duplication.then(|| find_duplicate_dependencies(&projects));
 
     let total_bytes: u64 = projects.iter().map(|p| p.total_cleanable_bytes).sum();
     let total_projects = projects.len();
 
-    let mut by_kind: HashMap<String, (usize, u64)> = HashMap::new();
-    for p in &projects {
-        let entry = by_kind.entry(p.kind.to_string()).or_insert((0, 0));
-        entry.0 += 1;
-        entry.1 += p.total_cleanable_bytes;
-    }
+    let by_kind = group_by_kind(&projects);
 
     if json {
-        let summary = serde_json::json!({
+        let mut summary = serde_json::json!({
             "total_projects": total_projects,
             "total_reclaimable_bytes": total_bytes,
             "total_reclaimable_human": format_bytes(total_bytes),
             "by_kind": by_kind.iter().map(|(k, (count, bytes))| {
+                let mut detected: Vec<ProjectKind> = projects
+                    .iter()
+                    .filter(|p| p.display_kind() == *k)
+                    .map(|p| p.kind)
+                    .collect();
+                detected.sort_by_key(|k| k.to_string());
+                detected.dedup();
                 serde_json::json!({
                     "kind": k,
+                    "detected_kinds": detected,
                     "projects": count,
                     "reclaimable_bytes": bytes,
                     "reclaimable_human": format_bytes(*bytes),
                 })
             }).collect::<Vec<_>>(),
         });
-        println!("{}", serde_json::to_string_pretty(&summary)?);
+        if by_owner {
+            let owners: Vec<_> = group_by_owner(&projects)
+                .into_iter()
+                .map(|(uid, (count, bytes))| {
+                    serde_json::json!({
+                        "uid": uid,
+                        "owner": uid.and_then(user_name),
+                        "projects": count,
+                        "reclaimable_bytes": bytes,
+                        "reclaimable_human": format_bytes(bytes),
+                    })
+                })
+                .collect();
+            summary["by_owner"] = owners.into();
+        }
+        if by_filesystem {
+            let filesystems: Vec<_> = group_by_filesystem(&projects)
+                .into_iter()
+                .map(|(mount, (count, bytes))| {
+                    serde_json::json!({
+                        "mount": mount,
+                        "available_bytes": available_space(&mount),
+                        "projects": count,
+                        "reclaimable_bytes": bytes,
+                        "reclaimable_human": format_bytes(bytes),
+                    })
+                })
+                .collect();
+            summary["by_filesystem"] = filesystems.into();
+        }
+        if tree_depth.is_some() {
+            let tree = build_path_tree(&common_ancestor(&args.paths), &projects);
+            summary["tree"] = serde_json::to_value(tree)?;
+        }
+        if let Some(report) = &duplication {
+            summary["duplication"] = serde_json::to_value(report)?;
+        }
+        print_json(summary, &args.paths)?;
     } else {
-        println!("\n  📊 dev-sweep summary for {}\n", path.display());
-        println!(
-            "  Total projects:     {}",
-            cyan(&total_projects.to_string())
-        );
         println!(
-            "  Reclaimable space:  {}",
-            yellow_bold(&format_bytes(total_bytes))
+            "\n  {} dev-sweep summary for {}\n",
+            glyphs::plain("📊"),
+            display_roots(&args.paths)
         );
-        println!();
+        if let Some(total) = format_total(total_bytes, args.totals) {
+            println!(
+                "  Total projects:     {}",
+                cyan(&total_projects.to_string())
+            );
+            println!("  Reclaimable space:  {}", yellow_bold(&total));
+            println!();
+        }
 
         if !by_kind.is_empty() {
             println!("  {}", dim("By project type:"));
 
-            let mut sorted: Vec<_> = by_kind.iter().collect();
-            sorted.sort_by(|a, b| b.1 .1.cmp(&a.1 .1));
-
-            for (kind, (count, bytes)) in sorted {
+            for (kind, (count, bytes)) in &by_kind {
                 println!(
                     "    {:>12}  {} projects, {}",
                     kind,
@@ -202,12 +1092,302 @@ pub fn cmd_summary(
             }
             println!();
         }
+
+        if by_owner && !projects.is_empty() {
+            println!("  {}", dim("By owner:"));
+            for (uid, (count, bytes)) in group_by_owner(&projects) {
+                let owner = match uid {
+                    Some(uid) => user_name(uid).unwrap_or_else(|| format!("uid {uid}")),
+                    None => "unknown".to_string(),
+                };
+                println!(
+                    "    {:>12}  {} projects, {}",
+                    owner,
+                    cyan(&count.to_string()),
+                    yellow_bold(&format_bytes(bytes)),
+                );
+            }
+            println!();
+        }
+
+        if by_filesystem && !projects.is_empty() {
+            println!("  {}", dim("By filesystem:"));
+            let filesystems = group_by_filesystem(&projects);
+            let labels: Vec<String> = filesystems
+                .iter()
+                .map(|(mount, _)| shorten_path(&mount.display().to_string()))
+                .collect();
+            let width = labels.iter().map(|l| l.len()).max().unwrap_or(0).max(12);
+            for ((mount, (count, bytes)), label) in filesystems.iter().zip(&labels) {
+                let free = available_space(mount)
+                    .map(|free| dim(&format!(" ({} free)", format_bytes(free))))
+                    .unwrap_or_default();
+                println!(
+                    "    {}  {} projects, {}{}",
+                    pad_left(label, width),
+                    cyan(&count.to_string()),
+                    yellow_bold(&format_bytes(*bytes)),
+                    free,
+                );
+            }
+            println!();
+        }
+
+        if let Some(depth) = tree_depth
+            && !projects.is_empty()
+        {
+            println!("  {}", dim("By directory:"));
+            let tree = build_path_tree(&common_ancestor(&args.paths), &projects);
+            for line in format_path_tree(&tree, depth) {
+                println!("  {line}");
+            }
+            println!();
+        }
+
+        if let Some(report) = &duplication {
+            println!("  {}", dim("Duplicated dependencies:"));
+            for line in format_duplication(report, DUPLICATION_LIMIT) {
+                println!("  {line}");
+            }
+            println!();
+        }
+    }
+
+    Ok(())
+}
+
+/// List the `count` biggest projects, one compact line each.
+pub fn cmd_top(args: &ScanArgs, count: usize, json: bool, config: &DevSweepConfig) -> Result<()> {
+    let mut projects = scan_projects(args, config)?;
+    sort_projects(&mut projects, SortKey::Size);
+    projects.truncate(count);
+
+    if json {
+        return print_json_list("projects", &projects, &args.paths);
+    }
+    if projects.is_empty() {
+        println!("  {} No projects with cleanable artifacts found.", blue("ℹ"));
+        return Ok(());
+    }
+    for line in format_top_projects(&projects) {
+        println!("{line}");
+    }
+    Ok(())
+}
+
+/// Rank the projects by reclaimable size per rebuild effort and list the best
+/// `count` candidates for cleaning.
+pub fn cmd_analyze(
+    args: &ScanArgs,
+    count: usize,
+    json: bool,
+    config: &DevSweepConfig,
+) -> Result<()> {
+    let projects = scan_projects(args, config)?;
+    let mut candidates = rank_by_effort(projects, &config.rebuild_costs, &config.rebuild_effort);
+    candidates.truncate(count);
+
+    if json {
+        return print_json_list("candidates", &candidates, &args.paths);
+    }
+    if candidates.is_empty() {
+        println!(
+            "  {} No projects with cleanable artifacts found.",
+            blue("ℹ")
+        );
+        return Ok(());
+    }
+    for line in format_effort_ranking(&candidates) {
+        println!("{line}");
+    }
+    Ok(())
+}
+
+/// Compare total reclaimable space with `thresholds` and print one status
+/// line (or a JSON report). Anything but OK is returned as a [`CheckAlert`]
+/// carrying the exit code; a failed scan is UNKNOWN.
+pub fn cmd_check(
+    args: &ScanArgs,
+    thresholds: CheckThresholds,
+    json: bool,
+    config: &DevSweepConfig,
+) -> Result<()> {
+    let projects = match scan_projects(args, config) {
+        Ok(projects) => projects,
+        Err(e) => {
+            let status = CheckStatus::Unknown;
+            if json {
+                let report = serde_json::json!({ "status": status, "error": format!("{e:#}") });
+                print_json(report, &args.paths)?;
+            } else {
+                println!("DEV-SWEEP {status} - {e:#}");
+            }
+            return Err(CheckAlert(status).into());
+        }
+    };
+
+    let report = CheckReport::new(&projects, thresholds);
+    if json {
+        print_json(&report, &args.paths)?;
+    } else {
+        println!("{}", report.status_line());
+    }
+    match report.status {
+        CheckStatus::Ok => Ok(()),
+        status => Err(CheckAlert(status).into()),
+    }
+}
+
+pub fn cmd_selftest(json: bool) -> Result<()> {
+    let root = std::env::temp_dir().join(format!("dev-sweep-selftest-{}", std::process::id()));
+    let checks = run_selftest(&root);
+    let failed = checks.iter().filter(|c| !c.passed).count();
+
+    if json {
+        let report: Vec<_> = checks
+            .iter()
+            .map(|c| serde_json::json!({ "check": c.name, "passed": c.passed, "detail": c.detail }))
+            .collect();
+        print_json_list("checks", &report, std::slice::from_ref(&root))?;
+    } else {
+        println!("\n  {} dev-sweep self-test\n", glyphs::plain("🧪"));
+        for check in &checks {
+            let mark = if check.passed { green("✓") } else { red_bold("✗") };
+            println!("    {} {} {}", mark, check.name, dim(&check.detail));
+        }
+        println!();
     }
 
+    if failed > 0 {
+        anyhow::bail!("{} of {} self-test checks failed", failed, checks.len());
+    }
+    if !json {
+        println!("  {} All {} checks passed.\n", green_bold("✓"), checks.len());
+    }
+    Ok(())
+}
+
+/// List every project kind with its markers and targets, as the config
+/// orders, renames, and excludes them. Nothing is scanned.
+pub fn cmd_kinds(json: bool, config: &DevSweepConfig) -> Result<()> {
+    let kinds = list_kinds(config);
+    if json {
+        return print_json_list("kinds", &kinds, &[]);
+    }
+    println!();
+    for line in format_kinds_table(&kinds) {
+        println!("{line}");
+    }
+    println!(
+        "\n  {} * only cleaned beside a marker of its own (--json lists which). \
+         Pass names from the first column to {}.\n",
+        dim("→"),
+        green("--kind")
+    );
+    Ok(())
+}
+
+/// Combine earlier `scan --json` and `summary --json` reports into one
+/// summary, without scanning anything.
+pub fn cmd_merge(files: &[PathBuf], totals: TotalFormat, json: bool) -> Result<()> {
+    let reports = files
+        .iter()
+        .map(|file| {
+            let contents = std::fs::read_to_string(file)
+                .with_context(|| format!("Failed to read {}", file.display()))?;
+            read_report(file, &contents)
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let merged = merge_reports(&reports)?;
+
+    if json {
+        let mut body = serde_json::to_value(&merged)?;
+        body["total_reclaimable_human"] = format_bytes(merged.total_reclaimable_bytes).into();
+        return print_json(body, &[]);
+    }
+
+    let count = merged.sources.len();
+    println!(
+        "\n  {} dev-sweep summary of {} report{}\n",
+        glyphs::plain("📊"),
+        cyan(&count.to_string()),
+        if count == 1 { "" } else { "s" }
+    );
+    if let (Some(first), Some(last)) = (merged.first_scanned_at, merged.last_scanned_at) {
+        println!(
+            "  Scanned:            {} to {}",
+            first.format("%Y-%m-%d %H:%M"),
+            last.format("%Y-%m-%d %H:%M")
+        );
+    }
+    if let Some(total) = format_total(merged.total_reclaimable_bytes, totals) {
+        println!(
+            "  Total projects:     {}",
+            cyan(&merged.total_projects.to_string())
+        );
+        println!("  Reclaimable space:  {}", yellow_bold(&total));
+        println!();
+    }
+
+    if !merged.by_kind.is_empty() {
+        println!("  {}", dim("By project type:"));
+        for group in &merged.by_kind {
+            println!(
+                "    {:>12}  {} projects, {}",
+                group.kind,
+                cyan(&group.projects.to_string()),
+                yellow_bold(&format_bytes(group.reclaimable_bytes)),
+            );
+        }
+        println!();
+    }
+
+    if !merged.by_host.is_empty() {
+        println!("  {}", dim("By host:"));
+        for group in &merged.by_host {
+            println!(
+                "    {:>12}  {} projects, {} {}",
+                group.host,
+                cyan(&group.projects.to_string()),
+                yellow_bold(&format_bytes(group.reclaimable_bytes)),
+                dim(&format!(
+                    "({} report{})",
+                    group.reports,
+                    if group.reports == 1 { "" } else { "s" }
+                )),
+            );
+        }
+        println!();
+    }
     Ok(())
 }
 
-pub fn cmd_config(show: bool, reset: bool) -> Result<()> {
+/// Show or reset the config. `config` and `source` are what this run loaded.
+pub fn cmd_config(
+    show: bool,
+    reset: bool,
+    print_path: bool,
+    edit: bool,
+    dry_run: bool,
+    snapshot: Option<SnapshotAction>,
+    layered: &LayeredConfig,
+) -> Result<()> {
+    let (config, source) = (&layered.config, &layered.source);
+    if print_path {
+        // Bare, so it can be used as `$EDITOR "$(dev-sweep config --path)"`.
+        println!("{}", DevSweepConfig::config_path().display());
+        return Ok(());
+    }
+    if let Some(action) = snapshot {
+        return config_snapshot(action, &DevSweepConfig::config_path());
+    }
+    if edit {
+        let editor = editor_command(|var| std::env::var_os(var));
+        return edit_config(&DevSweepConfig::config_path(), &editor);
+    }
+    if reset && dry_run {
+        return preview_config_reset();
+    }
     if reset {
         let config = DevSweepConfig::default();
         config.save()?;
@@ -220,14 +1400,20 @@ pub fn cmd_config(show: bool, reset: bool) -> Result<()> {
         return Ok(());
     }
 
+    let format = DevSweepConfig::active_format();
     if show {
-        let config = DevSweepConfig::load();
-        println!("{}", serde_json::to_string_pretty(&config)?);
+        println!("{}", format.render(config)?);
+        // On stderr, so what's on stdout stays a valid config document.
+        if !layered.origins.is_empty() {
+            for line in format_config_layers(&layered.files, &layered.origins) {
+                eprintln!("{line}");
+            }
+        }
         return Ok(());
     }
 
     let config_path = DevSweepConfig::config_path();
-    println!("\n  ⚙ dev-sweep configuration\n");
+    println!("\n  {} dev-sweep configuration\n", glyphs::plain("⚙"));
     println!("  Config file: {}", config_path.display());
     println!(
         "  Exists:      {}",
@@ -237,30 +1423,490 @@ pub fn cmd_config(show: bool, reset: bool) -> Result<()> {
             dim("no (using defaults)")
         }
     );
+    match source {
+        ConfigSource::Fallback { error, backup, .. } => {
+            println!("  Loaded from: {}", yellow(&source.to_string()));
+            println!("  Error:       {}", error);
+            if let Some(backup) = backup {
+                println!("  Backup:      {}", backup.display());
+            }
+        }
+        _ => println!("  Loaded from: {}", source),
+    }
+    for line in format_config_layers(&layered.files, &layered.origins) {
+        println!("{line}");
+    }
 
-    let config = DevSweepConfig::load();
-    println!("\n{}", serde_json::to_string_pretty(&config)?);
+    println!("\n{}", format.render(config)?);
     println!(
-        "\n  {} Use {} or {} to manage.\n",
+        "\n  {} Use {}, {}, {}, or {} to manage.\n",
         dim("→"),
         green("--show"),
+        green("--edit"),
+        green("--path"),
         green("--reset")
     );
 
     Ok(())
 }
 
+/// Save, restore, or list snapshots of the config file at `config_path`
+/// (`config --snapshot`, `--restore`, `--list-snapshots`).
+fn config_snapshot(action: SnapshotAction, config_path: &Path) -> Result<()> {
+    match action {
+        SnapshotAction::Save(name) => {
+            let path = save_snapshot(config_path, &name)?;
+            println!("  {} Saved config snapshot '{}'.", green("✓"), name);
+            println!("  {} {}", dim("→"), path.display());
+        }
+        SnapshotAction::Restore(name) => {
+            let snapshot = restore_snapshot(config_path, &name)?;
+            println!(
+                "  {} Restored config snapshot '{}' (taken {}).",
+                green("✓"),
+                name,
+                snapshot.taken.format("%Y-%m-%d %H:%M")
+            );
+            println!("  {} {}", dim("→"), config_path.display());
+        }
+        SnapshotAction::List => {
+            let snapshots = list_snapshots(config_path);
+            if snapshots.is_empty() {
+                println!(
+                    "  {} No config snapshots yet. Save one with {}.",
+                    blue("ℹ"),
+                    green("config --snapshot <NAME>")
+                );
+                return Ok(());
+            }
+            println!("\n  Config snapshots (oldest first):");
+            for line in format_config_snapshots(&snapshots, Local::now()) {
+                println!("{line}");
+            }
+            println!();
+        }
+    }
+    Ok(())
+}
+
+/// Show what `config --reset` would change in the config file, without writing.
+///
+/// The diff is between the file as saved (environment overrides aren't part of
+/// it) and the defaults, both rendered in the file's format.
+fn preview_config_reset() -> Result<()> {
+    let path = DevSweepConfig::config_path();
+    let format = DevSweepConfig::active_format();
+    let (current, source) = DevSweepConfig::peek_from(&path);
+    let current = match source {
+        ConfigSource::Fallback { error, .. } => {
+            println!(
+                "  {} {} can't be parsed ({}); a reset would replace it with:\n",
+                yellow("⚠"),
+                path.display(),
+                error
+            );
+            std::fs::read_to_string(&path).unwrap_or_default()
+        }
+        _ => format.render(&current)?,
+    };
+    let defaults = format.render(&DevSweepConfig::default())?;
+
+    let changes = diff_lines(&current, &defaults);
+    if changes.iter().all(|c| matches!(c, LineChange::Same(_))) {
+        println!(
+            "  {} {} already matches the defaults; a reset would change nothing.",
+            green("✓"),
+            path.display()
+        );
+        return Ok(());
+    }
+
+    println!("\n  {} {}\n", bold("Reset would change"), path.display());
+    for line in format_line_diff(&changes) {
+        println!("{line}");
+    }
+    println!(
+        "\n  {} Dry run, nothing written. Run {} to apply.\n",
+        dim("→"),
+        green("config --reset")
+    );
+    Ok(())
+}
+
 // ── Helpers ─────────────────────────────────────────────────────────────────
 
-fn sort_by_size(projects: &mut [ScannedProject]) {
-    projects.sort_unstable_by_key(|p| std::cmp::Reverse(p.total_cleanable_bytes));
+/// The scan roots for messages, comma-separated.
+fn display_roots(paths: &[PathBuf]) -> String {
+    paths
+        .iter()
+        .map(|p| p.display().to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
 }
 
-fn filter_by_age(projects: &mut Vec<ScannedProject>, older_than: Option<&str>) -> Result<()> {
-    if let Some(age_str) = older_than {
-        let duration = parse_age(age_str)?;
-        let cutoff = chrono::Local::now() - duration;
-        projects.retain(|p| p.last_modified < cutoff);
-    }
+/// Top-level fields stamped on every JSON report, so reports collected from
+/// many machines can be told apart.
+#[derive(Serialize)]
+struct JsonReport<'a, T: Serialize> {
+    /// Format version, [`JSON_SCHEMA_VERSION`], which `merge` checks.
+    schema_version: u32,
+    /// The machine's host name (null if it can't be determined).
+    host: Option<String>,
+    /// When the report was produced (RFC 3339).
+    scanned_at: DateTime<Local>,
+    /// The directory holding every scan root (the root itself if there's one).
+    scan_root: PathBuf,
+    scan_roots: &'a [PathBuf],
+    #[serde(flatten)]
+    body: T,
+}
+
+/// Set by `--json-compact`: JSON reports are printed on one line.
+static COMPACT_JSON: AtomicBool = AtomicBool::new(false);
+
+/// Print every JSON report on one line, without whitespace, instead of
+/// indented (`--json-compact`).
+pub fn set_compact_json(compact: bool) {
+    COMPACT_JSON.store(compact, Ordering::Relaxed);
+}
+
+/// Print a JSON report object with the [`JsonReport`] fields added to it.
+fn print_json(body: impl Serialize, roots: &[PathBuf]) -> Result<()> {
+    println!("{}", json_report(body, roots)?);
+    Ok(())
+}
+
+/// Like [`print_json`], but to stderr, for a second report that would leave
+/// stdout no longer parseable as one JSON document.
+fn eprint_json(body: impl Serialize, roots: &[PathBuf]) -> Result<()> {
+    eprintln!("{}", json_report(body, roots)?);
     Ok(())
 }
+
+fn json_report(body: impl Serialize, roots: &[PathBuf]) -> Result<String> {
+    let report = JsonReport {
+        schema_version: JSON_SCHEMA_VERSION,
+        host: hostname(),
+        scanned_at: Local::now(),
+        scan_root: common_ancestor(roots),
+        scan_roots: roots,
+        body,
+    };
+    let compact = COMPACT_JSON.load(Ordering::Relaxed);
+    Ok(to_json(&report, compact)?)
+}
+
+/// Print a list as a [`print_json`] report, with the list under `key`.
+fn print_json_list(key: &str, items: &impl Serialize, roots: &[PathBuf]) -> Result<()> {
+    print_json(BTreeMap::from([(key, items)]), roots)
+}
+
+/// Show what is about to be removed: capped before a real clean, in full for a dry run.
+fn print_plan(selected: &[&ScannedProject], dry_run: bool) {
+    let limit = (!dry_run).then_some(DELETION_PLAN_LIMIT);
+    print_deletion_plan(selected, limit, dry_run);
+}
+
+/// Ask before cleaning, printing "Aborted" if declined. With `--format events`
+/// or JSON output the prompt goes to stderr, leaving stdout to the report.
+fn confirm_clean(
+    prompt: &str,
+    bytes: u64,
+    policy: ConfirmPolicy,
+    format: CleanFormat,
+) -> Result<bool> {
+    if !policy.asks_for(bytes) {
+        if let ConfirmPolicy::Above(threshold) = policy {
+            let note = format!(
+                "  {} {} is under confirm_threshold ({}), not asking.",
+                blue("ℹ"),
+                format_bytes(bytes),
+                format_bytes(threshold),
+            );
+            if format != CleanFormat::Text {
+                eprintln!("{note}");
+            } else {
+                println!("{note}");
+            }
+        }
+        return Ok(true);
+    }
+    if format != CleanFormat::Text {
+        let confirmed = confirm_stderr(prompt)?;
+        if !confirmed {
+            eprintln!("  {} Aborted.\n", red_bold("✗"));
+        }
+        return Ok(confirmed);
+    }
+    let confirmed = confirm(prompt)?;
+    if !confirmed {
+        println!("  {} Aborted.\n", red_bold("✗"));
+    }
+    Ok(confirmed)
+}
+
+/// Ask for `phrase` to be typed before a clean (`--confirm-phrase`), printing
+/// "Aborted" if it isn't. Unlike [`confirm_clean`], `confirm_threshold`
+/// doesn't apply: the phrase is asked for whatever the size.
+fn confirm_clean_phrase(prompt: &str, phrase: &str, format: CleanFormat) -> Result<bool> {
+    if format != CleanFormat::Text {
+        let confirmed = confirm_phrase_stderr(prompt, phrase)?;
+        if !confirmed {
+            eprintln!("  {} Aborted.\n", red_bold("✗"));
+        }
+        return Ok(confirmed);
+    }
+    let confirmed = confirm_phrase(prompt, phrase)?;
+    if !confirmed {
+        println!("  {} Aborted.\n", red_bold("✗"));
+    }
+    Ok(confirmed)
+}
+
+/// Clean `projects`, streaming a [`CleanEvent`] per line as each one finishes
+/// when `format` is [`CleanFormat::Events`]. Text output to a terminal gets a
+/// live status line of the `unit`s done and space freed. Ctrl-C meanwhile
+/// stops the clean before the next target instead of killing the process.
+fn clean_reporting(
+    projects: &[&ScannedProject],
+    options: &CleanOptions,
+    format: CleanFormat,
+    unit: &str,
+) -> Vec<CleanResult> {
+    let _interrupt = InterruptGuard::install();
+    if format == CleanFormat::Text && io::stderr().is_terminal() {
+        return clean_with_status_line(projects, options, unit);
+    }
+    if format != CleanFormat::Events {
+        return clean_projects_with(projects, options);
+    }
+    emit_event(&CleanEvent::start(projects, options.dry_run));
+    let results = clean_projects_with_progress(projects, options, |project, result| {
+        for event in CleanEvent::for_result(project, result) {
+            emit_event(&event);
+        }
+    });
+    emit_event(&CleanEvent::done(
+        &results,
+        options.interrupted(),
+        options.ran_out_of_time(&results, projects.len()),
+    ));
+    results
+}
+
+/// Clean with a single status line on stderr, redrawn after each project with
+/// the space freed so far and cleared before the summary is printed.
+fn clean_with_status_line(
+    projects: &[&ScannedProject],
+    options: &CleanOptions,
+    unit: &str,
+) -> Vec<CleanResult> {
+    let (mut done, mut freed) = (0, 0);
+    let results = clean_projects_with_progress(projects, options, |_, result| {
+        done += 1;
+        freed += result.bytes_freed;
+        let line = format_clean_progress(done, projects.len(), unit, freed, options.dry_run);
+        eprint!("\r\x1b[2K{line}");
+        let _ = io::stderr().flush();
+    });
+    eprint!("\r\x1b[2K");
+    let _ = io::stderr().flush();
+    results
+}
+
+/// Print one event as a line of JSON.
+fn emit_event(event: &CleanEvent) {
+    if let Ok(line) = serde_json::to_string(event) {
+        println!("{line}");
+    }
+}
+
+/// Append what was cleaned to the clean log. Failing to log never fails the clean.
+fn record_cleans(projects: &[&ScannedProject], results: &[CleanResult]) {
+    let path = clean_log_path();
+    if let Err(e) = cleanlog::append(&path, &entries_for(projects, results)) {
+        eprintln!("  {} Could not update clean log: {:#}", yellow("⚠"), e);
+    }
+    let quarantined: Vec<QuarantineEntry> =
+        results.iter().flat_map(|r| r.quarantined.clone()).collect();
+    if let Err(e) = quarantine::append_index(&quarantine_index_path(), &quarantined) {
+        eprintln!(
+            "  {} Could not update quarantine index: {:#}",
+            yellow("⚠"),
+            e
+        );
+    }
+}
+
+/// The `large_file_fraction` from config, or the default.
+fn large_file_fraction(config: &DevSweepConfig) -> f64 {
+    config
+        .large_file_fraction
+        .unwrap_or(DEFAULT_LARGE_FILE_FRACTION)
+}
+
+/// Scan every path in `args.paths` and apply every result filter in `args`.
+fn scan_projects(args: &ScanArgs, config: &DevSweepConfig) -> Result<Vec<ScannedProject>> {
+    // Validate filters before walking so mistakes fail fast.
+    let kinds = parse_kinds(&args.kinds, args.options.strict)?;
+    let regex = args.regex.as_deref().map(build_regex).transpose()?;
+
+    let mut projects = match args.paths.split_first() {
+        Some((first, rest)) => {
+            let mut projects = scan_directory_with(first, &args.options, config)?;
+            if !rest.is_empty() {
+                // Global caches, relocated build output, and the temp
+                // directory don't live under any root; report them once.
+                let options = ScanOptions {
+                    global_caches: Vec::new(),
+                    artifact_dirs: Vec::new(),
+                    temp_older_than: None,
+                    ..args.options.clone()
+                };
+                for root in rest {
+                    projects.extend(scan_directory_with(root, &options, config)?);
+                }
+            }
+            projects
+        }
+        None => Vec::new(),
+    };
+    let detected = args.explain_filters.then(|| projects.clone());
+    // Filters that rewrite projects rather than testing them, each with the
+    // projects still standing after it (for --explain-filters).
+    let mut rewrites: Vec<(String, HashSet<PathBuf>)> = Vec::new();
+    let standing = |projects: &[ScannedProject]| projects.iter().map(|p| p.path.clone()).collect();
+    if !args.exclude_targets.is_empty() {
+        exclude_targets(&mut projects, &args.exclude_targets, args.options.size_mode);
+        let label = format!("exclude-target {}", args.exclude_targets.join(","));
+        rewrites.push((label, standing(&projects)));
+    }
+    if let Some(ref age) = args.target_older_than {
+        drop_recent_targets(&mut projects, age_cutoff(age)?);
+        rewrites.push((format!("target-older-than {age}"), standing(&projects)));
+    }
+    if args.safe {
+        protect_unsafe_targets(&mut projects);
+    }
+    // After safe mode, which would otherwise clear the protection again.
+    let kept = protect_last_builds(
+        &mut projects,
+        &config.keep_last_build,
+        args.options.size_mode,
+    )?;
+    if kept > 0 && args.verbose {
+        eprintln!(
+            "  {} Keeping {} last build{} (keep_last_build); listed as protected.",
+            blue("ℹ"),
+            kept,
+            if kept == 1 { "" } else { "s" }
+        );
+    }
+    if let Some(max_bytes) = args.options.skip_files_over {
+        let kept = projects
+            .iter()
+            .flat_map(|p| &p.clean_targets)
+            .filter(|t| t.large_file)
+            .count();
+        if kept > 0 && args.verbose {
+            eprintln!(
+                "  {} Keeping {} file{} over {} (--skip-files-over).",
+                blue("ℹ"),
+                kept,
+                if kept == 1 { "" } else { "s" },
+                format_bytes(max_bytes)
+            );
+        }
+    }
+
+    let last_cleaned = args
+        .since_clean
+        .then(|| last_cleaned(&cleanlog::read(&clean_log_path())));
+    // Ranked among every detected project, so with --older-than the newest
+    // projects count even though the age filter already drops them.
+    let recent = args.keep_recent.map(|n| most_recent_per_kind(&projects, n));
+    let mut filters = Vec::new();
+    if let Some(ref older_than) = args.older_than {
+        let cutoff = age_cutoff(older_than)?;
+        let label = format!("older-than {older_than}");
+        filters.push(ProjectFilter::new(label, move |p| is_older_than(p, cutoff)));
+    }
+    if let Some(min) = args.min_size {
+        let label = format!("min-size {}", format_bytes(min));
+        filters.push(ProjectFilter::new(label, move |p| p.total_cleanable_bytes >= min));
+    }
+    if !args.kinds.is_empty() {
+        let label = format!("kind {}", args.kinds.join(","));
+        filters.push(ProjectFilter::new(label, |p| {
+            p.kinds().any(|k| kinds.contains(&k))
+        }));
+    }
+    if let Some(ref needle) = args.filter {
+        let label = format!("filter {needle:?}");
+        filters.push(ProjectFilter::new(label, |p| matches_substring(p, needle)));
+    }
+    if let (Some(pattern), Some(re)) = (&args.regex, &regex) {
+        let label = format!("regex {pattern:?}");
+        filters.push(ProjectFilter::new(label, |p| matches_regex(p, re)));
+    }
+    if let Some(ref last_cleaned) = last_cleaned {
+        filters.push(ProjectFilter::new("since-clean", |p| {
+            regrown_since_clean(p, last_cleaned)
+        }));
+    }
+    if let (Some(n), Some(recent)) = (args.keep_recent, &recent) {
+        let label = format!("keep-recent {n}");
+        filters.push(ProjectFilter::new(label, |p| !recent.contains(&p.path)));
+    }
+
+    if let Some(detected) = detected {
+        let mut decisions = decide_filters(&detected, &filters);
+        decisions.sort_by(|a, b| a.path.cmp(&b.path));
+        // A rewriting filter's verdict is whether the project still had a
+        // target left afterwards.
+        for (i, (label, remaining)) in rewrites.iter().enumerate() {
+            for decision in &mut decisions {
+                decision.checks.insert(
+                    i,
+                    FilterCheck {
+                        filter: label.clone(),
+                        passed: remaining.contains(&decision.path),
+                    },
+                );
+            }
+        }
+        print_filter_decisions(&decisions);
+    }
+
+    if let (Some(n), Some((keep_recent, others))) = (args.keep_recent, filters.split_last()) {
+        // Only projects every other filter kept were actually spared by the rule.
+        let preserved: Vec<&ScannedProject> = projects
+            .iter()
+            .filter(|p| !(keep_recent.accepts)(p) && others.iter().all(|f| (f.accepts)(p)))
+            .collect();
+        print_preserved_recent(&preserved, n);
+    }
+
+    apply_filters(&mut projects, &filters);
+    apply_kind_aliases(&mut projects, &config.kind_aliases);
+    if args.estimate_rebuild_cost {
+        apply_rebuild_costs(&mut projects, &config.rebuild_costs);
+    }
+    Ok(projects)
+}
+
+/// Parse user-supplied kind names.
+///
+/// Unknown names are dropped with a warning, so they match nothing; in strict
+/// mode they are an error instead, so a typo can't silently produce an empty result.
+fn parse_kinds(names: &[String], strict: bool) -> Result<Vec<ProjectKind>> {
+    let mut kinds = Vec::new();
+    for name in names {
+        match name.parse::<ProjectKind>() {
+            Ok(kind) => kinds.push(kind),
+            Err(e) if strict => return Err(e),
+            Err(e) => eprintln!("  {} {}", yellow("⚠"), e),
+        }
+    }
+    Ok(kinds)
+}
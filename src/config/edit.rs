@@ -0,0 +1,40 @@
+use std::ffi::OsString;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+use super::DevSweepConfig;
+use crate::cleaner::hook::{shell, shell_quote};
+use crate::readonly::ensure_writable;
+
+/// The editor `config --edit` opens: `$VISUAL`, then `$EDITOR`, looked up
+/// through `lookup`, falling back to `vi` (`notepad` on Windows). Empty
+/// variables are skipped.
+pub fn editor_command(lookup: impl Fn(&str) -> Option<OsString>) -> String {
+    ["VISUAL", "EDITOR"]
+        .into_iter()
+        .filter_map(lookup)
+        .map(|value| value.to_string_lossy().trim().to_string())
+        .find(|value| !value.is_empty())
+        .unwrap_or_else(|| if cfg!(windows) { "notepad" } else { "vi" }.to_string())
+}
+
+/// Open the config at `path` in `editor`, first writing the defaults there if
+/// it doesn't exist yet.
+///
+/// `editor` goes through the shell, so it may carry arguments (`code --wait`).
+/// Fails if the editor can't be started or exits unsuccessfully.
+pub fn edit_config(path: &Path, editor: &str) -> Result<()> {
+    ensure_writable("edit the config")?;
+    if !path.exists() {
+        DevSweepConfig::default().save_to(path)?;
+    }
+    let command = format!("{} {}", editor, shell_quote(&path.display().to_string()));
+    let status = shell(&command)
+        .status()
+        .with_context(|| format!("Failed to start editor `{editor}`"))?;
+    if !status.success() {
+        anyhow::bail!("Editor `{editor}` failed ({status})");
+    }
+    Ok(())
+}
@@ -0,0 +1,217 @@
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use serde_json::{Map, Value};
+
+use super::{ConfigFormat, ConfigSource, DevSweepConfig, read_table};
+
+/// Names a project config file may have, checked in this order in each directory.
+pub const PROJECT_CONFIG_NAMES: [&str; 4] = [
+    ".dev-sweep.json",
+    ".dev-sweep.toml",
+    ".dev-sweep.yaml",
+    ".dev-sweep.yml",
+];
+
+/// The only settings a project config can change: ones that narrow what's
+/// scanned or cleaned, or only affect how results are shown and ordered.
+/// Anything else could run commands, send cleaning outside the project, or
+/// weaken the user's safeguards (`safe_mode`, the confirmation settings),
+/// which a cloned repository shouldn't be able to do.
+pub const PROJECT_CONFIG_ALLOWED: [&str; 18] = [
+    "ignore_paths",
+    "exclude_kinds",
+    "detection_priority",
+    "exclude_targets",
+    "max_depth",
+    "min_depth",
+    "older_than",
+    "exclude_under_size",
+    "one_file_system",
+    "clean_order",
+    "large_file_fraction",
+    "size_mode",
+    "parallel_min_files",
+    "parallel_min_bytes",
+    "kind_aliases",
+    "rebuild_costs",
+    "rebuild_effort",
+    "keep_last_build",
+];
+
+/// Where a setting came from, from lowest to highest priority.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ConfigLayer {
+    /// The machine-wide file, e.g. `/etc/dev-sweep/config.json`.
+    System,
+    /// The user's file in `~/.config/dev-sweep/`.
+    User,
+    /// The nearest `.dev-sweep.*` file in the current directory or above.
+    Project,
+    /// A `DEV_SWEEP_*` environment variable.
+    Environment,
+}
+
+impl fmt::Display for ConfigLayer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::System => "system",
+            Self::User => "user",
+            Self::Project => "project",
+            Self::Environment => "environment",
+        };
+        f.write_str(name)
+    }
+}
+
+/// The config merged from every layer, remembering where each setting came from.
+#[derive(Debug, Clone)]
+pub struct LayeredConfig {
+    /// The merged config.
+    pub config: DevSweepConfig,
+    /// How the user's config file was loaded.
+    pub source: ConfigSource,
+    /// The file read for each layer that has one, lowest priority first.
+    pub files: Vec<(ConfigLayer, PathBuf)>,
+    /// The layer each setting came from. Settings left at their defaults are absent.
+    pub origins: BTreeMap<String, ConfigLayer>,
+    /// Files and settings that were skipped, one description each.
+    pub warnings: Vec<String>,
+}
+
+impl LayeredConfig {
+    /// Merge the config files at `system`, `user`, and `project`: each setting
+    /// takes its value from the highest-priority file that sets it, and files
+    /// that don't exist are skipped.
+    ///
+    /// A broken user file is handled as by [`DevSweepConfig::read_from`]. A
+    /// broken system or project file is skipped with a warning, and so are
+    /// settings in a project file that aren't in [`PROJECT_CONFIG_ALLOWED`].
+    pub fn merge(system: Option<&Path>, user: &Path, project: Option<&Path>) -> Self {
+        let mut merged = Map::new();
+        let mut layered = LayeredConfig {
+            config: DevSweepConfig::default(),
+            source: ConfigSource::Defaults,
+            files: Vec::new(),
+            origins: BTreeMap::new(),
+            warnings: Vec::new(),
+        };
+
+        if let Some(path) = system {
+            layered.read_layer(ConfigLayer::System, path, &mut merged);
+        }
+
+        let (table, source) = read_table(user, true);
+        if let ConfigSource::File(ref path) = source {
+            layered.add(ConfigLayer::User, path, table, &mut merged);
+        }
+        layered.source = source;
+
+        if let Some(path) = project {
+            layered.read_layer(ConfigLayer::Project, path, &mut merged);
+        }
+
+        // Every layer parsed on its own, so together they do too.
+        layered.config = serde_json::from_value(Value::Object(merged)).unwrap_or_default();
+        layered
+    }
+
+    /// Apply `DEV_SWEEP_*` overrides looked up through `lookup` (see
+    /// [`DevSweepConfig::apply_env`]), crediting the settings they change to
+    /// the environment.
+    pub fn apply_env(&mut self, lookup: impl Fn(&str) -> Option<OsString>) {
+        let before = serde_json::to_value(&self.config).unwrap_or_default();
+        self.warnings.extend(self.config.apply_env(lookup));
+        let after = serde_json::to_value(&self.config).unwrap_or_default();
+        if let Some(after) = after.as_object() {
+            for (key, value) in after {
+                if before.get(key) != Some(value) {
+                    self.origins.insert(key.clone(), ConfigLayer::Environment);
+                }
+            }
+        }
+    }
+
+    /// Read the system or project file at `path` into `merged`, if it exists.
+    fn read_layer(&mut self, layer: ConfigLayer, path: &Path, merged: &mut Map<String, Value>) {
+        if !path.is_file() {
+            return;
+        }
+        let format = ConfigFormat::from_path(path).unwrap_or(ConfigFormat::Json);
+        let parsed = std::fs::read_to_string(path)
+            .map_err(anyhow::Error::from)
+            .and_then(|contents| format.parse_table(&contents));
+        match parsed {
+            Ok(mut table) => {
+                if layer == ConfigLayer::Project {
+                    // Settings dev-sweep doesn't know are ignored anyway.
+                    let known = serde_json::to_value(DevSweepConfig::default()).unwrap_or_default();
+                    let denied: Vec<String> = table
+                        .keys()
+                        .filter(|key| {
+                            known.get(key.as_str()).is_some()
+                                && !PROJECT_CONFIG_ALLOWED.contains(&key.as_str())
+                        })
+                        .cloned()
+                        .collect();
+                    for key in denied {
+                        table.remove(&key);
+                        self.warnings.push(format!(
+                            "Ignoring {} in {}: only the system and user configs can set it",
+                            key,
+                            path.display()
+                        ));
+                    }
+                }
+                self.add(layer, path, table, merged);
+            }
+            Err(e) => self.warnings.push(format!(
+                "Ignoring {} config {}: {:#}",
+                layer,
+                path.display(),
+                e
+            )),
+        }
+    }
+
+    /// Lay `table` over `merged`, crediting its known settings to `layer`.
+    fn add(
+        &mut self,
+        layer: ConfigLayer,
+        path: &Path,
+        table: Map<String, Value>,
+        merged: &mut Map<String, Value>,
+    ) {
+        let known = serde_json::to_value(DevSweepConfig::default()).unwrap_or_default();
+        for (key, value) in table {
+            if known.get(&key).is_some() {
+                self.origins.insert(key.clone(), layer);
+            }
+            merged.insert(key, value);
+        }
+        self.files.push((layer, path.to_path_buf()));
+    }
+}
+
+/// The nearest project config: the first of [`PROJECT_CONFIG_NAMES`] found in
+/// `start` or the closest of its ancestors.
+pub fn find_project_config(start: &Path) -> Option<PathBuf> {
+    start.ancestors().find_map(|dir| {
+        PROJECT_CONFIG_NAMES
+            .iter()
+            .map(|name| dir.join(name))
+            .find(|path| path.is_file())
+    })
+}
+
+/// Directory holding the machine-wide config: `/etc/dev-sweep`, or
+/// `%ProgramData%\dev-sweep` on Windows.
+pub fn system_config_dir() -> Option<PathBuf> {
+    if cfg!(windows) {
+        std::env::var_os("ProgramData").map(|dir| PathBuf::from(dir).join("dev-sweep"))
+    } else {
+        Some(PathBuf::from("/etc/dev-sweep"))
+    }
+}
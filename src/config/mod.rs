@@ -1,8 +1,28 @@
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
 
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 
-use crate::scanner::ProjectKind;
+use crate::filter::CleanOrder;
+use crate::readonly::ensure_writable;
+use crate::scanner::{ProjectKind, RebuildCost, SizeMode};
+use crate::tui::colors::yellow;
+use crate::util::{parse_age, parse_size};
+
+mod edit;
+mod layers;
+mod snapshot;
+
+pub use edit::{edit_config, editor_command};
+pub use layers::{
+    ConfigLayer, LayeredConfig, PROJECT_CONFIG_ALLOWED, PROJECT_CONFIG_NAMES, find_project_config,
+    system_config_dir,
+};
+pub use snapshot::{
+    ConfigSnapshot, SnapshotAction, list_snapshots, restore_snapshot, save_snapshot, snapshots_dir,
+};
 
 /// Persistent configuration for dev-sweep.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -15,6 +35,18 @@ pub struct DevSweepConfig {
     #[serde(default)]
     pub exclude_kinds: Vec<ProjectKind>,
 
+    /// Kinds to prefer when a directory matches several (e.g. `["Node", "Rust"]`
+    /// for a package with a native addon). The first match becomes the
+    /// project's kind and the rest are listed alongside it; unlisted kinds
+    /// follow in the built-in order.
+    #[serde(default)]
+    pub detection_priority: Vec<ProjectKind>,
+
+    /// Clean targets never to offer, as globs matched against target names
+    /// (see `--exclude-target`).
+    #[serde(default)]
+    pub exclude_targets: Vec<String>,
+
     /// Default scan roots.
     #[serde(default)]
     pub default_roots: Vec<PathBuf>,
@@ -22,38 +54,612 @@ pub struct DevSweepConfig {
     /// Maximum directory depth to scan.
     #[serde(default)]
     pub max_depth: Option<usize>,
+
+    /// Depth at which project detection starts (see `--min-depth`).
+    #[serde(default)]
+    pub min_depth: Option<usize>,
+
+    /// Machine-wide caches to always include (e.g. `Julia`). These are shared
+    /// by every project, so they are never scanned unless listed here or
+    /// requested with `--global-cache`.
+    #[serde(default)]
+    pub global_caches: Vec<ProjectKind>,
+
+    /// Default `--older-than` age (e.g. `"30d"`).
+    #[serde(default)]
+    pub older_than: Option<String>,
+
+    /// How long nothing in an entry of the OS temp directory must have changed
+    /// before `--include-temp` reports it (e.g. `"14d"`; default `"7d"`).
+    #[serde(default)]
+    pub temp_older_than: Option<String>,
+
+    /// Default `--min-size` (e.g. `"50MB"`): projects with less to reclaim are
+    /// left out unless `--min-size` says otherwise.
+    #[serde(default)]
+    pub exclude_under_size: Option<String>,
+
+    /// Never cross into other filesystems while scanning (see `--one-file-system`).
+    #[serde(default)]
+    pub one_file_system: bool,
+
+    /// Also offer regenerable IDE caches (`.vs`, `.idea/caches`, `.vscode/ipch`)
+    /// for cleaning (see `--ide-caches`). Off by default.
+    #[serde(default)]
+    pub ide_caches: bool,
+
+    /// Only ever remove well-known artifact directories (see `--safe`); other
+    /// targets are still listed, marked as protected.
+    #[serde(default)]
+    pub safe_mode: bool,
+
+    /// Only ask before a clean that frees more than this (e.g. `"1GB"`); smaller
+    /// cleans go ahead without a prompt. Unset means always ask.
+    #[serde(default)]
+    pub confirm_threshold: Option<String>,
+
+    /// Make `clean --all` ask for the size being freed to be typed out, rather
+    /// than a yes or no, when it frees more than this (e.g. `"20GB"`), as with
+    /// `--confirm-phrase`.
+    #[serde(default)]
+    pub confirm_phrase_above: Option<String>,
+
+    /// Give up on a prompt (a confirmation or the project picker) after this
+    /// many seconds without an answer, aborting the operation, so a forgotten
+    /// interactive run doesn't hang forever. Unset or `0` waits indefinitely.
+    #[serde(default)]
+    pub confirm_timeout_secs: Option<u64>,
+
+    /// Kinds that `clean --auto` removes without asking (e.g. `Node`, `Rust`).
+    /// Without `--auto` this has no effect.
+    #[serde(default)]
+    pub auto_clean_kinds: Vec<ProjectKind>,
+
+    /// Order selected projects are cleaned in: `"size"` (the default), `"age"`,
+    /// or a kind priority list such as `"Node,Rust"` (see `--clean-order`).
+    #[serde(default)]
+    pub clean_order: Option<String>,
+
+    /// Shell command to run in each project before cleaning it, with `{path}`
+    /// replaced by the project path (e.g. a project's own clean script). If it
+    /// fails, that project is left alone.
+    #[serde(default)]
+    pub pre_clean_cmd: Option<String>,
+
+    /// Shell command to run in each project after cleaning it, with `{path}`
+    /// substituted. A failure is reported as a warning.
+    #[serde(default)]
+    pub post_clean_cmd: Option<String>,
+
+    /// In verbose output, warn when one file is more than this fraction of its
+    /// project's reclaimable size (default 0.5).
+    #[serde(default)]
+    pub large_file_fraction: Option<f64>,
+
+    /// Whether sizes are disk usage (`"disk-usage"`, the default) or apparent
+    /// sizes (`"apparent"`), like `du` and `du --apparent-size`.
+    #[serde(default)]
+    pub size_mode: Option<SizeMode>,
+
+    /// Size a clean target across threads once it has at least this many files
+    /// and directories (default 10000). Smaller targets are sized on one thread.
+    #[serde(default)]
+    pub parallel_min_files: Option<u64>,
+
+    /// Size a clean target across threads once it holds at least this many
+    /// bytes (default 1 GiB), however few files it has.
+    #[serde(default)]
+    pub parallel_min_bytes: Option<u64>,
+
+    /// Reporting names for detected kinds (e.g. `Java` → `"JVM"`), so several
+    /// detectors can roll up under one name in tables and summaries. JSON output
+    /// keeps the detected kind alongside the alias.
+    #[serde(default)]
+    pub kind_aliases: BTreeMap<ProjectKind, String>,
+
+    /// Rebuild cost labels (`cheap`, `moderate`, `expensive`) replacing the
+    /// built-in ones for some kinds, shown with `--estimate-rebuild-cost`.
+    #[serde(default)]
+    pub rebuild_costs: BTreeMap<ProjectKind, RebuildCost>,
+
+    /// How much effort rebuilding each cost is worth when `analyze` ranks
+    /// projects (default: cheap 1, moderate 3, expensive 10). Projects score
+    /// their reclaimable megabytes divided by this.
+    #[serde(default)]
+    pub rebuild_effort: BTreeMap<RebuildCost, f64>,
+
+    /// Where a tool's build output goes when it's redirected out of the
+    /// projects (e.g. `Rust` → a central cargo target dir). Takes precedence
+    /// over `CARGO_TARGET_DIR`; each directory is reported once.
+    #[serde(default)]
+    pub artifact_dirs: BTreeMap<ProjectKind, PathBuf>,
+
+    /// Release output to keep when cleaning, for the kinds of projects deployed
+    /// from locally (e.g. `Rust` → keep `target/release`). No kind has a rule
+    /// by default.
+    #[serde(default)]
+    pub keep_last_build: BTreeMap<ProjectKind, KeepLastBuild>,
+
+    /// Where `clean --quarantine` moves targets (default: `.dev-sweep-quarantine`
+    /// under the scan root). Must be on the same filesystem as the projects.
+    #[serde(default)]
+    pub quarantine_dir: Option<PathBuf>,
+
+    /// Subcommand to run when none is given on the command line (default `scan`).
+    #[serde(default)]
+    pub default_command: Option<DefaultCommand>,
+}
+
+/// A subcommand that can be run by default, named as on the command line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum DefaultCommand {
+    Scan,
+    Clean,
+    Summary,
+    Top,
+    Selftest,
+    Config,
+}
+
+impl DefaultCommand {
+    /// The subcommand's name on the command line.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Scan => "scan",
+            Self::Clean => "clean",
+            Self::Summary => "summary",
+            Self::Top => "top",
+            Self::Selftest => "selftest",
+            Self::Config => "config",
+        }
+    }
+}
+
+/// A kind's "keep last successful build" rule (`keep_last_build`).
+///
+/// Of the `dirs` a project has, any holding a [`LAST_GOOD_MARKER`] file is
+/// kept, and so is the most recently built one if that was within `within`.
+/// Kept directories are still listed, marked as protected.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeepLastBuild {
+    /// Build output directories, relative to the project root (e.g.
+    /// `"target/release"`). Empty means the kind's usual release output
+    /// ([`ProjectKind::release_dirs`]).
+    #[serde(default)]
+    pub dirs: Vec<String>,
+    /// Keep the newest build if its newest file is younger than this age
+    /// (e.g. `"14d"`). Unset means only marked builds are kept.
+    #[serde(default)]
+    pub within: Option<String>,
+}
+
+/// A file marking a build directory as the last good build, for
+/// `keep_last_build` to keep however old it is.
+pub const LAST_GOOD_MARKER: &str = ".last-good";
+
+/// Default for [`DevSweepConfig::large_file_fraction`].
+pub const DEFAULT_LARGE_FILE_FRACTION: f64 = 0.5;
+
+/// Read the config file at `path` into its top-level settings, as
+/// [`DevSweepConfig::read_from`] does: a broken file yields no settings and,
+/// with `backup` and unless the run is read-only, is copied aside first.
+fn read_table(path: &Path, backup: bool) -> (Map<String, Value>, ConfigSource) {
+    let format = ConfigFormat::from_path(path).unwrap_or(ConfigFormat::Json);
+    let parsed = match std::fs::read_to_string(path) {
+        Ok(contents) => format.parse_table(&contents),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return (Map::new(), ConfigSource::Defaults);
+        }
+        Err(e) => Err(e.into()),
+    };
+
+    match parsed {
+        Ok(table) => (table, ConfigSource::File(path.to_path_buf())),
+        Err(e) => {
+            let backup = match path.file_name() {
+                Some(name) if backup && ensure_writable("back up the config file").is_ok() => {
+                    let mut name = name.to_os_string();
+                    name.push(".bak");
+                    let backup = path.with_file_name(name);
+                    std::fs::copy(path, &backup).ok().map(|_| backup)
+                }
+                _ => None,
+            };
+            let source = ConfigSource::Fallback {
+                path: path.to_path_buf(),
+                error: e.to_string(),
+                backup,
+            };
+            (Map::new(), source)
+        }
+    }
+}
+
+/// Parse a comma-separated list of kind names.
+fn parse_kind_list(list: &str) -> anyhow::Result<Vec<ProjectKind>> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(str::parse)
+        .collect()
+}
+
+/// Parse a boolean environment value (`true`/`false`, `1`/`0`, `yes`/`no`).
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Some(true),
+        "0" | "false" | "no" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+/// On-disk format of the config file, chosen by its extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFormat {
+    Json,
+    Toml,
+    Yaml,
+}
+
+/// Config file names looked for, in priority order. JSON comes first so an
+/// existing `config.json` keeps winning for backward compatibility.
+const CONFIG_FILE_NAMES: [&str; 4] = ["config.json", "config.toml", "config.yaml", "config.yml"];
+
+impl ConfigFormat {
+    /// Detect the format from a file extension (`.json`, `.toml`, `.yaml`/`.yml`).
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+            "json" => Some(Self::Json),
+            "toml" => Some(Self::Toml),
+            "yaml" | "yml" => Some(Self::Yaml),
+            _ => None,
+        }
+    }
+
+    /// Parse a config document in this format.
+    pub fn parse(self, contents: &str) -> anyhow::Result<DevSweepConfig> {
+        Ok(match self {
+            Self::Json => serde_json::from_str(contents)?,
+            Self::Toml => toml::from_str(contents)?,
+            Self::Yaml => serde_norway::from_str(contents)?,
+        })
+    }
+
+    /// Parse a config document in this format into its top-level settings, as
+    /// written, for layering. Fails if any setting is invalid.
+    pub fn parse_table(self, contents: &str) -> anyhow::Result<Map<String, Value>> {
+        let value: Value = match self {
+            Self::Json => serde_json::from_str(contents)?,
+            Self::Toml => toml::from_str(contents)?,
+            Self::Yaml => serde_norway::from_str(contents)?,
+        };
+        let table = match value {
+            Value::Object(table) => table,
+            // An empty YAML document
+            Value::Null => Map::new(),
+            other => anyhow::bail!("expected a table of settings, found {other}"),
+        };
+        serde_json::from_value::<DevSweepConfig>(Value::Object(table.clone()))?;
+        Ok(table)
+    }
+
+    /// Render a config document in this format.
+    pub fn render(self, config: &DevSweepConfig) -> anyhow::Result<String> {
+        Ok(match self {
+            Self::Json => serde_json::to_string_pretty(config)?,
+            Self::Toml => toml::to_string_pretty(config)?,
+            Self::Yaml => serde_norway::to_string(config)?,
+        })
+    }
+}
+
+/// Where the loaded config came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigSource {
+    /// Parsed from this file.
+    File(PathBuf),
+    /// No config file exists; defaults are in use.
+    Defaults,
+    /// The file exists but couldn't be read or parsed, so defaults are in use.
+    Fallback {
+        /// The broken config file.
+        path: PathBuf,
+        /// Why it couldn't be used.
+        error: String,
+        /// Where a copy of the broken file was saved, if that worked.
+        backup: Option<PathBuf>,
+    },
+}
+
+impl std::fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::File(path) => write!(f, "{}", path.display()),
+            Self::Defaults => write!(f, "defaults (no config file)"),
+            Self::Fallback { path, .. } => {
+                write!(f, "defaults ({} could not be parsed)", path.display())
+            }
+        }
+    }
 }
 
 impl DevSweepConfig {
-    /// Load config from the default location (~/.config/dev-sweep/config.json).
+    /// Load the config, merging the system, user, and project layers (see
+    /// [`DevSweepConfig::load_layered`]).
     pub fn load() -> Self {
-        let config_path = Self::config_path();
-        if config_path.exists() {
-            match std::fs::read_to_string(&config_path) {
-                Ok(contents) => serde_json::from_str(&contents).unwrap_or_default(),
-                Err(_) => Self::default(),
+        Self::load_with_source().0
+    }
+
+    /// Like [`DevSweepConfig::load`], also reporting where the user's config
+    /// came from.
+    pub fn load_with_source() -> (Self, ConfigSource) {
+        let layered = Self::load_layered();
+        (layered.config, layered.source)
+    }
+
+    /// Merge the machine-wide config in [`system_config_dir`], the user's config
+    /// at [`DevSweepConfig::config_path`], and the nearest project config above
+    /// the current directory (see [`find_project_config`]), later layers
+    /// winning setting by setting. `DEV_SWEEP_*` environment overrides then
+    /// apply on top.
+    ///
+    /// A broken config file and invalid override values are reported on stderr
+    /// and ignored.
+    pub fn load_layered() -> LayeredConfig {
+        let system = system_config_dir().map(|dir| Self::find_config_in(&dir));
+        let project = std::env::current_dir()
+            .ok()
+            .and_then(|dir| find_project_config(&dir));
+        let mut layered =
+            LayeredConfig::merge(system.as_deref(), &Self::config_path(), project.as_deref());
+        if let ConfigSource::Fallback {
+            ref path,
+            ref error,
+            ref backup,
+        } = layered.source
+        {
+            eprintln!(
+                "  {} Config file {} is invalid and was ignored: {}",
+                yellow("⚠"),
+                path.display(),
+                error
+            );
+            match backup {
+                Some(backup) => eprintln!(
+                    "  {} A copy was saved to {}; using defaults.",
+                    yellow("⚠"),
+                    backup.display()
+                ),
+                None => eprintln!("  {} Using defaults.", yellow("⚠")),
+            }
+        }
+        layered.apply_env(|name| std::env::var_os(name));
+        for warning in &layered.warnings {
+            eprintln!("  {} {}", yellow("⚠"), warning);
+        }
+        layered
+    }
+
+    /// Read config from `path`, in the format given by its extension.
+    ///
+    /// A file that can't be parsed is copied next to it with `.bak` appended
+    /// to its name, e.g. `config.toml.bak` (so a later save can't destroy the
+    /// hand-edited original), and the defaults are returned. A read-only run
+    /// makes no copy.
+    pub fn read_from(path: &Path) -> (Self, ConfigSource) {
+        let (table, source) = read_table(path, true);
+        let config = serde_json::from_value(Value::Object(table)).unwrap_or_default();
+        (config, source)
+    }
+
+    /// Like [`DevSweepConfig::read_from`], but never backs up a broken file,
+    /// for looking at the config without changing anything.
+    pub fn peek_from(path: &Path) -> (Self, ConfigSource) {
+        let (table, source) = read_table(path, false);
+        let config = serde_json::from_value(Value::Object(table)).unwrap_or_default();
+        (config, source)
+    }
+
+    /// Override fields from environment variables, looked up through `lookup`.
+    ///
+    /// | Variable                      | Field               | Format                     |
+    /// |-------------------------------|---------------------|----------------------------|
+    /// | `DEV_SWEEP_IGNORE_PATHS`      | `ignore_paths`      | path list (like `PATH`)    |
+    /// | `DEV_SWEEP_EXCLUDE_KINDS`     | `exclude_kinds`     | comma-separated kind names |
+    /// | `DEV_SWEEP_DETECTION_PRIORITY`| `detection_priority`| comma-separated kind names |
+    /// | `DEV_SWEEP_EXCLUDE_TARGETS`   | `exclude_targets`   | comma-separated globs      |
+    /// | `DEV_SWEEP_DEFAULT_ROOTS`     | `default_roots`     | path list (like `PATH`)    |
+    /// | `DEV_SWEEP_MAX_DEPTH`         | `max_depth`         | number                     |
+    /// | `DEV_SWEEP_MIN_DEPTH`         | `min_depth`         | number                     |
+    /// | `DEV_SWEEP_GLOBAL_CACHES`     | `global_caches`     | comma-separated kind names |
+    /// | `DEV_SWEEP_OLDER_THAN`        | `older_than`        | age string (e.g. `30d`)    |
+    /// | `DEV_SWEEP_TEMP_OLDER_THAN`   | `temp_older_than`   | age string (e.g. `14d`)    |
+    /// | `DEV_SWEEP_EXCLUDE_UNDER_SIZE`| `exclude_under_size`| size string (e.g. `50MB`)  |
+    /// | `DEV_SWEEP_CONFIRM_THRESHOLD` | `confirm_threshold` | size string (e.g. `1GB`)   |
+    /// | `DEV_SWEEP_CONFIRM_PHRASE_ABOVE` | `confirm_phrase_above` | size string (e.g. `20GB`) |
+    /// | `DEV_SWEEP_AUTO_CLEAN_KINDS`  | `auto_clean_kinds`  | comma-separated kind names |
+    /// | `DEV_SWEEP_CLEAN_ORDER`       | `clean_order`       | `size`, `age`, or kinds    |
+    /// | `DEV_SWEEP_ONE_FILE_SYSTEM`   | `one_file_system`   | `true`/`false`             |
+    /// | `DEV_SWEEP_IDE_CACHES`        | `ide_caches`        | `true`/`false`             |
+    /// | `DEV_SWEEP_SAFE_MODE`         | `safe_mode`         | `true`/`false`             |
+    /// | `DEV_SWEEP_DEFAULT_COMMAND`   | `default_command`   | subcommand name            |
+    /// | `DEV_SWEEP_SIZE_MODE`         | `size_mode`         | `disk-usage`/`apparent`    |
+    ///
+    /// Unset or empty variables leave the field alone. A variable that fails to
+    /// parse also leaves it alone; a description of each such failure is returned.
+    pub fn apply_env(&mut self, lookup: impl Fn(&str) -> Option<OsString>) -> Vec<String> {
+        let mut warnings = Vec::new();
+        let var = |name: &str| lookup(name).filter(|v| !v.is_empty());
+
+        if let Some(v) = var("DEV_SWEEP_IGNORE_PATHS") {
+            self.ignore_paths = std::env::split_paths(&v).collect();
+        }
+        if let Some(v) = var("DEV_SWEEP_DEFAULT_ROOTS") {
+            self.default_roots = std::env::split_paths(&v).collect();
+        }
+        if let Some(v) = var("DEV_SWEEP_EXCLUDE_TARGETS") {
+            self.exclude_targets = v
+                .to_string_lossy()
+                .split(',')
+                .map(str::trim)
+                .filter(|p| !p.is_empty())
+                .map(String::from)
+                .collect();
+        }
+        for (name, field) in [
+            ("DEV_SWEEP_EXCLUDE_KINDS", &mut self.exclude_kinds),
+            ("DEV_SWEEP_DETECTION_PRIORITY", &mut self.detection_priority),
+            ("DEV_SWEEP_GLOBAL_CACHES", &mut self.global_caches),
+            ("DEV_SWEEP_AUTO_CLEAN_KINDS", &mut self.auto_clean_kinds),
+        ] {
+            if let Some(v) = var(name) {
+                match parse_kind_list(&v.to_string_lossy()) {
+                    Ok(kinds) => *field = kinds,
+                    Err(e) => warnings.push(format!("Ignoring {}: {}", name, e)),
+                }
+            }
+        }
+        for (name, field) in [
+            ("DEV_SWEEP_MAX_DEPTH", &mut self.max_depth),
+            ("DEV_SWEEP_MIN_DEPTH", &mut self.min_depth),
+        ] {
+            if let Some(v) = var(name) {
+                match v.to_string_lossy().trim().parse() {
+                    Ok(depth) => *field = Some(depth),
+                    Err(_) => warnings.push(format!(
+                        "Ignoring {}: '{}' is not a number",
+                        name,
+                        v.to_string_lossy()
+                    )),
+                }
+            }
+        }
+        for (name, field) in [
+            ("DEV_SWEEP_OLDER_THAN", &mut self.older_than),
+            ("DEV_SWEEP_TEMP_OLDER_THAN", &mut self.temp_older_than),
+        ] {
+            if let Some(v) = var(name) {
+                let age = v.to_string_lossy().into_owned();
+                match parse_age(&age) {
+                    Ok(_) => *field = Some(age),
+                    Err(e) => warnings.push(format!("Ignoring {}: {}", name, e)),
+                }
+            }
+        }
+        for (name, field) in [
+            ("DEV_SWEEP_EXCLUDE_UNDER_SIZE", &mut self.exclude_under_size),
+            ("DEV_SWEEP_CONFIRM_THRESHOLD", &mut self.confirm_threshold),
+            (
+                "DEV_SWEEP_CONFIRM_PHRASE_ABOVE",
+                &mut self.confirm_phrase_above,
+            ),
+        ] {
+            if let Some(v) = var(name) {
+                let size = v.to_string_lossy().into_owned();
+                match parse_size(&size) {
+                    Ok(_) => *field = Some(size),
+                    Err(e) => warnings.push(format!("Ignoring {}: {}", name, e)),
+                }
+            }
+        }
+        if let Some(v) = var("DEV_SWEEP_CLEAN_ORDER") {
+            let order = v.to_string_lossy().into_owned();
+            match order.parse::<CleanOrder>() {
+                Ok(_) => self.clean_order = Some(order),
+                Err(e) => warnings.push(format!("Ignoring DEV_SWEEP_CLEAN_ORDER: {}", e)),
+            }
+        }
+
+        for (name, field) in [
+            ("DEV_SWEEP_ONE_FILE_SYSTEM", &mut self.one_file_system),
+            ("DEV_SWEEP_IDE_CACHES", &mut self.ide_caches),
+            ("DEV_SWEEP_SAFE_MODE", &mut self.safe_mode),
+        ] {
+            if let Some(v) = var(name) {
+                match parse_bool(&v.to_string_lossy()) {
+                    Some(on) => *field = on,
+                    None => warnings.push(format!(
+                        "Ignoring {}: '{}' is not true or false",
+                        name,
+                        v.to_string_lossy()
+                    )),
+                }
+            }
+        }
+
+        if let Some(v) = var("DEV_SWEEP_DEFAULT_COMMAND") {
+            match clap::ValueEnum::from_str(v.to_string_lossy().trim(), true) {
+                Ok(command) => self.default_command = Some(command),
+                Err(_) => warnings.push(format!(
+                    "Ignoring DEV_SWEEP_DEFAULT_COMMAND: '{}' is not a subcommand",
+                    v.to_string_lossy()
+                )),
             }
-        } else {
-            Self::default()
         }
+        if let Some(v) = var("DEV_SWEEP_SIZE_MODE") {
+            match clap::ValueEnum::from_str(v.to_string_lossy().trim(), true) {
+                Ok(mode) => self.size_mode = Some(mode),
+                Err(_) => warnings.push(format!(
+                    "Ignoring DEV_SWEEP_SIZE_MODE: '{}' is not disk-usage or apparent",
+                    v.to_string_lossy()
+                )),
+            }
+        }
+
+        warnings
+    }
+
+    /// Load config from `path`, in the format given by its extension.
+    ///
+    /// A missing or unparsable file yields the defaults (see [`DevSweepConfig::read_from`]).
+    pub fn load_from(path: &Path) -> Self {
+        Self::read_from(path).0
     }
 
-    /// Save config to the default location.
+    /// Save config to the default location, keeping the existing file's format.
     pub fn save(&self) -> anyhow::Result<()> {
-        let config_path = Self::config_path();
-        if let Some(parent) = config_path.parent() {
+        self.save_to(&Self::config_path())
+    }
+
+    /// Save config to `path`, in the format given by its extension.
+    pub fn save_to(&self, path: &Path) -> anyhow::Result<()> {
+        ensure_writable("write the config")?;
+        if let Some(parent) = path.parent() {
             std::fs::create_dir_all(parent)?;
         }
-        let json = serde_json::to_string_pretty(self)?;
-        std::fs::write(&config_path, json)?;
+        let format = ConfigFormat::from_path(path).unwrap_or(ConfigFormat::Json);
+        std::fs::write(path, format.render(self)?)?;
         Ok(())
     }
 
-    /// Get the default config file path.
+    /// Get the config file path.
+    ///
+    /// This is the first of `config.json`, `config.toml`, `config.yaml` or
+    /// `config.yml` that exists in the config directory, or `config.json` if
+    /// none do.
     pub fn config_path() -> PathBuf {
+        Self::find_config_in(&Self::config_dir())
+    }
+
+    /// The format of the active config file.
+    pub fn active_format() -> ConfigFormat {
+        ConfigFormat::from_path(&Self::config_path()).unwrap_or(ConfigFormat::Json)
+    }
+
+    /// Pick the config file inside `dir` (see [`DevSweepConfig::config_path`]).
+    pub fn find_config_in(dir: &Path) -> PathBuf {
+        CONFIG_FILE_NAMES
+            .iter()
+            .map(|name| dir.join(name))
+            .find(|path| path.is_file())
+            .unwrap_or_else(|| dir.join(CONFIG_FILE_NAMES[0]))
+    }
+
+    /// Directory holding the config file (~/.config/dev-sweep).
+    fn config_dir() -> PathBuf {
         dirs::config_dir()
             .unwrap_or_else(|| PathBuf::from("~/.config"))
             .join("dev-sweep")
-            .join("config.json")
     }
 }
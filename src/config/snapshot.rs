@@ -0,0 +1,149 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use serde_json::Value;
+
+use super::{ConfigFormat, DevSweepConfig};
+use crate::readonly::ensure_writable;
+
+/// What `config --snapshot`, `--restore`, or `--list-snapshots` asked for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SnapshotAction {
+    /// Save the config file under this name.
+    Save(String),
+    /// Replace the config file with the snapshot of this name.
+    Restore(String),
+    /// List the saved snapshots.
+    List,
+}
+
+/// A named copy of the config file, saved with `config --snapshot`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigSnapshot {
+    pub name: String,
+    pub path: PathBuf,
+    /// When it was taken (the file's modification time).
+    pub taken: DateTime<Local>,
+}
+
+/// Directory holding the snapshots of the config file at `config_path`
+/// (`~/.config/dev-sweep/snapshots`).
+pub fn snapshots_dir(config_path: &Path) -> PathBuf {
+    config_path
+        .parent()
+        .unwrap_or(Path::new("."))
+        .join("snapshots")
+}
+
+/// Snapshot names are used as file names, so they're limited to letters,
+/// digits, `-`, `_`, and `.`, and can't start with a dot.
+fn check_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if !valid {
+        anyhow::bail!(
+            "Invalid snapshot name '{name}': use letters, digits, '-', '_', and '.' (not first)"
+        );
+    }
+    Ok(())
+}
+
+/// Save the config file at `config_path` as snapshot `name`, replacing any
+/// snapshot already called that. The file is copied as it is, comments and
+/// all; without a config file, the defaults are saved instead.
+pub fn save_snapshot(config_path: &Path, name: &str) -> Result<PathBuf> {
+    check_name(name)?;
+    ensure_writable("save a config snapshot")?;
+    let dir = snapshots_dir(config_path);
+    fs::create_dir_all(&dir)
+        .with_context(|| format!("Failed to create snapshot directory: {}", dir.display()))?;
+    if let Some(old) = find_snapshot(config_path, name) {
+        fs::remove_file(&old.path)?;
+    }
+
+    let extension = config_path
+        .extension()
+        .map_or("json".into(), |e| e.to_string_lossy());
+    let path = dir.join(format!("{name}.{extension}"));
+    if config_path.is_file() {
+        fs::copy(config_path, &path)
+            .with_context(|| format!("Failed to save snapshot: {}", path.display()))?;
+    } else {
+        DevSweepConfig::default().save_to(&path)?;
+    }
+    Ok(path)
+}
+
+/// Every snapshot of the config file at `config_path`, oldest first (ties
+/// broken by name).
+pub fn list_snapshots(config_path: &Path) -> Vec<ConfigSnapshot> {
+    let mut snapshots: Vec<ConfigSnapshot> = fs::read_dir(snapshots_dir(config_path))
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let path = e.path();
+            ConfigFormat::from_path(&path)?;
+            let name = path.file_stem()?.to_str()?.to_string();
+            let taken = e.metadata().ok()?.modified().ok()?;
+            (e.file_type().ok()?.is_file()).then(|| ConfigSnapshot {
+                name,
+                path,
+                taken: DateTime::from(taken),
+            })
+        })
+        .collect();
+    snapshots.sort_by(|a, b| a.taken.cmp(&b.taken).then_with(|| a.name.cmp(&b.name)));
+    snapshots
+}
+
+fn find_snapshot(config_path: &Path, name: &str) -> Option<ConfigSnapshot> {
+    list_snapshots(config_path)
+        .into_iter()
+        .find(|s| s.name == name)
+}
+
+/// Replace the config file at `config_path` with snapshot `name`, returning
+/// the snapshot.
+///
+/// The snapshot is checked to be a valid config first, so a broken one never
+/// replaces a working file. One in the config file's own format is copied as
+/// it is; one in another format is converted.
+pub fn restore_snapshot(config_path: &Path, name: &str) -> Result<ConfigSnapshot> {
+    check_name(name)?;
+    ensure_writable("restore a config snapshot")?;
+    let Some(snapshot) = find_snapshot(config_path, name) else {
+        let names: Vec<String> = list_snapshots(config_path)
+            .into_iter()
+            .map(|s| s.name)
+            .collect();
+        if names.is_empty() {
+            anyhow::bail!("No snapshot named '{name}' (none saved yet)");
+        }
+        anyhow::bail!("No snapshot named '{name}' (saved: {})", names.join(", "));
+    };
+
+    let contents = fs::read_to_string(&snapshot.path)
+        .with_context(|| format!("Failed to read snapshot: {}", snapshot.path.display()))?;
+    let format = ConfigFormat::from_path(&snapshot.path).unwrap_or(ConfigFormat::Json);
+    let config: DevSweepConfig = format
+        .parse_table(&contents)
+        .and_then(|table| Ok(serde_json::from_value(Value::Object(table))?))
+        .with_context(|| format!("Snapshot '{name}' isn't a valid config"))?;
+
+    if ConfigFormat::from_path(config_path).unwrap_or(ConfigFormat::Json) == format {
+        if let Some(parent) = config_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(config_path, contents)
+            .with_context(|| format!("Failed to write config: {}", config_path.display()))?;
+    } else {
+        config.save_to(config_path)?;
+    }
+    Ok(snapshot)
+}
@@ -0,0 +1,107 @@
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use crate::scanner::ScannedProject;
+
+/// Dependency directories a shared store could deduplicate, with the lockfiles
+/// that pin their contents, in order of preference.
+pub const DEPENDENCY_LOCKFILES: &[(&str, &[&str])] = &[
+    (
+        "node_modules",
+        &["package-lock.json", "pnpm-lock.yaml", "yarn.lock", "bun.lock"],
+    ),
+    (".venv", &["uv.lock", "poetry.lock"]),
+    ("vendor/bundle", &["Gemfile.lock"]),
+];
+
+/// Dependency directory, lockfile name, and lockfile hash.
+type LockKey<'a> = (&'a str, &'a str, u64);
+
+/// Projects whose dependency directory was installed from an identical lockfile.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DuplicateSet {
+    /// The dependency directory, e.g. `node_modules`.
+    pub target: String,
+    /// The lockfile the projects share, e.g. `package-lock.json`.
+    pub lockfile: String,
+    /// Project roots holding a copy, largest copy first.
+    pub projects: Vec<PathBuf>,
+    /// Combined size of every copy.
+    pub total_bytes: u64,
+    /// What a shared store would save: every copy but the largest.
+    pub dedupable_bytes: u64,
+}
+
+/// Result of `summary --duplication`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct DuplicationReport {
+    /// Sum of [`DuplicateSet::dedupable_bytes`] over every set.
+    pub total_dedupable_bytes: u64,
+    /// Sets with at least two copies, most dedupable first.
+    pub sets: Vec<DuplicateSet>,
+}
+
+/// Group the dependency directories of `projects` by the hash of the lockfile
+/// next to them, and report the ones installed more than once.
+///
+/// Only the lockfile is read, so this is cheap next to the scan itself.
+/// Projects without a lockfile (or with an unreadable one) are left out, as
+/// their dependencies can't be told apart.
+pub fn find_duplicate_dependencies(projects: &[ScannedProject]) -> DuplicationReport {
+    let mut groups: HashMap<LockKey, Vec<(PathBuf, u64)>> = HashMap::new();
+
+    for project in projects {
+        for (target_name, lockfiles) in DEPENDENCY_LOCKFILES {
+            let Some(target) = project
+                .clean_targets
+                .iter()
+                .find(|t| t.path == project.path.join(target_name))
+            else {
+                continue;
+            };
+            let Some((lockfile, hash)) = lockfiles.iter().find_map(|name| {
+                let contents = fs::read(project.path.join(name)).ok()?;
+                let mut hasher = DefaultHasher::new();
+                contents.hash(&mut hasher);
+                Some((*name, hasher.finish()))
+            }) else {
+                continue;
+            };
+            groups
+                .entry((target_name, lockfile, hash))
+                .or_default()
+                .push((project.path.clone(), target.size_bytes));
+        }
+    }
+
+    let mut sets: Vec<DuplicateSet> = groups
+        .into_iter()
+        .filter(|(_, copies)| copies.len() > 1)
+        .map(|((target, lockfile, _), mut copies)| {
+            copies.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            let total_bytes: u64 = copies.iter().map(|(_, bytes)| bytes).sum();
+            DuplicateSet {
+                target: target.to_string(),
+                lockfile: lockfile.to_string(),
+                dedupable_bytes: total_bytes - copies[0].1,
+                total_bytes,
+                projects: copies.into_iter().map(|(path, _)| path).collect(),
+            }
+        })
+        .collect();
+    sets.sort_by(|a, b| {
+        b.dedupable_bytes
+            .cmp(&a.dedupable_bytes)
+            .then_with(|| a.projects.cmp(&b.projects))
+    });
+
+    DuplicationReport {
+        total_dedupable_bytes: sets.iter().map(|s| s.dedupable_bytes).sum(),
+        sets,
+    }
+}
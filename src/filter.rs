@@ -0,0 +1,730 @@
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use regex::{Regex, RegexBuilder};
+
+use crate::config::{KeepLastBuild, LAST_GOOD_MARKER};
+use crate::scanner::walk::dir_size_and_modified;
+use crate::scanner::{CleanTarget, ProjectKind, RebuildCost, ScannedProject, SizeMode};
+use crate::util::{glob_match, mount_point, parse_age};
+
+/// Keep only projects last modified before `older_than` (an age string like "30d").
+pub fn filter_by_age(projects: &mut Vec<ScannedProject>, older_than: Option<&str>) -> Result<()> {
+    if let Some(age_str) = older_than {
+        let cutoff = age_cutoff(age_str)?;
+        projects.retain(|p| is_older_than(p, cutoff));
+    }
+    Ok(())
+}
+
+/// The time an age string like "30d" reaches back to from now.
+pub fn age_cutoff(older_than: &str) -> Result<DateTime<Local>> {
+    Ok(Local::now() - parse_age(older_than)?)
+}
+
+/// Whether `p` was last modified before `cutoff`.
+pub fn is_older_than(p: &ScannedProject, cutoff: DateTime<Local>) -> bool {
+    p.last_modified < cutoff
+}
+
+/// Keep only projects with a kind in `kinds` (any of a polyglot project's kinds).
+pub fn filter_by_kind(projects: &mut Vec<ScannedProject>, kinds: &[ProjectKind]) {
+    projects.retain(|p| p.kinds().any(|k| kinds.contains(&k)));
+}
+
+/// Keep only projects whose name or path contains `needle`, ignoring case.
+pub fn filter_by_substring(projects: &mut Vec<ScannedProject>, needle: &str) {
+    projects.retain(|p| matches_substring(p, needle));
+}
+
+/// Whether `p`'s name or path contains `needle`, ignoring case.
+pub fn matches_substring(p: &ScannedProject, needle: &str) -> bool {
+    let needle = needle.to_lowercase();
+    p.name.to_lowercase().contains(&needle)
+        || p.path.to_string_lossy().to_lowercase().contains(&needle)
+}
+
+/// Keep only projects whose name or path matches `re`.
+pub fn filter_by_regex(projects: &mut Vec<ScannedProject>, re: &Regex) {
+    projects.retain(|p| matches_regex(p, re));
+}
+
+/// Whether `p`'s name or path matches `re`.
+pub fn matches_regex(p: &ScannedProject, re: &Regex) -> bool {
+    re.is_match(&p.name) || re.is_match(&p.path.to_string_lossy())
+}
+
+/// Compile a user-supplied `--regex` pattern case-insensitively, to match `--filter`.
+pub fn build_regex(pattern: &str) -> Result<Regex> {
+    RegexBuilder::new(pattern)
+        .case_insensitive(true)
+        .build()
+        .map_err(|e| anyhow::anyhow!("Invalid regex '{}': {}", pattern, e))
+}
+
+/// Label each project whose kind has an entry in `aliases` with that alias.
+pub fn apply_kind_aliases(
+    projects: &mut [ScannedProject],
+    aliases: &BTreeMap<ProjectKind, String>,
+) {
+    for project in projects {
+        project.kind_alias = aliases.get(&project.kind).cloned();
+    }
+}
+
+/// Label each project with its kind's [rebuild cost](ProjectKind::rebuild_cost),
+/// or the one set for its kind in `overrides`.
+pub fn apply_rebuild_costs(
+    projects: &mut [ScannedProject],
+    overrides: &BTreeMap<ProjectKind, RebuildCost>,
+) {
+    for project in projects {
+        // A polyglot project costs as much as its most expensive kind.
+        project.rebuild_cost = project
+            .kinds()
+            .map(|kind| overrides.get(&kind).copied().unwrap_or(kind.rebuild_cost()))
+            .max();
+    }
+}
+
+/// Keep only projects that were never cleaned, or whose artifacts reappeared
+/// after their last clean in `last_cleaned` (a target directory modified since).
+pub fn filter_by_since_clean(
+    projects: &mut Vec<ScannedProject>,
+    last_cleaned: &HashMap<PathBuf, DateTime<Local>>,
+) {
+    projects.retain(|p| regrown_since_clean(p, last_cleaned));
+}
+
+/// Whether `p` was never cleaned, or has a target modified since its last clean.
+pub fn regrown_since_clean(
+    p: &ScannedProject,
+    last_cleaned: &HashMap<PathBuf, DateTime<Local>>,
+) -> bool {
+    match last_cleaned.get(&p.path) {
+        Some(&cleaned) => p.clean_targets.iter().any(|t| {
+            fs::metadata(&t.path)
+                .and_then(|m| m.modified())
+                .is_ok_and(|modified| DateTime::<Local>::from(modified) > cleaned)
+        }),
+        None => true,
+    }
+}
+
+/// The paths of the `n` most recently modified projects of each kind, which
+/// `--keep-recent` leaves alone. Equally recent projects are picked by path.
+pub fn most_recent_per_kind(projects: &[ScannedProject], n: usize) -> HashSet<PathBuf> {
+    let mut by_kind: HashMap<ProjectKind, Vec<&ScannedProject>> = HashMap::new();
+    for p in projects {
+        by_kind.entry(p.kind).or_default().push(p);
+    }
+    by_kind
+        .into_values()
+        .flat_map(|mut group| {
+            group.sort_by(|a, b| {
+                b.last_modified
+                    .cmp(&a.last_modified)
+                    .then_with(|| a.path.cmp(&b.path))
+            });
+            group.into_iter().take(n).map(|p| p.path.clone())
+        })
+        .collect()
+}
+
+// ── Filter decisions ────────────────────────────────────────────────────────
+
+/// A filter that keeps the projects it accepts, labeled as the user gave it
+/// (e.g. `older-than 30d`).
+pub struct ProjectFilter<'a> {
+    pub label: String,
+    pub accepts: Box<dyn Fn(&ScannedProject) -> bool + 'a>,
+}
+
+impl<'a> ProjectFilter<'a> {
+    pub fn new(label: impl Into<String>, accepts: impl Fn(&ScannedProject) -> bool + 'a) -> Self {
+        ProjectFilter {
+            label: label.into(),
+            accepts: Box::new(accepts),
+        }
+    }
+}
+
+/// Keep only projects accepted by every filter in `filters`.
+pub fn apply_filters(projects: &mut Vec<ScannedProject>, filters: &[ProjectFilter]) {
+    projects.retain(|p| filters.iter().all(|f| (f.accepts)(p)));
+}
+
+/// One filter's verdict on a project.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilterCheck {
+    /// The filter's label, e.g. `kind Rust,Node`.
+    pub filter: String,
+    pub passed: bool,
+}
+
+/// Every active filter's verdict on one detected project, for `--explain-filters`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilterDecision {
+    pub name: String,
+    pub path: PathBuf,
+    /// In the order the filters are applied.
+    pub checks: Vec<FilterCheck>,
+}
+
+impl FilterDecision {
+    /// A project is kept only if it passed every filter.
+    pub fn kept(&self) -> bool {
+        self.checks.iter().all(|c| c.passed)
+    }
+}
+
+/// Check every project against every filter.
+///
+/// Each filter is evaluated on its own, not just on the projects that survived
+/// the ones before it, so every reason a project is dropped shows up.
+pub fn decide_filters(
+    projects: &[ScannedProject],
+    filters: &[ProjectFilter],
+) -> Vec<FilterDecision> {
+    projects
+        .iter()
+        .map(|p| FilterDecision {
+            name: p.name.clone(),
+            path: p.path.clone(),
+            checks: filters
+                .iter()
+                .map(|f| FilterCheck {
+                    filter: f.label.clone(),
+                    passed: (f.accepts)(p),
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+// ── Ordering ────────────────────────────────────────────────────────────────
+
+/// How results are ordered for display.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum SortKey {
+    /// Largest reclaimable size first
+    #[default]
+    Size,
+    /// Least recently modified first
+    Age,
+    /// Alphabetical by project name
+    Name,
+    /// Alphabetical by project path
+    Path,
+}
+
+/// Sort projects by `key`.
+///
+/// Ties are always broken by path and then name, so equal primary keys come out
+/// in the same order on every run (important for diffing JSON output).
+pub fn sort_projects(projects: &mut [ScannedProject], key: SortKey) {
+    projects.sort_by(|a, b| {
+        let primary = match key {
+            SortKey::Size => b.total_cleanable_bytes.cmp(&a.total_cleanable_bytes),
+            SortKey::Age => a.last_modified.cmp(&b.last_modified),
+            SortKey::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+            SortKey::Path => Ordering::Equal,
+        };
+        primary
+            .then_with(|| a.path.cmp(&b.path))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+}
+
+/// The order selected projects are cleaned in, separate from how they are
+/// listed. It decides which projects are already done when a clean is
+/// interrupted.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum CleanOrder {
+    /// Largest reclaimable size first.
+    #[default]
+    Size,
+    /// Least recently modified first.
+    Age,
+    /// The listed kinds first, in list order; other kinds after them. Within a
+    /// kind, largest first.
+    Kinds(Vec<ProjectKind>),
+}
+
+impl FromStr for CleanOrder {
+    type Err = anyhow::Error;
+
+    /// Parse `size`, `age`, or a comma-separated kind priority list such as
+    /// `Node,Rust`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("size") {
+            return Ok(Self::Size);
+        }
+        if s.eq_ignore_ascii_case("age") {
+            return Ok(Self::Age);
+        }
+        let kinds = s
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(str::parse)
+            .collect::<Result<Vec<ProjectKind>>>()
+            .map_err(|e| {
+                anyhow::anyhow!("Invalid clean order '{}': expected size, age, or kinds. {}", s, e)
+            })?;
+        if kinds.is_empty() {
+            anyhow::bail!(
+                "Invalid clean order '{}': expected size, age, or a list of kinds",
+                s
+            );
+        }
+        Ok(Self::Kinds(kinds))
+    }
+}
+
+/// Put `projects` in the order they should be cleaned.
+///
+/// Ties are broken by path, so the sequence is the same on every run.
+pub fn order_for_cleaning(projects: &mut [&ScannedProject], order: &CleanOrder) {
+    let priority = |p: &ScannedProject| match order {
+        CleanOrder::Kinds(kinds) => kinds
+            .iter()
+            .position(|&k| k == p.kind)
+            .unwrap_or(kinds.len()),
+        _ => 0,
+    };
+    projects.sort_by(|a, b| {
+        let primary = match order {
+            CleanOrder::Age => a.last_modified.cmp(&b.last_modified),
+            CleanOrder::Size | CleanOrder::Kinds(_) => priority(a)
+                .cmp(&priority(b))
+                .then_with(|| b.total_cleanable_bytes.cmp(&a.total_cleanable_bytes)),
+        };
+        primary.then_with(|| a.path.cmp(&b.path))
+    });
+}
+
+/// Total project count and reclaimable bytes per kind display name (or alias).
+///
+/// Largest first, ties broken alphabetically so the order is stable.
+pub fn group_by_kind(projects: &[ScannedProject]) -> Vec<(String, (usize, u64))> {
+    let mut by_kind: HashMap<String, (usize, u64)> = HashMap::new();
+    for p in projects {
+        let entry = by_kind.entry(p.display_kind()).or_insert((0, 0));
+        entry.0 += 1;
+        entry.1 += p.total_cleanable_bytes;
+    }
+
+    let mut sorted: Vec<_> = by_kind.into_iter().collect();
+    sorted.sort_by(|(a_kind, (_, a_bytes)), (b_kind, (_, b_bytes))| {
+        b_bytes.cmp(a_bytes).then_with(|| a_kind.cmp(b_kind))
+    });
+    sorted
+}
+
+/// Total project count and reclaimable bytes per owning user ID (`None` where
+/// the owner is unknown).
+///
+/// Largest first, ties broken by user ID so the order is stable.
+pub fn group_by_owner(projects: &[ScannedProject]) -> Vec<(Option<u32>, (usize, u64))> {
+    let mut by_owner: HashMap<Option<u32>, (usize, u64)> = HashMap::new();
+    for p in projects {
+        let entry = by_owner.entry(p.owner).or_insert((0, 0));
+        entry.0 += 1;
+        entry.1 += p.total_cleanable_bytes;
+    }
+
+    let mut sorted: Vec<_> = by_owner.into_iter().collect();
+    sorted.sort_by(|(a_uid, (_, a_bytes)), (b_uid, (_, b_bytes))| {
+        b_bytes.cmp(a_bytes).then_with(|| a_uid.cmp(b_uid))
+    });
+    sorted
+}
+
+/// Total project count and reclaimable bytes per filesystem, labelled by its
+/// mount point (by drive on Windows, where device IDs aren't recorded).
+///
+/// Projects are bucketed by the device ID recorded during the scan, so the
+/// mount point is only looked up once per device. Largest first, ties broken
+/// by mount point.
+pub fn group_by_filesystem(projects: &[ScannedProject]) -> Vec<(PathBuf, (usize, u64))> {
+    let mut mounts: HashMap<u64, PathBuf> = HashMap::new();
+    let mut by_fs: HashMap<(Option<u64>, PathBuf), (usize, u64)> = HashMap::new();
+    for p in projects {
+        let mount = match p.device {
+            Some(dev) => mounts
+                .entry(dev)
+                .or_insert_with(|| mount_point(&p.path))
+                .clone(),
+            None => mount_point(&p.path),
+        };
+        let entry = by_fs.entry((p.device, mount)).or_insert((0, 0));
+        entry.0 += 1;
+        entry.1 += p.total_cleanable_bytes;
+    }
+
+    let mut sorted: Vec<_> = by_fs
+        .into_iter()
+        .map(|((_, mount), totals)| (mount, totals))
+        .collect();
+    sorted.sort_by(|(a_mount, (_, a_bytes)), (b_mount, (_, b_bytes))| {
+        b_bytes.cmp(a_bytes).then_with(|| a_mount.cmp(b_mount))
+    });
+    sorted
+}
+
+// ── Targets ─────────────────────────────────────────────────────────────────
+
+/// Keep only the clean targets called `name`, dropping projects left with none.
+///
+/// A target matches on its full display name or its last path component, so
+/// `__pycache__` also selects nested caches like `src/__pycache__`. Project
+/// totals are recomputed to cover only the remaining targets.
+pub fn filter_by_target(projects: &mut Vec<ScannedProject>, name: &str) {
+    let name = name.trim_end_matches(['/', '\\']);
+    for project in projects.iter_mut() {
+        project.clean_targets.retain(|t| {
+            t.name == name || t.path.file_name().is_some_and(|f| f == name)
+        });
+        project.total_cleanable_bytes = removable_bytes(&project.clean_targets);
+    }
+    projects.retain(|p| !p.clean_targets.is_empty());
+}
+
+/// Drop clean targets matching any of `patterns`, dropping projects left with none.
+///
+/// A pattern is a glob (`*`, `?`) matched like [`filter_by_target`]: against a
+/// target's full display name or its last path component. A pattern that
+/// reaches inside a target, such as `target/release`, keeps just that part: the
+/// target is replaced by its subdirectories, minus the ones matching the rest
+/// of the pattern. Loose files directly in a split target are left alone.
+/// Project totals are recomputed to cover only the remaining targets, with the
+/// parts of split targets measured as `mode` says.
+pub fn exclude_targets(projects: &mut Vec<ScannedProject>, patterns: &[String], mode: SizeMode) {
+    if patterns.is_empty() {
+        return;
+    }
+    let patterns: Vec<&str> = patterns
+        .iter()
+        .map(|p| p.trim_end_matches(['/', '\\']))
+        .collect();
+
+    for project in projects.iter_mut() {
+        let targets = std::mem::take(&mut project.clean_targets);
+        for target in targets {
+            project
+                .clean_targets
+                .extend(exclude_from_target(target, &patterns, mode));
+        }
+        project.total_cleanable_bytes = removable_bytes(&project.clean_targets);
+    }
+    projects.retain(|p| !p.clean_targets.is_empty());
+}
+
+/// What remains of `target` once everything matching `patterns` is excluded.
+fn exclude_from_target(target: CleanTarget, patterns: &[&str], mode: SizeMode) -> Vec<CleanTarget> {
+    let last = target
+        .path
+        .file_name()
+        .map(|f| f.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    if patterns
+        .iter()
+        .any(|p| glob_match(p, &target.name) || glob_match(p, &last))
+    {
+        return Vec::new();
+    }
+
+    // Patterns reaching inside this target, with the target's part stripped.
+    let inner: Vec<&str> = patterns
+        .iter()
+        .filter_map(|p| {
+            if let Some(rest) = p.strip_prefix(target.name.as_str())
+                && let Some(rest) = rest.strip_prefix('/')
+            {
+                return Some(rest);
+            }
+            let (head, rest) = p.split_once('/')?;
+            glob_match(head, &last).then_some(rest)
+        })
+        .collect();
+    if inner.is_empty() {
+        return vec![target];
+    }
+
+    split_target(&target, mode)
+        .into_iter()
+        .flat_map(|child| exclude_from_target(child, &inner, mode))
+        .collect()
+}
+
+/// The non-empty subdirectories of `target` as targets of their own, sized as
+/// `mode` says. Loose files directly in `target` aren't included.
+fn split_target(target: &CleanTarget, mode: SizeMode) -> Vec<CleanTarget> {
+    let mut children: Vec<PathBuf> = fs::read_dir(&target.path)
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+        .map(|e| e.path())
+        .collect();
+    children.sort();
+
+    children
+        .into_iter()
+        .filter_map(|path| {
+            let child = path.file_name()?.to_string_lossy().into_owned();
+            let (size_bytes, last_modified) = dir_size_and_modified(&path, mode);
+            (size_bytes > 0).then(|| CleanTarget {
+                name: format!("{}/{}", target.name, child),
+                path,
+                size_bytes,
+                protected: target.protected,
+                last_modified,
+                runtime_data: target.runtime_data,
+                large_file: false,
+            })
+        })
+        .collect()
+}
+
+/// Drop clean targets modified since `cutoff`, dropping projects left with none
+/// (`--target-older-than`).
+///
+/// A target's age is that of the newest file in it, so a `target/debug` rebuilt
+/// yesterday is kept out while an untouched `node_modules` next to it stays.
+/// Targets whose age is unknown are dropped too, since they can't be shown to
+/// be stale. Project totals are recomputed to cover only the remaining targets.
+pub fn drop_recent_targets(projects: &mut Vec<ScannedProject>, cutoff: DateTime<Local>) {
+    for project in projects.iter_mut() {
+        project
+            .clean_targets
+            .retain(|t| t.last_modified.is_some_and(|m| m < cutoff));
+        project.total_cleanable_bytes = removable_bytes(&project.clean_targets);
+        let targets = &project.clean_targets;
+        if project
+            .largest_file
+            .as_ref()
+            .is_some_and(|f| !targets.iter().any(|t| f.path.starts_with(&t.path)))
+        {
+            project.largest_file = None;
+        }
+    }
+    projects.retain(|p| !p.clean_targets.is_empty());
+}
+
+/// In safe mode: mark every target not on the [`SAFE_TARGET_NAMES`] allowlist
+/// as protected, so it is still listed but never removed.
+///
+/// Projects are kept even when all their targets are protected; their totals
+/// are recomputed to cover only the targets that can be removed.
+///
+/// [`SAFE_TARGET_NAMES`]: crate::scanner::SAFE_TARGET_NAMES
+pub fn protect_unsafe_targets(projects: &mut [ScannedProject]) {
+    for project in projects {
+        for target in &mut project.clean_targets {
+            target.protected = !target.is_safe();
+        }
+        project.total_cleanable_bytes = removable_bytes(&project.clean_targets);
+    }
+}
+
+/// Apply the config's `keep_last_build` rules: in each project of a kind with
+/// a rule, the build directories the rule keeps are marked as protected, so
+/// they are still listed but never removed.
+///
+/// A kept directory inside a larger target, like `target/release` in
+/// `target`, is split out the way [`exclude_targets`] does it, and the rest of
+/// the target stays removable. Returns how many directories were kept. Fails
+/// on a rule whose `within` isn't a valid age.
+pub fn protect_last_builds(
+    projects: &mut [ScannedProject],
+    rules: &BTreeMap<ProjectKind, KeepLastBuild>,
+    mode: SizeMode,
+) -> Result<usize> {
+    let mut cutoffs = BTreeMap::new();
+    for (kind, rule) in rules {
+        let cutoff = rule
+            .within
+            .as_deref()
+            .map(age_cutoff)
+            .transpose()
+            .with_context(|| format!("Invalid keep_last_build within for {kind:?}"))?;
+        cutoffs.insert(*kind, cutoff);
+    }
+
+    let mut kept_total = 0;
+    for project in projects {
+        let mut kept: Vec<PathBuf> = Vec::new();
+        for kind in project.kinds() {
+            let Some(rule) = rules.get(&kind) else {
+                continue;
+            };
+            let dirs: Vec<&str> = if rule.dirs.is_empty() {
+                kind.release_dirs().to_vec()
+            } else {
+                rule.dirs.iter().map(String::as_str).collect()
+            };
+            kept.extend(last_builds(&project.path, &dirs, cutoffs[&kind], mode));
+        }
+        if kept.is_empty() {
+            continue;
+        }
+        kept_total += kept.len();
+
+        let targets = std::mem::take(&mut project.clean_targets);
+        for target in targets {
+            project
+                .clean_targets
+                .extend(protect_within(target, &kept, mode));
+        }
+        project.total_cleanable_bytes = removable_bytes(&project.clean_targets);
+    }
+    Ok(kept_total)
+}
+
+/// Which of `dirs` under `project` to keep: those marked with
+/// [`LAST_GOOD_MARKER`], and the most recently built one if that was after
+/// `cutoff`.
+fn last_builds(
+    project: &Path,
+    dirs: &[&str],
+    cutoff: Option<DateTime<Local>>,
+    mode: SizeMode,
+) -> Vec<PathBuf> {
+    let builds: Vec<PathBuf> = dirs
+        .iter()
+        .map(|dir| project.join(dir.trim_end_matches(['/', '\\'])))
+        .filter(|path| path.is_dir())
+        .collect();
+    let mut kept: Vec<PathBuf> = builds
+        .iter()
+        .filter(|path| path.join(LAST_GOOD_MARKER).exists())
+        .cloned()
+        .collect();
+    if let Some(cutoff) = cutoff
+        && let Some((newest, modified)) = builds
+            .into_iter()
+            .filter_map(|path| {
+                let (_, modified) = dir_size_and_modified(&path, mode);
+                Some((path, modified?))
+            })
+            .max_by_key(|(_, modified)| *modified)
+        && modified >= cutoff
+        && !kept.contains(&newest)
+    {
+        kept.push(newest);
+    }
+    kept
+}
+
+/// `target` with every directory in `kept` inside it protected, splitting it
+/// into its subdirectories as far as needed to reach them.
+fn protect_within(target: CleanTarget, kept: &[PathBuf], mode: SizeMode) -> Vec<CleanTarget> {
+    if kept.iter().any(|k| target.path.starts_with(k)) {
+        return vec![CleanTarget {
+            protected: true,
+            ..target
+        }];
+    }
+    if !kept.iter().any(|k| k.starts_with(&target.path)) {
+        return vec![target];
+    }
+    split_target(&target, mode)
+        .into_iter()
+        .flat_map(|child| protect_within(child, kept, mode))
+        .collect()
+}
+
+/// Combined size of the targets that aren't protected.
+fn removable_bytes(targets: &[CleanTarget]) -> u64 {
+    targets
+        .iter()
+        .filter(|t| !t.protected)
+        .map(|t| t.size_bytes)
+        .sum()
+}
+
+/// Distinct kinds present in `projects`, in display-name order.
+pub fn kinds_present(projects: &[ScannedProject]) -> Vec<ProjectKind> {
+    let mut kinds: Vec<ProjectKind> = Vec::new();
+    for p in projects {
+        if !kinds.contains(&p.kind) {
+            kinds.push(p.kind);
+        }
+    }
+    kinds.sort_by_key(|k| k.to_string());
+    kinds
+}
+
+// ── Path tree ───────────────────────────────────────────────────────────────
+
+/// Reclaimable space aggregated up the directory hierarchy.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize)]
+pub struct PathTree {
+    /// Directory name (the scan root's full path at the top).
+    pub name: String,
+    /// Reclaimable bytes of every project at or below this directory.
+    pub bytes: u64,
+    /// Number of projects at or below this directory.
+    pub projects: usize,
+    /// Subdirectories holding projects, largest first.
+    pub children: Vec<PathTree>,
+}
+
+/// Aggregate projects under `root` by path prefix.
+///
+/// Projects outside `root` (such as global caches) are grouped under their own
+/// full path at the top level. Children are ordered by bytes descending, then name.
+pub fn build_path_tree(root: &Path, projects: &[ScannedProject]) -> PathTree {
+    let mut tree = PathTree {
+        name: root.display().to_string(),
+        ..Default::default()
+    };
+
+    for project in projects {
+        let components: Vec<String> = match project.path.strip_prefix(root) {
+            Ok(relative) => relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().into_owned())
+                .collect(),
+            Err(_) => vec![project.path.display().to_string()],
+        };
+
+        let mut node = &mut tree;
+        node.bytes += project.total_cleanable_bytes;
+        node.projects += 1;
+        for name in components {
+            let index = match node.children.iter().position(|c| c.name == name) {
+                Some(i) => i,
+                None => {
+                    node.children.push(PathTree {
+                        name,
+                        ..Default::default()
+                    });
+                    node.children.len() - 1
+                }
+            };
+            node = &mut node.children[index];
+            node.bytes += project.total_cleanable_bytes;
+            node.projects += 1;
+        }
+    }
+
+    sort_tree(&mut tree);
+    tree
+}
+
+fn sort_tree(node: &mut PathTree) {
+    node.children
+        .sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.name.cmp(&b.name)));
+    for child in &mut node.children {
+        sort_tree(child);
+    }
+}
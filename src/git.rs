@@ -0,0 +1,125 @@
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::{Context, Result};
+
+use crate::readonly::ensure_writable;
+
+/// State of the git working tree around a project.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GitStatus {
+    /// No uncommitted changes or untracked files under the project.
+    Clean,
+    /// Something under the project is modified, staged, or untracked.
+    Dirty,
+    /// The project isn't inside a git repository.
+    NotARepo,
+}
+
+/// Check for uncommitted work under `dir` with `git status --porcelain`.
+///
+/// Only changes inside `dir` count, so one dirty package doesn't hold back the
+/// rest of a monorepo. Ignored files (such as build artifacts) never count.
+/// Fails if git can't be run.
+pub fn working_tree_status(dir: &Path) -> Result<GitStatus> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["status", "--porcelain", "--", "."])
+        .output()
+        .context("Failed to run git (is it installed?)")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if stderr.contains("not a git repository") {
+            return Ok(GitStatus::NotARepo);
+        }
+        anyhow::bail!(
+            "git status failed in {}: {}",
+            dir.display(),
+            stderr.trim()
+        );
+    }
+
+    Ok(if output.stdout.is_empty() {
+        GitStatus::Clean
+    } else {
+        GitStatus::Dirty
+    })
+}
+
+/// Repositories with less than this reclaimable by `git gc` (1 MiB) aren't
+/// worth a target.
+pub const GIT_GC_MIN_BYTES: u64 = 1024 * 1024;
+
+/// What `git count-objects -v` reports about a repository's object store.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ObjectCounts {
+    /// Number of loose objects.
+    pub loose: u64,
+    /// Bytes taken by loose objects.
+    pub loose_bytes: u64,
+    /// Bytes taken by garbage files in the object store.
+    pub garbage_bytes: u64,
+}
+
+impl ObjectCounts {
+    /// Roughly what `git gc --prune=now` frees: the loose objects (which are
+    /// packed, or pruned if unreachable) and the garbage.
+    pub fn reclaimable(&self) -> u64 {
+        self.loose_bytes + self.garbage_bytes
+    }
+}
+
+/// Parse the output of `git count-objects -v`. Sizes are given in KiB; unknown
+/// lines are ignored.
+pub fn parse_count_objects(output: &str) -> ObjectCounts {
+    let mut counts = ObjectCounts::default();
+    for line in output.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let Ok(value) = value.trim().parse::<u64>() else {
+            continue;
+        };
+        match key.trim() {
+            "count" => counts.loose = value,
+            "size" => counts.loose_bytes = value * 1024,
+            "size-garbage" => counts.garbage_bytes = value * 1024,
+            _ => {}
+        }
+    }
+    counts
+}
+
+/// Count the objects of the repository whose work tree is `repo`.
+pub fn count_objects(repo: &Path) -> Result<ObjectCounts> {
+    let output = git_output(repo, &["count-objects", "-v"])?;
+    Ok(parse_count_objects(&String::from_utf8_lossy(&output)))
+}
+
+/// Pack loose objects and prune unreachable ones with `git gc --prune=now`.
+/// History is untouched; only the storage changes.
+pub fn gc(repo: &Path) -> Result<()> {
+    ensure_writable(&format!("run git gc in {}", repo.display()))?;
+    git_output(repo, &["gc", "--prune=now", "--quiet"]).map(|_| ())
+}
+
+/// Run git in `repo` and return its stdout, failing on a non-zero exit.
+fn git_output(repo: &Path, args: &[&str]) -> Result<Vec<u8>> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(args)
+        .output()
+        .context("Failed to run git (is it installed?)")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "git {} failed in {}: {}",
+            args[0],
+            repo.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(output.stdout)
+}
@@ -0,0 +1,62 @@
+use serde::Serialize;
+
+use crate::config::DevSweepConfig;
+use crate::scanner::ProjectKind;
+
+/// A target only cleaned when one of `markers` is in the project root (see
+/// [`ProjectKind::gated_dirs`]).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GatedTarget {
+    pub target: String,
+    pub markers: Vec<String>,
+}
+
+/// One entry of `dev-sweep kinds`: how a kind is detected and what it cleans.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct KindInfo {
+    /// The kind, written as its config name (`DotNet`), which `--kind` and
+    /// the config accept.
+    pub kind: ProjectKind,
+    /// The name shown in tables (`.NET`), which `--kind` also accepts.
+    pub display_name: String,
+    /// The name the config's `kind_aliases` reports the kind under, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+    /// Files whose presence marks a project of this kind.
+    pub markers: Vec<String>,
+    /// Targets cleaned in every project of this kind.
+    pub targets: Vec<String>,
+    /// Targets only cleaned beside a marker of their own.
+    pub gated_targets: Vec<GatedTarget>,
+    /// A machine-wide cache, reported with `--global-cache` instead of being
+    /// found by scanning.
+    pub global: bool,
+    /// Listed in the config's `exclude_kinds`, so scans skip it.
+    pub excluded: bool,
+}
+
+/// Every kind in the order the config's `detection_priority` checks them,
+/// with its aliases and exclusions applied.
+pub fn list_kinds(config: &DevSweepConfig) -> Vec<KindInfo> {
+    let strings = |items: &[&str]| items.iter().map(|s| s.to_string()).collect();
+    ProjectKind::by_priority(&config.detection_priority)
+        .into_iter()
+        .map(|kind| KindInfo {
+            kind,
+            display_name: kind.to_string(),
+            alias: config.kind_aliases.get(&kind).cloned(),
+            markers: strings(kind.marker_files()),
+            targets: strings(kind.cleanable_dirs()),
+            gated_targets: kind
+                .gated_dirs()
+                .iter()
+                .map(|(target, markers)| GatedTarget {
+                    target: target.to_string(),
+                    markers: strings(markers),
+                })
+                .collect(),
+            global: kind.is_global(),
+            excluded: config.exclude_kinds.contains(&kind),
+        })
+        .collect()
+}
@@ -1,6 +1,20 @@
+pub mod analyze;
+pub mod check;
 pub mod cleaner;
+pub mod cleanlog;
 pub mod cli;
 pub mod config;
+pub mod duplication;
+pub mod filter;
+pub mod git;
+pub mod kinds;
+pub mod logging;
+pub mod merge;
+pub mod peaks;
+pub mod readonly;
+pub mod resume;
+pub mod scancache;
 pub mod scanner;
+pub mod selftest;
 pub mod tui;
 pub mod util;
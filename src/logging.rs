@@ -0,0 +1,55 @@
+use std::io;
+
+use tracing_subscriber::EnvFilter;
+use tracing_subscriber::fmt::format::FmtSpan;
+
+/// Levels logged when `--log-format` is given without `RUST_LOG`.
+pub const DEFAULT_LOG_FILTER: &str = "dev_sweep=info";
+
+/// How structured logs are written to stderr.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum LogFormat {
+    /// One human-readable line per event
+    #[default]
+    Text,
+    /// One JSON object per event, for log pipelines
+    Json,
+}
+
+/// The filter directives to log with, or `None` to leave logging off.
+///
+/// Logging is opt-in: it starts when `RUST_LOG` is set (and not empty) or a
+/// format was asked for, in which case [`DEFAULT_LOG_FILTER`] applies.
+pub fn log_filter(rust_log: Option<&str>, format: Option<LogFormat>) -> Option<String> {
+    match rust_log.map(str::trim).filter(|s| !s.is_empty()) {
+        Some(directives) => Some(directives.to_string()),
+        None => format.map(|_| DEFAULT_LOG_FILTER.to_string()),
+    }
+}
+
+/// Send structured logs of scans and cleans to stderr, if asked for (see
+/// [`log_filter`]).
+///
+/// Spans are logged as they close, with their duration, so each scan, sizing
+/// pass, and clean comes out as one record carrying its path and bytes. This
+/// is separate from the user-facing output, which is unchanged. `color` is
+/// whether stderr should be colored (see [`ColorMode`](crate::tui::colors::ColorMode)).
+pub fn init_logging(rust_log: Option<&str>, format: Option<LogFormat>, color: bool) {
+    let Some(directives) = log_filter(rust_log, format) else {
+        return;
+    };
+    let filter = EnvFilter::try_new(&directives).unwrap_or_else(|e| {
+        eprintln!("Ignoring invalid RUST_LOG '{directives}': {e}");
+        EnvFilter::new(DEFAULT_LOG_FILTER)
+    });
+    let builder = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_span_events(FmtSpan::CLOSE)
+        .with_writer(io::stderr);
+
+    // A subscriber may already be installed (e.g. when embedded); keep that one.
+    let _ = match format.unwrap_or_default() {
+        LogFormat::Text => builder.with_ansi(color).try_init(),
+        LogFormat::Json => builder.json().try_init(),
+    };
+}
@@ -1,84 +1,548 @@
-use std::path::PathBuf;
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::path::{Path, PathBuf};
 use std::process;
+use std::time::Duration;
 
 use anyhow::Result;
-use clap::Parser;
+use chrono::Local;
+use clap::{CommandFactory, Parser};
 
-use dev_sweep::cli::commands::{cmd_clean, cmd_config, cmd_scan, cmd_summary};
-use dev_sweep::cli::{Cli, Commands};
-use dev_sweep::config::DevSweepConfig;
-use dev_sweep::tui::colors::red_bold;
+use dev_sweep::check::{CheckAlert, CheckStatus, CheckThresholds};
+use dev_sweep::cleaner::{
+    CLEAN_FAILED_EXIT_CODE, CleanFailed, CleanOptions, INTERRUPTED_EXIT_CODE, Interrupted,
+    QUARANTINE_DIR_NAME, interrupt_flag, quarantine_batch_dir,
+};
+use dev_sweep::cli::commands::{
+    ConfirmPolicy, Confirmation, ScanArgs, cmd_analyze, cmd_check, cmd_clean, cmd_clean_auto,
+    cmd_clean_target, cmd_config, cmd_kinds, cmd_merge, cmd_prune_empty, cmd_purge_quarantine,
+    cmd_scan, cmd_selftest, cmd_summary, cmd_top, set_compact_json,
+};
+use dev_sweep::cli::{CleanFormat, Cli, Commands, ScanFormat};
+use dev_sweep::config::{DevSweepConfig, SnapshotAction};
+use dev_sweep::filter::{CleanOrder, age_cutoff};
+use dev_sweep::logging::init_logging;
+use dev_sweep::readonly::enable_read_only;
+use dev_sweep::resume::scan_checkpoint_path;
+use dev_sweep::scancache::{self, scan_cache_path};
+use dev_sweep::scanner::{
+    ChangedSince, DEFAULT_TEMP_OLDER_THAN, ScanOptions, SizeMode, resolve_artifact_dirs,
+};
+use dev_sweep::tui::colors::{self, ColorMode, red_bold, yellow};
+use dev_sweep::tui::display::{PathList, TotalFormat, set_confirm_timeout};
+use dev_sweep::tui::glyphs;
+use dev_sweep::util::{outermost_paths, parse_age, parse_duration, parse_size};
 
 fn main() {
-    if let Err(e) = run() {
+    let cli = match Cli::try_parse() {
+        Ok(cli) => cli,
+        // A monitoring system reads any exit code but 3 as a verdict on disk
+        // usage, so a bad `check` command line is UNKNOWN rather than clap's 2.
+        Err(e) if e.use_stderr() && is_check_invocation() => {
+            let _ = e.print();
+            process::exit(CheckStatus::Unknown.exit_code());
+        }
+        Err(e) => e.exit(),
+    };
+    let check = matches!(cli.command, Some(Commands::Check { .. }));
+    if let Err(e) = run(cli) {
+        // `check` has already printed its status line.
+        if let Some(CheckAlert(status)) = e.downcast_ref::<CheckAlert>() {
+            process::exit(status.exit_code());
+        }
+        if check {
+            eprintln!("  {} {}", red_bold("Error:"), e);
+            process::exit(CheckStatus::Unknown.exit_code());
+        }
+        if e.is::<Interrupted>() {
+            eprintln!("  {} {}", yellow("⚠"), e);
+            process::exit(INTERRUPTED_EXIT_CODE);
+        }
         eprintln!("  {} {}", red_bold("Error:"), e);
+        if e.is::<CleanFailed>() {
+            process::exit(CLEAN_FAILED_EXIT_CODE);
+        }
         process::exit(1);
     }
 }
 
-fn run() -> Result<()> {
-    let cli = Cli::parse();
-    let config = DevSweepConfig::load();
+/// Whether the command line names the `check` subcommand, judged leniently
+/// so that it still works when the arguments don't parse.
+fn is_check_invocation() -> bool {
+    Cli::command()
+        .ignore_errors(true)
+        .try_get_matches()
+        .is_ok_and(|matches| matches.subcommand_name() == Some("check"))
+}
+
+fn run(mut cli: Cli) -> Result<()> {
+    cli.json |= cli.json_compact;
+    set_compact_json(cli.json_compact);
+    // A terminal that can't show Unicode is unlikely to handle ANSI colors.
+    let ascii = cli.ascii || glyphs::detect_ascii(|var| std::env::var_os(var));
+    glyphs::set_ascii(ascii);
+    let mode = match cli.color {
+        _ if cli.no_color => ColorMode::Never,
+        ColorMode::Auto if ascii => ColorMode::Never,
+        mode => mode,
+    };
+    let no_color = colors::no_color_requested(|var| std::env::var_os(var));
+    colors::set_enabled(mode.resolve(io::stdout().is_terminal(), no_color));
+    init_logging(
+        std::env::var("RUST_LOG").ok().as_deref(),
+        cli.log_format,
+        mode.resolve(io::stderr().is_terminal(), no_color),
+    );
+    // Before the config loads, so that a broken config isn't backed up either
+    // (`config --reset --dry-run` only looks).
+    if matches!(
+        cli.command,
+        Some(
+            Commands::Scan {
+                read_only: true,
+                ..
+            } | Commands::Config { dry_run: true, .. }
+        )
+    ) {
+        enable_read_only();
+    }
+    let layered = DevSweepConfig::load_layered();
+    let config = layered.config.clone();
+    set_confirm_timeout(config.confirm_timeout_secs);
 
-    // CLI flags take precedence over config; config provides defaults.
+    // CLI flags take precedence over config (file plus DEV_SWEEP_* env overrides).
     let max_depth = cli.max_depth.or(config.max_depth);
+    let min_depth = cli.min_depth.or(config.min_depth);
+    if let (Some(min), Some(max)) = (min_depth, max_depth)
+        && min > max
+    {
+        anyhow::bail!("--min-depth ({}) is greater than --max-depth ({})", min, max);
+    }
+    let mut global_caches = config.global_caches.clone();
+    for kind in &cli.global_caches {
+        if !kind.is_global() {
+            anyhow::bail!("{} is not a global cache", kind);
+        }
+        if !global_caches.contains(kind) {
+            global_caches.push(*kind);
+        }
+    }
 
-    let scan_path = resolve_scan_path(&cli, &config)?;
+    // Merging reads report files rather than scanning, and its first file
+    // lands in the global path positional, so it can't go through
+    // resolve_scan_paths.
+    if let Some(Commands::Merge { ref reports }) = cli.command {
+        let files: Vec<PathBuf> = cli.path.iter().chain(reports).cloned().collect();
+        return cmd_merge(&files, total_format(&cli), cli.json);
+    }
 
-    match cli.command.unwrap_or(Commands::Scan) {
-        Commands::Scan => cmd_scan(
-            &scan_path,
+    let scan_args = ScanArgs {
+        paths: resolve_scan_paths(&cli, &config)?,
+        options: ScanOptions {
             max_depth,
-            cli.older_than.as_deref(),
-            cli.json,
-            &config,
-        ),
-        Commands::Clean { all, dry_run } => cmd_clean(
-            &scan_path,
-            max_depth,
-            cli.older_than.as_deref(),
+            min_depth,
+            strict: cli.strict,
+            global_caches,
+            one_file_system: cli.one_file_system || config.one_file_system,
+            profile: cli.profile_scan.then_some(cli.profile_top),
+            ide_caches: cli.ide_caches || config.ide_caches,
+            include_logs: cli.include_logs,
+            parallel_min_files: config.parallel_min_files,
+            parallel_min_bytes: config.parallel_min_bytes,
+            artifact_dirs: resolve_artifact_dirs(
+                &config.artifact_dirs,
+                std::env::var_os("CARGO_TARGET_DIR").as_deref(),
+            ),
+            changed_since: cli.changed_since_file.as_deref().and_then(changed_since),
+            git_gc: cli.git_gc,
+            size_mode: config.size_mode.unwrap_or(SizeMode::DiskUsage),
+            temp_older_than: cli
+                .include_temp
+                .then(|| temp_older_than(&config))
+                .transpose()?,
+            warn_depth_limit: cli.verbose,
+            follow_project_references: cli.follow_project_references,
+            resume: cli.resume.then(scan_checkpoint_path),
+            largest_first: cli.largest_first,
+            skip_files_over: cli
+                .skip_files_over
+                .as_deref()
+                .map(parse_size)
+                .transpose()
+                .map_err(|e| anyhow::anyhow!("--skip-files-over: {e}"))?,
+        },
+        older_than: cli.older_than.clone().or_else(|| config.older_than.clone()),
+        min_size: min_size(
+            cli.min_size.as_deref(),
+            config.exclude_under_size.as_deref(),
+        )?,
+        keep_recent: cli.keep_recent,
+        kinds: cli.kinds.clone(),
+        filter: cli.filter.clone(),
+        regex: cli.regex.clone(),
+        exclude_targets: config
+            .exclude_targets
+            .iter()
+            .chain(&cli.exclude_targets)
+            .cloned()
+            .collect(),
+        target_older_than: cli.target_older_than.clone(),
+        safe: cli.safe || config.safe_mode,
+        since_clean: cli.since_clean,
+        record_max: cli.record_max,
+        since_last: cli.since_last,
+        sort: cli.sort,
+        verbose: cli.verbose,
+        explain_filters: cli.explain_filters,
+        quiet: cli.quiet,
+        compact: cli.compact,
+        totals: total_format(&cli),
+        estimate_rebuild_cost: cli.estimate_rebuild_cost,
+    };
+
+    // A subcommand on the command line wins over the configured default.
+    let command = match (cli.command, config.default_command) {
+        (Some(command), _) => command,
+        (None, Some(default)) => Cli::try_parse_from(["dev-sweep", default.name()])?
+            .command
+            .unwrap_or(Commands::Scan {
+                format: ScanFormat::Text,
+                print_paths0: false,
+                targets: false,
+                read_only: false,
+            }),
+        (None, None) => Commands::Scan {
+            format: ScanFormat::Text,
+            print_paths0: false,
+            targets: false,
+            read_only: false,
+        },
+    };
+
+    match command {
+        Commands::Scan {
+            format,
+            print_paths0,
+            targets,
+            ..
+        } => {
+            let format = match format {
+                ScanFormat::Text if cli.json => ScanFormat::Json,
+                format => format,
+            };
+            let paths0 = print_paths0.then_some(if targets {
+                PathList::Targets
+            } else {
+                PathList::Projects
+            });
+            cmd_scan(&scan_args, format, paths0, &config)
+        }
+        Commands::Clean {
             all,
+            yes,
+            no_auto,
+            confirm_phrase,
             dry_run,
+            archive,
+            quarantine,
+            target,
+            auto,
+            clean_order,
+            time_budget,
+            exclude_newer_subtree,
+            force,
+            require_git_clean,
+            exclude_active_editors,
+            prune_empty,
+            ignore_errors,
+            format,
+        } => {
+            let options = CleanOptions {
+                dry_run,
+                archive_dir: archive,
+                quarantine_dir: quarantine.then(|| {
+                    let base = match config.quarantine_dir {
+                        Some(ref dir) => expand_home(dir),
+                        None => scan_args.paths[0].join(QUARANTINE_DIR_NAME),
+                    };
+                    quarantine_batch_dir(&base, Local::now())
+                }),
+                active_window: (!force && exclude_newer_subtree > 0)
+                    .then(|| Duration::from_secs(exclude_newer_subtree)),
+                require_git_clean,
+                strict: cli.strict,
+                exclude_active_editors,
+                safe_mode: scan_args.safe,
+                interrupt: Some(interrupt_flag()),
+                pre_clean_cmd: config.pre_clean_cmd.clone(),
+                post_clean_cmd: config.post_clean_cmd.clone(),
+                size_mode: scan_args.options.size_mode,
+                temp_older_than: scan_args.options.temp_older_than,
+                time_budget: time_budget.as_deref().map(parse_time_budget).transpose()?,
+            };
+            let confirm = match config.confirm_threshold.as_deref() {
+                _ if yes => ConfirmPolicy::Never,
+                Some(threshold) if !no_auto => ConfirmPolicy::Above(
+                    parse_size(threshold).map_err(|e| anyhow::anyhow!("confirm_threshold: {e}"))?,
+                ),
+                _ => ConfirmPolicy::Always,
+            };
+            // `--all` cleans freeing more than this need the phrase typed out.
+            let phrase_above = match config.confirm_phrase_above.as_deref() {
+                _ if yes => None,
+                _ if confirm_phrase => Some(0),
+                Some(size) => Some(
+                    parse_size(size).map_err(|e| anyhow::anyhow!("confirm_phrase_above: {e}"))?,
+                ),
+                None => None,
+            };
+            let order: CleanOrder = match clean_order {
+                Some(order) => order.parse()?,
+                None => match config.clean_order.as_deref() {
+                    Some(order) => order
+                        .parse()
+                        .map_err(|e| anyhow::anyhow!("clean_order: {e}"))?,
+                    None => CleanOrder::default(),
+                },
+            };
+            let format = match format {
+                CleanFormat::Text if cli.json => CleanFormat::Json,
+                format => format,
+            };
+            if prune_empty && format == CleanFormat::Events {
+                anyhow::bail!("--prune-empty can't be combined with --format events");
+            }
+            let cleaned = match target {
+                _ if auto => cmd_clean_auto(
+                    &scan_args,
+                    &config.auto_clean_kinds,
+                    &options,
+                    &order,
+                    format,
+                    &config,
+                )
+                .map(|()| true),
+                Some(name) => cmd_clean_target(
+                    &scan_args, &name, &options, &order, confirm, format, &config,
+                ),
+                None => cmd_clean(
+                    &scan_args,
+                    all,
+                    &options,
+                    &order,
+                    Confirmation {
+                        policy: confirm,
+                        phrase_above,
+                    },
+                    format,
+                    &config,
+                ),
+            };
+            // Failed targets were already reported; only the exit status is left.
+            let cleaned = match cleaned {
+                Err(e) if e.is::<CleanFailed>() && ignore_errors && !cli.strict => Ok(true),
+                other => other,
+            };
+            // Not after a declined confirmation: answering "n" removes nothing.
+            let finished = match &cleaned {
+                Ok(went_ahead) => *went_ahead,
+                Err(e) => e.is::<CleanFailed>(),
+            };
+            if prune_empty && finished {
+                let json = format == CleanFormat::Json;
+                cmd_prune_empty(&scan_args, all || auto, dry_run, json, &config)?;
+            }
+            cleaned.map(|_| ())
+        }
+        Commands::Summary {
+            tree,
+            tree_depth,
+            duplication,
+            by_owner,
+            by_filesystem,
+        } => cmd_summary(
+            &scan_args,
+            tree.then_some(tree_depth),
+            duplication,
+            by_owner,
+            by_filesystem,
             cli.json,
             &config,
         ),
-        Commands::Summary => cmd_summary(
-            &scan_path,
-            max_depth,
-            cli.older_than.as_deref(),
-            cli.json,
-            &config,
-        ),
-        Commands::Config { show, reset } => cmd_config(show, reset),
+        Commands::Top { count } => cmd_top(&scan_args, count, cli.json, &config),
+        Commands::Analyze { count } => cmd_analyze(&scan_args, count, cli.json, &config),
+        Commands::Check { warn, crit } => {
+            let thresholds = CheckThresholds {
+                warn: threshold("--warn", warn.as_deref())?,
+                crit: threshold("--crit", crit.as_deref())?,
+            };
+            if let (Some(w), Some(c)) = (thresholds.warn, thresholds.crit)
+                && w > c
+            {
+                anyhow::bail!(
+                    "--warn ({}) is above --crit ({})",
+                    warn.unwrap_or_default(),
+                    crit.unwrap_or_default()
+                );
+            }
+            cmd_check(&scan_args, thresholds, cli.json, &config)
+        }
+        Commands::PurgeQuarantine { all, yes, dry_run } => {
+            let cutoff = cli.older_than.as_deref().map(age_cutoff).transpose()?;
+            // Purging the whole quarantine is asked about like a clean is;
+            // an age limit or `--all` is already a deliberate choice.
+            let confirm = if all || yes || cutoff.is_some() {
+                ConfirmPolicy::Never
+            } else {
+                ConfirmPolicy::Always
+            };
+            cmd_purge_quarantine(cutoff, confirm, dry_run, cli.json)
+        }
+        Commands::Kinds => cmd_kinds(cli.json, &config),
+        Commands::Merge { .. } => unreachable!("merge is handled before scanning"),
+        Commands::Selftest => cmd_selftest(cli.json),
+        Commands::Config {
+            show,
+            reset,
+            print_path,
+            edit,
+            dry_run,
+            snapshot,
+            restore,
+            list_snapshots,
+        } => {
+            let snapshot = match (snapshot, restore) {
+                (Some(name), _) => Some(SnapshotAction::Save(name)),
+                (_, Some(name)) => Some(SnapshotAction::Restore(name)),
+                _ => list_snapshots.then_some(SnapshotAction::List),
+            };
+            cmd_config(show, reset, print_path, edit, dry_run, snapshot, &layered)
+        }
     }
 }
 
-/// Determine the scan path from CLI args, config defaults, or the current directory.
+/// Determine the scan roots from CLI args, config defaults, or the current directory.
 ///
-/// Priority: CLI `--path` > config `default_roots[0]` > current directory.
-fn resolve_scan_path(cli: &Cli, config: &DevSweepConfig) -> Result<PathBuf> {
-    let raw = if let Some(ref p) = cli.path {
-        p.clone()
-    } else if let Some(first) = config.default_roots.first() {
-        first.clone()
-    } else {
-        std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."))
+/// Priority: CLI `--path` > every config `default_roots` entry > current
+/// directory. A CLI path must be a directory. Configured roots that aren't are
+/// skipped with a warning (unless none are left), and they are canonicalized so
+/// duplicates and roots inside another root are scanned only once.
+fn resolve_scan_paths(cli: &Cli, config: &DevSweepConfig) -> Result<Vec<PathBuf>> {
+    if let Some(ref p) = cli.path {
+        let path = expand_home(p);
+        if !path.is_dir() {
+            anyhow::bail!(
+                "Path does not exist or is not a directory: {}",
+                path.display()
+            );
+        }
+        return Ok(vec![path]);
+    }
+    if config.default_roots.is_empty() {
+        return Ok(vec![
+            std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
+        ]);
+    }
+
+    let mut roots = Vec::new();
+    for raw in &config.default_roots {
+        let path = expand_home(raw);
+        match fs::canonicalize(&path) {
+            Ok(canonical) if canonical.is_dir() => roots.push(canonical),
+            _ => eprintln!(
+                "  {} Skipping default root (not a directory): {}",
+                yellow("⚠"),
+                path.display()
+            ),
+        }
+    }
+    if roots.is_empty() {
+        anyhow::bail!("None of the configured default_roots is a directory");
+    }
+    Ok(outermost_paths(roots))
+}
+
+/// The `--min-size` floor in bytes: the flag, else `exclude_under_size` from
+/// the config. A floor of zero (`--min-size 0`) means no floor at all.
+fn min_size(flag: Option<&str>, configured: Option<&str>) -> Result<Option<u64>> {
+    let bytes = match (flag, configured) {
+        (Some(size), _) => parse_size(size).map_err(|e| anyhow::anyhow!("--min-size: {e}"))?,
+        (None, Some(size)) => {
+            parse_size(size).map_err(|e| anyhow::anyhow!("exclude_under_size: {e}"))?
+        }
+        (None, None) => 0,
     };
+    Ok((bytes > 0).then_some(bytes))
+}
 
-    let expanded = if raw.starts_with("~") {
-        dirs::home_dir()
-            .unwrap_or_default()
-            .join(raw.strip_prefix("~").unwrap_or(&raw))
+/// How grand totals are printed: hidden with `--no-total`, exact with
+/// `--total-bytes`, human-readable otherwise.
+fn total_format(cli: &Cli) -> TotalFormat {
+    if cli.no_total {
+        TotalFormat::Hidden
+    } else if cli.total_bytes {
+        TotalFormat::Bytes
     } else {
-        raw
+        TotalFormat::Human
+    }
+}
+
+/// Parse a `check --warn` or `--crit` size.
+fn threshold(flag: &str, size: Option<&str>) -> Result<Option<u64>> {
+    size.map(|s| parse_size(s).map_err(|e| anyhow::anyhow!("{flag}: {e}")))
+        .transpose()
+}
+
+/// How long temp directory entries must have gone unmodified for
+/// `--include-temp`: `temp_older_than` from the config, or a week.
+fn temp_older_than(config: &DevSweepConfig) -> Result<Duration> {
+    let Some(ref age) = config.temp_older_than else {
+        return Ok(DEFAULT_TEMP_OLDER_THAN);
     };
+    let age = parse_age(age)
+        .map_err(|e| anyhow::anyhow!("temp_older_than: {e}"))?
+        .to_std()
+        .unwrap_or_default();
+    if age.is_zero() {
+        anyhow::bail!("temp_older_than must be at least 1d, so active temp files are left alone");
+    }
+    Ok(age)
+}
 
-    if !expanded.is_dir() {
-        anyhow::bail!(
-            "Path does not exist or is not a directory: {}",
-            expanded.display()
-        );
+/// Parse `--time-budget`, which has to leave time for at least one project.
+fn parse_time_budget(budget: &str) -> Result<Duration> {
+    let budget = parse_duration(budget).map_err(|e| anyhow::anyhow!("--time-budget: {e}"))?;
+    if budget.is_zero() {
+        anyhow::bail!("--time-budget must be more than zero");
     }
+    Ok(budget)
+}
 
-    Ok(expanded)
+/// The cutoff for `--changed-since-file`: the marker's mtime, with the scan
+/// cache to stand in for what's skipped. Without a readable marker (say, on
+/// the first run), everything is scanned.
+fn changed_since(marker: &Path) -> Option<ChangedSince> {
+    let marker = expand_home(marker);
+    match fs::metadata(&marker).and_then(|m| m.modified()) {
+        Ok(time) => Some(ChangedSince {
+            time,
+            cache: scancache::load(&scan_cache_path()),
+        }),
+        Err(_) => {
+            eprintln!(
+                "  {} No marker at {}; scanning everything",
+                yellow("⚠"),
+                marker.display()
+            );
+            None
+        }
+    }
+}
+
+/// Replace a leading `~` with the home directory.
+fn expand_home(path: &Path) -> PathBuf {
+    match path.strip_prefix("~") {
+        Ok(rest) => dirs::home_dir().unwrap_or_default().join(rest),
+        Err(_) => path.to_path_buf(),
+    }
 }
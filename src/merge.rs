@@ -0,0 +1,219 @@
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::scanner::ScannedProject;
+
+/// Version of the `--json` report format, stamped on every report as
+/// `schema_version`. Reports from before it was added carry no version and
+/// count as version 1, whose format they share.
+pub const JSON_SCHEMA_VERSION: u32 = 1;
+
+/// What `merge` takes from one `scan --json` or `summary --json` report.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReportTotals {
+    /// The file the report was read from.
+    pub source: PathBuf,
+    pub schema_version: u32,
+    /// The machine that produced the report, if it recorded one.
+    pub host: Option<String>,
+    pub scanned_at: Option<DateTime<FixedOffset>>,
+    pub projects: u64,
+    pub reclaimable_bytes: u64,
+    /// Projects and reclaimable bytes by the kind shown in tables (aliases and
+    /// polyglot kinds included, as in `summary`).
+    pub by_kind: BTreeMap<String, (u64, u64)>,
+}
+
+/// A `summary --json` `by_kind` entry.
+#[derive(Deserialize)]
+struct KindGroup {
+    kind: String,
+    projects: u64,
+    reclaimable_bytes: u64,
+}
+
+/// Read the totals out of the JSON report `contents`, which came from `source`.
+///
+/// Scan reports are totaled from their `projects`; summary reports already
+/// carry totals. Any other report (a clean, `--since-last`, `check`, ...) is
+/// an error.
+pub fn read_report(source: &Path, contents: &str) -> Result<ReportTotals> {
+    let report: Value = serde_json::from_str(contents)
+        .with_context(|| format!("{} is not a JSON report", source.display()))?;
+    let field = |key: &str| report.get(key).filter(|v| !v.is_null());
+    let schema_version = match field("schema_version") {
+        Some(v) => v
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .with_context(|| format!("{}: schema_version is not a number", source.display()))?,
+        None => 1,
+    };
+    let mut totals = ReportTotals {
+        source: source.to_path_buf(),
+        schema_version,
+        host: field("host").and_then(Value::as_str).map(String::from),
+        scanned_at: field("scanned_at")
+            .and_then(Value::as_str)
+            .and_then(|s| DateTime::parse_from_rfc3339(s).ok()),
+        projects: 0,
+        reclaimable_bytes: 0,
+        by_kind: BTreeMap::new(),
+    };
+    let invalid = || format!("{}: unexpected report contents", source.display());
+
+    if let Some(projects) = field("projects") {
+        let projects: Vec<ScannedProject> =
+            serde_json::from_value(projects.clone()).with_context(invalid)?;
+        for p in &projects {
+            let entry = totals.by_kind.entry(p.display_kind()).or_default();
+            entry.0 += 1;
+            entry.1 += p.total_cleanable_bytes;
+        }
+        totals.projects = projects.len() as u64;
+        totals.reclaimable_bytes = projects.iter().map(|p| p.total_cleanable_bytes).sum();
+    } else if let Some(groups) = field("by_kind") {
+        let groups: Vec<KindGroup> =
+            serde_json::from_value(groups.clone()).with_context(invalid)?;
+        for g in groups {
+            let entry = totals.by_kind.entry(g.kind).or_default();
+            entry.0 += g.projects;
+            entry.1 += g.reclaimable_bytes;
+        }
+        totals.projects = totals.by_kind.values().map(|(count, _)| count).sum();
+        totals.reclaimable_bytes = totals.by_kind.values().map(|(_, bytes)| bytes).sum();
+    } else {
+        anyhow::bail!(
+            "{} is neither a scan nor a summary report (no projects or by_kind)",
+            source.display()
+        );
+    }
+    Ok(totals)
+}
+
+/// One kind's share of a [`MergedReport`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct KindTotals {
+    pub kind: String,
+    /// How many of the reports list the kind.
+    pub reports: u64,
+    pub projects: u64,
+    pub reclaimable_bytes: u64,
+}
+
+/// One machine's share of a [`MergedReport`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HostTotals {
+    pub host: String,
+    /// How many of the reports came from the machine.
+    pub reports: u64,
+    pub projects: u64,
+    pub reclaimable_bytes: u64,
+}
+
+/// Several reports added up by `dev-sweep merge`.
+///
+/// `by_kind` has the same shape as in `summary --json`, so a merged report can
+/// be merged again.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MergedReport {
+    /// The files merged, in the order given.
+    pub sources: Vec<PathBuf>,
+    /// The span of the reports' `scanned_at` times, if they recorded any.
+    pub first_scanned_at: Option<DateTime<FixedOffset>>,
+    pub last_scanned_at: Option<DateTime<FixedOffset>>,
+    pub total_projects: u64,
+    pub total_reclaimable_bytes: u64,
+    /// Largest first.
+    pub by_kind: Vec<KindTotals>,
+    /// Largest first. Reports that recorded no host aren't listed.
+    pub by_host: Vec<HostTotals>,
+}
+
+/// Add up `reports`. They must all have the same schema version, one this
+/// build understands; otherwise the mismatch is the error.
+pub fn merge_reports(reports: &[ReportTotals]) -> Result<MergedReport> {
+    let Some(first) = reports.first() else {
+        anyhow::bail!("No reports to merge");
+    };
+    if reports
+        .iter()
+        .any(|r| r.schema_version != first.schema_version)
+    {
+        let versions: Vec<String> = reports
+            .iter()
+            .map(|r| format!("{} (v{})", r.source.display(), r.schema_version))
+            .collect();
+        anyhow::bail!(
+            "Reports have different schema versions and can't be merged: {}",
+            versions.join(", ")
+        );
+    }
+    if first.schema_version > JSON_SCHEMA_VERSION {
+        anyhow::bail!(
+            "{} has schema version {}, but this dev-sweep reads up to version {}",
+            first.source.display(),
+            first.schema_version,
+            JSON_SCHEMA_VERSION
+        );
+    }
+
+    // Reports, projects, and bytes for each kind and each host.
+    let mut by_kind: BTreeMap<&str, (u64, u64, u64)> = BTreeMap::new();
+    let mut by_host: BTreeMap<&str, (u64, u64, u64)> = BTreeMap::new();
+    for report in reports {
+        for (kind, (projects, bytes)) in &report.by_kind {
+            add(by_kind.entry(kind).or_default(), *projects, *bytes);
+        }
+        if let Some(ref host) = report.host {
+            add(
+                by_host.entry(host).or_default(),
+                report.projects,
+                report.reclaimable_bytes,
+            );
+        }
+    }
+
+    let mut by_kind: Vec<KindTotals> = by_kind
+        .into_iter()
+        .map(|(kind, (reports, projects, bytes))| KindTotals {
+            kind: kind.to_string(),
+            reports,
+            projects,
+            reclaimable_bytes: bytes,
+        })
+        .collect();
+    by_kind.sort_by_key(|k| Reverse(k.reclaimable_bytes));
+    let mut by_host: Vec<HostTotals> = by_host
+        .into_iter()
+        .map(|(host, (reports, projects, bytes))| HostTotals {
+            host: host.to_string(),
+            reports,
+            projects,
+            reclaimable_bytes: bytes,
+        })
+        .collect();
+    by_host.sort_by_key(|h| Reverse(h.reclaimable_bytes));
+
+    Ok(MergedReport {
+        sources: reports.iter().map(|r| r.source.clone()).collect(),
+        first_scanned_at: reports.iter().filter_map(|r| r.scanned_at).min(),
+        last_scanned_at: reports.iter().filter_map(|r| r.scanned_at).max(),
+        total_projects: reports.iter().map(|r| r.projects).sum(),
+        total_reclaimable_bytes: reports.iter().map(|r| r.reclaimable_bytes).sum(),
+        by_kind,
+        by_host,
+    })
+}
+
+/// Count one more report with `projects` and `bytes` towards a group.
+fn add(group: &mut (u64, u64, u64), projects: u64, bytes: u64) {
+    group.0 += 1;
+    group.1 += projects;
+    group.2 += bytes;
+}
@@ -0,0 +1,83 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::readonly::ensure_writable;
+use crate::scanner::ScannedProject;
+
+/// The largest reclaimable size `--record-max` has seen for one project.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Peak {
+    pub bytes: u64,
+    /// The scan that first saw this size.
+    pub recorded_at: DateTime<Local>,
+}
+
+/// Each project's peak reclaimable size, keyed by canonical project path.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PeakHistory {
+    pub projects: BTreeMap<PathBuf, Peak>,
+}
+
+/// Default location of the peak history (~/.local/share/dev-sweep/peaks.json).
+pub fn peak_history_path() -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("~/.local/share"))
+        .join("dev-sweep")
+        .join("peaks.json")
+}
+
+/// Read the history at `path`. A missing or unreadable history is empty.
+pub fn load(path: &Path) -> PeakHistory {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+/// Write the history to `path`, creating parent directories.
+pub fn save(path: &Path, history: &PeakHistory) -> Result<()> {
+    ensure_writable("write the peak history")?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, serde_json::to_string_pretty(history)?)
+        .with_context(|| format!("Failed to write peak history: {}", path.display()))
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+impl PeakHistory {
+    /// Raise each project's peak to its current size where that is larger,
+    /// as seen by a scan at `now`. Projects that weren't found are kept, so a
+    /// peak survives cleans and narrower scans.
+    pub fn record(&mut self, projects: &[ScannedProject], now: DateTime<Local>) {
+        for project in projects {
+            let bytes = project.total_cleanable_bytes;
+            let peak = self
+                .projects
+                .entry(canonical(&project.path))
+                .or_insert(Peak {
+                    bytes,
+                    recorded_at: now,
+                });
+            if bytes > peak.bytes {
+                *peak = Peak {
+                    bytes,
+                    recorded_at: now,
+                };
+            }
+        }
+    }
+
+    /// The recorded peak of `project`, if it has one.
+    pub fn peak(&self, project: &ScannedProject) -> Option<&Peak> {
+        self.projects.get(&canonical(&project.path))
+    }
+}
@@ -0,0 +1,26 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use anyhow::Result;
+
+/// Set once by `scan --read-only`; never cleared for the rest of the process.
+static READ_ONLY: AtomicBool = AtomicBool::new(false);
+
+/// Promise that this process won't modify the filesystem: from now on every
+/// write dev-sweep makes (removing or archiving targets, pruning, `git gc`,
+/// clean hooks, the scan cache, the clean log, the config) fails instead.
+pub fn enable_read_only() {
+    READ_ONLY.store(true, Ordering::SeqCst);
+}
+
+/// Whether [`enable_read_only`] has been called.
+pub fn is_read_only() -> bool {
+    READ_ONLY.load(Ordering::SeqCst)
+}
+
+/// Fail if the process is read-only, naming the write that was refused.
+pub fn ensure_writable(what: &str) -> Result<()> {
+    if is_read_only() {
+        anyhow::bail!("Refusing to {what}: this run is read-only (--read-only)");
+    }
+    Ok(())
+}
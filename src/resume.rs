@@ -0,0 +1,87 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::readonly::ensure_writable;
+use crate::scanner::ScannedProject;
+
+/// How far `--resume` scans have got: for each scan root (canonical), the
+/// top-level directories already walked and sized, by name, with the projects
+/// found in each. The root itself, which may be a project too, is recorded
+/// under an empty name.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScanCheckpoint {
+    pub roots: BTreeMap<PathBuf, BTreeMap<PathBuf, Vec<ScannedProject>>>,
+}
+
+/// Default location of the checkpoint (~/.local/share/dev-sweep/scan-resume.json).
+pub fn scan_checkpoint_path() -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("~/.local/share"))
+        .join("dev-sweep")
+        .join("scan-resume.json")
+}
+
+/// Read the checkpoint at `path`. A missing or unreadable checkpoint is
+/// empty, so the scan starts over.
+pub fn load(path: &Path) -> ScanCheckpoint {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+/// Write the checkpoint to `path`, creating parent directories. An empty
+/// checkpoint removes the file instead.
+pub fn save(path: &Path, checkpoint: &ScanCheckpoint) -> Result<()> {
+    ensure_writable("write the scan checkpoint")?;
+    if checkpoint.roots.is_empty() {
+        return match fs::remove_file(path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e)
+                .with_context(|| format!("Failed to remove scan checkpoint: {}", path.display())),
+            _ => Ok(()),
+        };
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, serde_json::to_string_pretty(checkpoint)?)
+        .with_context(|| format!("Failed to write scan checkpoint: {}", path.display()))
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// `dir` relative to `root`: a name, or empty for the root itself.
+fn part_name(root: &Path, dir: &Path) -> PathBuf {
+    dir.strip_prefix(root).unwrap_or(dir).to_path_buf()
+}
+
+impl ScanCheckpoint {
+    /// What was found under `dir`, a top-level directory of `root` (or `root`
+    /// itself), if a previous scan finished it.
+    pub fn done(&self, root: &Path, dir: &Path) -> Option<&[ScannedProject]> {
+        self.roots
+            .get(&canonical(root))?
+            .get(&part_name(root, dir))
+            .map(Vec::as_slice)
+    }
+
+    /// Record `dir` under `root` as finished, with the projects found in it.
+    pub fn record(&mut self, root: &Path, dir: &Path, projects: Vec<ScannedProject>) {
+        self.roots
+            .entry(canonical(root))
+            .or_default()
+            .insert(part_name(root, dir), projects);
+    }
+
+    /// Forget `root`, once its scan has run to the end.
+    pub fn finish(&mut self, root: &Path) {
+        self.roots.remove(&canonical(root));
+    }
+}
@@ -0,0 +1,267 @@
+use std::fs;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::readonly::ensure_writable;
+use crate::scanner::{CleanTarget, ProjectKind, ScannedProject};
+use crate::util::{device_id, long_path, owner_uid};
+
+/// What a previous `scan` found for one project.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CachedProject {
+    /// Canonical project root.
+    pub path: PathBuf,
+    pub name: String,
+    pub kind: ProjectKind,
+    /// Further kinds detected in the project directory (see
+    /// [`ScannedProject::other_kinds`]).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub other_kinds: Vec<ProjectKind>,
+    /// Reclaimable bytes at the time of the scan.
+    pub bytes: u64,
+    /// When the project was last modified, as of the scan.
+    #[serde(default)]
+    pub last_modified: Option<DateTime<Local>>,
+    /// The project's clean targets and their sizes, so it can be reported again
+    /// without re-sizing (see `--changed-since-file`). Empty in caches written
+    /// before targets were recorded.
+    #[serde(default)]
+    pub targets: Vec<CleanTarget>,
+}
+
+impl CachedProject {
+    /// Every kind detected in the project directory, `kind` first.
+    pub fn kinds(&self) -> Vec<ProjectKind> {
+        std::iter::once(self.kind)
+            .chain(self.other_kinds.iter().copied())
+            .collect()
+    }
+
+    /// The cached project as if scanned at `path` (its root as the walk sees
+    /// it), or `None` if the cache doesn't hold enough to rebuild it.
+    ///
+    /// Targets that no longer exist (removed outside dev-sweep since) are
+    /// dropped, and a project left without any is `None` too, so it's sized
+    /// again. Protection is left to be reapplied, as the current run's
+    /// `--safe` asks.
+    pub fn to_project(&self, path: &Path) -> Option<ScannedProject> {
+        let last_modified = self.last_modified?;
+        let clean_targets: Vec<CleanTarget> = self
+            .targets
+            .iter()
+            .map(|t| CleanTarget {
+                path: match t.path.strip_prefix(&self.path) {
+                    Ok(rel) => path.join(rel),
+                    Err(_) => t.path.clone(),
+                },
+                protected: false,
+                ..t.clone()
+            })
+            .filter(|t| fs::symlink_metadata(long_path(&t.path)).is_ok())
+            .collect();
+        if clean_targets.is_empty() {
+            return None;
+        }
+        Some(ScannedProject {
+            path: path.to_path_buf(),
+            kind: self.kind,
+            other_kinds: self.other_kinds.clone(),
+            name: self.name.clone(),
+            last_modified,
+            total_cleanable_bytes: clean_targets.iter().map(|t| t.size_bytes).sum(),
+            clean_targets,
+            largest_file: None,
+            kind_alias: None,
+            rebuild_cost: None,
+            owner: owner_uid(path),
+            device: device_id(path),
+            workspace_members: Vec::new(),
+        })
+    }
+}
+
+/// The results of past scans, keyed by canonical project path.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScanCache {
+    /// When the cache was last updated.
+    pub timestamp: Option<DateTime<Local>>,
+    /// Sorted by path, so lookups can binary search and the projects under a
+    /// root form one contiguous run.
+    pub projects: Vec<CachedProject>,
+}
+
+/// How a project's reclaimable size changed since the cached scan.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeStatus {
+    /// Not in the cache.
+    New,
+    /// In the cache with a different size.
+    Changed,
+    /// In the cache but no longer found.
+    Removed,
+}
+
+/// One project whose reclaimable size differs from the cached scan.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProjectChange {
+    pub path: PathBuf,
+    pub name: String,
+    pub kind: ProjectKind,
+    pub status: ChangeStatus,
+    pub previous_bytes: u64,
+    pub current_bytes: u64,
+}
+
+impl ProjectChange {
+    /// Growth in bytes (negative if the project shrank or was removed).
+    pub fn delta(&self) -> i128 {
+        self.current_bytes as i128 - self.previous_bytes as i128
+    }
+}
+
+/// Default location of the scan cache (~/.local/share/dev-sweep/scan-cache.json).
+pub fn scan_cache_path() -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("~/.local/share"))
+        .join("dev-sweep")
+        .join("scan-cache.json")
+}
+
+/// Read the cache at `path`. A missing or unreadable cache is empty.
+pub fn load(path: &Path) -> ScanCache {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+/// Write the cache to `path`, creating parent directories.
+pub fn save(path: &Path, cache: &ScanCache) -> Result<()> {
+    ensure_writable("write the scan cache")?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, serde_json::to_string_pretty(cache)?)
+        .with_context(|| format!("Failed to write scan cache: {}", path.display()))
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+impl ScanCache {
+    /// Record `projects` as the latest scan of `roots`.
+    ///
+    /// With `complete` set (no filters narrowed the scan), cached projects under
+    /// any of `roots` that weren't found are dropped; otherwise they are kept,
+    /// since a filter may simply have hidden them.
+    pub fn update(&mut self, roots: &[PathBuf], projects: &[ScannedProject], complete: bool) {
+        if complete {
+            for root in roots {
+                let range = self.under_root(&canonical(root));
+                self.projects.drain(range);
+            }
+        }
+        for project in projects {
+            let cached = CachedProject {
+                path: canonical(&project.path),
+                name: project.name.clone(),
+                kind: project.kind,
+                other_kinds: project.other_kinds.clone(),
+                bytes: project.total_cleanable_bytes,
+                last_modified: Some(project.last_modified),
+                targets: project.clean_targets.clone(),
+            };
+            match self.position(&cached.path) {
+                Ok(i) => self.projects[i] = cached,
+                Err(i) => self.projects.insert(i, cached),
+            }
+        }
+        self.timestamp = Some(Local::now());
+    }
+
+    /// Index of the cached project at `path`, or where it would be inserted.
+    fn position(&self, path: &Path) -> Result<usize, usize> {
+        self.projects
+            .binary_search_by(|c| c.path.as_path().cmp(path))
+    }
+
+    /// The cached projects at or below `root` (a canonical path).
+    pub fn projects_under(&self, root: &Path) -> &[CachedProject] {
+        &self.projects[self.under_root(root)]
+    }
+
+    /// Index range of the cached projects at or below `root`.
+    fn under_root(&self, root: &Path) -> Range<usize> {
+        let start = self.projects.partition_point(|c| c.path.as_path() < root);
+        let len = self.projects[start..]
+            .iter()
+            .take_while(|c| c.path.starts_with(root))
+            .count();
+        start..start + len
+    }
+
+    /// Projects under `roots` whose reclaimable size differs from the cache.
+    ///
+    /// Cached projects that weren't found are only reported as removed when
+    /// `include_removed` is set (the scan wasn't narrowed by filters). Largest
+    /// change first, ties broken by path.
+    pub fn diff(
+        &self,
+        roots: &[PathBuf],
+        projects: &[ScannedProject],
+        include_removed: bool,
+    ) -> Vec<ProjectChange> {
+        let mut seen = Vec::with_capacity(projects.len());
+        let mut changes = Vec::new();
+        for project in projects {
+            let path = canonical(&project.path);
+            let change = |status, previous_bytes| ProjectChange {
+                path: path.clone(),
+                name: project.name.clone(),
+                kind: project.kind,
+                status,
+                previous_bytes,
+                current_bytes: project.total_cleanable_bytes,
+            };
+            match self.position(&path).map(|i| &self.projects[i]) {
+                Err(_) => changes.push(change(ChangeStatus::New, 0)),
+                Ok(c) if c.bytes != project.total_cleanable_bytes => {
+                    changes.push(change(ChangeStatus::Changed, c.bytes))
+                }
+                Ok(_) => {}
+            }
+            seen.push(path);
+        }
+
+        if include_removed {
+            for root in roots {
+                for c in &self.projects[self.under_root(&canonical(root))] {
+                    if !seen.contains(&c.path) {
+                        changes.push(ProjectChange {
+                            path: c.path.clone(),
+                            name: c.name.clone(),
+                            kind: c.kind,
+                            status: ChangeStatus::Removed,
+                            previous_bytes: c.bytes,
+                            current_bytes: 0,
+                        });
+                    }
+                }
+            }
+        }
+
+        changes.sort_by(|a, b| {
+            b.delta()
+                .unsigned_abs()
+                .cmp(&a.delta().unsigned_abs())
+                .then_with(|| a.path.cmp(&b.path))
+        });
+        changes
+    }
+}
@@ -0,0 +1,337 @@
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+
+use super::project::{ProjectKind, ScannedProject};
+use super::size::Sizer;
+use super::walk::{ScanOptions, analyze_project, as_clean_target};
+use crate::util::{device_id, owner_uid};
+
+/// Reports opt-in, machine-wide caches that live outside any single project.
+///
+/// These are never found by walking a scan root: each kind knows where its cache
+/// lives (honoring the owning tool's environment overrides) and is only reported
+/// when explicitly requested. Clearing one affects every project that uses it.
+pub fn scan_global_caches(kinds: &[ProjectKind], strict: bool) -> Result<Vec<ScannedProject>> {
+    let mut projects = Vec::new();
+
+    for &kind in kinds {
+        if !kind.is_global() {
+            anyhow::bail!("{} is not a global cache", kind);
+        }
+
+        match global_cache_root(kind) {
+            Some(root) if root.is_dir() => projects.push(scan_global_cache_at(kind, &root)?),
+            Some(root) if strict => {
+                anyhow::bail!("{} not found at {}", global_label(kind), root.display())
+            }
+            None if strict => anyhow::bail!("Could not locate {}", global_label(kind)),
+            _ => {}
+        }
+
+        // sccache is optional, so its absence is never an error.
+        if kind == ProjectKind::CargoCache
+            && let Some(dir) = resolve_sccache_dir(
+                std::env::var_os("SCCACHE_DIR").as_deref(),
+                dirs::cache_dir().as_deref(),
+            )
+            && dir.is_dir()
+        {
+            projects.push(scan_sccache_at(&dir)?);
+        }
+
+        // Likewise the Android plugin's build cache, kept apart from Gradle's.
+        if kind == ProjectKind::AndroidCache
+            && let Some(dir) = resolve_android_user_home(
+                std::env::var_os("ANDROID_USER_HOME").as_deref(),
+                std::env::var_os("ANDROID_SDK_HOME").as_deref(),
+                dirs::home_dir().as_deref(),
+            )
+            && dir.is_dir()
+        {
+            let mut project = analyze_project(&dir, kind)?;
+            project.name = "Android build cache (global cache)".to_string();
+            projects.push(project);
+        }
+    }
+    projects.retain(|p| p.total_cleanable_bytes > 0);
+
+    Ok(projects)
+}
+
+/// Analyze a global cache rooted at `root`, labeling it so it can't be mistaken
+/// for an ordinary project.
+pub fn scan_global_cache_at(kind: ProjectKind, root: &Path) -> Result<ScannedProject> {
+    let mut project = analyze_project(root, kind)?;
+    project.name = format!("{} (global cache)", global_label(kind));
+    Ok(project)
+}
+
+/// Where a global cache lives on this machine, if it can be determined.
+pub fn global_cache_root(kind: ProjectKind) -> Option<PathBuf> {
+    match kind {
+        ProjectKind::Julia => resolve_julia_depot(
+            std::env::var_os("JULIA_DEPOT_PATH").as_deref(),
+            dirs::home_dir().as_deref(),
+        ),
+        ProjectKind::Poetry => {
+            let config_dir = std::env::var_os("POETRY_CONFIG_DIR")
+                .map(PathBuf::from)
+                .or_else(|| dirs::config_dir().map(|d| d.join("pypoetry")));
+            let config = config_dir.and_then(|d| fs::read_to_string(d.join("config.toml")).ok());
+            // Poetry uses %LOCALAPPDATA%\pypoetry\Cache on Windows, <cache>/pypoetry elsewhere.
+            let platform_cache = dirs::cache_dir().map(|d| {
+                if cfg!(windows) {
+                    d.join("pypoetry").join("Cache")
+                } else {
+                    d.join("pypoetry")
+                }
+            });
+            resolve_poetry_virtualenvs(
+                std::env::var_os("POETRY_VIRTUALENVS_PATH").as_deref(),
+                std::env::var_os("POETRY_CACHE_DIR").as_deref(),
+                config.as_deref(),
+                platform_cache.as_deref(),
+            )
+        }
+        ProjectKind::Conda => {
+            let home = dirs::home_dir();
+            let condarc = home
+                .as_ref()
+                .and_then(|h| fs::read_to_string(h.join(".condarc")).ok());
+            let envs_path =
+                std::env::var_os("CONDA_ENVS_PATH").or_else(|| std::env::var_os("CONDA_ENVS_DIRS"));
+            resolve_conda_envs(
+                envs_path.as_deref(),
+                condarc.as_deref(),
+                std::env::var_os("CONDA_EXE").as_deref().map(Path::new),
+                home.as_deref(),
+            )
+        }
+        ProjectKind::CargoCache => resolve_cargo_home(
+            std::env::var_os("CARGO_HOME").as_deref(),
+            dirs::home_dir().as_deref(),
+        ),
+        ProjectKind::AndroidCache => resolve_gradle_caches(
+            std::env::var_os("GRADLE_USER_HOME").as_deref(),
+            dirs::home_dir().as_deref(),
+        ),
+        _ => None,
+    }
+}
+
+/// Resolve Gradle's cache directory: `caches/` in `GRADLE_USER_HOME`, or in
+/// `~/.gradle` without it.
+pub fn resolve_gradle_caches(
+    gradle_user_home: Option<&OsStr>,
+    home: Option<&Path>,
+) -> Option<PathBuf> {
+    gradle_user_home
+        .filter(|p| !p.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| home.map(|h| h.join(".gradle")))
+        .map(|dir| dir.join("caches"))
+}
+
+/// Resolve the Android tools' user directory: `ANDROID_USER_HOME`, then
+/// `.android` in the older `ANDROID_SDK_HOME`, then `~/.android`.
+pub fn resolve_android_user_home(
+    android_user_home: Option<&OsStr>,
+    android_sdk_home: Option<&OsStr>,
+    home: Option<&Path>,
+) -> Option<PathBuf> {
+    if let Some(dir) = android_user_home.filter(|p| !p.is_empty()) {
+        return Some(PathBuf::from(dir));
+    }
+    android_sdk_home
+        .filter(|p| !p.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| home.map(Path::to_path_buf))
+        .map(|dir| dir.join(".android"))
+}
+
+/// Resolve Cargo's home directory: `CARGO_HOME`, or `~/.cargo` without it.
+pub fn resolve_cargo_home(cargo_home: Option<&OsStr>, home: Option<&Path>) -> Option<PathBuf> {
+    cargo_home
+        .filter(|p| !p.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| home.map(|h| h.join(".cargo")))
+}
+
+/// Resolve sccache's local disk cache: `SCCACHE_DIR`, or sccache's default
+/// under the platform cache directory.
+pub fn resolve_sccache_dir(
+    sccache_dir: Option<&OsStr>,
+    cache_dir: Option<&Path>,
+) -> Option<PathBuf> {
+    if let Some(dir) = sccache_dir.filter(|p| !p.is_empty()) {
+        return Some(PathBuf::from(dir));
+    }
+    let cache_dir = cache_dir?;
+    Some(if cfg!(windows) {
+        cache_dir.join("Mozilla").join("sccache").join("cache")
+    } else if cfg!(target_os = "macos") {
+        cache_dir.join("Mozilla.sccache")
+    } else {
+        cache_dir.join("sccache")
+    })
+}
+
+/// Analyze an sccache directory as a Cargo cache. sccache shards its entries
+/// into subdirectories, and each one is a target of its own.
+pub fn scan_sccache_at(root: &Path) -> Result<ScannedProject> {
+    let mut shards: Vec<PathBuf> = fs::read_dir(root)?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.is_dir())
+        .collect();
+    shards.sort();
+
+    let options = ScanOptions::default();
+    let sizer = Sizer::new(options.size_mode);
+    let (clean_targets, largest): (Vec<_>, Vec<_>) = shards
+        .into_iter()
+        .filter_map(|path| {
+            let name = format!("sccache/{}", path.file_name()?.to_string_lossy());
+            as_clean_target(path, name, &options, &sizer)
+        })
+        .unzip();
+
+    Ok(ScannedProject {
+        path: root.to_path_buf(),
+        kind: ProjectKind::CargoCache,
+        other_kinds: Vec::new(),
+        name: "sccache (global cache)".to_string(),
+        last_modified: fs::metadata(root)?.modified()?.into(),
+        total_cleanable_bytes: clean_targets.iter().map(|t| t.size_bytes).sum(),
+        clean_targets,
+        largest_file: largest.into_iter().flatten().max_by_key(|f| f.size_bytes),
+        kind_alias: None,
+        rebuild_cost: None,
+        owner: owner_uid(root),
+        device: device_id(root),
+        workspace_members: Vec::new(),
+    })
+}
+
+/// Resolve the user's Julia depot.
+///
+/// `JULIA_DEPOT_PATH` is a path list whose first entry is the depot Julia writes
+/// packages and compiled caches into; without it the depot is `~/.julia`.
+pub fn resolve_julia_depot(depot_path: Option<&OsStr>, home: Option<&Path>) -> Option<PathBuf> {
+    depot_path
+        .and_then(|v| std::env::split_paths(v).find(|p| !p.as_os_str().is_empty()))
+        .or_else(|| home.map(|h| h.join(".julia")))
+}
+
+/// Resolve the directory Poetry creates project virtualenvs in.
+///
+/// `POETRY_VIRTUALENVS_PATH` wins, then `virtualenvs.path` from Poetry's
+/// `config.toml`, then `virtualenvs/` in Poetry's cache directory. The cache
+/// directory is `POETRY_CACHE_DIR`, then `cache-dir` from the config, then the
+/// platform default. A `{cache-dir}` placeholder in the configured path is
+/// expanded the way Poetry does.
+pub fn resolve_poetry_virtualenvs(
+    virtualenvs_path: Option<&OsStr>,
+    cache_dir: Option<&OsStr>,
+    config_toml: Option<&str>,
+    platform_cache: Option<&Path>,
+) -> Option<PathBuf> {
+    if let Some(path) = virtualenvs_path.filter(|p| !p.is_empty()) {
+        return Some(PathBuf::from(path));
+    }
+
+    let config: Option<toml::Table> = config_toml.and_then(|c| c.parse().ok());
+    let configured_cache = config.as_ref().and_then(|c| c.get("cache-dir")?.as_str());
+    let configured_path = config
+        .as_ref()
+        .and_then(|c| c.get("virtualenvs")?.get("path")?.as_str());
+
+    let cache = cache_dir
+        .filter(|p| !p.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| configured_cache.map(PathBuf::from))
+        .or_else(|| platform_cache.map(Path::to_path_buf));
+
+    match configured_path {
+        Some(path) if path.contains("{cache-dir}") => {
+            let cache = cache?;
+            Some(PathBuf::from(
+                path.replace("{cache-dir}", &cache.to_string_lossy()),
+            ))
+        }
+        Some(path) => Some(PathBuf::from(path)),
+        None => cache.map(|c| c.join("virtualenvs")),
+    }
+}
+
+/// Install directories Conda distributions default to, under the home directory.
+const CONDA_INSTALL_DIRS: &[&str] = &[
+    "miniconda3",
+    "anaconda3",
+    "miniforge3",
+    "mambaforge",
+    "micromamba",
+];
+
+/// Resolve the directory Conda keeps named environments in.
+///
+/// The first entry of `CONDA_ENVS_PATH` wins, then the first `envs_dirs` entry
+/// in `~/.condarc`, then `envs/` in the installation `CONDA_EXE` belongs to,
+/// then `envs/` in the first usual install location under `home` that has one.
+/// The base environment is the installation itself and is never included.
+pub fn resolve_conda_envs(
+    envs_path: Option<&OsStr>,
+    condarc: Option<&str>,
+    conda_exe: Option<&Path>,
+    home: Option<&Path>,
+) -> Option<PathBuf> {
+    let expand = |p: &str| match (p.strip_prefix("~/"), home) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(p),
+    };
+
+    if let Some(first) =
+        envs_path.and_then(|v| std::env::split_paths(v).find(|p| !p.as_os_str().is_empty()))
+    {
+        return Some(first);
+    }
+
+    let configured = condarc
+        .and_then(|c| serde_norway::from_str::<serde_norway::Value>(c).ok())
+        .and_then(|rc| {
+            rc.get("envs_dirs")?
+                .as_sequence()?
+                .first()?
+                .as_str()
+                .map(expand)
+        });
+    if configured.is_some() {
+        return configured;
+    }
+
+    // CONDA_EXE is <base>/bin/conda, <base>/condabin/conda, or <base>\Scripts\conda.exe.
+    if let Some(base) = conda_exe.and_then(|exe| exe.parent()?.parent()) {
+        return Some(base.join("envs"));
+    }
+
+    let home = home?;
+    CONDA_INSTALL_DIRS
+        .iter()
+        .map(|dir| home.join(dir).join("envs"))
+        .find(|envs| envs.is_dir())
+}
+
+/// Human-readable name of the cache itself (e.g. "Julia depot").
+fn global_label(kind: ProjectKind) -> &'static str {
+    match kind {
+        ProjectKind::Julia => "Julia depot",
+        ProjectKind::Poetry => "Poetry virtualenvs",
+        ProjectKind::Conda => "Conda environments",
+        ProjectKind::CargoCache => "Cargo registry and git caches",
+        ProjectKind::AndroidCache => "Gradle transform caches",
+        _ => "global cache",
+    }
+}
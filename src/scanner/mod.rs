@@ -1,5 +1,16 @@
+pub mod global;
 pub mod project;
+pub mod relocated;
+pub mod size;
+pub mod temp;
 pub mod walk;
+pub mod workspace;
 
-pub use project::{CleanTarget, ProjectKind, ScannedProject};
-pub use walk::scan_directory;
+pub use project::{
+    CleanTarget, IDE_CACHE_DIRS, LOG_DIRS, LargestFile, ProjectKind, RebuildCost,
+    SAFE_TARGET_NAMES, ScannedProject, VCS_DIRS, holds_vcs_metadata, is_core_dump_name,
+};
+pub use relocated::{RelocatedDir, resolve_artifact_dirs};
+pub use size::{SizeMode, Sizer};
+pub use temp::{DEFAULT_TEMP_OLDER_THAN, scan_temp_dir};
+pub use walk::{ChangedSince, DirTiming, ScanOptions, scan_directory, scan_directory_with};
@@ -1,11 +1,12 @@
 use std::fmt;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
 use chrono::{DateTime, Local};
 use serde::{Deserialize, Serialize};
 
 /// The kind of development project detected.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum ProjectKind {
     Rust,
     Node,
@@ -15,6 +16,7 @@ pub enum ProjectKind {
     Go,
     Zig,
     CMake,
+    IosDeps,
     Swift,
     Elixir,
     Haskell,
@@ -24,8 +26,46 @@ pub enum ProjectKind {
     Unity,
     Godot,
     Terraform,
+    Julia,
+    Poetry,
+    Conda,
+    CargoCache,
+    AndroidCache,
+    GitGc,
+    Temp,
 }
 
+/// .NET project files (not solutions), whose presence makes `bin` and `obj`
+/// build output.
+const DOTNET_PROJECT_FILES: &[&str] = &["*.csproj", "*.fsproj"];
+
+/// Packaging metadata whose presence makes `build` and `dist` in a Python
+/// project setuptools or wheel output.
+const PYTHON_PACKAGING: &[&str] = &["setup.py", "pyproject.toml"];
+
+/// Bundler and framework configs whose presence makes `dist` and `build` in a
+/// Node project build output rather than hand-written static files.
+const BUNDLER_CONFIGS: &[&str] = &[
+    "vite.config.js",
+    "vite.config.ts",
+    "vite.config.mjs",
+    "webpack.config.js",
+    "webpack.config.ts",
+    "webpack.config.cjs",
+    "rollup.config.js",
+    "rollup.config.ts",
+    "rollup.config.mjs",
+    "esbuild.config.js",
+    "esbuild.config.mjs",
+    "tsup.config.ts",
+    "tsup.config.js",
+    ".parcelrc",
+    ".parcel-cache",
+    "angular.json",
+    "vue.config.js",
+    "svelte.config.js",
+];
+
 impl ProjectKind {
     /// Returns the marker file(s) used to detect this project kind.
     pub fn marker_files(&self) -> &[&str] {
@@ -38,6 +78,7 @@ impl ProjectKind {
             Self::Go => &["go.mod"],
             Self::Zig => &["build.zig"],
             Self::CMake => &["CMakeLists.txt"],
+            Self::IosDeps => &["Podfile", "Cartfile"],
             Self::Swift => &["Package.swift"],
             Self::Elixir => &["mix.exs"],
             Self::Haskell => &["stack.yaml", "*.cabal"],
@@ -47,20 +88,37 @@ impl ProjectKind {
             Self::Unity => &["ProjectSettings/ProjectVersion.txt"],
             Self::Godot => &["project.godot"],
             Self::Terraform => &["main.tf", "*.tf"],
+            // Global caches are located directly, never detected by walking.
+            Self::Julia | Self::Poetry | Self::Conda | Self::CargoCache | Self::AndroidCache => &[],
+            // Git repositories are only looked for with `--git-gc`.
+            Self::GitGc => &[],
+            // The OS temp directory is only looked at with `--include-temp`.
+            Self::Temp => &[],
         }
     }
 
     /// Returns the directories that can be safely cleaned for this project kind.
+    ///
+    /// A target nested inside another (e.g. `node_modules/.vite`) must be listed
+    /// before its parent so it is removed first.
     pub fn cleanable_dirs(&self) -> &[&str] {
         match self {
             Self::Rust => &["target"],
-            Self::Node => &["node_modules", ".next", ".nuxt", "dist", ".cache"],
-            Self::Python => &["__pycache__", ".venv", "venv", ".tox", "*.egg-info", ".mypy_cache", ".pytest_cache"],
+            Self::Node => &[
+                "node_modules/.vite", // Vite
+                "node_modules",
+                ".next", // Next.js
+                ".nuxt", // Nuxt
+                ".cache", // Gatsby, Parcel 1
+                ".parcel-cache", // Parcel 2
+            ],
+            Self::Python => &["__pycache__", ".venv", "venv", ".tox", "*.egg-info", ".eggs", ".mypy_cache", ".pytest_cache"],
             Self::Java => &["target", "build", ".gradle"],
-            Self::DotNet => &["bin", "obj"],
+            Self::DotNet => &[], // gated on a project file alongside
             Self::Go => &[],  // Go modules are shared, not per-project artifacts
             Self::Zig => &["zig-cache", "zig-out"],
             Self::CMake => &["build", "cmake-build-debug", "cmake-build-release"],
+            Self::IosDeps => &[], // gated on which dependency managers are in use
             Self::Swift => &[".build"],
             Self::Elixir => &["_build", "deps"],
             Self::Haskell => &[".stack-work"],
@@ -70,9 +128,189 @@ impl ProjectKind {
             Self::Unity => &["Library", "Temp", "Obj", "Logs"],
             Self::Godot => &[".godot"],
             Self::Terraform => &[".terraform"],
+            Self::Julia => &["compiled", "artifacts", "packages"],
+            // Every environment is its own target
+            Self::Poetry | Self::Conda => &["*"],
+            // Downloaded crates, their unpacked sources, and git dependencies;
+            // all fetched again on demand
+            Self::CargoCache => &["registry/cache", "registry/src", "git/checkouts", "git/db"],
+            // Gradle's artifact transforms (one directory per Gradle version)
+            // and the Android plugin's build cache; redone on the next build
+            Self::AndroidCache => &["transforms-*", "build-cache"],
+            // The object store is compacted by `git gc`, never deleted
+            Self::GitGc => &[],
+            // Stale entries are picked by age, not by name
+            Self::Temp => &[],
+        }
+    }
+
+    /// Returns directories that are only cleanable when a sibling marker exists.
+    ///
+    /// Each entry is `(pattern, markers)`: `pattern` is treated like an entry in
+    /// [`cleanable_dirs`](Self::cleanable_dirs), but only when at least one of
+    /// `markers` exists in the project root (a marker starting with `*` matches
+    /// any name ending in the rest, also inside a subdirectory as in
+    /// `pkg/*_bg.wasm`). This is for generic names like `public` that are only
+    /// build output in the presence of a specific tool.
+    pub fn gated_dirs(&self) -> &[(&str, &[&str])] {
+        match self {
+            Self::Node => &[
+                // Nuxt 3 server build
+                (
+                    ".output",
+                    &[".nuxt", "nuxt.config.js", "nuxt.config.ts", "nuxt.config.mjs"],
+                ),
+                // Gatsby writes its site to public/, but so do many static sites —
+                // only trust it when Gatsby's .cache is alongside.
+                ("public", &[".cache"]),
+                // dist/ and build/ are just as often checked-in static files,
+                // so they're only output beside a bundler config.
+                ("dist", BUNDLER_CONFIGS),
+                ("build", BUNDLER_CONFIGS),
+                // Turborepo's task cache
+                (".turbo", &["turbo.json"]),
+            ],
+            // `setup.py build` and `pip wheel` output; without packaging
+            // metadata, build/ and dist/ may be the project's own files.
+            Self::Python => &[
+                ("build", PYTHON_PACKAGING),
+                ("dist", PYTHON_PACKAGING),
+            ],
+            // wasm-pack's JS package, recognised by the `<crate>_bg.wasm` it
+            // always writes; a plain pkg/ may be a hand-written package.
+            Self::Rust => &[("pkg", &["pkg/*_bg.wasm"])],
+            // Flutter keeps each platform's dependencies and build output in its folder.
+            Self::Dart => &[
+                ("ios/Pods", &["ios"]),
+                ("ios/.symlinks", &["ios"]),
+                ("macos/Pods", &["macos"]),
+                ("android/.gradle", &["android"]),
+                ("android/app/build", &["android"]),
+            ],
+            // A bin next to only a solution is often checked-in tools, so
+            // build output is only trusted beside a project file. MAUI apps
+            // also build per platform under Platforms/.
+            Self::DotNet => &[
+                ("bin", DOTNET_PROJECT_FILES),
+                ("obj", DOTNET_PROJECT_FILES),
+                ("publish", DOTNET_PROJECT_FILES),
+                ("Platforms/*/bin", DOTNET_PROJECT_FILES),
+                ("Platforms/*/obj", DOTNET_PROJECT_FILES),
+            ],
+            // An app may mix CocoaPods, Carthage, and SwiftPM; clean whichever are present.
+            Self::IosDeps => &[
+                ("Pods", &["Podfile"]),
+                ("Carthage/Build", &["Cartfile"]),
+                (".build", &["Package.swift"]),
+            ],
+            _ => &[],
+        }
+    }
+
+    /// Where a release build usually goes, relative to the project root: what
+    /// a `keep_last_build` rule without `dirs` keeps.
+    pub fn release_dirs(&self) -> &[&str] {
+        match self {
+            Self::Rust => &["target/release"],
+            Self::Node => &["dist", "build"],
+            Self::Java => &["target", "build/libs"],
+            Self::DotNet => &["bin/Release", "publish"],
+            Self::Zig => &["zig-out"],
+            Self::CMake => &["build", "cmake-build-release"],
+            Self::Swift => &[".build/release"],
+            Self::Elixir => &["_build/prod"],
+            Self::Dart => &["build"],
+            Self::Scala => &["target"],
+            _ => &[],
         }
     }
 
+    /// Whether this kind is a machine-wide cache shared by every project, rather
+    /// than something found inside a project directory. Global caches are opt-in.
+    pub fn is_global(&self) -> bool {
+        matches!(
+            self,
+            Self::Julia | Self::Poetry | Self::Conda | Self::CargoCache | Self::AndroidCache
+        )
+    }
+
+    /// How painful it is to get this kind's artifacts back after a clean, by
+    /// default (see `--estimate-rebuild-cost`). Downloads are moderate; long
+    /// compiles and asset imports are expensive.
+    pub fn rebuild_cost(&self) -> RebuildCost {
+        match self {
+            // Compiled from source on the next build
+            Self::Rust | Self::CMake | Self::Swift | Self::Haskell | Self::Scala => {
+                RebuildCost::Expensive
+            }
+            // Unity re-imports every asset into Library
+            Self::Unity => RebuildCost::Expensive,
+            // Mostly dependencies fetched again over the network
+            Self::Node
+            | Self::Python
+            | Self::Java
+            | Self::DotNet
+            | Self::Zig
+            | Self::IosDeps
+            | Self::Elixir
+            | Self::Dart
+            | Self::Ruby
+            | Self::Julia
+            | Self::Poetry
+            | Self::Conda
+            | Self::CargoCache
+            | Self::AndroidCache => RebuildCost::Moderate,
+            // Small, local, or regenerated in seconds
+            Self::Go | Self::Godot | Self::Terraform | Self::GitGc | Self::Temp => {
+                RebuildCost::Cheap
+            }
+        }
+    }
+
+    /// A short name for dense layouts (`--compact`), mostly the usual file
+    /// extension of the language.
+    pub fn abbreviation(&self) -> &'static str {
+        match self {
+            Self::Rust => "rs",
+            Self::Node => "js",
+            Self::Python => "py",
+            Self::Java => "java",
+            Self::DotNet => ".net",
+            Self::Go => "go",
+            Self::Zig => "zig",
+            Self::CMake => "cmake",
+            Self::IosDeps => "ios",
+            Self::Swift => "swift",
+            Self::Elixir => "ex",
+            Self::Haskell => "hs",
+            Self::Dart => "dart",
+            Self::Ruby => "rb",
+            Self::Scala => "scala",
+            Self::Unity => "unity",
+            Self::Godot => "godot",
+            Self::Terraform => "tf",
+            Self::Julia => "jl",
+            Self::Poetry => "poetry",
+            Self::Conda => "conda",
+            Self::CargoCache => "cargo",
+            Self::AndroidCache => "android",
+            Self::GitGc => "git",
+            Self::Temp => "tmp",
+        }
+    }
+
+    /// All known kinds in detection order: those in `priority` first, in the
+    /// order given, then the rest in their built-in order.
+    pub fn by_priority(priority: &[ProjectKind]) -> Vec<ProjectKind> {
+        let mut kinds: Vec<ProjectKind> = Vec::with_capacity(Self::all().len());
+        for &kind in priority.iter().chain(Self::all()) {
+            if !kinds.contains(&kind) {
+                kinds.push(kind);
+            }
+        }
+        kinds
+    }
+
     /// Returns all known project kinds.
     pub fn all() -> &'static [ProjectKind] {
         &[
@@ -84,6 +322,7 @@ impl ProjectKind {
             Self::Go,
             Self::Zig,
             Self::CMake,
+            Self::IosDeps,
             Self::Swift,
             Self::Elixir,
             Self::Haskell,
@@ -93,6 +332,13 @@ impl ProjectKind {
             Self::Unity,
             Self::Godot,
             Self::Terraform,
+            Self::Julia,
+            Self::Poetry,
+            Self::Conda,
+            Self::CargoCache,
+            Self::AndroidCache,
+            Self::GitGc,
+            Self::Temp,
         ]
     }
 }
@@ -108,6 +354,7 @@ impl fmt::Display for ProjectKind {
             Self::Go => "Go",
             Self::Zig => "Zig",
             Self::CMake => "CMake",
+            Self::IosDeps => "iOS deps",
             Self::Swift => "Swift",
             Self::Elixir => "Elixir",
             Self::Haskell => "Haskell",
@@ -117,13 +364,115 @@ impl fmt::Display for ProjectKind {
             Self::Unity => "Unity",
             Self::Godot => "Godot",
             Self::Terraform => "Terraform",
+            Self::Julia => "Julia",
+            Self::Poetry => "Poetry",
+            Self::Conda => "Conda",
+            Self::CargoCache => "Cargo cache",
+            Self::AndroidCache => "Android cache",
+            Self::GitGc => "git-gc",
+            Self::Temp => "Temp",
         };
         write!(f, "{name}")
     }
 }
 
+impl FromStr for ProjectKind {
+    type Err = anyhow::Error;
+
+    /// Parse a kind from either its config name (`DotNet`) or display name
+    /// (`.NET`), case-insensitively.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        Self::all()
+            .iter()
+            .find(|k| {
+                format!("{k:?}").eq_ignore_ascii_case(s) || k.to_string().eq_ignore_ascii_case(s)
+            })
+            .copied()
+            .ok_or_else(|| {
+                let valid: Vec<String> = Self::all().iter().map(|k| format!("{k:?}")).collect();
+                anyhow::anyhow!(
+                    "Unknown project kind '{}'. Valid kinds: {}",
+                    s,
+                    valid.join(", ")
+                )
+            })
+    }
+}
+
+/// A rough label for how costly it is to rebuild what a clean removes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RebuildCost {
+    /// Regenerated quickly, or small to download.
+    Cheap,
+    /// Typically a round of dependency downloads.
+    Moderate,
+    /// A long compile or import.
+    Expensive,
+}
+
+impl fmt::Display for RebuildCost {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Cheap => "cheap",
+            Self::Moderate => "moderate",
+            Self::Expensive => "expensive",
+        })
+    }
+}
+
+/// Target names that are always build output or downloaded dependencies, and
+/// so are the only targets cleaned in safe mode (see `--safe`).
+///
+/// Generic names that a project might also use for its own files (`dist`,
+/// `bin`, `deps`, `packages`, `Library`, environments, ...) are deliberately
+/// left out.
+pub const SAFE_TARGET_NAMES: &[&str] = &[
+    "target",
+    "node_modules",
+    "node_modules/.vite",
+    ".next",
+    ".nuxt",
+    ".parcel-cache",
+    ".turbo",
+    "build",
+    "obj",
+    "__pycache__",
+    ".mypy_cache",
+    ".pytest_cache",
+    ".tox",
+    ".eggs",
+    ".gradle",
+    "zig-cache",
+    "zig-out",
+    "cmake-build-debug",
+    "cmake-build-release",
+    ".build",
+    "_build",
+    ".stack-work",
+    ".dart_tool",
+    ".godot",
+    ".terraform",
+];
+
+/// Version control metadata directories. They hold a repository's history,
+/// so they are never clean targets and never count towards a project's
+/// reclaimable size; only `--git-gc` looks inside `.git`, to compact it.
+pub const VCS_DIRS: &[&str] = &[".git", ".hg", ".svn"];
+
+/// Whether removing `dir` would lose version control history: it is a
+/// [`VCS_DIRS`] directory itself, or the root of a working copy holding one
+/// (a `.git` file, as in worktrees and submodules, counts too).
+pub fn holds_vcs_metadata(dir: &Path) -> bool {
+    let is_vcs = dir
+        .file_name()
+        .is_some_and(|name| VCS_DIRS.iter().any(|vcs| name == *vcs));
+    is_vcs || VCS_DIRS.iter().any(|vcs| dir.join(vcs).exists())
+}
+
 /// A directory within a project that can be cleaned.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CleanTarget {
     /// Absolute path to the cleanable directory.
     pub path: PathBuf,
@@ -131,6 +480,35 @@ pub struct CleanTarget {
     pub name: String,
     /// Size in bytes.
     pub size_bytes: u64,
+    /// Shown but never removed: safe mode is on and the target isn't on the
+    /// [`SAFE_TARGET_NAMES`] allowlist.
+    #[serde(default)]
+    pub protected: bool,
+    /// When the newest file in the target was modified, if known.
+    #[serde(default)]
+    pub last_modified: Option<DateTime<Local>>,
+    /// Logs or a core dump the project left behind while running, not build
+    /// output (see [`LOG_DIRS`] and `--include-logs`). Listed as such so
+    /// nobody deletes them thinking they'll be rebuilt.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub runtime_data: bool,
+    /// A single file over the `--skip-files-over` size, split out of the
+    /// target it was in and kept (always protected): most likely a dataset
+    /// or asset checked in on purpose, not build output.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub large_file: bool,
+}
+
+impl CleanTarget {
+    /// Whether this target is on the [`SAFE_TARGET_NAMES`] allowlist, by its
+    /// display name or its last path component (so nested caches like
+    /// `src/__pycache__` count).
+    pub fn is_safe(&self) -> bool {
+        let last = self.path.file_name().and_then(|f| f.to_str());
+        SAFE_TARGET_NAMES
+            .iter()
+            .any(|&safe| self.name == safe || last == Some(safe))
+    }
 }
 
 /// A discovered developer project on disk.
@@ -138,14 +516,138 @@ pub struct CleanTarget {
 pub struct ScannedProject {
     /// The project root directory.
     pub path: PathBuf,
-    /// The detected project kind.
+    /// The detected project kind; for a polyglot project, the one that comes
+    /// first in detection priority.
     pub kind: ProjectKind,
+    /// Further kinds detected in the same directory, in priority order. Their
+    /// targets are part of `clean_targets`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub other_kinds: Vec<ProjectKind>,
     /// A human-friendly project name (usually the directory name).
     pub name: String,
     /// When the project was last modified (based on marker file).
     pub last_modified: DateTime<Local>,
     /// Directories that can be cleaned.
     pub clean_targets: Vec<CleanTarget>,
-    /// Total reclaimable bytes across all clean targets.
+    /// Total reclaimable bytes across all clean targets (except protected ones).
     pub total_cleanable_bytes: u64,
+    /// The single largest file inside any clean target.
+    #[serde(default)]
+    pub largest_file: Option<LargestFile>,
+    /// Reporting name from the config's `kind_aliases`, if `kind` is remapped.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind_alias: Option<String>,
+    /// User ID owning the project directory (Unix only), for `summary --by-owner`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<u32>,
+    /// ID of the device holding the project directory (Unix only), for
+    /// `summary --by-filesystem`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub device: Option<u64>,
+    /// Member projects folded into this workspace root by
+    /// `--follow-project-references`, whose targets are in `clean_targets`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub workspace_members: Vec<PathBuf>,
+    /// Estimated cost of rebuilding the cleaned artifacts, only filled in with
+    /// `--estimate-rebuild-cost`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rebuild_cost: Option<RebuildCost>,
+}
+
+/// A single file found while sizing a project's clean targets.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LargestFile {
+    /// Absolute path to the file.
+    pub path: PathBuf,
+    /// Size in bytes.
+    pub size_bytes: u64,
+}
+
+/// IDE cache subdirectories that are safe to delete, checked in every project
+/// when IDE caches are enabled (off by default).
+///
+/// Only caches the IDE rebuilds on its own are listed. IDE settings, JetBrains
+/// shelved changes (`.idea/shelf`), and local edit history (`.vscode/.history`,
+/// `.history`) can hold work that exists nowhere else and are never included.
+pub const IDE_CACHE_DIRS: &[&str] = &[
+    // Visual Studio: IntelliSense database, precompiled headers, and per-user
+    // options (deleting it resets open documents and breakpoints)
+    ".vs",
+    // JetBrains IDEs: project-local caches
+    ".idea/caches",
+    // VS Code C/C++ extension: IntelliSense precompiled headers
+    ".vscode/ipch",
+];
+
+/// Log directories offered in every project with `--include-logs` (off by
+/// default), alongside core dumps (see [`is_core_dump_name`]).
+///
+/// Only a directory holding log files directly (`*.log`, or rotated ones like
+/// `app.log.1` and `app.log.gz`) qualifies, so a source package that happens
+/// to be called `log` is never offered.
+pub const LOG_DIRS: &[&str] = &["logs", "log"];
+
+/// Whether a file name is one a core dump gets: `core`, or `core.<pid>` when
+/// the kernel appends the process ID. Names like `core.py` don't qualify.
+pub fn is_core_dump_name(name: &str) -> bool {
+    match name.strip_prefix("core") {
+        Some("") => true,
+        Some(rest) => rest
+            .strip_prefix('.')
+            .is_some_and(|pid| !pid.is_empty() && pid.bytes().all(|b| b.is_ascii_digit())),
+        None => false,
+    }
+}
+
+/// Files smaller than this never count as dominating a project, however small
+/// the project is.
+pub const LARGE_FILE_MIN_BYTES: u64 = 10 * 1024 * 1024;
+
+impl ScannedProject {
+    /// Every kind detected in the project directory, `kind` first.
+    pub fn kinds(&self) -> impl Iterator<Item = ProjectKind> + '_ {
+        std::iter::once(self.kind).chain(self.other_kinds.iter().copied())
+    }
+
+    /// The kind as shown in tables and summaries: the alias if one is set,
+    /// otherwise the detected kind's display name. A polyglot project's other
+    /// kinds follow, as in `Rust + Node.js`.
+    pub fn display_kind(&self) -> String {
+        let primary = self
+            .kind_alias
+            .clone()
+            .unwrap_or_else(|| self.kind.to_string());
+        std::iter::once(primary)
+            .chain(self.other_kinds.iter().map(|k| k.to_string()))
+            .collect::<Vec<_>>()
+            .join(" + ")
+    }
+
+    /// The kind as shown in compact tables: the alias if one is set, otherwise
+    /// the detected kind's [abbreviation](ProjectKind::abbreviation), joined
+    /// with any other kinds' (`rs+js`).
+    pub fn short_kind(&self) -> String {
+        let primary = self
+            .kind_alias
+            .clone()
+            .unwrap_or_else(|| self.kind.abbreviation().to_string());
+        let others = self
+            .other_kinds
+            .iter()
+            .map(|k| k.abbreviation().to_string());
+        std::iter::once(primary)
+            .chain(others)
+            .collect::<Vec<_>>()
+            .join("+")
+    }
+
+    /// The largest file, if it alone is more than `fraction` (0.0–1.0) of the
+    /// project's reclaimable size — one runaway log or core dump rather than
+    /// lots of small build files. Files under [`LARGE_FILE_MIN_BYTES`] are ignored.
+    pub fn dominant_file(&self, fraction: f64) -> Option<&LargestFile> {
+        self.largest_file.as_ref().filter(|f| {
+            f.size_bytes >= LARGE_FILE_MIN_BYTES
+                && f.size_bytes as f64 > self.total_cleanable_bytes as f64 * fraction
+        })
+    }
 }
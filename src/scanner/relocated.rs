@@ -0,0 +1,112 @@
+use std::collections::BTreeMap;
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Local};
+
+use super::project::{ProjectKind, ScannedProject};
+use super::size::Sizer;
+use super::walk::{ScanOptions, as_clean_target};
+use crate::util::{device_id, owner_uid};
+
+/// A tool's build output redirected out of its projects into one shared
+/// directory, such as Cargo's `CARGO_TARGET_DIR`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelocatedDir {
+    /// The kind of project whose artifacts land here.
+    pub kind: ProjectKind,
+    /// The shared directory.
+    pub path: PathBuf,
+    /// Where the location came from: the environment variable or `artifact_dirs`.
+    pub source: String,
+}
+
+/// Resolve the relocated build directories to report.
+///
+/// An `artifact_dirs` entry in the config wins; otherwise Rust output goes to
+/// `CARGO_TARGET_DIR` when that is set to an absolute path (a relative one is
+/// resolved against wherever cargo runs, so it can't be located from here).
+pub fn resolve_artifact_dirs(
+    configured: &BTreeMap<ProjectKind, PathBuf>,
+    cargo_target_dir: Option<&OsStr>,
+) -> Vec<RelocatedDir> {
+    let mut dirs: Vec<RelocatedDir> = configured
+        .iter()
+        .map(|(&kind, path)| RelocatedDir {
+            kind,
+            path: path.clone(),
+            source: "artifact_dirs".to_string(),
+        })
+        .collect();
+
+    if !configured.contains_key(&ProjectKind::Rust)
+        && let Some(dir) = cargo_target_dir
+            .map(PathBuf::from)
+            .filter(|p| p.is_absolute())
+    {
+        dirs.push(RelocatedDir {
+            kind: ProjectKind::Rust,
+            path: dir,
+            source: "CARGO_TARGET_DIR".to_string(),
+        });
+    }
+
+    dirs
+}
+
+/// Report each relocated directory as a single-target project, so the shared
+/// output is counted once rather than missed by every project that uses it.
+///
+/// Directories that don't exist or are empty are left out, as are any already
+/// covered by a target of one of `found` (a workspace pointing
+/// `CARGO_TARGET_DIR` at its own `target`, say).
+pub fn scan_relocated_dirs(
+    dirs: &[RelocatedDir],
+    found: &[ScannedProject],
+    options: &ScanOptions,
+) -> Vec<ScannedProject> {
+    dirs.iter()
+        .filter(|dir| dir.path.is_dir())
+        .filter(|dir| {
+            !found
+                .iter()
+                .flat_map(|p| &p.clean_targets)
+                .any(|t| dir.path.starts_with(&t.path))
+        })
+        .filter_map(|dir| relocated_project(dir, options))
+        .collect()
+}
+
+fn relocated_project(dir: &RelocatedDir, options: &ScanOptions) -> Option<ScannedProject> {
+    let name = dir
+        .path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| dir.path.display().to_string());
+    let sizer = Sizer::new(options.size_mode);
+    let (target, largest_file) = as_clean_target(dir.path.clone(), name, options, &sizer)?;
+
+    Some(ScannedProject {
+        path: dir.path.clone(),
+        kind: dir.kind,
+        other_kinds: Vec::new(),
+        name: format!("{} build output ({})", dir.kind, dir.source),
+        last_modified: last_modified(&dir.path),
+        total_cleanable_bytes: target.size_bytes,
+        clean_targets: vec![target],
+        largest_file,
+        kind_alias: None,
+        rebuild_cost: None,
+        owner: owner_uid(&dir.path),
+        device: device_id(&dir.path),
+        workspace_members: Vec::new(),
+    })
+}
+
+fn last_modified(path: &Path) -> DateTime<Local> {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .map(DateTime::from)
+        .unwrap_or_else(|_| Local::now())
+}
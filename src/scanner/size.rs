@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::fs::Metadata;
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+use std::sync::Mutex;
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+/// How sizes are measured (`size_mode` in the config).
+///
+/// Either way, a file hard-linked under several names in one project is
+/// counted once.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum SizeMode {
+    /// Space the files take up on disk, so sparse files count only their
+    /// allocated blocks (like `du`). This is what `df` shows coming back after
+    /// a clean, and what the CLI uses unless configured otherwise. Falls back
+    /// to the apparent size where the platform doesn't report blocks.
+    DiskUsage,
+    /// The files' lengths, however much of them is allocated (like
+    /// `du --apparent-size`). The default for library callers, whose sizes
+    /// then don't depend on the filesystem.
+    #[default]
+    Apparent,
+}
+
+/// Sizes the files of one project, counting each hard-linked file only once
+/// however many of its names the project's targets contain.
+///
+/// Shared between threads sizing parts of the same project. Nothing is kept
+/// per file except one entry per hard-linked inode, so memory doesn't grow
+/// with the number of files sized.
+#[derive(Debug, Default)]
+pub struct Sizer {
+    mode: SizeMode,
+    /// Files with several links that have been counted, keyed by device and
+    /// inode, with a hash of the path they were first counted under.
+    linked: Mutex<HashMap<(u64, u64), u64>>,
+    /// Files larger than this many bytes aren't counted (`--skip-files-over`).
+    max_file: Option<u64>,
+}
+
+impl Sizer {
+    pub fn new(mode: SizeMode) -> Self {
+        Sizer {
+            mode,
+            linked: Mutex::new(HashMap::new()),
+            max_file: None,
+        }
+    }
+
+    /// This sizer, leaving out files larger than `max_file` bytes, if set.
+    pub fn skipping_files_over(self, max_file: Option<u64>) -> Self {
+        Sizer { max_file, ..self }
+    }
+
+    /// Whether the file described by `meta` is too large to count (see
+    /// [`Sizer::skipping_files_over`]). Judged by its apparent size, whatever
+    /// the mode.
+    pub fn skips(&self, meta: &Metadata) -> bool {
+        self.max_file.is_some_and(|max| meta.len() > max)
+    }
+
+    /// Size of the file described by `meta`, as the mode counts it.
+    pub fn file_size(&self, meta: &Metadata) -> u64 {
+        match self.mode {
+            SizeMode::Apparent => meta.len(),
+            SizeMode::DiskUsage => allocated_size(meta),
+        }
+    }
+
+    /// Count hard-linked files found by one walk, given as `(key, path hash,
+    /// size)` (see [`path_hash`]), returning the bytes that weren't already
+    /// counted under another name.
+    ///
+    /// Finding the same path again counts it again: targets nested in other
+    /// targets are walked twice, and their sizes are made disjoint afterwards.
+    pub fn claim(&self, found: impl IntoIterator<Item = ((u64, u64), u64, u64)>) -> u64 {
+        let mut linked = self.linked.lock().unwrap_or_else(|e| e.into_inner());
+        found
+            .into_iter()
+            .map(|(key, path, size)| {
+                let first = linked.entry(key).or_insert(path);
+                if *first == path { size } else { 0 }
+            })
+            .sum()
+    }
+}
+
+/// A hash standing in for a hard-linked file's path in [`Sizer::claim`], so
+/// the path itself needn't be kept.
+pub fn path_hash(path: &Path) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    path.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(unix)]
+fn allocated_size(meta: &Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+    // st_blocks is always in 512-byte units, whatever the filesystem's block size.
+    meta.blocks() * 512
+}
+
+#[cfg(not(unix))]
+fn allocated_size(meta: &Metadata) -> u64 {
+    meta.len()
+}
+
+/// The device and inode of a file with more than one link, which only the
+/// first of its names found should be counted for; `None` for other files.
+#[cfg(unix)]
+pub(super) fn link_key(meta: &Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    (meta.nlink() > 1).then(|| (meta.dev(), meta.ino()))
+}
+
+#[cfg(not(unix))]
+pub(super) fn link_key(_meta: &Metadata) -> Option<(u64, u64)> {
+    None
+}
@@ -0,0 +1,86 @@
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+
+use super::project::{ProjectKind, ScannedProject};
+use super::size::Sizer;
+use super::walk::{ScanOptions, as_clean_target};
+use crate::util::{current_uid, device_id, owner_uid};
+
+/// Default for how long nothing in a temp directory entry must have changed
+/// before `--include-temp` reports it (`temp_older_than`): a week.
+pub const DEFAULT_TEMP_OLDER_THAN: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Report the stale directories directly inside the temp directory `root`:
+/// those owned by the current user in which nothing was modified within
+/// `older_than`. Each one is a target of its own.
+///
+/// Files, symlinks, hidden entries, and other users' directories are left
+/// alone. Returns `None` if nothing is stale.
+pub fn scan_temp_dir(
+    root: &Path,
+    older_than: Duration,
+    options: &ScanOptions,
+) -> Result<Option<ScannedProject>> {
+    let cutoff: DateTime<Local> = SystemTime::now()
+        .checked_sub(older_than)
+        .unwrap_or(SystemTime::UNIX_EPOCH)
+        .into();
+    let me = current_uid();
+    let modified_before_cutoff = |meta: &fs::Metadata| {
+        meta.modified()
+            .is_ok_and(|m| DateTime::<Local>::from(m) < cutoff)
+    };
+
+    let mut entries: Vec<_> = fs::read_dir(root)
+        .with_context(|| format!("Failed to read temp directory {}", root.display()))?
+        .filter_map(|e| e.ok())
+        .filter(|e| {
+            !e.file_name().to_string_lossy().starts_with('.')
+                && e.file_type().is_ok_and(|t| t.is_dir())
+                && owner_uid(&e.path()) == me
+                && e.metadata().is_ok_and(|m| modified_before_cutoff(&m))
+        })
+        .map(|e| e.path())
+        .collect();
+    entries.sort();
+
+    // A directory's own time only changes with its direct entries, so the
+    // newest file anywhere inside has to be old too.
+    let sizer = Sizer::new(options.size_mode);
+    let (clean_targets, largest): (Vec<_>, Vec<_>) = entries
+        .into_iter()
+        .filter_map(|path| {
+            let name = path.file_name()?.to_string_lossy().into_owned();
+            as_clean_target(path, name, options, &sizer)
+        })
+        .filter(|(target, _)| target.last_modified.is_none_or(|m| m < cutoff))
+        .unzip();
+    if clean_targets.is_empty() {
+        return Ok(None);
+    }
+
+    let last_modified = clean_targets
+        .iter()
+        .filter_map(|t| t.last_modified)
+        .max()
+        .unwrap_or(cutoff);
+    Ok(Some(ScannedProject {
+        path: root.to_path_buf(),
+        kind: ProjectKind::Temp,
+        other_kinds: Vec::new(),
+        name: "Stale temp directories".to_string(),
+        last_modified,
+        total_cleanable_bytes: clean_targets.iter().map(|t| t.size_bytes).sum(),
+        clean_targets,
+        largest_file: largest.into_iter().flatten().max_by_key(|f| f.size_bytes),
+        kind_alias: None,
+        rebuild_cost: None,
+        owner: owner_uid(root),
+        device: device_id(root),
+        workspace_members: Vec::new(),
+    }))
+}
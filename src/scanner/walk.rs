@@ -1,17 +1,34 @@
-use std::collections::HashSet;
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::io::{self, Write};
+use std::io::{self, Read, Write};
 use std::path::{Path, PathBuf};
 use std::sync::LazyLock;
-use std::time::SystemTime;
+use std::time::{Duration, Instant, SystemTime};
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use chrono::{DateTime, Local};
 use rayon::prelude::*;
+use tracing::field::Empty;
+use tracing::{debug_span, info_span};
 use walkdir::WalkDir;
 
-use super::project::{CleanTarget, ProjectKind, ScannedProject};
+use super::global::scan_global_caches;
+use super::project::{
+    CleanTarget, IDE_CACHE_DIRS, LOG_DIRS, LargestFile, ProjectKind, ScannedProject,
+    holds_vcs_metadata, is_core_dump_name,
+};
+use super::relocated::{RelocatedDir, scan_relocated_dirs};
+use super::size::{SizeMode, Sizer, link_key, path_hash};
+use super::temp::scan_temp_dir;
+use super::workspace::group_workspace_members;
 use crate::config::DevSweepConfig;
+use crate::git::{self, GIT_GC_MIN_BYTES};
+use crate::resume;
+use crate::scancache::ScanCache;
+use crate::tui::colors::{blue, cyan, dim, yellow};
+use crate::tui::glyphs;
+use crate::util::{device_id, glob_match, long_path, owner_uid};
 
 /// Directory names to skip during scanning (build artifacts, VCS, caches, etc.).
 ///
@@ -35,6 +52,8 @@ static SKIP_DIRS: LazyLock<HashSet<&'static str>> = LazyLock::new(|| {
         ".build",
         "zig-cache",
         "zig-out",
+        "Pods",     // CocoaPods
+        "Carthage", // Carthage checkouts and builds
     ])
 });
 
@@ -46,15 +65,17 @@ struct Spinner {
 
 impl Spinner {
     fn new() -> Self {
-        Self {
-            frames: &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"],
-            idx: 0,
-        }
+        let frames: &[&str] = if glyphs::ascii() {
+            &["|", "/", "-", "\\"]
+        } else {
+            &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"]
+        };
+        Self { frames, idx: 0 }
     }
 
     fn tick(&mut self, msg: &str) {
         let frame = self.frames[self.idx % self.frames.len()];
-        eprint!("\r  \x1b[36m{frame}\x1b[0m {msg}");
+        eprint!("\r  {} {msg}", cyan(frame));
         let _ = io::stderr().flush();
         self.idx += 1;
     }
@@ -65,6 +86,93 @@ impl Spinner {
     }
 }
 
+/// Options controlling how the filesystem is walked.
+#[derive(Debug, Clone, Default)]
+pub struct ScanOptions {
+    /// Maximum directory depth to descend.
+    pub max_depth: Option<usize>,
+    /// Only detect projects at this depth or deeper. Shallower directories are
+    /// still walked through, just never classified (e.g. org/team folders).
+    pub min_depth: Option<usize>,
+    /// Fail on problems that are normally skipped (unreadable directories,
+    /// projects that can't be analyzed, ignore paths that don't exist).
+    pub strict: bool,
+    /// Global caches to report alongside the projects found under the root.
+    pub global_caches: Vec<ProjectKind>,
+    /// Don't descend into directories on a different filesystem than the root
+    /// (like `du -x`), e.g. network mounts and external drives.
+    pub one_file_system: bool,
+    /// Time each top-level directory and print this many of the slowest to
+    /// stderr after the scan. `None` skips all timing.
+    pub profile: Option<usize>,
+    /// Also report the IDE caches in [`IDE_CACHE_DIRS`] as clean targets.
+    pub ide_caches: bool,
+    /// Also report log directories ([`LOG_DIRS`]) and core dumps in each
+    /// project, marked as runtime data (`--include-logs`).
+    pub include_logs: bool,
+    /// Size a target across threads once it has at least this many entries
+    /// (default [`DEFAULT_PARALLEL_MIN_FILES`]).
+    pub parallel_min_files: Option<u64>,
+    /// Size a target across threads once it holds at least this many bytes
+    /// (default [`DEFAULT_PARALLEL_MIN_BYTES`]).
+    pub parallel_min_bytes: Option<u64>,
+    /// Build output redirected out of the projects (e.g. `CARGO_TARGET_DIR`),
+    /// reported alongside the projects found under the root.
+    pub artifact_dirs: Vec<RelocatedDir>,
+    /// Don't enter directories unchanged since a marker; report the cached
+    /// projects under them instead (`--changed-since-file`).
+    pub changed_since: Option<ChangedSince>,
+    /// Also report git repositories whose loose objects `git gc` would compact
+    /// (`--git-gc`).
+    pub git_gc: bool,
+    /// Whether sizes are disk usage or apparent sizes.
+    pub size_mode: SizeMode,
+    /// Also report the directories in the OS temp directory that nothing was
+    /// modified in for this long (`--include-temp`).
+    pub temp_older_than: Option<Duration>,
+    /// Warn on stderr when `max_depth` stopped the walk above directories it
+    /// would otherwise have entered (verbose output).
+    pub warn_depth_limit: bool,
+    /// Report each Cargo, npm, Yarn, or pnpm workspace as one project holding
+    /// its members' targets (`--follow-project-references`).
+    pub follow_project_references: bool,
+    /// Walk and size the root one top-level directory at a time, recording
+    /// each finished directory in this checkpoint file and skipping those it
+    /// already holds (`--resume`). The root is dropped from the checkpoint
+    /// once its scan completes.
+    pub resume: Option<PathBuf>,
+    /// With `resume`, walk the root's top-level directories biggest first,
+    /// going by [`estimate_dir_size`], so the largest projects are checkpointed
+    /// early (`--largest-first`). Has no effect without `resume`. Only the
+    /// order of the walk changes; results are still sorted for display.
+    pub largest_first: bool,
+    /// Leave files larger than this many bytes out of project targets' sizes,
+    /// and split each out of its target as a kept large file, so it's never
+    /// removed (`--skip-files-over`).
+    pub skip_files_over: Option<u64>,
+}
+
+/// An incremental scan's cutoff, and the previous results to fall back on for
+/// the directories it skips.
+///
+/// Only a directory's own mtime is checked, which changes when entries are
+/// added, removed, or renamed directly inside it, not when files deeper down
+/// are rewritten. Growth inside an old directory is missed until the next full
+/// scan.
+#[derive(Debug, Clone)]
+pub struct ChangedSince {
+    /// Directories last modified before this are skipped.
+    pub time: SystemTime,
+    /// The scan cache, whose projects stand in for skipped directories.
+    pub cache: ScanCache,
+}
+
+/// Default for [`ScanOptions::parallel_min_files`].
+pub const DEFAULT_PARALLEL_MIN_FILES: u64 = 10_000;
+
+/// Default for [`ScanOptions::parallel_min_bytes`] (1 GiB).
+pub const DEFAULT_PARALLEL_MIN_BYTES: u64 = 1024 * 1024 * 1024;
+
 /// Scan a directory tree for developer projects.
 ///
 /// Returns a list of discovered projects with their cleanable targets and sizes.
@@ -77,57 +185,410 @@ pub fn scan_directory(
     max_depth: Option<usize>,
     config: &DevSweepConfig,
 ) -> Result<Vec<ScannedProject>> {
+    scan_directory_with(
+        root,
+        &ScanOptions {
+            max_depth,
+            ..Default::default()
+        },
+        config,
+    )
+}
+
+/// Scan a directory tree for developer projects using the given options.
+pub fn scan_directory_with(
+    root: &Path,
+    options: &ScanOptions,
+    config: &DevSweepConfig,
+) -> Result<Vec<ScannedProject>> {
+    let scan_span = info_span!("scan", root = %root.display(), projects = Empty, bytes = Empty);
+    let _scan = scan_span.enter();
     let mut spinner = Spinner::new();
     spinner.tick(&format!("Scanning {}...", root.display()));
 
-    let candidates = find_project_roots(root, max_depth, config, &mut spinner)?;
+    let scanned = match options.resume {
+        Some(ref checkpoint) => scan_resumable(root, options, config, &mut spinner, checkpoint),
+        None => walk_and_size(root, options, config, &mut spinner, WalkPart::All),
+    };
+    spinner.finish();
+    let Scanned {
+        projects: found,
+        skipped_mounts,
+        depth_limited,
+        walk_times,
+        sizing_times,
+    } = scanned?;
+
+    report_skipped_mounts(&skipped_mounts);
+    if let Some(depth) = options.max_depth {
+        report_depth_limit(depth, depth_limited);
+    }
+
+    if let Some(n) = options.profile {
+        report_profile(&slowest_dirs(&walk_times, &sizing_times, n));
+    }
+
+    // A workspace root often has nothing to clean until its members' targets
+    // are folded in, so empty projects are only dropped after grouping.
+    let mut projects = found;
+    if options.follow_project_references {
+        group_workspace_members(&mut projects);
+    }
+    projects.retain(|p| p.total_cleanable_bytes > 0);
+    drop_targets_claimed_by_outer(&mut projects);
+
+    let relocated: Vec<RelocatedDir> = options
+        .artifact_dirs
+        .iter()
+        .filter(|dir| !config.exclude_kinds.contains(&dir.kind))
+        .cloned()
+        .collect();
+    projects.extend(scan_relocated_dirs(&relocated, &projects, options));
+    projects.extend(scan_global_caches(&options.global_caches, options.strict)?);
+    if let Some(older_than) = options.temp_older_than
+        && !config.exclude_kinds.contains(&ProjectKind::Temp)
+    {
+        match scan_temp_dir(&std::env::temp_dir(), older_than, options) {
+            Ok(temp) => projects.extend(temp),
+            Err(e) if options.strict => return Err(e),
+            Err(_) => {}
+        }
+    }
 
+    let total: u64 = projects.iter().map(|p| p.total_cleanable_bytes).sum();
+    scan_span.record("projects", projects.len());
+    scan_span.record("bytes", total);
+    Ok(projects)
+}
+
+/// What walking and sizing (part of) the tree under a root turned up.
+#[derive(Default)]
+struct Scanned {
+    /// Every project found, including those with nothing to clean.
+    projects: Vec<ScannedProject>,
+    skipped_mounts: Vec<PathBuf>,
+    depth_limited: u64,
+    walk_times: Vec<(PathBuf, Duration)>,
+    sizing_times: Vec<(PathBuf, Duration)>,
+}
+
+impl Scanned {
+    fn extend(&mut self, other: Scanned) {
+        self.projects.extend(other.projects);
+        self.skipped_mounts.extend(other.skipped_mounts);
+        self.depth_limited += other.depth_limited;
+        self.walk_times.extend(other.walk_times);
+        self.sizing_times.extend(other.sizing_times);
+    }
+}
+
+/// Which part of the tree under the root [`find_project_roots`] walks.
+#[derive(Debug, Clone, Copy)]
+enum WalkPart<'a> {
+    /// Everything.
+    All,
+    /// Only the root directory itself, not what's in it.
+    Root,
+    /// Only this top-level directory and everything in it.
+    Dir(&'a Path),
+}
+
+/// Find the projects in `part` of the tree under `root` and size them.
+fn walk_and_size(
+    root: &Path,
+    options: &ScanOptions,
+    config: &DevSweepConfig,
+    spinner: &mut Spinner,
+    part: WalkPart,
+) -> Result<Scanned> {
+    let walk_span = info_span!("walk", candidates = Empty);
+    let WalkOutcome {
+        candidates,
+        reused,
+        skipped_mounts,
+        depth_limited,
+        walk_times,
+    } = walk_span.in_scope(|| find_project_roots(root, options, config, spinner, part))?;
+    walk_span.record("candidates", candidates.len());
+    drop(walk_span);
     spinner.tick(&format!(
         "Found {} projects, calculating sizes...",
         candidates.len()
     ));
 
-    let projects: Vec<ScannedProject> = candidates
-        .into_par_iter()
-        .filter_map(|(path, kind)| analyze_project(&path, kind).ok())
-        .filter(|p| p.total_cleanable_bytes > 0)
+    let (analyzed, sizing_times) = size_candidates(root, candidates, options);
+    let mut projects = reused;
+    for project in analyzed {
+        match project {
+            Ok(p) => projects.push(p),
+            Err(e) if options.strict => return Err(e),
+            Err(_) => {}
+        }
+    }
+    Ok(Scanned {
+        projects,
+        skipped_mounts,
+        depth_limited,
+        walk_times,
+        sizing_times,
+    })
+}
+
+/// Measure every candidate project, in parallel, with the time each took
+/// when profiling.
+fn size_candidates(
+    root: &Path,
+    candidates: Vec<(PathBuf, Vec<ProjectKind>)>,
+    options: &ScanOptions,
+) -> (Vec<Result<ScannedProject>>, Vec<(PathBuf, Duration)>) {
+    // Rayon's threads don't inherit the current span, so each project's is
+    // parented explicitly.
+    let sizing_span = info_span!("size", projects = candidates.len(), bytes = Empty);
+    let (analyzed, sizing_times): (Vec<Result<ScannedProject>>, Vec<_>) =
+        sizing_span.in_scope(|| {
+            candidates
+                .into_par_iter()
+                .map(|(path, kinds)| {
+                    let span = debug_span!(
+                        parent: &sizing_span,
+                        "size_project",
+                        path = %path.display(),
+                        kind = %kinds[0],
+                        bytes = Empty,
+                    );
+                    let _entered = span.enter();
+                    let started = options.profile.map(|_| Instant::now());
+                    let project = analyze_project_kinds(&path, &kinds, options)
+                        .map_err(|e| e.context(format!("Failed to analyze {}", path.display())));
+                    if let Ok(ref p) = project {
+                        span.record("bytes", p.total_cleanable_bytes);
+                    }
+                    let timing = started.map(|t| (top_level_dir(root, &path), t.elapsed()));
+                    (project, timing)
+                })
+                .unzip()
+        });
+    let sized: u64 = analyzed
+        .iter()
+        .flatten()
+        .map(|p| p.total_cleanable_bytes)
+        .sum();
+    sizing_span.record("bytes", sized);
+    (analyzed, sizing_times.into_iter().flatten().collect())
+}
+
+/// Scan the tree under `root` one top-level directory at a time (the root
+/// itself first), saving the checkpoint at `path` after each one. Directories
+/// the checkpoint already lists, from an earlier scan that was cut short, are
+/// taken from it instead of being walked again. Once every directory is done,
+/// the root is dropped from the checkpoint.
+fn scan_resumable(
+    root: &Path,
+    options: &ScanOptions,
+    config: &DevSweepConfig,
+    spinner: &mut Spinner,
+    path: &Path,
+) -> Result<Scanned> {
+    let mut checkpoint = resume::load(path);
+    let mut dirs: Vec<PathBuf> = fs::read_dir(root)
+        .with_context(|| format!("Failed to read {}", root.display()))?
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+        .map(|entry| entry.path())
         .collect();
+    dirs.sort();
+    if options.largest_first {
+        dirs.sort_by_cached_key(|dir| Reverse(estimate_dir_size(dir)));
+    }
+    let parts: Vec<PathBuf> = std::iter::once(root.to_path_buf()).chain(dirs).collect();
 
-    spinner.finish();
+    let done = parts
+        .iter()
+        .filter(|part| checkpoint.done(root, part).is_some())
+        .count();
+    if done > 0 {
+        spinner.finish();
+        eprintln!(
+            "  {} Resuming the scan of {}: {} of {} directories already done.",
+            blue("ℹ"),
+            root.display(),
+            done,
+            parts.len()
+        );
+    }
 
-    Ok(projects)
+    let mut scanned = Scanned::default();
+    for part in &parts {
+        if let Some(projects) = checkpoint.done(root, part) {
+            scanned.projects.extend(projects.iter().cloned());
+            continue;
+        }
+        spinner.tick(&format!("Scanning {}...", part.display()));
+        let walk_part = if part == root {
+            WalkPart::Root
+        } else {
+            WalkPart::Dir(part)
+        };
+        let found = walk_and_size(root, options, config, spinner, walk_part)?;
+        checkpoint.record(root, part, found.projects.clone());
+        resume::save(path, &checkpoint)?;
+        scanned.extend(found);
+    }
+
+    checkpoint.finish(root);
+    resume::save(path, &checkpoint)?;
+    Ok(scanned)
+}
+
+/// Drop targets that an enclosing project already lists, so nothing is counted
+/// twice: a Flutter app claims `ios/Pods`, and its `ios/` folder is detected as
+/// a CocoaPods project of its own. The outer project keeps the target, and
+/// projects left with nothing to clean are dropped.
+fn drop_targets_claimed_by_outer(projects: &mut Vec<ScannedProject>) {
+    let claimed: HashMap<PathBuf, Vec<PathBuf>> = projects
+        .iter()
+        .map(|p| {
+            let targets = p.clean_targets.iter().map(|t| t.path.clone()).collect();
+            (p.path.clone(), targets)
+        })
+        .collect();
+
+    for project in projects.iter_mut() {
+        let outer: Vec<&PathBuf> = project
+            .path
+            .ancestors()
+            .skip(1)
+            .filter_map(|dir| claimed.get(dir))
+            .flatten()
+            .collect();
+        if outer.is_empty() {
+            continue;
+        }
+        project
+            .clean_targets
+            .retain(|t| !outer.iter().any(|o| t.path.starts_with(o)));
+        project.total_cleanable_bytes = project.clean_targets.iter().map(|t| t.size_bytes).sum();
+        let targets = &project.clean_targets;
+        if let Some(ref file) = project.largest_file
+            && !targets.iter().any(|t| file.path.starts_with(&t.path))
+        {
+            project.largest_file = None;
+        }
+    }
+    projects.retain(|p| p.total_cleanable_bytes > 0);
+}
+
+/// What a walk of the scan root turned up.
+struct WalkOutcome {
+    /// Project roots and their detected kinds, in priority order.
+    candidates: Vec<(PathBuf, Vec<ProjectKind>)>,
+    /// Projects taken from the cache for directories skipped as unchanged.
+    reused: Vec<ScannedProject>,
+    /// Mount points not entered because of [`ScanOptions::one_file_system`].
+    skipped_mounts: Vec<PathBuf>,
+    /// Directories at `max_depth` with subdirectories left unwalked (only
+    /// counted with [`ScanOptions::warn_depth_limit`]).
+    depth_limited: u64,
+    /// Time spent walking each top-level directory (only with [`ScanOptions::profile`]).
+    walk_times: Vec<(PathBuf, Duration)>,
 }
 
 /// Walk the filesystem to find project root directories.
 fn find_project_roots(
     root: &Path,
-    max_depth: Option<usize>,
+    options: &ScanOptions,
     config: &DevSweepConfig,
     spinner: &mut Spinner,
-) -> Result<Vec<(PathBuf, ProjectKind)>> {
+    part: WalkPart,
+) -> Result<WalkOutcome> {
     let mut candidates = Vec::new();
+    let priority = ProjectKind::by_priority(&config.detection_priority);
+    let mut skipped_mounts = Vec::new();
+    let mut unchanged = Vec::new();
     let mut walker = WalkDir::new(root).follow_links(false);
 
-    if let Some(depth) = max_depth {
+    // Without device IDs to compare, let walkdir enforce the boundary silently.
+    #[cfg(not(unix))]
+    {
+        walker = walker.same_file_system(options.one_file_system);
+    }
+    let root_device = options.one_file_system.then(|| device_id(root)).flatten();
+
+    if matches!(part, WalkPart::Root) {
+        walker = walker.max_depth(0);
+    } else if let Some(depth) = options.max_depth {
         walker = walker.max_depth(depth);
     }
 
     // Canonicalize ignored paths once up front for reliable comparison.
-    let ignored: HashSet<PathBuf> = config
-        .ignore_paths
-        .iter()
-        .filter_map(|p| fs::canonicalize(p).ok())
-        .collect();
+    let mut ignored: HashSet<PathBuf> = HashSet::new();
+    for path in &config.ignore_paths {
+        match fs::canonicalize(path) {
+            Ok(canonical) => {
+                ignored.insert(canonical);
+            }
+            Err(e) if options.strict => {
+                anyhow::bail!("Ignore path {} cannot be resolved: {}", path.display(), e)
+            }
+            Err(_) => {}
+        }
+    }
 
     let mut dirs_scanned: u64 = 0;
+    let mut depth_limited: u64 = 0;
+    let mut walk_times = Vec::new();
+    let mut current_top: Option<(PathBuf, Instant)> = None;
 
-    for entry in walker.into_iter().filter_entry(should_visit) {
+    let visit = |entry: &walkdir::DirEntry| {
+        if let WalkPart::Dir(dir) = part
+            && entry.depth() == 1
+            && entry.path() != dir
+        {
+            return false;
+        }
+        if !should_visit(entry) {
+            return false;
+        }
+        if let Some(root_device) = root_device
+            && entry.file_type().is_dir()
+            && device_id(entry.path()).is_some_and(|dev| dev != root_device)
+        {
+            skipped_mounts.push(entry.path().to_path_buf());
+            return false;
+        }
+        if let Some(ref changed) = options.changed_since
+            && entry.depth() > 0
+            && entry.file_type().is_dir()
+            && modified_before(entry, changed.time)
+        {
+            unchanged.push(entry.path().to_path_buf());
+            return false;
+        }
+        true
+    };
+
+    for entry in walker.into_iter().filter_entry(visit) {
         let entry = match entry {
             Ok(e) => e,
+            Err(e) if options.strict => {
+                return Err(anyhow::Error::new(e).context("Failed to read directory"));
+            }
             Err(_) => continue,
         };
 
-        if !entry.file_type().is_dir() {
+        // The walk is depth-first, so a top-level directory's subtree is done
+        // as soon as the next top-level entry shows up.
+        if options.profile.is_some() && entry.depth() == 1 {
+            if let Some((path, started)) = current_top.take() {
+                walk_times.push((path, started.elapsed()));
+            }
+            if entry.file_type().is_dir() {
+                current_top = Some((entry.path().to_path_buf(), Instant::now()));
+            }
+        }
+
+        // The root's own part of the scan classifies it.
+        if !entry.file_type().is_dir() || (matches!(part, WalkPart::Dir(_)) && entry.depth() == 0) {
             continue;
         }
 
@@ -137,6 +598,17 @@ fn find_project_roots(
             spinner.tick(&format!("Scanning... {} directories checked", dirs_scanned));
         }
 
+        if options.warn_depth_limit
+            && options.max_depth == Some(entry.depth())
+            && has_walkable_subdirs(entry.path())
+        {
+            depth_limited += 1;
+        }
+
+        if options.min_depth.is_some_and(|min| entry.depth() < min) {
+            continue;
+        }
+
         let dir_path = entry.path();
 
         // Skip paths the user has explicitly told us to ignore.
@@ -146,16 +618,193 @@ fn find_project_roots(
             }
         }
 
-        if let Some(kind) = detect_project_kind(dir_path) {
-            // Skip project kinds the user has excluded.
-            if config.exclude_kinds.contains(&kind) {
+        // A repository is reported by its `.git` directory, so it doesn't
+        // collide with a project detected at the work tree root.
+        if options.git_gc && !config.exclude_kinds.contains(&ProjectKind::GitGc) {
+            let git_dir = dir_path.join(".git");
+            if git_dir.is_dir() {
+                candidates.push((git_dir, vec![ProjectKind::GitGc]));
+            }
+        }
+
+        // Skip project kinds the user has excluded.
+        let mut kinds = detect_project_kinds(dir_path, &priority);
+        kinds.retain(|kind| !config.exclude_kinds.contains(kind));
+        if !kinds.is_empty() {
+            candidates.push((dir_path.to_path_buf(), kinds));
+        }
+    }
+
+    if let Some((path, started)) = current_top {
+        walk_times.push((path, started.elapsed()));
+    }
+
+    // Stand in for skipped directories with what the cache recorded. Cached
+    // projects without their targets are sized again.
+    let mut reused = Vec::new();
+    if let Some(ref changed) = options.changed_since {
+        for dir in &unchanged {
+            let Ok(canonical) = fs::canonicalize(dir) else {
                 continue;
+            };
+            for cached in changed.cache.projects_under(&canonical) {
+                if config.exclude_kinds.contains(&cached.kind) || ignored.contains(&cached.path) {
+                    continue;
+                }
+                let path = match cached.path.strip_prefix(&canonical) {
+                    Ok(rel) if !rel.as_os_str().is_empty() => dir.join(rel),
+                    _ => dir.clone(),
+                };
+                match cached.to_project(&path) {
+                    Some(project) => reused.push(project),
+                    None => candidates.push((path, cached.kinds())),
+                }
             }
-            candidates.push((dir_path.to_path_buf(), kind));
         }
     }
 
-    Ok(candidates)
+    Ok(WalkOutcome {
+        candidates,
+        reused,
+        skipped_mounts,
+        depth_limited,
+        walk_times,
+    })
+}
+
+/// A cheap guess at how much `dir` holds, for ordering a `--largest-first`
+/// resumable walk: the sizes its direct entries report, without descending any further.
+/// On most filesystems a directory's own size grows with the entries in it,
+/// so a subdirectory full of files still weighs in.
+pub fn estimate_dir_size(dir: &Path) -> u64 {
+    fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|entry| entry.metadata().ok())
+                .map(|metadata| metadata.len())
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+/// Whether `entry` was last modified before `time`. Unknown mtimes count as
+/// changed, so the directory is walked.
+fn modified_before(entry: &walkdir::DirEntry, time: SystemTime) -> bool {
+    entry
+        .metadata()
+        .ok()
+        .and_then(|m| m.modified().ok())
+        .is_some_and(|modified| modified < time)
+}
+
+/// Tell the user which mount points `--one-file-system` kept the scan out of.
+fn report_skipped_mounts(mounts: &[PathBuf]) {
+    if mounts.is_empty() {
+        return;
+    }
+    eprintln!(
+        "  {} Skipped {} mount points on other filesystems:",
+        blue("ℹ"),
+        mounts.len()
+    );
+    for mount in mounts {
+        eprintln!("    {}", dim(&mount.display().to_string()));
+    }
+}
+
+fn report_depth_limit(max_depth: usize, skipped: u64) {
+    if skipped == 0 {
+        return;
+    }
+    let noun = if skipped == 1 {
+        "directory"
+    } else {
+        "directories"
+    };
+    eprintln!(
+        "  {} Stopped at --max-depth {}: subdirectories of {} {} weren't searched for \
+         projects. Raise --max-depth if projects are missing.",
+        yellow("⚠"),
+        max_depth,
+        skipped,
+        noun
+    );
+}
+
+// ── Scan profiling ──────────────────────────────────────────────────────────
+
+/// Time spent under one top-level directory of the scan root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirTiming {
+    pub path: PathBuf,
+    /// Time walking the directory looking for projects.
+    pub walk: Duration,
+    /// Time measuring the projects found in it, summed across threads.
+    pub sizing: Duration,
+}
+
+impl DirTiming {
+    pub fn total(&self) -> Duration {
+        self.walk + self.sizing
+    }
+}
+
+/// Merge walk and sizing times per directory and keep the `n` slowest.
+///
+/// Slowest first; ties are broken by path so the report is stable.
+pub fn slowest_dirs(
+    walk: &[(PathBuf, Duration)],
+    sizing: &[(PathBuf, Duration)],
+    n: usize,
+) -> Vec<DirTiming> {
+    let mut by_dir: HashMap<&PathBuf, (Duration, Duration)> = HashMap::new();
+    for (path, time) in walk {
+        by_dir.entry(path).or_default().0 += *time;
+    }
+    for (path, time) in sizing {
+        by_dir.entry(path).or_default().1 += *time;
+    }
+
+    let mut timings: Vec<DirTiming> = by_dir
+        .into_iter()
+        .map(|(path, (walk, sizing))| DirTiming {
+            path: path.clone(),
+            walk,
+            sizing,
+        })
+        .collect();
+    timings.sort_by(|a, b| b.total().cmp(&a.total()).then_with(|| a.path.cmp(&b.path)));
+    timings.truncate(n);
+    timings
+}
+
+/// The directory directly under `root` that contains `path` (or `root` itself).
+fn top_level_dir(root: &Path, path: &Path) -> PathBuf {
+    match path.strip_prefix(root).ok().and_then(|r| r.components().next()) {
+        Some(first) => root.join(first),
+        None => path.to_path_buf(),
+    }
+}
+
+/// Print the `--profile-scan` report to stderr.
+fn report_profile(timings: &[DirTiming]) {
+    if timings.is_empty() {
+        return;
+    }
+    eprintln!("  {} Slowest directories to scan:", blue("ℹ"));
+    for timing in timings {
+        eprintln!(
+            "    {:>9.3}s  {}  {}",
+            timing.total().as_secs_f64(),
+            timing.path.display(),
+            dim(&format!(
+                "(walk {:.3}s, sizing {:.3}s)",
+                timing.walk.as_secs_f64(),
+                timing.sizing.as_secs_f64()
+            )),
+        );
+    }
 }
 
 /// Determine if a walkdir entry should be descended into.
@@ -179,12 +828,37 @@ pub fn should_visit(entry: &walkdir::DirEntry) -> bool {
     !SKIP_DIRS.contains(name.as_ref())
 }
 
-/// Detect what kind of project a directory contains, if any.
+/// Whether `dir` has a subdirectory the walk would enter (see [`should_visit`]).
+pub fn has_walkable_subdirs(dir: &Path) -> bool {
+    WalkDir::new(dir)
+        .min_depth(1)
+        .max_depth(1)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .any(|e| e.file_type().is_dir() && should_visit(&e))
+}
+
+/// Detect what kind of project a directory contains, if any. When several
+/// kinds match, the first in the built-in order wins.
 pub fn detect_project_kind(dir: &Path) -> Option<ProjectKind> {
-    ProjectKind::all()
+    detect_project_kinds(dir, ProjectKind::all())
+        .first()
+        .copied()
+}
+
+/// Every kind whose markers `dir` holds, in the order of `priority` (see
+/// [`ProjectKind::by_priority`]).
+pub fn detect_project_kinds(dir: &Path, priority: &[ProjectKind]) -> Vec<ProjectKind> {
+    priority
         .iter()
-        .find(|kind| kind.marker_files().iter().any(|m| marker_exists(dir, m)))
+        .filter(|kind| has_marker(dir, **kind))
         .copied()
+        .collect()
+}
+
+/// Whether `dir` holds one of `kind`'s marker files.
+pub fn has_marker(dir: &Path, kind: ProjectKind) -> bool {
+    kind.marker_files().iter().any(|m| marker_exists(dir, m))
 }
 
 /// Check whether a single marker pattern matches anything in `dir`.
@@ -210,8 +884,46 @@ fn marker_exists(dir: &Path, marker: &str) -> bool {
     }
 }
 
+/// Check whether a [`ProjectKind::gated_dirs`] marker exists in `dir`: a file or
+/// directory of that name, or for `"*suffix"` any entry ending in `suffix`
+/// (in a subdirectory for `"sub/*suffix"`).
+fn gate_exists(dir: &Path, marker: &str) -> bool {
+    match marker.rsplit_once('/') {
+        Some((sub, glob)) if glob.starts_with('*') => marker_exists(&dir.join(sub), glob),
+        _ if marker.starts_with('*') => marker_exists(dir, marker),
+        _ => dir.join(marker).exists(),
+    }
+}
+
 /// Analyze a single project: find cleanable targets and calculate sizes.
 pub fn analyze_project(project_root: &Path, kind: ProjectKind) -> Result<ScannedProject> {
+    analyze_project_with(project_root, kind, &ScanOptions::default())
+}
+
+/// Analyze a single project using the given options.
+pub fn analyze_project_with(
+    project_root: &Path,
+    kind: ProjectKind,
+    options: &ScanOptions,
+) -> Result<ScannedProject> {
+    analyze_project_kinds(project_root, &[kind], options)
+}
+
+/// Analyze a directory that matched each of `kinds` (a polyglot project): the
+/// first is the project's kind, and every kind's targets are collected, each
+/// path once.
+pub fn analyze_project_kinds(
+    project_root: &Path,
+    kinds: &[ProjectKind],
+    options: &ScanOptions,
+) -> Result<ScannedProject> {
+    let Some((&kind, others)) = kinds.split_first() else {
+        anyhow::bail!("No project kind given for {}", project_root.display());
+    };
+    if kind == ProjectKind::GitGc {
+        return analyze_git_repo(project_root);
+    }
+
     let name = project_root
         .file_name()
         .map(|n| n.to_string_lossy().to_string())
@@ -219,45 +931,178 @@ pub fn analyze_project(project_root: &Path, kind: ProjectKind) -> Result<Scanned
 
     let last_modified = get_last_modified(project_root, &kind)?;
 
-    let mut clean_targets: Vec<CleanTarget> = kind
-        .cleanable_dirs()
-        .iter()
+    // Two kinds may share a target name (`build`, `target`); the first claims it.
+    let mut patterns: Vec<&str> = Vec::new();
+    for kind in kinds {
+        let gated = kind
+            .gated_dirs()
+            .iter()
+            .filter(|(_, markers)| markers.iter().any(|m| gate_exists(project_root, m)))
+            .map(|(pattern, _)| pattern);
+        for &pattern in kind.cleanable_dirs().iter().chain(gated) {
+            if !patterns.contains(&pattern) {
+                patterns.push(pattern);
+            }
+        }
+    }
+    if options.ide_caches {
+        patterns.extend(IDE_CACHE_DIRS);
+    }
+
+    // One sizer for every target, so a file hard-linked into two counts once.
+    let sizer = Sizer::new(options.size_mode).skipping_files_over(options.skip_files_over);
+    // Files over the --skip-files-over size, found while sizing.
+    let mut kept = Vec::new();
+    let mut measured: Vec<(CleanTarget, Option<LargestFile>)> = patterns
+        .into_iter()
         .flat_map(|pattern| resolve_pattern(project_root, pattern))
-        .filter_map(|(path, name)| as_clean_target(path, name))
+        .filter_map(|(path, name)| {
+            let (target, largest, large) = measure_clean_target(path, name, options, &sizer)?;
+            kept.extend(large);
+            Some((target, largest))
+        })
         .collect();
 
-    if kind == ProjectKind::Python {
-        find_pycache_recursive(project_root, &mut clean_targets);
+    if options.include_logs {
+        measured.extend(
+            find_runtime_data(project_root)
+                .into_iter()
+                .filter_map(|(path, name)| {
+                    let (target, largest, large) =
+                        measure_clean_target(path, name, options, &sizer)?;
+                    kept.extend(large);
+                    Some((target, largest))
+                })
+                .map(|(target, largest)| {
+                    let target = CleanTarget {
+                        runtime_data: true,
+                        large_file: false,
+                        ..target
+                    };
+                    (target, largest)
+                }),
+        );
+    }
+
+    if kinds.contains(&ProjectKind::Python) {
+        // A `__pycache__` in a regular target (the root one, or one under
+        // `build/` or `.tox/`) is already counted there; don't list it twice.
+        for (target, largest, large) in find_pycache_targets(project_root, &sizer) {
+            let counted = measured
+                .iter()
+                .any(|(t, _)| target.path.starts_with(&t.path));
+            if !counted {
+                measured.push((target, largest));
+                kept.extend(large);
+            }
+        }
     }
 
-    let total_cleanable_bytes = clean_targets.iter().map(|t| t.size_bytes).sum();
+    let (mut clean_targets, largest): (Vec<_>, Vec<_>) = measured.into_iter().unzip();
+    let largest_file = largest.into_iter().flatten().max_by_key(|f| f.size_bytes);
+
+    if !kept.is_empty() {
+        clean_targets = split_out_kept_files(clean_targets, &kept, &sizer);
+    }
+    subtract_nested_sizes(&mut clean_targets);
+
+    let total_cleanable_bytes = clean_targets
+        .iter()
+        .filter(|t| !t.protected)
+        .map(|t| t.size_bytes)
+        .sum();
 
     Ok(ScannedProject {
         path: project_root.to_path_buf(),
         kind,
+        other_kinds: others.to_vec(),
         name,
         last_modified,
         clean_targets,
         total_cleanable_bytes,
+        largest_file,
+        kind_alias: None,
+        rebuild_cost: None,
+        owner: owner_uid(project_root),
+        device: device_id(project_root),
+        workspace_members: Vec::new(),
+    })
+}
+
+/// Analyze a repository's `.git` directory for `--git-gc`: its object store is
+/// the one target, sized by what `git gc --prune=now` would reclaim. Nothing is
+/// reported below [`GIT_GC_MIN_BYTES`].
+fn analyze_git_repo(git_dir: &Path) -> Result<ScannedProject> {
+    let repo = git_dir.parent().unwrap_or(git_dir);
+    let name = repo
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| repo.display().to_string());
+    let last_modified = get_last_modified(git_dir, &ProjectKind::GitGc)?;
+
+    let reclaimable = git::count_objects(repo)?.reclaimable();
+    let clean_targets = if reclaimable >= GIT_GC_MIN_BYTES {
+        vec![CleanTarget {
+            path: git_dir.join("objects"),
+            name: "loose objects".to_string(),
+            size_bytes: reclaimable,
+            protected: false,
+            last_modified: get_last_modified(&git_dir.join("objects"), &ProjectKind::GitGc).ok(),
+            runtime_data: false,
+            large_file: false,
+        }]
+    } else {
+        Vec::new()
+    };
+
+    Ok(ScannedProject {
+        path: git_dir.to_path_buf(),
+        kind: ProjectKind::GitGc,
+        other_kinds: Vec::new(),
+        name,
+        last_modified,
+        total_cleanable_bytes: clean_targets.iter().map(|t| t.size_bytes).sum(),
+        clean_targets,
+        largest_file: None,
+        kind_alias: None,
+        rebuild_cost: None,
+        owner: owner_uid(git_dir),
+        device: device_id(git_dir),
+        workspace_members: Vec::new(),
     })
 }
 
 /// Resolve a cleanable-dir pattern into concrete (path, display_name) candidates.
 ///
-/// - `"*suffix"` → glob: scan the project root for matching directories
+/// - `"*suffix"`, `"prefix-*"` → glob: scan the project root for matching directories
+/// - `"sub/*/dir"` → wildcard level: resolve `dir` under every subdirectory of `sub`
 /// - `"sub/dir"` → nested path: check if the exact subdirectory exists
 /// - `"dirname"` → simple: check if the directory exists at the project root
 fn resolve_pattern(project_root: &Path, pattern: &str) -> Vec<(PathBuf, String)> {
+    if let Some((parent, rest)) = pattern.split_once("/*/") {
+        // Wildcard level (e.g. "Platforms/*/bin") — one candidate per subdirectory
+        return fs::read_dir(project_root.join(parent))
+            .into_iter()
+            .flatten()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path().is_dir())
+            .flat_map(|e| {
+                let level = e.file_name().to_string_lossy().into_owned();
+                resolve_pattern(&e.path(), rest)
+                    .into_iter()
+                    .map(move |(path, name)| (path, format!("{parent}/{level}/{name}")))
+            })
+            .collect();
+    }
     if pattern.contains('*') {
-        // Glob pattern — match directory names by suffix
-        let suffix = pattern.trim_start_matches('*');
+        // Glob pattern — match directory names at the project root
         fs::read_dir(project_root)
             .into_iter()
             .flatten()
             .filter_map(|e| e.ok())
             .filter_map(|e| {
                 let name = e.file_name().to_string_lossy().into_owned();
-                (name.ends_with(suffix) && e.path().is_dir())
+                (glob_match(pattern, &name) && e.path().is_dir())
                     .then(|| (e.path(), name))
             })
             .collect()
@@ -272,14 +1117,190 @@ fn resolve_pattern(project_root: &Path, pattern: &str) -> Vec<(PathBuf, String)>
     }
 }
 
-/// Try to turn a candidate directory into a CleanTarget. Returns None if empty or unreadable.
-fn as_clean_target(path: PathBuf, name: String) -> Option<CleanTarget> {
-    let size = dir_size(&path).ok()?;
-    (size > 0).then_some(CleanTarget {
-        path,
-        name,
-        size_bytes: size,
-    })
+/// The log directories and core dumps directly in `project_root`, for
+/// `--include-logs`: a [`LOG_DIRS`] directory holding log files, and a file
+/// with a core dump's name (see [`is_core_dump_name`]) that starts like an ELF
+/// or Mach-O core file.
+fn find_runtime_data(project_root: &Path) -> Vec<(PathBuf, String)> {
+    let mut found: Vec<(PathBuf, String)> = fs::read_dir(project_root)
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let name = e.file_name().to_string_lossy().into_owned();
+            let file_type = e.file_type().ok()?;
+            let keep = if file_type.is_dir() {
+                LOG_DIRS.contains(&name.as_str()) && holds_log_files(&e.path())
+            } else {
+                file_type.is_file() && is_core_dump_name(&name) && has_core_magic(&e.path())
+            };
+            keep.then(|| (e.path(), name))
+        })
+        .collect();
+    found.sort();
+    found
+}
+
+/// Whether `dir` directly holds a log file: `*.log`, or a rotated one such as
+/// `app.log.1` or `app.log.gz`.
+fn holds_log_files(dir: &Path) -> bool {
+    fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_file()))
+        .any(|e| {
+            let name = e.file_name().to_string_lossy().to_lowercase();
+            name.ends_with(".log") || name.contains(".log.")
+        })
+}
+
+/// Whether the file at `path` starts with the magic number of an ELF file or
+/// a 32- or 64-bit Mach-O file, the formats core dumps are written in.
+fn has_core_magic(path: &Path) -> bool {
+    let mut magic = [0u8; 4];
+    let read = fs::File::open(path).and_then(|mut file| file.read_exact(&mut magic));
+    read.is_ok()
+        && matches!(
+            magic,
+            [0x7f, b'E', b'L', b'F'] | [0xce, 0xfa, 0xed, 0xfe] | [0xcf, 0xfa, 0xed, 0xfe]
+        )
+}
+
+/// Try to turn a candidate directory into a CleanTarget, along with its largest
+/// file. Returns None if empty, or if removing it would lose version control
+/// history (see [`holds_vcs_metadata`]).
+pub(super) fn as_clean_target(
+    path: PathBuf,
+    name: String,
+    options: &ScanOptions,
+    sizer: &Sizer,
+) -> Option<(CleanTarget, Option<LargestFile>)> {
+    measure_clean_target(path, name, options, sizer).map(|(target, largest, _)| (target, largest))
+}
+
+/// [`as_clean_target`], along with the files in the target that `sizer` left
+/// out for their size. A target holding nothing but such files is kept, so
+/// they can be listed.
+fn measure_clean_target(
+    path: PathBuf,
+    name: String,
+    options: &ScanOptions,
+    sizer: &Sizer,
+) -> Option<(CleanTarget, Option<LargestFile>, Vec<PathBuf>)> {
+    if holds_vcs_metadata(&path) {
+        return None;
+    }
+    let measured = measure_target(&path, options, sizer);
+    (measured.bytes > 0 || !measured.kept.is_empty()).then_some((
+        CleanTarget {
+            path,
+            name,
+            size_bytes: measured.bytes,
+            protected: false,
+            last_modified: measured.newest.map(DateTime::from),
+            runtime_data: false,
+            large_file: false,
+        },
+        measured.largest,
+        measured.kept,
+    ))
+}
+
+/// Split every target holding one of the `kept` files (see
+/// [`ScanOptions::skip_files_over`]) into its subdirectories, as far as needed
+/// to reach them, listing each kept file as a protected target of its own.
+/// Loose files next to a kept one are left alone too. A target nested in
+/// another is split along with it, so its parts are only listed once.
+fn split_out_kept_files(
+    targets: Vec<CleanTarget>,
+    kept: &[PathBuf],
+    sizer: &Sizer,
+) -> Vec<CleanTarget> {
+    let mut split: Vec<CleanTarget> = Vec::new();
+    for part in targets
+        .into_iter()
+        .flat_map(|target| keep_files_within(target, kept, sizer))
+    {
+        if !split.iter().any(|t| t.path == part.path) {
+            split.push(part);
+        }
+    }
+    split
+}
+
+/// The parts of `target` left once the `kept` files in it are split out.
+fn keep_files_within(target: CleanTarget, kept: &[PathBuf], sizer: &Sizer) -> Vec<CleanTarget> {
+    if kept.contains(&target.path) {
+        let meta = fs::symlink_metadata(long_path(&target.path)).ok();
+        return vec![CleanTarget {
+            size_bytes: meta.as_ref().map_or(0, |m| sizer.file_size(m)),
+            protected: true,
+            last_modified: meta.and_then(|m| m.modified().ok()).map(DateTime::from),
+            large_file: true,
+            ..target
+        }];
+    }
+    if !kept.iter().any(|f| f.starts_with(&target.path)) {
+        return vec![target];
+    }
+
+    let mut children: Vec<fs::DirEntry> = fs::read_dir(long_path(&target.path))
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok())
+        .collect();
+    children.sort_by_key(|e| e.file_name());
+
+    let mut parts = Vec::new();
+    for child in children {
+        let path = target.path.join(child.file_name());
+        let is_dir = child.file_type().is_ok_and(|t| t.is_dir());
+        if !is_dir && !kept.contains(&path) {
+            continue;
+        }
+        let measured = if is_dir {
+            measure_dir(&path, sizer)
+        } else {
+            Measurement::default()
+        };
+        if is_dir && measured.bytes == 0 && measured.kept.is_empty() {
+            continue;
+        }
+        let part = CleanTarget {
+            name: format!("{}/{}", target.name, child.file_name().to_string_lossy()),
+            path,
+            size_bytes: measured.bytes,
+            protected: target.protected,
+            last_modified: measured.newest.map(DateTime::from),
+            runtime_data: target.runtime_data,
+            large_file: false,
+        };
+        parts.extend(keep_files_within(part, kept, sizer));
+    }
+    parts
+}
+
+/// Make target sizes disjoint when one target lives inside another.
+///
+/// A parent target (e.g. `node_modules`) is reduced by the size of any target
+/// nested in it (e.g. `node_modules/.vite`), so the project total counts each
+/// byte once and each target reports only what removing it alone would free.
+fn subtract_nested_sizes(targets: &mut [CleanTarget]) {
+    // Deepest first, so every descendant is already exclusive when its parent
+    // subtracts it and no byte is subtracted twice.
+    let mut order: Vec<usize> = (0..targets.len()).collect();
+    order.sort_by_key(|&i| std::cmp::Reverse(targets[i].path.components().count()));
+
+    for i in order {
+        let nested: u64 = targets
+            .iter()
+            .enumerate()
+            .filter(|&(j, t)| j != i && t.path.starts_with(&targets[i].path))
+            .map(|(_, t)| t.size_bytes)
+            .sum();
+        targets[i].size_bytes = targets[i].size_bytes.saturating_sub(nested);
+    }
 }
 
 /// Get the last modified time of a project based on its marker files.
@@ -310,23 +1331,209 @@ fn get_last_modified(project_root: &Path, kind: &ProjectKind) -> Result<DateTime
     Ok(DateTime::<Local>::from(time))
 }
 
-/// Calculate the total size of a directory recursively.
+/// Calculate the total size of a directory recursively, as its apparent size.
 pub fn dir_size(path: &Path) -> Result<u64> {
+    dir_size_with(path, SizeMode::default())
+}
+
+/// Calculate the total size of a directory recursively, measured as `mode`
+/// says, counting each hard-linked file once.
+pub fn dir_size_with(path: &Path, mode: SizeMode) -> Result<u64> {
+    Ok(measure_dir(path, &Sizer::new(mode)).bytes)
+}
+
+/// Total size of a directory and when the newest file in it was modified
+/// (`None` if it holds no files), in one walk.
+pub fn dir_size_and_modified(path: &Path, mode: SizeMode) -> (u64, Option<DateTime<Local>>) {
+    let measured = measure_dir(path, &Sizer::new(mode));
+    (measured.bytes, measured.newest.map(DateTime::from))
+}
+
+/// What one walk of a directory found.
+#[derive(Debug, Default)]
+struct Measurement {
+    /// Total size of the files in it.
+    bytes: u64,
+    /// Its single largest file.
+    largest: Option<LargestFile>,
+    /// Modification time of its most recently modified file.
+    newest: Option<SystemTime>,
+    /// Files left out of `bytes` for being over the sizer's limit (see
+    /// [`Sizer::skipping_files_over`]).
+    kept: Vec<PathBuf>,
+}
+
+impl Measurement {
+    /// Combine the measurements of two disjoint parts of a directory.
+    fn merge(mut self, other: Measurement) -> Measurement {
+        let largest = match (self.largest, other.largest) {
+            (Some(a), Some(b)) if b.size_bytes > a.size_bytes => Some(b),
+            (Some(a), _) => Some(a),
+            (None, b) => b,
+        };
+        self.kept.extend(other.kept);
+        Measurement {
+            bytes: self.bytes + other.bytes,
+            largest,
+            newest: self.newest.max(other.newest),
+            kept: self.kept,
+        }
+    }
+}
+
+/// Size, largest file, and newest modification of a directory, in one walk.
+fn measure_dir(path: &Path, sizer: &Sizer) -> Measurement {
+    measure_dir_bounded(path, u64::MAX, u64::MAX, sizer).unwrap_or_default()
+}
+
+/// Like [`measure_dir`], but gives up and returns `None` as soon as the walk
+/// has seen `max_entries` entries or `max_bytes` bytes.
+///
+/// Entries are summed as they stream past, so memory stays flat however many
+/// files there are. The one exception is a bounded walk, which holds back its
+/// hard-linked files (at most `max_entries`) until it completes, so an
+/// abandoned walk doesn't claim them.
+fn measure_dir_bounded(
+    path: &Path,
+    max_entries: u64,
+    max_bytes: u64,
+    sizer: &Sizer,
+) -> Option<Measurement> {
+    let bounded = max_entries < u64::MAX || max_bytes < u64::MAX;
     let mut total: u64 = 0;
+    let mut entries: u64 = 0;
+    let mut largest: Option<LargestFile> = None;
+    let mut newest: Option<SystemTime> = None;
+    let mut kept = Vec::new();
+    let mut pending = Vec::new();
 
-    for entry in WalkDir::new(path).into_iter().filter_map(|e| e.ok()) {
+    let walk_root = long_path(path);
+    for entry in WalkDir::new(&walk_root).into_iter().filter_map(|e| e.ok()) {
+        entries += 1;
+        if entries >= max_entries || total >= max_bytes {
+            return None;
+        }
         if entry.file_type().is_file()
             && let Ok(meta) = entry.metadata()
         {
-            total += meta.len();
+            // Report paths as given, not in their long form.
+            let relative = entry
+                .path()
+                .strip_prefix(&walk_root)
+                .unwrap_or(entry.path());
+            if sizer.skips(&meta) {
+                kept.push(path.join(relative));
+                continue;
+            }
+            let size = sizer.file_size(&meta);
+            match link_key(&meta) {
+                Some(key) => {
+                    let found = (key, path_hash(&path.join(relative)), size);
+                    if bounded {
+                        pending.push(found);
+                    } else {
+                        total += sizer.claim([found]);
+                    }
+                }
+                None => total += size,
+            }
+            newest = newest.max(meta.modified().ok());
+            if largest.as_ref().is_none_or(|l| size > l.size_bytes) {
+                largest = Some(LargestFile {
+                    path: path.join(relative),
+                    size_bytes: size,
+                });
+            }
         }
     }
 
-    Ok(total)
+    Some(Measurement {
+        bytes: total + sizer.claim(pending),
+        largest,
+        newest,
+        kept,
+    })
+}
+
+/// Measure a clean target, splitting the work across threads when it's large.
+///
+/// Threads only pay off for big trees, so the target is first walked serially
+/// up to the [`ScanOptions::parallel_min_files`] and
+/// [`ScanOptions::parallel_min_bytes`] thresholds. A target that stays under
+/// both is fully measured by that walk; one that reaches either is measured
+/// again with each top-level entry sized on its own thread.
+fn measure_target(path: &Path, options: &ScanOptions, sizer: &Sizer) -> Measurement {
+    let min_files = options
+        .parallel_min_files
+        .unwrap_or(DEFAULT_PARALLEL_MIN_FILES);
+    let min_bytes = options
+        .parallel_min_bytes
+        .unwrap_or(DEFAULT_PARALLEL_MIN_BYTES);
+    match measure_dir_bounded(path, min_files, min_bytes, sizer) {
+        Some(measured) => measured,
+        None => measure_dir_parallel(path, sizer),
+    }
+}
+
+/// [`measure_dir`] with each entry directly in `path` measured in parallel.
+fn measure_dir_parallel(path: &Path, sizer: &Sizer) -> Measurement {
+    let children: Vec<fs::DirEntry> = fs::read_dir(long_path(path))
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok())
+        .collect();
+
+    children
+        .into_par_iter()
+        .map(|child| match child.file_type() {
+            // Symlinks are never followed, as in the serial walk.
+            Ok(t) if t.is_dir() => measure_dir(&path.join(child.file_name()), sizer),
+            Ok(t) if t.is_file() => match child.metadata() {
+                Ok(meta) if sizer.skips(&meta) => Measurement {
+                    kept: vec![path.join(child.file_name())],
+                    ..Default::default()
+                },
+                Ok(meta) => {
+                    let size = sizer.file_size(&meta);
+                    let file = path.join(child.file_name());
+                    let bytes = match link_key(&meta) {
+                        Some(key) => sizer.claim([(key, path_hash(&file), size)]),
+                        None => size,
+                    };
+                    Measurement {
+                        bytes,
+                        largest: Some(LargestFile {
+                            path: file,
+                            size_bytes: size,
+                        }),
+                        newest: meta.modified().ok(),
+                        kept: Vec::new(),
+                    }
+                }
+                Err(_) => Measurement::default(),
+            },
+            _ => Measurement::default(),
+        })
+        .reduce(Measurement::default, Measurement::merge)
 }
 
 /// Recursively find all __pycache__ directories under a path.
 pub fn find_pycache_recursive(root: &Path, targets: &mut Vec<CleanTarget>) {
+    let sizer = Sizer::default();
+    targets.extend(
+        find_pycache_targets(root, &sizer)
+            .into_iter()
+            .map(|(t, _, _)| t),
+    );
+}
+
+/// Nested `__pycache__` targets under `root`, each with its largest file and
+/// the files in it that `sizer` left out for their size.
+fn find_pycache_targets(
+    root: &Path,
+    sizer: &Sizer,
+) -> Vec<(CleanTarget, Option<LargestFile>, Vec<PathBuf>)> {
+    let mut targets = Vec::new();
     for entry in WalkDir::new(root)
         .into_iter()
         .filter_entry(|e| {
@@ -335,18 +1542,26 @@ pub fn find_pycache_recursive(root: &Path, targets: &mut Vec<CleanTarget>) {
         })
         .filter_map(|e| e.ok())
     {
-        if entry.file_type().is_dir()
-            && entry.file_name() == "__pycache__"
-            && entry.depth() > 0
-            && let Ok(size) = dir_size(entry.path())
-            && size > 0
-        {
+        if entry.file_type().is_dir() && entry.file_name() == "__pycache__" && entry.depth() > 0 {
+            let measured = measure_dir(entry.path(), sizer);
+            if measured.bytes == 0 && measured.kept.is_empty() {
+                continue;
+            }
             let relative = entry.path().strip_prefix(root).unwrap_or(entry.path());
-            targets.push(CleanTarget {
-                path: entry.path().to_path_buf(),
-                name: relative.display().to_string(),
-                size_bytes: size,
-            });
+            targets.push((
+                CleanTarget {
+                    path: entry.path().to_path_buf(),
+                    name: relative.display().to_string(),
+                    size_bytes: measured.bytes,
+                    protected: false,
+                    last_modified: measured.newest.map(DateTime::from),
+                    runtime_data: false,
+                    large_file: false,
+                },
+                measured.largest,
+                measured.kept,
+            ));
         }
     }
+    targets
 }
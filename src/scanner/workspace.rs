@@ -0,0 +1,192 @@
+use std::fs;
+use std::path::{Component, Path};
+
+use serde_json::Value;
+
+use super::project::{ProjectKind, ScannedProject};
+use crate::util::glob_match;
+
+/// The member globs a workspace root declares, relative to the root.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WorkspaceMembers {
+    pub include: Vec<String>,
+    /// Cargo's `exclude`, and `!`-prefixed npm and pnpm patterns.
+    pub exclude: Vec<String>,
+}
+
+impl WorkspaceMembers {
+    /// Whether the directory at `rel`, relative to the workspace root, is a
+    /// member. Globs match one path component per `*`, with `**` spanning any
+    /// number of them.
+    pub fn contains(&self, rel: &Path) -> bool {
+        let components: Vec<String> = rel
+            .components()
+            .filter_map(|c| match c {
+                Component::Normal(name) => Some(name.to_string_lossy().into_owned()),
+                _ => None,
+            })
+            .collect();
+        if components.is_empty() {
+            return false;
+        }
+        let matches = |pattern: &String| {
+            let parts: Vec<&str> = pattern
+                .trim_start_matches("./")
+                .trim_end_matches('/')
+                .split('/')
+                .filter(|part| !part.is_empty() && *part != ".")
+                .collect();
+            components_match(&parts, &components)
+        };
+        self.include.iter().any(matches) && !self.exclude.iter().any(matches)
+    }
+}
+
+fn components_match(pattern: &[&str], path: &[String]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|skip| components_match(rest, &path[skip..])),
+        Some((part, rest)) => path
+            .split_first()
+            .is_some_and(|(name, path)| glob_match(part, name) && components_match(rest, path)),
+    }
+}
+
+/// The members declared by the workspace rooted at `root` for `kind`: a
+/// `[workspace]` table in `Cargo.toml`, or `workspaces` in `package.json` (as
+/// a list, or Yarn's `{ "packages": [...] }`) or `packages` in
+/// `pnpm-workspace.yaml`. `None` if `root` isn't a workspace root.
+pub fn workspace_members(root: &Path, kind: ProjectKind) -> Option<WorkspaceMembers> {
+    match kind {
+        ProjectKind::Rust => {
+            let manifest: toml::Table = fs::read_to_string(root.join("Cargo.toml"))
+                .ok()?
+                .parse()
+                .ok()?;
+            let workspace = manifest.get("workspace")?.as_table()?;
+            let strings = |key: &str| -> Vec<String> {
+                workspace
+                    .get(key)
+                    .and_then(|v| v.as_array())
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|v| v.as_str().map(String::from))
+                    .collect()
+            };
+            Some(WorkspaceMembers {
+                include: strings("members"),
+                exclude: strings("exclude"),
+            })
+        }
+        ProjectKind::Node => {
+            let patterns = pnpm_packages(root).or_else(|| npm_workspaces(root))?;
+            let (exclude, include): (Vec<String>, Vec<String>) =
+                patterns.into_iter().partition(|p| p.starts_with('!'));
+            Some(WorkspaceMembers {
+                include,
+                exclude: exclude
+                    .into_iter()
+                    .map(|p| p.trim_start_matches('!').to_string())
+                    .collect(),
+            })
+        }
+        _ => None,
+    }
+}
+
+fn npm_workspaces(root: &Path) -> Option<Vec<String>> {
+    let manifest: Value =
+        serde_json::from_str(&fs::read_to_string(root.join("package.json")).ok()?).ok()?;
+    let workspaces = manifest.get("workspaces")?;
+    let list = workspaces
+        .as_array()
+        .or_else(|| workspaces.get("packages")?.as_array())?;
+    Some(
+        list.iter()
+            .filter_map(|v| v.as_str().map(String::from))
+            .collect(),
+    )
+}
+
+fn pnpm_packages(root: &Path) -> Option<Vec<String>> {
+    let contents = fs::read_to_string(root.join("pnpm-workspace.yaml")).ok()?;
+    let manifest: serde_norway::Value = serde_norway::from_str(&contents).ok()?;
+    Some(
+        manifest
+            .get("packages")?
+            .as_sequence()?
+            .iter()
+            .filter_map(|v| v.as_str().map(String::from))
+            .collect(),
+    )
+}
+
+/// Fold each workspace's member projects into the project at the workspace
+/// root (`--follow-project-references`), so a workspace is reported, sized,
+/// and cleaned as one unit.
+///
+/// A member must be of the workspace's kind. Its targets move to the root
+/// project, named by their path from the root (`crates/cli/target`), and it
+/// is recorded under `workspace_members`. Outer workspaces are grouped first,
+/// so a nested workspace that is also a member of an outer one joins the outer.
+pub(super) fn group_workspace_members(projects: &mut Vec<ScannedProject>) {
+    let mut order: Vec<usize> = (0..projects.len()).collect();
+    order.sort_by_key(|&i| projects[i].path.components().count());
+
+    let mut absorbed = vec![false; projects.len()];
+    for root in order {
+        if absorbed[root] {
+            continue;
+        }
+        let kind = projects[root].kind;
+        let Some(members) = workspace_members(&projects[root].path, kind) else {
+            continue;
+        };
+        for member in 0..projects.len() {
+            let project = &projects[member];
+            let is_member = member != root
+                && !absorbed[member]
+                && (project.kind == kind || project.other_kinds.contains(&kind))
+                && project
+                    .path
+                    .strip_prefix(&projects[root].path)
+                    .is_ok_and(|rel| members.contains(rel));
+            if !is_member {
+                continue;
+            }
+            absorbed[member] = true;
+            let member = projects[member].clone();
+            absorb(&mut projects[root], member);
+        }
+        projects[root].workspace_members.sort();
+    }
+
+    let mut absorbed = absorbed.into_iter();
+    projects.retain(|_| !absorbed.next().unwrap_or(false));
+}
+
+fn absorb(root: &mut ScannedProject, member: ScannedProject) {
+    let rel = member
+        .path
+        .strip_prefix(&root.path)
+        .unwrap_or(&member.path)
+        .to_path_buf();
+    for mut target in member.clean_targets {
+        if root.clean_targets.iter().any(|t| t.path == target.path) {
+            continue;
+        }
+        target.name = rel.join(&target.name).to_string_lossy().replace('\\', "/");
+        root.clean_targets.push(target);
+    }
+    root.total_cleanable_bytes = root.clean_targets.iter().map(|t| t.size_bytes).sum();
+    root.last_modified = root.last_modified.max(member.last_modified);
+    if let Some(file) = member.largest_file
+        && root
+            .largest_file
+            .as_ref()
+            .is_none_or(|f| file.size_bytes > f.size_bytes)
+    {
+        root.largest_file = Some(file);
+    }
+    root.workspace_members.push(member.path);
+}
@@ -0,0 +1,260 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+
+use crate::cleaner::{CleanOptions, clean_projects_with};
+use crate::config::DevSweepConfig;
+use crate::scanner::{ProjectKind, ScanOptions, ScannedProject, scan_directory_with};
+
+/// Outcome of a single self-test check.
+#[derive(Debug, Clone)]
+pub struct Check {
+    /// What was checked.
+    pub name: String,
+    /// Whether the check passed.
+    pub passed: bool,
+    /// What went wrong, or a short note on what was found.
+    pub detail: String,
+}
+
+impl Check {
+    fn new(name: &str, passed: bool, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            passed,
+            detail: detail.into(),
+        }
+    }
+}
+
+/// A sandbox project: directory name, kind, marker file, and the artifact
+/// files (relative path, size in bytes) that should be found as cleanable.
+type SandboxProject = (
+    &'static str,
+    ProjectKind,
+    &'static str,
+    &'static [(&'static str, usize)],
+);
+
+const SANDBOX: &[SandboxProject] = &[
+    (
+        "rust-app",
+        ProjectKind::Rust,
+        "Cargo.toml",
+        &[("target/debug/app", 4096), ("target/debug/app.d", 128)],
+    ),
+    (
+        "web-app",
+        ProjectKind::Node,
+        "package.json",
+        &[
+            ("node_modules/pkg/index.js", 2048),
+            (".next/build-manifest.json", 1024),
+        ],
+    ),
+    (
+        "py-app",
+        ProjectKind::Python,
+        "requirements.txt",
+        &[
+            ("__pycache__/main.pyc", 512),
+            ("pkg/__pycache__/mod.pyc", 256),
+        ],
+    ),
+    (
+        "java-app",
+        ProjectKind::Java,
+        "pom.xml",
+        &[("target/classes/App.class", 768)],
+    ),
+];
+
+/// Total artifact bytes the sandbox is built with.
+fn expected_bytes() -> u64 {
+    SANDBOX
+        .iter()
+        .flat_map(|(_, _, _, files)| files.iter())
+        .map(|(_, size)| *size as u64)
+        .sum()
+}
+
+/// Build the sandbox tree under `root`, run detection, a dry-run clean and a
+/// real clean against it, and report every check.
+///
+/// `root` must not exist yet or be safe to delete: it is removed at the end.
+pub fn run_selftest(root: &Path) -> Vec<Check> {
+    let mut checks = Vec::new();
+
+    match build_sandbox(root) {
+        Ok(()) => checks.push(Check::new(
+            "create sandbox",
+            true,
+            root.display().to_string(),
+        )),
+        Err(e) => {
+            checks.push(Check::new("create sandbox", false, format!("{e:#}")));
+            let _ = fs::remove_dir_all(root);
+            return checks;
+        }
+    }
+
+    let projects =
+        match scan_directory_with(root, &ScanOptions::default(), &DevSweepConfig::default()) {
+            Ok(p) => {
+                checks.push(Check::new(
+                    "scan sandbox",
+                    true,
+                    format!("{} projects", p.len()),
+                ));
+                p
+            }
+            Err(e) => {
+                checks.push(Check::new("scan sandbox", false, format!("{e:#}")));
+                let _ = fs::remove_dir_all(root);
+                return checks;
+            }
+        };
+
+    checks.push(check_kinds(root, &projects));
+    checks.push(check_sizes(&projects));
+
+    let selected: Vec<&ScannedProject> = projects.iter().collect();
+    checks.push(check_dry_run(&selected));
+    checks.push(check_clean(&selected));
+
+    let removed = fs::remove_dir_all(root);
+    checks.push(Check::new(
+        "remove sandbox",
+        removed.is_ok(),
+        removed.err().map(|e| e.to_string()).unwrap_or_default(),
+    ));
+
+    checks
+}
+
+/// Write every sandbox project, plus a read-only artifact to exercise deletion
+/// of files the user can't write.
+fn build_sandbox(root: &Path) -> Result<()> {
+    if root.exists() {
+        fs::remove_dir_all(root)?;
+    }
+    for (dir, _, marker, files) in SANDBOX {
+        let project = root.join(dir);
+        fs::create_dir_all(&project)?;
+        fs::write(project.join(marker), "")?;
+        for (file, size) in files.iter() {
+            let path = project.join(file);
+            fs::create_dir_all(path.parent().unwrap_or(&project))?;
+            fs::write(&path, vec![b'x'; *size])?;
+        }
+    }
+
+    let read_only = root.join("rust-app/target/debug/app");
+    let mut perms = fs::metadata(&read_only)?.permissions();
+    perms.set_readonly(true);
+    fs::set_permissions(&read_only, perms)?;
+    Ok(())
+}
+
+/// Every sandbox project is found, with the right kind.
+fn check_kinds(root: &Path, projects: &[ScannedProject]) -> Check {
+    let missing: Vec<String> = SANDBOX
+        .iter()
+        .filter(|(dir, kind, _, _)| {
+            !projects
+                .iter()
+                .any(|p| p.path == root.join(dir) && p.kind == *kind)
+        })
+        .map(|(dir, kind, _, _)| format!("{dir} ({kind})"))
+        .collect();
+
+    if missing.is_empty() && projects.len() == SANDBOX.len() {
+        Check::new(
+            "detect project kinds",
+            true,
+            format!("{} kinds", SANDBOX.len()),
+        )
+    } else if missing.is_empty() {
+        Check::new(
+            "detect project kinds",
+            false,
+            format!(
+                "expected {} projects, found {}",
+                SANDBOX.len(),
+                projects.len()
+            ),
+        )
+    } else {
+        Check::new(
+            "detect project kinds",
+            false,
+            format!("missing {}", missing.join(", ")),
+        )
+    }
+}
+
+/// Reported sizes add up to exactly what was written.
+fn check_sizes(projects: &[ScannedProject]) -> Check {
+    let found: u64 = projects.iter().map(|p| p.total_cleanable_bytes).sum();
+    let expected = expected_bytes();
+    Check::new(
+        "measure sizes",
+        found == expected,
+        format!("expected {expected} bytes, found {found}"),
+    )
+}
+
+/// A dry run reports every byte and deletes nothing.
+fn check_dry_run(selected: &[&ScannedProject]) -> Check {
+    let options = CleanOptions {
+        dry_run: true,
+        ..Default::default()
+    };
+    let results = clean_projects_with(selected, &options);
+    let reported: u64 = results.iter().map(|r| r.bytes_freed).sum();
+    let deleted: Vec<&PathBuf> = targets(selected).filter(|t| !t.exists()).collect();
+
+    if let Some(path) = deleted.first() {
+        Check::new(
+            "dry-run clean",
+            false,
+            format!("{} deleted during a dry run", path.display()),
+        )
+    } else {
+        Check::new(
+            "dry-run clean",
+            reported == expected_bytes(),
+            format!("would free {reported} bytes"),
+        )
+    }
+}
+
+/// A real clean removes every target, including one containing a read-only file.
+fn check_clean(selected: &[&ScannedProject]) -> Check {
+    let results = clean_projects_with(selected, &CleanOptions::default());
+    let errors: Vec<&String> = results.iter().flat_map(|r| &r.errors).collect();
+    let left: Vec<&PathBuf> = targets(selected).filter(|t| t.exists()).collect();
+
+    if let Some(error) = errors.first() {
+        Check::new("clean targets", false, error.to_string())
+    } else if let Some(path) = left.first() {
+        Check::new(
+            "clean targets",
+            false,
+            format!("{} still exists", path.display()),
+        )
+    } else {
+        Check::new(
+            "clean targets",
+            true,
+            format!("{} targets removed", targets(selected).count()),
+        )
+    }
+}
+
+fn targets<'a>(selected: &'a [&ScannedProject]) -> impl Iterator<Item = &'a PathBuf> {
+    selected
+        .iter()
+        .flat_map(|p| p.clean_targets.iter().map(|t| &t.path))
+}
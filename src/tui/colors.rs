@@ -1,45 +1,105 @@
+use std::ffi::OsString;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use super::glyphs;
+
 // ── ANSI color helpers ──────────────────────────────────────────────────────
 
+static ENABLED: AtomicBool = AtomicBool::new(true);
+
+/// When to color output (`--color`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ColorMode {
+    /// Color output going to a terminal, unless NO_COLOR is set
+    #[default]
+    Auto,
+    /// Always color, even when piped or NO_COLOR is set
+    Always,
+    /// Never color (the same as --no-color)
+    Never,
+}
+
+impl ColorMode {
+    /// Whether a stream should be colored: `is_terminal` is whether it goes
+    /// to a terminal, and `no_color` whether `NO_COLOR` asks for no colors
+    /// (see [`no_color_requested`]). Only `auto` looks at either.
+    pub fn resolve(self, is_terminal: bool, no_color: bool) -> bool {
+        match self {
+            ColorMode::Auto => is_terminal && !no_color,
+            ColorMode::Always => true,
+            ColorMode::Never => false,
+        }
+    }
+}
+
+/// Whether the environment, read through `var`, sets `NO_COLOR` to anything
+/// but the empty string, which by convention turns colors off by default.
+pub fn no_color_requested(var: impl Fn(&str) -> Option<OsString>) -> bool {
+    var("NO_COLOR").is_some_and(|value| !value.is_empty())
+}
+
+/// Turn ANSI colors on or off for every helper below, as resolved from
+/// [`ColorMode`] for stdout.
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether the helpers below emit ANSI colors.
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Symbols are passed through [`glyphs::plain`], so `yellow("⚠")` and the like
+/// follow `--ascii` without every caller having to.
+fn paint(code: &str, s: &str) -> String {
+    let s = glyphs::plain(s);
+    if enabled() {
+        format!("\x1b[{code}m{s}\x1b[0m")
+    } else {
+        s.into_owned()
+    }
+}
+
 pub fn bold(s: &str) -> String {
-    format!("\x1b[1m{s}\x1b[0m")
+    paint("1", s)
 }
 
 pub fn green(s: &str) -> String {
-    format!("\x1b[32m{s}\x1b[0m")
+    paint("32", s)
 }
 
 pub fn green_bold(s: &str) -> String {
-    format!("\x1b[1;32m{s}\x1b[0m")
+    paint("1;32", s)
 }
 
 pub fn cyan(s: &str) -> String {
-    format!("\x1b[36m{s}\x1b[0m")
+    paint("36", s)
 }
 
 pub fn cyan_bold(s: &str) -> String {
-    format!("\x1b[1;36m{s}\x1b[0m")
+    paint("1;36", s)
 }
 
 pub fn yellow(s: &str) -> String {
-    format!("\x1b[33m{s}\x1b[0m")
+    paint("33", s)
 }
 
 pub fn yellow_bold(s: &str) -> String {
-    format!("\x1b[1;33m{s}\x1b[0m")
+    paint("1;33", s)
 }
 
 pub fn red(s: &str) -> String {
-    format!("\x1b[31m{s}\x1b[0m")
+    paint("31", s)
 }
 
 pub fn red_bold(s: &str) -> String {
-    format!("\x1b[1;31m{s}\x1b[0m")
+    paint("1;31", s)
 }
 
 pub fn dim(s: &str) -> String {
-    format!("\x1b[2m{s}\x1b[0m")
+    paint("2", s)
 }
 
 pub fn blue(s: &str) -> String {
-    format!("\x1b[34m{s}\x1b[0m")
+    paint("34", s)
 }
\ No newline at end of file
@@ -1,11 +1,29 @@
-use std::io::{self, Write};
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, IsTerminal, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
 
-use crate::cleaner::CleanResult;
-use crate::scanner::ScannedProject;
+use chrono::{DateTime, Local};
+
+use crate::analyze::Candidate;
+use crate::cleaner::{CleanResult, elevated_remove_command, freed_drift};
+use crate::config::{ConfigLayer, ConfigSnapshot};
+use crate::duplication::DuplicationReport;
+use crate::filter::{FilterDecision, PathTree};
+use crate::kinds::KindInfo;
+use crate::peaks::PeakHistory;
+use crate::scancache::{ChangeStatus, ProjectChange};
+use crate::scanner::{ProjectKind, ScannedProject};
 use crate::tui::colors::{
-    blue, bold, cyan, cyan_bold, dim, green, green_bold, red, yellow, yellow_bold,
+    self, blue, bold, cyan, cyan_bold, dim, green, green_bold, red, yellow, yellow_bold,
+};
+use crate::tui::glyphs;
+use crate::util::{
+    LineChange, format_age, format_bytes, pad_left, pad_right, shorten_path, truncate, visible_len,
 };
-use crate::util::{format_age, format_bytes, pad_left, pad_right, shorten_path, truncate};
 
 // ── Table rendering ─────────────────────────────────────────────────────────
 
@@ -19,8 +37,75 @@ struct TableRow {
     path: String,
 }
 
+/// Projects at least this large are shown in yellow in the results table.
+pub const SIZE_WARN_BYTES: u64 = 100 * 1024 * 1024;
+
+/// Projects at least this large are shown in red in the results table.
+pub const SIZE_ALERT_BYTES: u64 = 1024 * 1024 * 1024;
+
+/// Color a size by how much it is: green below [`SIZE_WARN_BYTES`], yellow
+/// below [`SIZE_ALERT_BYTES`], red from there up.
+pub fn color_size(bytes: u64, text: &str) -> String {
+    if bytes >= SIZE_ALERT_BYTES {
+        red(text)
+    } else if bytes >= SIZE_WARN_BYTES {
+        yellow(text)
+    } else {
+        green(text)
+    }
+}
+
+/// One-line explanation of the size colors used by [`color_size`].
+pub fn format_size_legend() -> String {
+    format!(
+        "  {} {}  {} {}  {} {}",
+        green("■"),
+        dim(&format!("under {}", format_bytes(SIZE_WARN_BYTES))),
+        yellow("■"),
+        dim(&format!(
+            "{} – {}",
+            format_bytes(SIZE_WARN_BYTES),
+            format_bytes(SIZE_ALERT_BYTES)
+        )),
+        red("■"),
+        dim(&format!("{} or more", format_bytes(SIZE_ALERT_BYTES))),
+    )
+}
+
+/// How grand totals are printed (`--no-total`, `--total-bytes`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TotalFormat {
+    /// Human-readable, like every other size (`1.5 GB`).
+    #[default]
+    Human,
+    /// The exact number of bytes (`1610612736 bytes`).
+    Bytes,
+    /// Left out.
+    Hidden,
+}
+
+/// `bytes` as a grand total in `format`, or `None` if totals are hidden.
+pub fn format_total(bytes: u64, format: TotalFormat) -> Option<String> {
+    match format {
+        TotalFormat::Human => Some(format_bytes(bytes)),
+        TotalFormat::Bytes => Some(format!("{bytes} bytes")),
+        TotalFormat::Hidden => None,
+    }
+}
+
 /// Print a formatted table of scanned projects.
-pub fn print_results_table(projects: &[ScannedProject]) {
+///
+/// With `footer` set, the table ends with a totals row, followed by a legend
+/// for the size colors when colors are on and stdout is a terminal. With
+/// `compact` set, each project gets one unboxed line instead (see
+/// [`format_compact_table`]). `totals` says how the grand total is printed,
+/// in the headline and the totals row, or whether it is left out.
+pub fn print_results_table(
+    projects: &[ScannedProject],
+    footer: bool,
+    compact: bool,
+    totals: TotalFormat,
+) {
     if projects.is_empty() {
         println!(
             "\n  {} No projects with cleanable artifacts found.\n",
@@ -30,14 +115,33 @@ pub fn print_results_table(projects: &[ScannedProject]) {
     }
 
     let total_bytes: u64 = projects.iter().map(|p| p.total_cleanable_bytes).sum();
+    let total = format_total(total_bytes, totals);
     let total_projects = projects.len();
 
-    println!(
-        "\n  {} Found {} projects with {} of reclaimable space\n",
-        green_bold("✓"),
-        cyan_bold(&total_projects.to_string()),
-        yellow_bold(&format_bytes(total_bytes)),
-    );
+    match &total {
+        Some(total) => println!(
+            "\n  {} Found {} projects with {} of reclaimable space\n",
+            green_bold("✓"),
+            cyan_bold(&total_projects.to_string()),
+            yellow_bold(total),
+        ),
+        None => println!(
+            "\n  {} Found {} projects with cleanable artifacts\n",
+            green_bold("✓"),
+            cyan_bold(&total_projects.to_string()),
+        ),
+    }
+
+    if compact {
+        for line in format_compact_table(projects, footer, totals) {
+            println!("{line}");
+        }
+        if footer && colors::enabled() && io::stdout().is_terminal() {
+            println!("{}", format_size_legend());
+        }
+        println!();
+        return;
+    }
 
     let now = chrono::Local::now();
 
@@ -51,7 +155,18 @@ pub fn print_results_table(projects: &[ScannedProject]) {
             let targets_str = p
                 .clean_targets
                 .iter()
-                .map(|t| format!("{} ({})", t.name, format_bytes(t.size_bytes)))
+                .map(|t| {
+                    let size = format_bytes(t.size_bytes);
+                    if t.large_file {
+                        format!("{} ({}, kept: large file)", t.name, size)
+                    } else if t.protected {
+                        format!("{} ({}, protected)", t.name, size)
+                    } else if t.runtime_data {
+                        format!("{} ({}, runtime data)", t.name, size)
+                    } else {
+                        format!("{} ({})", t.name, size)
+                    }
+                })
                 .collect::<Vec<_>>()
                 .join(", ");
 
@@ -60,8 +175,14 @@ pub fn print_results_table(projects: &[ScannedProject]) {
             TableRow {
                 index: format!("{}", i + 1),
                 name: p.name.clone(),
-                kind: p.kind.to_string(),
-                size: format_bytes(p.total_cleanable_bytes),
+                kind: match p.rebuild_cost {
+                    Some(cost) => format!("{} ({cost})", p.display_kind()),
+                    None => p.display_kind(),
+                },
+                size: color_size(
+                    p.total_cleanable_bytes,
+                    &format_bytes(p.total_cleanable_bytes),
+                ),
                 targets: targets_str,
                 last_modified: age_str,
                 path: display_path,
@@ -85,11 +206,14 @@ pub fn print_results_table(projects: &[ScannedProject]) {
         widths[0] = widths[0].max(row.index.len());
         widths[1] = widths[1].max(row.name.len());
         widths[2] = widths[2].max(row.kind.len());
-        widths[3] = widths[3].max(row.size.len());
+        widths[3] = widths[3].max(visible_len(&row.size));
         widths[4] = widths[4].max(row.targets.len());
         widths[5] = widths[5].max(row.last_modified.len());
         widths[6] = widths[6].max(row.path.len());
     }
+    if footer && let Some(total) = &total {
+        widths[3] = widths[3].max(total.len());
+    }
 
     // Clamp columns to prevent insanely wide tables
     widths[4] = widths[4].min(50);
@@ -99,36 +223,23 @@ pub fn print_results_table(projects: &[ScannedProject]) {
     let mut out = stdout.lock();
 
     // Top border
-    write!(out, "  ╭").unwrap();
-    for (i, w) in widths.iter().enumerate() {
-        write!(out, "{}", "─".repeat(w + 2)).unwrap();
-        if i < widths.len() - 1 {
-            write!(out, "┬").unwrap();
-        }
-    }
-    writeln!(out, "╮").unwrap();
+    writeln!(out, "{}", border_line(&widths, "╭", "┬", "╮")).unwrap();
 
     // Header row
-    write!(out, "  │").unwrap();
+    let bar = glyphs::plain("│");
+    write!(out, "  {bar}").unwrap();
     for (i, header) in headers.iter().enumerate() {
         let padded = if i == 0 || i == 3 {
             pad_left(&bold(header), widths[i])
         } else {
             pad_right(&bold(header), widths[i])
         };
-        write!(out, " {padded} │").unwrap();
+        write!(out, " {padded} {bar}").unwrap();
     }
     writeln!(out).unwrap();
 
     // Header separator
-    write!(out, "  ├").unwrap();
-    for (i, w) in widths.iter().enumerate() {
-        write!(out, "{}", "─".repeat(w + 2)).unwrap();
-        if i < widths.len() - 1 {
-            write!(out, "┼").unwrap();
-        }
-    }
-    writeln!(out, "┤").unwrap();
+    writeln!(out, "{}", border_line(&widths, "├", "┼", "┤")).unwrap();
 
     // Data rows
     for row in &rows {
@@ -136,32 +247,507 @@ pub fn print_results_table(projects: &[ScannedProject]) {
             pad_left(&dim(&row.index), widths[0]),
             pad_right(&row.name, widths[1]),
             pad_right(&cyan(&row.kind), widths[2]),
-            pad_left(&yellow(&row.size), widths[3]),
+            pad_left(&row.size, widths[3]),
             pad_right(&truncate(&row.targets, widths[4]), widths[4]),
             pad_right(&dim(&row.last_modified), widths[5]),
             pad_right(&dim(&truncate(&row.path, widths[6])), widths[6]),
         ];
 
-        write!(out, "  │").unwrap();
+        write!(out, "  {bar}").unwrap();
         for field in &fields {
-            write!(out, " {field} │").unwrap();
+            write!(out, " {field} {bar}").unwrap();
         }
         writeln!(out).unwrap();
     }
 
-    // Bottom border
-    write!(out, "  ╰").unwrap();
-    for (i, w) in widths.iter().enumerate() {
-        write!(out, "{}", "─".repeat(w + 2)).unwrap();
-        if i < widths.len() - 1 {
-            write!(out, "┴").unwrap();
+    if footer && let Some(total) = &total {
+        let total_targets: usize = projects.iter().map(|p| p.clean_targets.len()).sum();
+        writeln!(out, "{}", border_line(&widths, "├", "┼", "┤")).unwrap();
+
+        let fields = [
+            " ".repeat(widths[0]),
+            pad_right(&bold("Total"), widths[1]),
+            " ".repeat(widths[2]),
+            pad_left(&yellow_bold(total), widths[3]),
+            pad_right(&truncate(&format!("{total_targets} targets"), widths[4]), widths[4]),
+            " ".repeat(widths[5]),
+            " ".repeat(widths[6]),
+        ];
+        write!(out, "  {bar}").unwrap();
+        for field in &fields {
+            write!(out, " {field} {bar}").unwrap();
         }
+        writeln!(out).unwrap();
+    }
+
+    // Bottom border
+    writeln!(out, "{}", border_line(&widths, "╰", "┴", "╯")).unwrap();
+
+    if footer && colors::enabled() && io::stdout().is_terminal() {
+        writeln!(out, "{}", format_size_legend()).unwrap();
     }
-    writeln!(out, "╯").unwrap();
 
     writeln!(out).unwrap();
 }
 
+/// One horizontal border of the results table, drawn with the `left`,
+/// `middle`, and `right` corners (in ASCII with `--ascii`).
+fn border_line(widths: &[usize], left: &str, middle: &str, right: &str) -> String {
+    let segments: Vec<String> = widths.iter().map(|w| "─".repeat(w + 2)).collect();
+    let line = format!("  {left}{}{right}", segments.join(middle));
+    glyphs::plain(&line).into_owned()
+}
+
+/// Build the `--compact` results table: one line per project with its number,
+/// name, abbreviated kind (and rebuild cost, if estimated), and size, and no
+/// borders or headers. With `footer` set, a last line gives the total, in
+/// `totals` format; there is no such line when totals are hidden.
+pub fn format_compact_table(
+    projects: &[ScannedProject],
+    footer: bool,
+    totals: TotalFormat,
+) -> Vec<String> {
+    let kind = |p: &ScannedProject| match p.rebuild_cost {
+        Some(cost) => format!("{} {cost}", p.short_kind()),
+        None => p.short_kind(),
+    };
+    let index_width = projects.len().to_string().len();
+    let name_width = projects.iter().map(|p| p.name.len()).max().unwrap_or(0);
+    let kind_width = projects.iter().map(|p| kind(p).len()).max().unwrap_or(0);
+    let total_bytes = projects.iter().map(|p| p.total_cleanable_bytes).sum();
+    let total = format_total(total_bytes, totals).filter(|_| footer);
+    let size_width = projects
+        .iter()
+        .map(|p| format_bytes(p.total_cleanable_bytes).len())
+        .chain(total.as_ref().map(String::len))
+        .max()
+        .unwrap_or(0);
+    let mut lines: Vec<String> = projects
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            let size = format_bytes(p.total_cleanable_bytes);
+            format!(
+                "  {} {} {} {}",
+                dim(&pad_left(&(i + 1).to_string(), index_width)),
+                pad_right(&p.name, name_width),
+                cyan(&pad_right(&kind(p), kind_width)),
+                color_size(p.total_cleanable_bytes, &pad_left(&size, size_width)),
+            )
+        })
+        .collect();
+    if let Some(total) = total {
+        lines.push(format!(
+            "  {} {} {}",
+            " ".repeat(index_width),
+            bold(&pad_right("Total", name_width + 1 + kind_width)),
+            yellow_bold(&pad_left(&total, size_width)),
+        ));
+    }
+    lines
+}
+
+/// What `scan --print-paths0` lists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathList {
+    /// Each project's root.
+    Projects,
+    /// Each target that a clean would remove.
+    Targets,
+}
+
+/// Build the `scan --print-paths0` output: each path followed by a NUL byte and
+/// nothing else, for `xargs -0`. Protected targets are left out, as a clean
+/// would leave them alone, and so are `--git-gc` repositories: a clean
+/// compacts them in place, and their paths (`.git`, `.git/objects`) must never
+/// reach an `xargs -0 rm -rf`. Paths are written as-is, even if not valid UTF-8.
+pub fn null_separated_paths(projects: &[ScannedProject], list: PathList) -> Vec<u8> {
+    let removable = projects.iter().filter(|p| p.kind != ProjectKind::GitGc);
+    let paths: Vec<&PathBuf> = match list {
+        PathList::Projects => removable.map(|p| &p.path).collect(),
+        PathList::Targets => removable
+            .flat_map(|p| &p.clean_targets)
+            .filter(|t| !t.protected)
+            .map(|t| &t.path)
+            .collect(),
+    };
+    let mut out = Vec::new();
+    for path in paths {
+        out.extend_from_slice(path.as_os_str().as_encoded_bytes());
+        out.push(0);
+    }
+    out
+}
+
+/// Build one compact line per project for `top`: rank, size, name, kind, path.
+pub fn format_top_projects(projects: &[ScannedProject]) -> Vec<String> {
+    let rank_width = projects.len().to_string().len();
+    let name_width = projects.iter().map(|p| p.name.len()).max().unwrap_or(0);
+    let kind_width = projects
+        .iter()
+        .map(|p| p.display_kind().len())
+        .max()
+        .unwrap_or(0);
+    projects
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            let size = format_bytes(p.total_cleanable_bytes);
+            format!(
+                "  {}  {}  {}  {}  {}",
+                dim(&pad_left(&format!("{}.", i + 1), rank_width + 1)),
+                color_size(p.total_cleanable_bytes, &pad_left(&size, 10)),
+                pad_right(&p.name, name_width),
+                cyan(&pad_right(&p.display_kind(), kind_width)),
+                dim(&shorten_path(&p.path.display().to_string())),
+            )
+        })
+        .collect()
+}
+
+/// Build the `analyze` ranking: a header, then one line per candidate with its
+/// rank, score, reclaimable size (colored like the scan table), rebuild cost,
+/// name, and path.
+pub fn format_effort_ranking(candidates: &[Candidate]) -> Vec<String> {
+    let rank_width = candidates.len().to_string().len() + 1;
+    let scores: Vec<String> = candidates
+        .iter()
+        .map(|c| format!("{:.1}", c.score))
+        .collect();
+    let score_width = scores.iter().map(String::len).max().unwrap_or(0).max(5);
+    let name_width = candidates
+        .iter()
+        .map(|c| c.project.name.len())
+        .max()
+        .unwrap_or(0)
+        .max(7);
+    let mut lines = vec![dim(&format!(
+        "  {}  {}  {}  {}  {}  Path",
+        pad_left("", rank_width),
+        pad_left("Score", score_width),
+        pad_left("Size", 10),
+        pad_right("Rebuild", 9),
+        pad_right("Project", name_width),
+    ))];
+    for (i, (c, score)) in candidates.iter().zip(&scores).enumerate() {
+        let p = &c.project;
+        let size = format_bytes(p.total_cleanable_bytes);
+        let cost = p.rebuild_cost.map_or_else(String::new, |c| c.to_string());
+        lines.push(format!(
+            "  {}  {}  {}  {}  {}  {}",
+            dim(&pad_left(&format!("{}.", i + 1), rank_width)),
+            bold(&pad_left(score, score_width)),
+            color_size(p.total_cleanable_bytes, &pad_left(&size, 10)),
+            cyan(&pad_right(&cost, 9)),
+            pad_right(&p.name, name_width),
+            dim(&shorten_path(&p.path.display().to_string())),
+        ));
+    }
+    lines
+}
+
+/// Build the `kinds` table: a header, then one line per kind with the name
+/// `--kind` takes, its display name, markers, and targets. Targets that need a
+/// marker of their own are starred, and global caches, `--git-gc`, aliases,
+/// and exclusions are noted at the end of the line.
+pub fn format_kinds_table(kinds: &[KindInfo]) -> Vec<String> {
+    // Kind, name, markers, targets, and notes.
+    type Row = (String, String, String, String, Vec<String>);
+    let rows: Vec<Row> = kinds
+        .iter()
+        .map(|k| {
+            let markers = if k.markers.is_empty() {
+                glyphs::plain("—").into_owned()
+            } else {
+                k.markers.join(", ")
+            };
+            let targets: Vec<String> = k
+                .targets
+                .iter()
+                .cloned()
+                .chain(k.gated_targets.iter().map(|g| format!("{}*", g.target)))
+                .collect();
+            let mut notes = Vec::new();
+            if k.global {
+                notes.push(format!("global cache, with --global-cache {:?}", k.kind));
+            }
+            if k.kind == ProjectKind::GitGc {
+                notes.push("with --git-gc, runs git gc".to_string());
+            }
+            if k.kind == ProjectKind::Temp {
+                notes.push("with --include-temp, picked by age".to_string());
+            }
+            if let Some(ref alias) = k.alias {
+                notes.push(format!("shown as {alias}"));
+            }
+            if k.excluded {
+                notes.push("excluded by the config".to_string());
+            }
+            (
+                format!("{:?}", k.kind),
+                k.display_name.clone(),
+                markers,
+                targets.join(", "),
+                notes,
+            )
+        })
+        .collect();
+
+    let width = |header: &str, column: &dyn Fn(&Row) -> &str| {
+        rows.iter()
+            .map(|r| visible_len(column(r)))
+            .chain([header.len()])
+            .max()
+            .unwrap_or(0)
+    };
+    let kind_width = width("Kind", &|r| &r.0);
+    let name_width = width("Name", &|r| &r.1);
+    let marker_width = width("Markers", &|r| &r.2);
+
+    let mut lines = vec![format!(
+        "  {}  {}  {}  {}",
+        bold(&pad_right("Kind", kind_width)),
+        bold(&pad_right("Name", name_width)),
+        bold(&pad_right("Markers", marker_width)),
+        bold("Targets"),
+    )];
+    for (kind, name, markers, targets, notes) in rows {
+        let mut line = format!(
+            "  {}  {}  {}  {}",
+            cyan(&pad_right(&kind, kind_width)),
+            pad_right(&name, name_width),
+            pad_right(&markers, marker_width),
+            if targets.is_empty() { dim("—") } else { targets },
+        );
+        if !notes.is_empty() {
+            line.push_str(&format!("  {}", dim(&format!("({})", notes.join("; ")))));
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+/// Build one line per project naming its largest file, flagging any file that
+/// alone is more than `fraction` of the project (see [`ScannedProject::dominant_file`]).
+pub fn format_largest_files(projects: &[ScannedProject], fraction: f64) -> Vec<String> {
+    projects
+        .iter()
+        .filter_map(|p| {
+            let file = p.largest_file.as_ref()?;
+            let path = shorten_path(&file.path.display().to_string());
+            Some(if p.dominant_file(fraction).is_some() {
+                let share = file.size_bytes as f64 / p.total_cleanable_bytes.max(1) as f64;
+                format!(
+                    "    {} {}: {} ({}, {:.0}% of the project)",
+                    yellow("⚠"),
+                    bold(&p.name),
+                    path,
+                    yellow_bold(&format_bytes(file.size_bytes)),
+                    share * 100.0,
+                )
+            } else {
+                format!(
+                    "    {} {}: {} ({})",
+                    dim("·"),
+                    p.name,
+                    dim(&path),
+                    format_bytes(file.size_bytes),
+                )
+            })
+        })
+        .collect()
+}
+
+/// Print each project's largest file (see [`format_largest_files`]).
+pub fn print_largest_files(projects: &[ScannedProject], fraction: f64) {
+    let lines = format_largest_files(projects, fraction);
+    if lines.is_empty() {
+        return;
+    }
+    println!("  {}", bold("Largest file per project:"));
+    for line in lines {
+        println!("{line}");
+    }
+    println!();
+}
+
+/// Build one line per detected project with each filter's verdict and
+/// whether the project was kept, for `--explain-filters`.
+pub fn format_filter_decisions(decisions: &[FilterDecision]) -> Vec<String> {
+    decisions
+        .iter()
+        .map(|d| {
+            let mark = if d.kept() { green("✓") } else { red("✗") };
+            let checks = if d.checks.is_empty() {
+                dim("no filters")
+            } else {
+                d.checks
+                    .iter()
+                    .map(|c| {
+                        let verdict = if c.passed { green("pass") } else { red("fail") };
+                        format!("{} {}", c.filter, verdict)
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            };
+            format!(
+                "    {} {} {}: {}",
+                mark,
+                bold(&d.name),
+                dim(&shorten_path(&d.path.display().to_string())),
+                checks,
+            )
+        })
+        .collect()
+}
+
+/// Print [`format_filter_decisions`] to stderr, so `--json` output stays parseable.
+pub fn print_filter_decisions(decisions: &[FilterDecision]) {
+    let kept = decisions.iter().filter(|d| d.kept()).count();
+    let counts = format!("{kept} of {} detected projects kept", decisions.len());
+    eprintln!("\n  {} {}", bold("Filter decisions:"), dim(&counts));
+    for line in format_filter_decisions(decisions) {
+        eprintln!("{line}");
+    }
+    eprintln!();
+}
+
+/// Build one line per project spared by `--keep-recent`, newest first within
+/// each kind.
+pub fn format_preserved_recent(preserved: &[&ScannedProject]) -> Vec<String> {
+    let mut sorted = preserved.to_vec();
+    sorted.sort_by(|a, b| {
+        a.display_kind()
+            .cmp(&b.display_kind())
+            .then_with(|| b.last_modified.cmp(&a.last_modified))
+    });
+    let now = Local::now();
+    sorted
+        .iter()
+        .map(|p| {
+            format!(
+                "    {} {} {} {}",
+                cyan(&format!("({})", p.display_kind())),
+                bold(&p.name),
+                dim(&format_age(now - p.last_modified)),
+                dim(&shorten_path(&p.path.display().to_string())),
+            )
+        })
+        .collect()
+}
+
+/// Print [`format_preserved_recent`] to stderr, so `--json` output stays parseable.
+pub fn print_preserved_recent(preserved: &[&ScannedProject], n: usize) {
+    if preserved.is_empty() {
+        return;
+    }
+    let counts = format!("{n} newest per kind");
+    eprintln!("\n  {} {}", bold("Kept as recent:"), dim(&counts));
+    for line in format_preserved_recent(preserved) {
+        eprintln!("{line}");
+    }
+    eprintln!();
+}
+
+/// Build one line per project saying when it was last cleaned, for `--since-clean`.
+pub fn format_since_clean(
+    projects: &[ScannedProject],
+    last_cleaned: &HashMap<PathBuf, DateTime<Local>>,
+) -> Vec<String> {
+    let now = Local::now();
+    projects
+        .iter()
+        .map(|p| match last_cleaned.get(&p.path) {
+            Some(&when) => format!(
+                "    {} {}: cleaned {}, {} regrown since",
+                yellow("↻"),
+                p.name,
+                format_age(now.signed_duration_since(when)),
+                yellow(&format_bytes(p.total_cleanable_bytes)),
+            ),
+            None => format!("    {} {}: {}", dim("·"), p.name, dim("never cleaned")),
+        })
+        .collect()
+}
+
+/// Build one line per project comparing its size with the largest recorded
+/// for it, for `--record-max`. The history should already include this scan.
+pub fn format_peak_sizes(projects: &[ScannedProject], history: &PeakHistory) -> Vec<String> {
+    let now = Local::now();
+    projects
+        .iter()
+        .map(|p| {
+            let current = p.total_cleanable_bytes;
+            match history.peak(p) {
+                Some(peak) if peak.bytes > current => format!(
+                    "    {} {}: {} of a {} peak ({}%), seen {}",
+                    dim("·"),
+                    p.name,
+                    format_bytes(current),
+                    yellow(&format_bytes(peak.bytes)),
+                    current * 100 / peak.bytes,
+                    format_age(now.signed_duration_since(peak.recorded_at)),
+                ),
+                _ => format!(
+                    "    {} {}: {}, {}",
+                    yellow("▲"),
+                    p.name,
+                    yellow(&format_bytes(current)),
+                    dim("the largest recorded")
+                ),
+            }
+        })
+        .collect()
+}
+
+/// Build one line per project whose size changed since the cached scan, for
+/// `--since-last`: the delta, what happened, and the project.
+pub fn format_scan_changes(changes: &[ProjectChange]) -> Vec<String> {
+    changes
+        .iter()
+        .map(|c| {
+            let amount = format_bytes(c.delta().unsigned_abs() as u64);
+            let delta = if c.delta() < 0 {
+                green(&pad_left(&format!("-{amount}"), 11))
+            } else {
+                yellow(&pad_left(&format!("+{amount}"), 11))
+            };
+            let (status, detail) = match c.status {
+                ChangeStatus::New => (cyan("new    "), String::new()),
+                ChangeStatus::Changed => (
+                    blue("changed"),
+                    dim(&format!(
+                        " ({} → {})",
+                        format_bytes(c.previous_bytes),
+                        format_bytes(c.current_bytes)
+                    )),
+                ),
+                ChangeStatus::Removed => (dim("removed"), String::new()),
+            };
+            format!(
+                "  {}  {}  {} {}{}",
+                delta,
+                status,
+                c.name,
+                dim(&shorten_path(&c.path.display().to_string())),
+                detail,
+            )
+        })
+        .collect()
+}
+
+/// Render a line diff with `-`/`+` markers, removed lines red and added lines green.
+pub fn format_line_diff(changes: &[LineChange]) -> Vec<String> {
+    changes
+        .iter()
+        .map(|change| match change {
+            LineChange::Same(line) => format!("    {line}"),
+            LineChange::Removed(line) => red(&format!("  - {line}")),
+            LineChange::Added(line) => green(&format!("  + {line}")),
+        })
+        .collect()
+}
+
 /// Print a summary after cleaning.
 pub fn print_clean_summary(results: &[CleanResult], dry_run: bool) {
     let total_freed: u64 = results.iter().map(|r| r.bytes_freed).sum();
@@ -176,6 +762,11 @@ pub fn print_clean_summary(results: &[CleanResult], dry_run: bool) {
             cyan(&total_targets.to_string()),
             cyan(&results.len().to_string()),
         );
+        print_skipped_active(results);
+        print_skipped_unsafe(results);
+        print_skipped_stale(results);
+        print_skipped_git(results);
+        print_skipped_in_use(results);
         println!(
             "  {} Run without {} to actually clean.\n",
             dim("→"),
@@ -189,6 +780,28 @@ pub fn print_clean_summary(results: &[CleanResult], dry_run: bool) {
             cyan(&total_targets.to_string()),
             cyan(&results.len().to_string()),
         );
+        if total_targets > 0 {
+            println!("{}", format_freed_estimate(results));
+        }
+
+        let archives: Vec<_> = results.iter().flat_map(|r| &r.archives).collect();
+        if let Some(dir) = archives.first().and_then(|a| a.parent()) {
+            println!(
+                "  {} {} archives written to {}",
+                dim("→"),
+                cyan(&archives.len().to_string()),
+                dir.display(),
+            );
+        }
+        if let Some(note) = format_quarantine_note(results) {
+            println!("{note}");
+        }
+
+        print_skipped_active(results);
+        print_skipped_unsafe(results);
+        print_skipped_stale(results);
+        print_skipped_git(results);
+        print_skipped_in_use(results);
 
         if total_errors > 0 {
             println!("  {} {} errors occurred:", yellow("⚠"), total_errors,);
@@ -198,40 +811,836 @@ pub fn print_clean_summary(results: &[CleanResult], dry_run: bool) {
                 }
             }
         }
+
+        let warnings: usize = results.iter().map(|r| r.warnings.len()).sum();
+        if warnings > 0 {
+            println!("  {} {} warnings:", yellow("⚠"), warnings);
+            for result in results {
+                for warning in &result.warnings {
+                    println!("    {} {}: {}", yellow("!"), result.project_name, warning);
+                }
+            }
+        }
+
+        if let Some(hint) = format_permission_hint(results) {
+            println!("{hint}");
+        }
         println!();
     }
 }
 
+/// Where `clean --quarantine` moved targets to, and how to free their space,
+/// or `None` if nothing was quarantined.
+pub fn format_quarantine_note(results: &[CleanResult]) -> Option<String> {
+    let entries: Vec<_> = results.iter().flat_map(|r| &r.quarantined).collect();
+    let dir = entries.first()?.location.parent()?;
+    let bytes: u64 = entries.iter().map(|e| e.size_bytes).sum();
+    let noun = if entries.len() == 1 {
+        "target"
+    } else {
+        "targets"
+    };
+    Some(format!(
+        "  {} {} {} ({}) moved to quarantine in {}; {} frees the space",
+        dim("→"),
+        cyan(&entries.len().to_string()),
+        noun,
+        format_bytes(bytes),
+        dir.display(),
+        green("dev-sweep purge-quarantine"),
+    ))
+}
+
+/// Compare the scan's estimate with the bytes measured at removal, highlighting
+/// the difference when it's beyond [`crate::cleaner::FREED_DRIFT_THRESHOLD`].
+pub fn format_freed_estimate(results: &[CleanResult]) -> String {
+    let estimated: u64 = results.iter().map(|r| r.bytes_estimated).sum();
+    let freed: u64 = results.iter().map(|r| r.bytes_freed).sum();
+    let line = format!(
+        "  {} Scan estimated {}, measured {} at removal",
+        dim("→"),
+        format_bytes(estimated),
+        format_bytes(freed),
+    );
+    match freed_drift(estimated, freed) {
+        Some(delta) => {
+            let sign = if delta < 0 { "-" } else { "+" };
+            let change = format!(
+                "({}{}, changed since the scan)",
+                sign,
+                format_bytes(delta.unsigned_abs() as u64)
+            );
+            format!("{} {}", line, yellow(&change))
+        }
+        None => line,
+    }
+}
+
+/// The live status line shown while a clean runs: how many of `total` projects
+/// (or targets, per `unit`) are done and the space freed so far.
+pub fn format_clean_progress(
+    done: usize,
+    total: usize,
+    unit: &str,
+    freed: u64,
+    dry_run: bool,
+) -> String {
+    let verb = if dry_run { "would be freed" } else { "freed" };
+    format!(
+        "  {} Cleaned {} of {} {}, {} {}",
+        cyan("⠿"),
+        done,
+        total,
+        unit,
+        format_bytes(freed),
+        verb,
+    )
+}
+
+/// After Ctrl-C: how far a clean of `total` projects (or targets, per `unit`)
+/// got before it stopped, and what it freed until then.
+pub fn format_interrupted(results: &[CleanResult], total: usize, unit: &str) -> String {
+    let freed: u64 = results.iter().map(|r| r.bytes_freed).sum();
+    format!(
+        "  {} Interrupted after {} of {} {}; {} freed so far. The rest was left in place.",
+        yellow("⚠"),
+        cyan(&results.len().to_string()),
+        cyan(&total.to_string()),
+        unit,
+        yellow_bold(&format_bytes(freed)),
+    )
+}
+
+/// After `--time-budget` ran out: how far the clean got, what it freed, and
+/// the projects (or targets, per `unit`) in `remaining` it never started.
+pub fn format_time_budget_exhausted(
+    results: &[CleanResult],
+    remaining: &[&ScannedProject],
+    unit: &str,
+) -> String {
+    let freed: u64 = results.iter().map(|r| r.bytes_freed).sum();
+    let left: u64 = remaining.iter().map(|p| p.total_cleanable_bytes).sum();
+    format!(
+        "  {} Time budget used up after {} of {} {}; {} freed. {} {} with {} were left for another run.",
+        yellow("⏱"),
+        cyan(&results.len().to_string()),
+        cyan(&(results.len() + remaining.len()).to_string()),
+        unit,
+        yellow_bold(&format_bytes(freed)),
+        cyan(&remaining.len().to_string()),
+        unit,
+        yellow_bold(&format_bytes(left)),
+    )
+}
+
+/// List targets skipped because they were modified too recently.
+fn print_skipped_active(results: &[CleanResult]) {
+    let skipped: Vec<_> = results.iter().flat_map(|r| &r.skipped_active).collect();
+    if skipped.is_empty() {
+        return;
+    }
+    println!(
+        "  {} {} targets skipped as active (recently modified):",
+        yellow("⚠"),
+        skipped.len()
+    );
+    for path in skipped {
+        println!(
+            "    {} {}",
+            yellow("⏸"),
+            shorten_path(&path.display().to_string())
+        );
+    }
+    println!(
+        "  {} Use {} to clean them anyway.",
+        dim("→"),
+        green("--force")
+    );
+}
+
+/// List targets left alone because safe mode protects them.
+fn print_skipped_unsafe(results: &[CleanResult]) {
+    let skipped: Vec<_> = results.iter().flat_map(|r| &r.skipped_unsafe).collect();
+    if skipped.is_empty() {
+        return;
+    }
+    println!(
+        "  {} {} targets kept as protected ({}, {}, or keep_last_build):",
+        yellow("⚠"),
+        skipped.len(),
+        green("--safe"),
+        green("--skip-files-over")
+    );
+    for path in skipped {
+        println!(
+            "    {} {}",
+            yellow("⏸"),
+            shorten_path(&path.display().to_string())
+        );
+    }
+}
+
+/// List targets left alone because they changed since the scan.
+fn print_skipped_stale(results: &[CleanResult]) {
+    let skipped: Vec<_> = results.iter().flat_map(|r| &r.skipped_stale).collect();
+    if skipped.is_empty() {
+        return;
+    }
+    println!(
+        "  {} {} targets skipped because they moved or changed since the scan:",
+        yellow("⚠"),
+        skipped.len()
+    );
+    for path in skipped {
+        println!(
+            "    {} {}",
+            yellow("⏸"),
+            shorten_path(&path.display().to_string())
+        );
+    }
+    println!("  {} Scan again to pick up their new location.", dim("→"));
+}
+
+/// List projects left alone by `--require-git-clean`, with the reason.
+fn print_skipped_git(results: &[CleanResult]) {
+    let skipped: Vec<_> = results
+        .iter()
+        .filter_map(|r| Some((&r.project_name, r.skipped_git.as_ref()?)))
+        .collect();
+    if skipped.is_empty() {
+        return;
+    }
+    println!(
+        "  {} {} projects skipped by {}:",
+        yellow("⚠"),
+        skipped.len(),
+        green("--require-git-clean")
+    );
+    for (name, reason) in skipped {
+        println!("    {} {} {}", yellow("⏸"), name, dim(&format!("({reason})")));
+    }
+}
+
+/// List projects left alone by `--exclude-active-editors`, with the editor
+/// file that gave them away.
+fn print_skipped_in_use(results: &[CleanResult]) {
+    let skipped: Vec<_> = results
+        .iter()
+        .filter_map(|r| Some((&r.project_name, r.skipped_in_use.as_ref()?)))
+        .collect();
+    if skipped.is_empty() {
+        return;
+    }
+    println!(
+        "  {} {} projects in use, skipped by {}:",
+        yellow("⚠"),
+        skipped.len(),
+        green("--exclude-active-editors")
+    );
+    for (name, marker) in skipped {
+        let marker = shorten_path(&marker.display().to_string());
+        println!("    {} {} {}", yellow("⏸"), name, dim(&format!("({marker})")));
+    }
+}
+
+/// The config files in use, lowest priority first, and the layer each
+/// setting came from, for `config`.
+pub fn format_config_layers(
+    files: &[(ConfigLayer, PathBuf)],
+    origins: &BTreeMap<String, ConfigLayer>,
+) -> Vec<String> {
+    if files.is_empty() && origins.is_empty() {
+        return vec![format!("  Layers:      {}", dim("none (using defaults)"))];
+    }
+    let mut lines = vec![format!("  Layers:      {}", dim("(later ones win)"))];
+    for (layer, path) in files {
+        lines.push(format!(
+            "    {}  {}",
+            pad_right(&layer.to_string(), 11),
+            path.display()
+        ));
+    }
+    if !origins.is_empty() {
+        let width = origins.keys().map(|k| k.len()).max().unwrap_or(0);
+        lines.push("  Set by:".to_string());
+        for (key, layer) in origins {
+            lines.push(format!(
+                "    {}  {}",
+                pad_right(key, width),
+                cyan(&layer.to_string())
+            ));
+        }
+    }
+    lines
+}
+
+/// One line per config snapshot, for `config --list-snapshots`: its name,
+/// then when it was taken, relative to `now` as well.
+pub fn format_config_snapshots(snapshots: &[ConfigSnapshot], now: DateTime<Local>) -> Vec<String> {
+    let width = snapshots
+        .iter()
+        .map(|s| visible_len(&s.name))
+        .max()
+        .unwrap_or(0);
+    snapshots
+        .iter()
+        .map(|s| {
+            let age = format_age(now.signed_duration_since(s.taken));
+            format!(
+                "    {}  {} {}",
+                cyan(&pad_right(&s.name, width)),
+                s.taken.format("%Y-%m-%d %H:%M:%S"),
+                dim(&format!("({age})")),
+            )
+        })
+        .collect()
+}
+
+/// A hint with the exact command to remove targets that failed for lack of
+/// permission, or `None` if every failure had some other cause.
+pub fn format_permission_hint(results: &[CleanResult]) -> Option<String> {
+    let denied: Vec<&PathBuf> = results.iter().flat_map(|r| &r.permission_denied).collect();
+    if denied.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "  {} {} targets need elevated privileges. To remove just those, run:\n      {}",
+        dim("→"),
+        denied.len(),
+        green(&elevated_remove_command(&denied)),
+    ))
+}
+
+// ── Path tree ───────────────────────────────────────────────────────────────
+
+/// Build the indented lines of a `summary --tree` report, down to `max_depth`
+/// levels below the root. Single-child chains are kept as-is so every line is a
+/// real directory.
+pub fn format_path_tree(tree: &PathTree, max_depth: usize) -> Vec<String> {
+    let mut lines = vec![format!(
+        "  {} {}",
+        yellow_bold(&pad_left(&format_bytes(tree.bytes), 10)),
+        bold(&tree.name)
+    )];
+    push_tree_children(tree, 1, max_depth, &mut lines);
+    lines
+}
+
+fn push_tree_children(node: &PathTree, depth: usize, max_depth: usize, lines: &mut Vec<String>) {
+    if depth > max_depth {
+        return;
+    }
+    for child in &node.children {
+        let projects = if child.children.is_empty() {
+            String::new()
+        } else {
+            dim(&format!(" ({} projects)", child.projects))
+        };
+        lines.push(format!(
+            "  {} {}{}{}",
+            yellow(&pad_left(&format_bytes(child.bytes), 10)),
+            "  ".repeat(depth),
+            child.name,
+            projects,
+        ));
+        push_tree_children(child, depth + 1, max_depth, lines);
+    }
+}
+
+// ── Duplicated dependencies ─────────────────────────────────────────────────
+
+/// How many duplicate sets `summary --duplication` lists.
+pub const DUPLICATION_LIMIT: usize = 10;
+
+/// Build the lines of a `summary --duplication` report: the dedupable total,
+/// then up to `limit` sets, each with the projects sharing it.
+pub fn format_duplication(report: &DuplicationReport, limit: usize) -> Vec<String> {
+    if report.sets.is_empty() {
+        return vec![format!(
+            "  {}",
+            dim("No dependency directories installed from identical lockfiles.")
+        )];
+    }
+
+    let mut lines = vec![format!(
+        "  Potentially dedupable: {} in {} duplicated dependency sets",
+        yellow_bold(&format_bytes(report.total_dedupable_bytes)),
+        cyan(&report.sets.len().to_string()),
+    )];
+    for set in report.sets.iter().take(limit) {
+        lines.push(format!(
+            "  {} {} ({} copies of {}, {} total)",
+            yellow(&pad_left(&format_bytes(set.dedupable_bytes), 10)),
+            set.target,
+            set.projects.len(),
+            set.lockfile,
+            format_bytes(set.total_bytes),
+        ));
+        for path in &set.projects {
+            lines.push(format!(
+                "  {}   {}",
+                " ".repeat(10),
+                dim(&shorten_path(&path.display().to_string()))
+            ));
+        }
+    }
+    if report.sets.len() > limit {
+        lines.push(format!(
+            "  {}",
+            dim(&format!("...and {} more sets", report.sets.len() - limit))
+        ));
+    }
+    lines
+}
+
+// ── Deletion plan ───────────────────────────────────────────────────────────
+
+/// Maximum number of target lines shown before a confirmation prompt.
+pub const DELETION_PLAN_LIMIT: usize = 20;
+
+/// Build the lines listing every target that is about to be removed.
+///
+/// At most `limit` target lines are produced (projects are listed as long as at
+/// least one of their targets fits); the rest are summarized in a final
+/// "...and N more" line. `None` lists everything.
+pub fn format_deletion_plan(projects: &[&ScannedProject], limit: Option<usize>) -> Vec<String> {
+    let total_targets: usize = projects.iter().map(|p| p.clean_targets.len()).sum();
+    let limit = limit.unwrap_or(usize::MAX);
+    let mut lines = Vec::new();
+    let mut shown = 0;
+
+    for project in projects {
+        if shown >= limit {
+            break;
+        }
+        lines.push(format!(
+            "    {} {}",
+            bold(&project.name),
+            dim(&format!("({})", project.display_kind()))
+        ));
+        for target in &project.clean_targets {
+            if shown >= limit {
+                break;
+            }
+            let path = dim(&shorten_path(&target.path.display().to_string()));
+            if target.large_file {
+                lines.push(format!("      {} {}", path, dim("kept (large file)")));
+            } else if target.protected {
+                lines.push(format!("      {} {}", path, dim("(protected, kept)")));
+            } else if target.runtime_data {
+                lines.push(format!(
+                    "      {} {} {}",
+                    path,
+                    yellow(&format_bytes(target.size_bytes)),
+                    yellow_bold("(logs/core dump, not build output)"),
+                ));
+            } else {
+                lines.push(format!(
+                    "      {} {}",
+                    path,
+                    yellow(&format_bytes(target.size_bytes)),
+                ));
+            }
+            shown += 1;
+        }
+    }
+
+    if shown < total_targets {
+        lines.push(format!(
+            "    {} Run with {} to see the full list.",
+            dim(&format!("...and {} more targets.", total_targets - shown)),
+            green("--dry-run"),
+        ));
+    }
+
+    lines
+}
+
+/// Print the targets that are about to be removed (see [`format_deletion_plan`]).
+pub fn print_deletion_plan(projects: &[&ScannedProject], limit: Option<usize>, dry_run: bool) {
+    let heading = if dry_run {
+        "The following would be deleted:"
+    } else {
+        "The following will be permanently deleted:"
+    };
+    println!("\n  {}", bold(heading));
+    for line in format_deletion_plan(projects, limit) {
+        println!("{line}");
+    }
+    println!();
+
+    let active: Vec<PathBuf> = ["VIRTUAL_ENV", "CONDA_PREFIX"]
+        .iter()
+        .filter_map(|var| std::env::var_os(var).map(PathBuf::from))
+        .collect();
+    let warning = format_environment_warning(projects, &active);
+    if !warning.is_empty() {
+        for line in warning {
+            println!("{line}");
+        }
+        println!();
+    }
+}
+
+/// Warn that deleting Poetry or Conda environments breaks the projects using
+/// them, calling out any of `active` (the shell's current environments) that
+/// would be removed. Empty when no environments are selected.
+pub fn format_environment_warning(projects: &[&ScannedProject], active: &[PathBuf]) -> Vec<String> {
+    let envs: Vec<&PathBuf> = projects
+        .iter()
+        .filter(|p| matches!(p.kind, ProjectKind::Poetry | ProjectKind::Conda))
+        .flat_map(|p| p.clean_targets.iter().map(|t| &t.path))
+        .collect();
+    if envs.is_empty() {
+        return Vec::new();
+    }
+
+    let mut lines = vec![
+        format!(
+            "  {} {}",
+            yellow_bold("⚠"),
+            yellow_bold(&format!(
+                "{} Python environments selected. Projects using them will stop working",
+                envs.len()
+            ))
+        ),
+        format!(
+            "    {}",
+            yellow("until the environment is recreated (poetry install / conda env create).")
+        ),
+    ];
+    for env in envs.iter().filter(|e| active.contains(e)) {
+        lines.push(format!(
+            "    {} {} is the active environment in this shell",
+            red("✗"),
+            env.display()
+        ));
+    }
+    lines
+}
+
+// ── Target results ──────────────────────────────────────────────────────────
+
+/// Build one result line per target cleaned by `clean --target`.
+///
+/// `batch` holds one single-target project per entry, paired index-for-index
+/// with `results`.
+pub fn format_target_results(
+    batch: &[ScannedProject],
+    results: &[CleanResult],
+    dry_run: bool,
+) -> Vec<String> {
+    batch
+        .iter()
+        .zip(results)
+        .flat_map(|(project, result)| {
+            project.clean_targets.iter().map(move |target| {
+                let path = dim(&shorten_path(&target.path.display().to_string()));
+                let kind = dim(&format!("({})", project.display_kind()));
+                if let Some(error) = result.errors.first() {
+                    format!("    {} {} {}", red("✗"), path, red(error))
+                } else if let Some(reason) = &result.skipped_git {
+                    format!(
+                        "    {} {} {}",
+                        yellow("⏸"),
+                        path,
+                        yellow(&format!("skipped ({reason})"))
+                    )
+                } else if result.skipped_in_use.is_some() {
+                    format!(
+                        "    {} {} {}",
+                        yellow("⏸"),
+                        path,
+                        yellow("skipped (in use)")
+                    )
+                } else if target.large_file {
+                    format!(
+                        "    {} {} {}",
+                        yellow("⏸"),
+                        path,
+                        yellow("kept (large file)")
+                    )
+                } else if result.skipped_unsafe.contains(&target.path) {
+                    format!(
+                        "    {} {} {}",
+                        yellow("⏸"),
+                        path,
+                        yellow("skipped (protected)")
+                    )
+                } else if result.skipped_stale.contains(&target.path) {
+                    format!(
+                        "    {} {} {}",
+                        yellow("⏸"),
+                        path,
+                        yellow("skipped (changed since the scan)")
+                    )
+                } else if result.skipped_active.contains(&target.path) {
+                    format!(
+                        "    {} {} {}",
+                        yellow("⏸"),
+                        path,
+                        yellow("skipped (active)")
+                    )
+                } else if dry_run {
+                    let size = yellow(&format_bytes(target.size_bytes));
+                    format!("    {} {} {} {}", blue("•"), path, size, kind)
+                } else {
+                    let size = green(&format_bytes(target.size_bytes));
+                    let line = format!("    {} {} {} {}", green("✓"), path, size, kind);
+                    match result.warnings.first() {
+                        Some(warning) => format!("{line} {}", yellow(warning)),
+                        None => line,
+                    }
+                }
+            })
+        })
+        .collect()
+}
+
+/// Summarize a `clean --auto` run: one line per kind, with how many of its
+/// projects were cleaned and what that freed. Projects where nothing was
+/// removed aren't counted.
+pub fn format_auto_clean_summary(
+    projects: &[&ScannedProject],
+    results: &[CleanResult],
+    dry_run: bool,
+) -> Vec<String> {
+    let mut by_kind: BTreeMap<String, (usize, u64)> = BTreeMap::new();
+    for (project, result) in projects.iter().zip(results) {
+        if result.targets_cleaned > 0 {
+            let entry = by_kind.entry(project.display_kind()).or_default();
+            entry.0 += 1;
+            entry.1 += result.bytes_freed;
+        }
+    }
+
+    let verb = if dry_run {
+        "Would auto-clean"
+    } else {
+        "Auto-cleaned"
+    };
+    if by_kind.is_empty() {
+        return vec![format!("\n  {} {} nothing.", blue("ℹ"), verb)];
+    }
+    let kind_width = by_kind.keys().map(String::len).max().unwrap_or(0);
+    let mut lines = vec![format!("\n  {} {}:", bold("⚙"), verb)];
+    for (kind, (count, bytes)) in by_kind {
+        let noun = if count == 1 { "project" } else { "projects" };
+        lines.push(format!(
+            "    {}  {} {}, {}",
+            cyan(&pad_right(&kind, kind_width)),
+            count,
+            noun,
+            format_bytes(bytes),
+        ));
+    }
+    lines
+}
+
 // ── Prompt helpers ──────────────────────────────────────────────────────────
 
+/// How long prompts wait for an answer, in seconds; `0` waits forever.
+static CONFIRM_TIMEOUT_SECS: AtomicU64 = AtomicU64::new(0);
+
+/// Make every prompt give up, aborting, after `secs` seconds without an answer
+/// (`confirm_timeout_secs`). `None` or `0` waits forever, the default.
+pub fn set_confirm_timeout(secs: Option<u64>) {
+    CONFIRM_TIMEOUT_SECS.store(secs.unwrap_or(0), Ordering::Relaxed);
+}
+
+/// Read one line of an answer from stdin, within the configured timeout.
+fn read_answer() -> anyhow::Result<String> {
+    let secs = CONFIRM_TIMEOUT_SECS.load(Ordering::Relaxed);
+    let timeout = (secs > 0).then(|| Duration::from_secs(secs));
+    read_line_within(|input| io::stdin().read_line(input), timeout)
+}
+
+/// Read one line with `read_line`. With a `timeout`, the read happens on its
+/// own thread, and if nothing arrives in time the prompt fails with a "timed
+/// out, aborting" error instead of waiting: no answer never counts as a yes.
+/// An empty string means end of input.
+pub fn read_line_within(
+    read_line: impl FnOnce(&mut String) -> io::Result<usize> + Send + 'static,
+    timeout: Option<Duration>,
+) -> anyhow::Result<String> {
+    let Some(timeout) = timeout else {
+        let mut input = String::new();
+        read_line(&mut input)?;
+        return Ok(input);
+    };
+
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let mut input = String::new();
+        let _ = tx.send(read_line(&mut input).map(|_| input));
+    });
+    match rx.recv_timeout(timeout) {
+        Ok(read) => Ok(read?),
+        Err(_) => {
+            // The prompt is still waiting on its line.
+            eprintln!();
+            let secs = timeout.as_secs_f64();
+            let unit = if secs == 1.0 { "second" } else { "seconds" };
+            anyhow::bail!("No answer within {secs} {unit}: timed out, aborting")
+        }
+    }
+}
+
 /// Display a multi-select prompt. Returns the indices selected.
+///
+/// Entering numbers straight away selects them and finishes, as before. Typing
+/// `/text` first narrows the list by fuzzy match; from then on numbers toggle
+/// items in the current view and an empty line finishes.
 pub fn multi_select(prompt: &str, items: &[String]) -> anyhow::Result<Vec<usize>> {
     println!("\n  {}", bold(prompt));
     println!(
-        "  {}\n",
+        "  {}",
         dim("Enter numbers separated by commas/spaces, ranges with dash (e.g. 1,3,5-8), or 'all'")
     );
+    println!(
+        "  {}\n",
+        dim("Or type /text to filter, then toggle by number (+ all shown, - none, Enter done)")
+    );
 
-    for (i, item) in items.iter().enumerate() {
-        println!("    {}  {}", cyan_bold(&format!("{:>3}", i + 1)), item);
+    let mut picker = Picker::new(items);
+    loop {
+        for line in picker.render() {
+            println!("{line}");
+        }
+
+        print!("\n  {} ", green_bold("❯"));
+        io::stdout().flush()?;
+
+        // End of input reads as an empty line, which finishes the selection.
+        let input = read_answer()?;
+        match picker.apply(&input) {
+            Ok(true) => return Ok(picker.selection()),
+            Ok(false) => println!(),
+            Err(e) if picker.is_pristine() => return Err(e),
+            Err(e) => println!("  {} {}\n", red("✗"), e),
+        }
     }
+}
 
-    print!("\n  {} ", green_bold("❯"));
-    io::stdout().flush()?;
+/// State of the interactive multi-select list: the filter typed so far and the
+/// items toggled on. Indices always refer to the full, unfiltered `items`.
+#[derive(Debug, Clone)]
+pub struct Picker<'a> {
+    items: &'a [String],
+    query: String,
+    selected: Vec<bool>,
+    interactive: bool,
+}
 
-    let mut input = String::new();
-    io::stdin().read_line(&mut input)?;
-    let input = input.trim();
+impl<'a> Picker<'a> {
+    pub fn new(items: &'a [String]) -> Self {
+        Self {
+            items,
+            query: String::new(),
+            selected: vec![false; items.len()],
+            interactive: false,
+        }
+    }
 
-    if input.is_empty() {
-        return Ok(Vec::new());
+    /// Whether nothing has been filtered or toggled yet.
+    pub fn is_pristine(&self) -> bool {
+        !self.interactive
     }
 
-    if input.eq_ignore_ascii_case("all") {
-        return Ok((0..items.len()).collect());
+    /// Indices of the items matching the current filter, in list order.
+    pub fn visible(&self) -> Vec<usize> {
+        (0..self.items.len())
+            .filter(|&i| fuzzy_match(&self.query, &self.items[i]))
+            .collect()
+    }
+
+    /// Selected indices, sorted.
+    pub fn selection(&self) -> Vec<usize> {
+        (0..self.items.len())
+            .filter(|&i| self.selected[i])
+            .collect()
+    }
+
+    /// Apply one line of input. Returns `true` once the selection is final.
+    pub fn apply(&mut self, input: &str) -> anyhow::Result<bool> {
+        let input = input.trim();
+
+        if let Some(query) = input.strip_prefix('/') {
+            self.query = query.trim().to_string();
+            self.interactive = true;
+            return Ok(false);
+        }
+
+        let visible = self.visible();
+        match input {
+            "" => return Ok(true),
+            "+" | "*" => visible.iter().for_each(|&i| self.selected[i] = true),
+            "-" => visible.iter().for_each(|&i| self.selected[i] = false),
+            _ if input.eq_ignore_ascii_case("all") => {
+                visible.iter().for_each(|&i| self.selected[i] = true);
+                return Ok(!self.interactive);
+            }
+            _ => {
+                let picked = parse_selection(input, visible.len())?;
+                if !self.interactive {
+                    for n in picked {
+                        self.selected[visible[n]] = true;
+                    }
+                    return Ok(true);
+                }
+                for n in picked {
+                    self.selected[visible[n]] ^= true;
+                }
+            }
+        }
+
+        self.interactive = true;
+        Ok(false)
     }
 
-    parse_selection(input, items.len())
+    /// The numbered lines of the current view, with checkboxes once toggling starts.
+    pub fn render(&self) -> Vec<String> {
+        let visible = self.visible();
+        let mut lines: Vec<String> = visible
+            .iter()
+            .enumerate()
+            .map(|(n, &i)| {
+                let number = cyan_bold(&format!("{:>3}", n + 1));
+                if !self.interactive {
+                    format!("    {}  {}", number, self.items[i])
+                } else if self.selected[i] {
+                    format!("    {} {} {}", number, green("[x]"), self.items[i])
+                } else {
+                    format!("    {} {} {}", number, dim("[ ]"), self.items[i])
+                }
+            })
+            .collect();
+
+        if !self.query.is_empty() {
+            lines.push(format!(
+                "\n  {}",
+                dim(&format!(
+                    "Filter '{}': {} of {} shown, {} selected ('/' clears)",
+                    self.query,
+                    visible.len(),
+                    self.items.len(),
+                    self.selected.iter().filter(|&&s| s).count(),
+                ))
+            ));
+        }
+        lines
+    }
+}
+
+/// Whether every character of `query` appears in `text` in order, ignoring case
+/// and whitespace in the query (so "dsw" matches "dev-sweep").
+pub fn fuzzy_match(query: &str, text: &str) -> bool {
+    let mut haystack = text.chars().flat_map(char::to_lowercase);
+    query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .all(|q| haystack.any(|c| c == q))
 }
 
 /// Parse a selection string like "1,3,5-8" into a sorted, deduplicated list of zero-based indices.
@@ -275,11 +1684,54 @@ pub fn parse_selection(input: &str, max: usize) -> anyhow::Result<Vec<usize>> {
 
 /// Display a yes/no confirmation prompt.
 pub fn confirm(prompt: &str) -> anyhow::Result<bool> {
-    print!("  {} {} {} ", yellow("⚠"), prompt, dim("[y/N]"));
-    io::stdout().flush()?;
+    confirm_on(&mut io::stdout(), prompt)
+}
+
+/// Like [`confirm`], but prompting on stderr so stdout stays machine-readable.
+pub fn confirm_stderr(prompt: &str) -> anyhow::Result<bool> {
+    confirm_on(&mut io::stderr(), prompt)
+}
+
+/// Like [`confirm`], but for the most destructive cleans: instead of a yes or
+/// no, the user has to type `expected` (such as the size being freed), the way
+/// deleting a repository asks for its name. Anything else declines.
+pub fn confirm_phrase(prompt: &str, expected: &str) -> anyhow::Result<bool> {
+    confirm_phrase_on(&mut io::stdout(), prompt, expected)
+}
+
+/// Like [`confirm_phrase`], but prompting on stderr so stdout stays machine-readable.
+pub fn confirm_phrase_stderr(prompt: &str, expected: &str) -> anyhow::Result<bool> {
+    confirm_phrase_on(&mut io::stderr(), prompt, expected)
+}
+
+fn confirm_phrase_on(out: &mut impl Write, prompt: &str, expected: &str) -> anyhow::Result<bool> {
+    writeln!(out, "  {} {}", yellow("⚠"), prompt)?;
+    write!(out, "    Type {} to confirm: ", bold(expected))?;
+    out.flush()?;
+
+    let input = read_answer()?;
+
+    Ok(phrase_matches(&input, expected))
+}
+
+/// Whether typed `input` is the confirmation phrase `expected`. Case and
+/// whitespace don't matter, so `12.5gb` confirms `12.5 GB`.
+pub fn phrase_matches(input: &str, expected: &str) -> bool {
+    let normalize = |s: &str| -> String {
+        s.chars()
+            .filter(|c| !c.is_whitespace())
+            .flat_map(char::to_lowercase)
+            .collect()
+    };
+    let expected = normalize(expected);
+    !expected.is_empty() && normalize(input) == expected
+}
+
+fn confirm_on(out: &mut impl Write, prompt: &str) -> anyhow::Result<bool> {
+    write!(out, "  {} {} {} ", yellow("⚠"), prompt, dim("[y/N]"))?;
+    out.flush()?;
 
-    let mut input = String::new();
-    io::stdin().read_line(&mut input)?;
+    let input = read_answer()?;
 
     Ok(input.trim().eq_ignore_ascii_case("y") || input.trim().eq_ignore_ascii_case("yes"))
 }
@@ -0,0 +1,85 @@
+use std::borrow::Cow;
+use std::ffi::OsString;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// ── ASCII fallback for symbols ──────────────────────────────────────────────
+
+static ASCII: AtomicBool = AtomicBool::new(false);
+
+/// Switch every symbol in the output to its ASCII stand-in (e.g. for `--ascii`).
+pub fn set_ascii(ascii: bool) {
+    ASCII.store(ascii, Ordering::Relaxed);
+}
+
+/// Whether symbols are printed as ASCII.
+pub fn ascii() -> bool {
+    ASCII.load(Ordering::Relaxed)
+}
+
+/// Whether the environment, read through `var`, suggests a terminal that
+/// can't show Unicode: `TERM=dumb`, or a locale that is set but isn't UTF-8
+/// (`LANG=C`, `LC_ALL=en_US.ISO-8859-1`, ...). The first non-empty of
+/// `LC_ALL`, `LC_CTYPE`, and `LANG` is the locale, as in POSIX. With no
+/// locale set at all, nothing can be told, so Unicode is kept.
+pub fn detect_ascii(var: impl Fn(&str) -> Option<OsString>) -> bool {
+    if var("TERM").is_some_and(|term| term == "dumb") {
+        return true;
+    }
+    let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+        .into_iter()
+        .filter_map(&var)
+        .find(|value| !value.is_empty());
+    locale.is_some_and(|locale| {
+        let locale = locale.to_string_lossy().to_ascii_lowercase();
+        !locale.contains("utf-8") && !locale.contains("utf8")
+    })
+}
+
+/// The ASCII stand-in for a symbol dev-sweep prints. Box-drawing characters
+/// map to one character each so tables keep their alignment.
+fn ascii_for(c: char) -> Option<&'static str> {
+    Some(match c {
+        '✓' => "+",
+        '✗' => "x",
+        '⚠' | '⏱' => "!",
+        'ℹ' => "i",
+        '⏸' => "-",
+        '→' => "->",
+        '↻' => "~",
+        '▲' => "^",
+        '■' => "#",
+        '•' => "*",
+        '·' => "-",
+        '—' | '–' => "-",
+        '…' => "...",
+        '❯' => ">",
+        '🔍' | '🧹' | '📊' | '🧪' | '⚙' | '🔒' | '⠿' => "*",
+        '│' => "|",
+        '─' => "-",
+        '╭' | '╮' | '╰' | '╯' | '├' | '┤' | '┬' | '┴' | '┼' => "+",
+        _ => return None,
+    })
+}
+
+/// `s` with every symbol replaced by its ASCII stand-in. Anything else, such
+/// as a project name, is left as it is.
+pub fn to_ascii(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match ascii_for(c) {
+            Some(stand_in) => out.push_str(stand_in),
+            None => out.push(c),
+        }
+    }
+    out
+}
+
+/// `s` as it should be printed: unchanged, or through [`to_ascii`] when
+/// ASCII symbols are on.
+pub fn plain(s: &str) -> Cow<'_, str> {
+    if ascii() {
+        Cow::Owned(to_ascii(s))
+    } else {
+        Cow::Borrowed(s)
+    }
+}
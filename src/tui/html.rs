@@ -0,0 +1,158 @@
+use std::fmt::Write;
+use std::path::PathBuf;
+
+use chrono::{DateTime, Local};
+
+use crate::filter::group_by_kind;
+use crate::scanner::ScannedProject;
+use crate::util::format_bytes;
+
+/// Inline styles, so the report has no external assets.
+const STYLE: &str = "\
+body { font-family: -apple-system, 'Segoe UI', Helvetica, Arial, sans-serif; margin: 2em; color: #222; }
+h1 { font-size: 1.5em; margin-bottom: 0.2em; }
+.meta { color: #666; margin-top: 0; }
+.totals { font-size: 1.1em; }
+.totals strong { color: #b45309; }
+table { border-collapse: collapse; margin: 1em 0 2em; }
+th, td { padding: 0.4em 0.8em; border-bottom: 1px solid #ddd; text-align: left; vertical-align: top; }
+th { background: #f4f4f5; }
+#projects th { cursor: pointer; user-select: none; }
+#projects th:hover { background: #e4e4e7; }
+td.size { text-align: right; white-space: nowrap; font-variant-numeric: tabular-nums; }
+td.path { color: #666; font-family: ui-monospace, Menlo, Consolas, monospace; font-size: 0.9em; }
+.protected { color: #999; }
+";
+
+/// Click a project table header to sort by it; click again to reverse.
+const SCRIPT: &str = "\
+document.querySelectorAll('#projects th').forEach(function (th, col) {
+  th.addEventListener('click', function () {
+    var body = th.closest('table').tBodies[0];
+    var rows = Array.from(body.rows);
+    var numeric = th.dataset.type === 'number';
+    var dir = th.dataset.dir === 'asc' ? -1 : 1;
+    rows.sort(function (a, b) {
+      var x = a.cells[col].dataset.sort || a.cells[col].textContent;
+      var y = b.cells[col].dataset.sort || b.cells[col].textContent;
+      return dir * (numeric ? x - y : x.localeCompare(y));
+    });
+    th.dataset.dir = dir === 1 ? 'asc' : 'desc';
+    rows.forEach(function (row) { body.appendChild(row); });
+  });
+});
+";
+
+/// Render a self-contained HTML report of `projects` for `scan --format html`:
+/// totals, a by-kind summary, and a sortable project table.
+///
+/// Styles and the sorting script are inline, so the page can be mailed or
+/// hosted as a single file.
+pub fn render_html_report(
+    projects: &[ScannedProject],
+    roots: &[PathBuf],
+    generated_at: DateTime<Local>,
+) -> String {
+    let total_bytes: u64 = projects.iter().map(|p| p.total_cleanable_bytes).sum();
+    let roots = roots
+        .iter()
+        .map(|r| escape_html(&r.display().to_string()))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str("<title>dev-sweep report</title>\n");
+    let _ = writeln!(html, "<style>\n{STYLE}</style>\n</head>\n<body>");
+    html.push_str("<h1>dev-sweep report</h1>\n");
+    let _ = writeln!(
+        html,
+        "<p class=\"meta\">Scanned {} on {}</p>",
+        roots,
+        generated_at.format("%Y-%m-%d %H:%M")
+    );
+    let _ = writeln!(
+        html,
+        "<p class=\"totals\">{} projects with <strong>{}</strong> of reclaimable space</p>",
+        projects.len(),
+        format_bytes(total_bytes)
+    );
+
+    html.push_str("<h2>By project type</h2>\n<table>\n");
+    html.push_str(
+        "<thead><tr><th>Type</th><th>Projects</th><th>Reclaimable</th></tr></thead>\n<tbody>\n",
+    );
+    for (kind, (count, bytes)) in group_by_kind(projects) {
+        let _ = writeln!(
+            html,
+            "<tr><td>{}</td><td class=\"size\">{}</td><td class=\"size\">{}</td></tr>",
+            escape_html(&kind),
+            count,
+            format_bytes(bytes)
+        );
+    }
+    html.push_str("</tbody>\n</table>\n");
+
+    html.push_str("<h2>Projects</h2>\n<table id=\"projects\">\n<thead><tr>");
+    html.push_str("<th>Project</th><th>Type</th><th data-type=\"number\">Reclaimable</th>");
+    html.push_str("<th>Targets</th><th data-type=\"number\">Last modified</th><th>Path</th>");
+    html.push_str("</tr></thead>\n<tbody>\n");
+    for p in projects {
+        let targets = p
+            .clean_targets
+            .iter()
+            .map(|t| {
+                let label = format!("{} ({})", t.name, format_bytes(t.size_bytes));
+                if t.large_file {
+                    format!(
+                        "<span class=\"protected\">{}, kept: large file</span>",
+                        escape_html(&label)
+                    )
+                } else if t.protected {
+                    format!(
+                        "<span class=\"protected\">{}, protected</span>",
+                        escape_html(&label)
+                    )
+                } else if t.runtime_data {
+                    format!("{}, runtime data", escape_html(&label))
+                } else {
+                    escape_html(&label)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("<br>");
+        let _ = writeln!(
+            html,
+            "<tr><td>{}</td><td>{}</td><td class=\"size\" data-sort=\"{}\">{}</td><td>{}</td>\
+             <td data-sort=\"{}\">{}</td><td class=\"path\">{}</td></tr>",
+            escape_html(&p.name),
+            escape_html(&p.display_kind()),
+            p.total_cleanable_bytes,
+            format_bytes(p.total_cleanable_bytes),
+            targets,
+            p.last_modified.timestamp(),
+            p.last_modified.format("%Y-%m-%d"),
+            escape_html(&p.path.display().to_string()),
+        );
+    }
+    html.push_str("</tbody>\n</table>\n");
+
+    let _ = writeln!(html, "<script>\n{SCRIPT}</script>\n</body>\n</html>");
+    html
+}
+
+/// Escape the characters HTML treats specially in text and attribute values.
+pub fn escape_html(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
@@ -1,2 +1,4 @@
 pub mod display;
 pub mod colors;
+pub mod glyphs;
+pub mod html;
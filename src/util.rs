@@ -1,4 +1,10 @@
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+
 use anyhow::Result;
+use serde::Serialize;
+
+use crate::tui::glyphs;
 
 /// Parse an age string like "30d", "3m", "1y" into a chrono TimeDelta.
 ///
@@ -39,6 +45,64 @@ pub fn parse_age(s: &str) -> Result<chrono::TimeDelta> {
     chrono::TimeDelta::try_days(days).ok_or_else(|| anyhow::anyhow!("Duration too large"))
 }
 
+/// Parse a duration string like "90s", "2m", or "1h" into a [`Duration`].
+///
+/// Supported units:
+/// - `s` — seconds
+/// - `m` — minutes (unlike [`parse_age`], where it means months)
+/// - `h` — hours
+///
+/// [`Duration`]: std::time::Duration
+pub fn parse_duration(s: &str) -> Result<std::time::Duration> {
+    let s = s.trim().to_lowercase();
+    let secs_per_unit = match s.chars().last() {
+        Some('s') => 1,
+        Some('m') => 60,
+        Some('h') => 60 * 60,
+        _ => anyhow::bail!(
+            "Invalid duration format '{}'. Use e.g. '90s' (seconds), '2m' (minutes), '1h' (hours)",
+            s
+        ),
+    };
+    let num_str = &s[..s.len() - 1];
+    let num: u64 = num_str
+        .parse()
+        .map_err(|_| anyhow::anyhow!("Invalid number in duration string: '{}'", num_str))?;
+    num.checked_mul(secs_per_unit)
+        .map(std::time::Duration::from_secs)
+        .ok_or_else(|| anyhow::anyhow!("Duration too large"))
+}
+
+/// Parse a size string like "500MB", "1.5 GB", or "2g" into bytes.
+///
+/// Units are powers of 1024, as in [`format_bytes`]: `B`, `K`/`KB`, `M`/`MB`,
+/// `G`/`GB`, `T`/`TB` (case-insensitive). A bare number is a byte count.
+pub fn parse_size(s: &str) -> Result<u64> {
+    let lower = s.trim().to_lowercase();
+    let split = lower
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(lower.len());
+    let (num_str, unit) = lower.split_at(split);
+
+    let multiplier: u64 = match unit.trim() {
+        "" | "b" => 1,
+        "k" | "kb" => 1024,
+        "m" | "mb" => 1024 * 1024,
+        "g" | "gb" => 1024 * 1024 * 1024,
+        "t" | "tb" => 1024 * 1024 * 1024 * 1024,
+        _ => anyhow::bail!(
+            "Invalid size format '{}'. Use e.g. '500MB', '1.5GB', or a byte count",
+            s.trim()
+        ),
+    };
+
+    let num: f64 = num_str
+        .parse()
+        .map_err(|_| anyhow::anyhow!("Invalid number in size string: '{}'", num_str))?;
+
+    Ok((num * multiplier as f64).round() as u64)
+}
+
 /// Format a byte count into a human-readable string (e.g. "1.5 GB").
 pub fn format_bytes(bytes: u64) -> String {
     const KB: u64 = 1024;
@@ -116,14 +180,17 @@ pub fn format_age(duration: chrono::TimeDelta) -> String {
     }
 }
 
-/// Truncate a string to a max visible width, appending "…" if truncated.
+/// Truncate a string to a max visible width, appending "…" (or "..." with
+/// `--ascii`) if truncated.
 pub fn truncate(s: &str, max_width: usize) -> String {
+    let ellipsis = if glyphs::ascii() { "..." } else { "…" };
+    let ellipsis_width = ellipsis.chars().count();
     if s.len() <= max_width {
         s.to_string()
-    } else if max_width > 1 {
-        format!("{}…", &s[..max_width - 1])
+    } else if max_width > ellipsis_width {
+        format!("{}{ellipsis}", &s[..max_width - ellipsis_width])
     } else {
-        "…".to_string()
+        ellipsis.chars().take(max_width.max(1)).collect()
     }
 }
 
@@ -137,3 +204,278 @@ pub fn shorten_path(path: &str) -> String {
     }
     path.to_string()
 }
+
+/// The deepest directory containing every path in `paths`.
+///
+/// A single path is its own ancestor; paths with nothing in common (or an
+/// empty list) give an empty path.
+pub fn common_ancestor(paths: &[PathBuf]) -> PathBuf {
+    let Some((first, rest)) = paths.split_first() else {
+        return PathBuf::new();
+    };
+    let mut ancestor = first.clone();
+    for path in rest {
+        while !path.starts_with(&ancestor) {
+            if !ancestor.pop() {
+                return PathBuf::new();
+            }
+        }
+    }
+    ancestor
+}
+
+/// Drop duplicate paths and any path inside another one in the list, keeping
+/// the original order of the rest.
+pub fn outermost_paths(paths: Vec<PathBuf>) -> Vec<PathBuf> {
+    let mut kept: Vec<PathBuf> = Vec::new();
+    for (i, path) in paths.iter().enumerate() {
+        let covered = paths
+            .iter()
+            .enumerate()
+            .any(|(j, other)| path.starts_with(other) && (path != other || j < i));
+        if !covered {
+            kept.push(path.clone());
+        }
+    }
+    kept
+}
+
+/// One line of a [`diff_lines`] result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineChange<'a> {
+    /// In both texts.
+    Same(&'a str),
+    /// Only in the old text.
+    Removed(&'a str),
+    /// Only in the new text.
+    Added(&'a str),
+}
+
+/// Line-by-line diff of `old` against `new` (longest common subsequence).
+///
+/// Removed lines come before the added lines that replace them.
+pub fn diff_lines<'a>(old: &'a str, new: &'a str) -> Vec<LineChange<'a>> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    // lcs[i][j] = length of the longest common subsequence of old[i..] and new[j..].
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut changes = Vec::with_capacity(old.len().max(new.len()));
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            changes.push(LineChange::Same(old[i]));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            changes.push(LineChange::Removed(old[i]));
+            i += 1;
+        } else {
+            changes.push(LineChange::Added(new[j]));
+            j += 1;
+        }
+    }
+    changes
+}
+
+/// Match `text` against a glob `pattern`, where `*` matches any run of
+/// characters (including none) and `?` matches exactly one.
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Where the last `*` was, and the text position it is currently matched up to.
+    let mut star: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = star {
+            // Let the last `*` swallow one more character and retry.
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Serialize `value` as JSON: on one line without whitespace if `compact`
+/// (`--json-compact`), indented otherwise.
+pub fn to_json(value: &impl Serialize, compact: bool) -> serde_json::Result<String> {
+    if compact {
+        serde_json::to_string(value)
+    } else {
+        serde_json::to_string_pretty(value)
+    }
+}
+
+/// This machine's host name, or `None` if it can't be determined.
+#[cfg(unix)]
+pub fn hostname() -> Option<String> {
+    let mut buf = [0u8; 256];
+    // SAFETY: `buf` is a valid, writable buffer of the length passed.
+    let rc = unsafe { libc::gethostname(buf.as_mut_ptr().cast(), buf.len()) };
+    if rc != 0 {
+        return None;
+    }
+    let len = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+    let name = String::from_utf8_lossy(&buf[..len]).into_owned();
+    (!name.is_empty()).then_some(name)
+}
+
+/// This machine's host name, or `None` if it can't be determined.
+#[cfg(not(unix))]
+pub fn hostname() -> Option<String> {
+    std::env::var("COMPUTERNAME").ok().filter(|n| !n.is_empty())
+}
+
+/// User ID owning `path` (without following symlinks), or `None` if it can't
+/// be read.
+#[cfg(unix)]
+pub fn owner_uid(path: &Path) -> Option<u32> {
+    use std::os::unix::fs::MetadataExt;
+    std::fs::symlink_metadata(path).ok().map(|m| m.uid())
+}
+
+/// User ID owning `path`. Windows has no UIDs, so this is always `None`.
+#[cfg(not(unix))]
+pub fn owner_uid(_path: &Path) -> Option<u32> {
+    None
+}
+
+/// ID of the device (filesystem) holding `path`, without following symlinks.
+#[cfg(unix)]
+pub fn device_id(path: &Path) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+    std::fs::symlink_metadata(path).ok().map(|m| m.dev())
+}
+
+/// ID of the device holding `path`. Device IDs aren't available off Unix, so
+/// this is always `None`; drive letters stand in for them (see [`mount_point`]).
+#[cfg(not(unix))]
+pub fn device_id(_path: &Path) -> Option<u64> {
+    None
+}
+
+/// The mount point of the filesystem holding `path`: its highest ancestor
+/// still on the same device.
+#[cfg(unix)]
+pub fn mount_point(path: &Path) -> PathBuf {
+    let Some(device) = device_id(path) else {
+        return path.to_path_buf();
+    };
+    path.ancestors()
+        .take_while(|dir| device_id(dir) == Some(device))
+        .last()
+        .unwrap_or(path)
+        .to_path_buf()
+}
+
+/// The drive `path` is on, such as `C:\`, which stands in for a mount point.
+#[cfg(not(unix))]
+pub fn mount_point(path: &Path) -> PathBuf {
+    path.ancestors().last().unwrap_or(path).to_path_buf()
+}
+
+/// User ID dev-sweep is running as.
+#[cfg(unix)]
+pub fn current_uid() -> Option<u32> {
+    // SAFETY: geteuid has no preconditions and can't fail.
+    Some(unsafe { libc::geteuid() })
+}
+
+/// User ID dev-sweep is running as. Windows has no UIDs, so this is always `None`.
+#[cfg(not(unix))]
+pub fn current_uid() -> Option<u32> {
+    None
+}
+
+/// Login name of the user with ID `uid`, or `None` if there's no such user.
+#[cfg(unix)]
+pub fn user_name(uid: u32) -> Option<String> {
+    let mut buf = vec![0u8; 4096];
+    let mut pwd: libc::passwd = unsafe { std::mem::zeroed() };
+    let mut found: *mut libc::passwd = std::ptr::null_mut();
+    // SAFETY: `pwd` and `buf` are valid for writes of the sizes passed, and
+    // `found` is only read once the call succeeded.
+    let rc = unsafe {
+        libc::getpwuid_r(
+            uid,
+            &mut pwd,
+            buf.as_mut_ptr().cast(),
+            buf.len(),
+            &mut found,
+        )
+    };
+    if rc != 0 || found.is_null() {
+        return None;
+    }
+    // SAFETY: on success `pw_name` points to a NUL-terminated string inside `buf`.
+    let name = unsafe { std::ffi::CStr::from_ptr(pwd.pw_name) };
+    Some(name.to_string_lossy().into_owned())
+}
+
+/// Login name of the user with ID `uid`. Windows has no UIDs, so this is always `None`.
+#[cfg(not(unix))]
+pub fn user_name(_uid: u32) -> Option<String> {
+    None
+}
+
+/// Rewrite an absolute Windows path in extended-length form (`\\?\C:\…` or
+/// `\\?\UNC\server\share\…`), which lifts the 260-character `MAX_PATH` limit.
+///
+/// Extended-length paths reach the filesystem verbatim, so forward slashes are
+/// turned into backslashes. Relative paths and paths already in extended-length
+/// or device (`\\.\`) form are returned unchanged.
+pub fn extended_length_path(path: &str) -> Cow<'_, str> {
+    if path.starts_with(r"\\?\") || path.starts_with(r"\\.\") {
+        return Cow::Borrowed(path);
+    }
+    let bytes = path.as_bytes();
+    let is_separator = |b: u8| b == b'\\' || b == b'/';
+    if bytes.len() > 2 && is_separator(bytes[0]) && is_separator(bytes[1]) {
+        Cow::Owned(format!(r"\\?\UNC\{}", path[2..].replace('/', r"\")))
+    } else if bytes.len() > 2
+        && bytes[0].is_ascii_alphabetic()
+        && bytes[1] == b':'
+        && is_separator(bytes[2])
+    {
+        Cow::Owned(format!(r"\\?\{}", path.replace('/', r"\")))
+    } else {
+        Cow::Borrowed(path)
+    }
+}
+
+/// `path` in a form the OS accepts at any length.
+///
+/// On Windows this is the [`extended_length_path`] of the absolute path, so deep
+/// trees like nested `node_modules` can be sized and removed past `MAX_PATH`.
+/// Elsewhere paths have no such limit and are returned unchanged.
+pub fn long_path(path: &Path) -> PathBuf {
+    if cfg!(windows) {
+        let absolute = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+        match absolute.to_str() {
+            Some(s) => PathBuf::from(extended_length_path(s).into_owned()),
+            None => absolute,
+        }
+    } else {
+        path.to_path_buf()
+    }
+}
@@ -1,6 +1,9 @@
-//! Tests for the age string parser (e.g. "30d", "3m", "1y", "2w").
+//! Tests for the age string parser (e.g. "30d", "3m", "1y", "2w") and the
+//! duration parser (e.g. "90s", "2m", "1h").
 
-use dev_sweep::util::parse_age;
+use std::time::Duration;
+
+use dev_sweep::util::{parse_age, parse_duration};
 
 // ── valid inputs ────────────────────────────────────────────────────────────
 
@@ -108,3 +111,20 @@ fn parse_zero() {
     let d = parse_age("0d").unwrap();
     assert_eq!(d.num_days(), 0);
 }
+
+// ── durations ───────────────────────────────────────────────────────────────
+
+#[test]
+fn parse_duration_units() {
+    assert_eq!(parse_duration("90s").unwrap(), Duration::from_secs(90));
+    assert_eq!(parse_duration("2m").unwrap(), Duration::from_secs(120));
+    assert_eq!(parse_duration(" 1H ").unwrap(), Duration::from_secs(3600));
+}
+
+#[test]
+fn parse_duration_rejects_ages_and_bare_numbers() {
+    assert!(parse_duration("30").is_err());
+    assert!(parse_duration("1d").is_err());
+    assert!(parse_duration("-5m").is_err());
+    assert!(parse_duration("").is_err());
+}
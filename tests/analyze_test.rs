@@ -0,0 +1,105 @@
+//! Tests for `dev-sweep analyze`: reclaim-per-effort scores and their ranking.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use chrono::Local;
+use dev_sweep::analyze::{rank_by_effort, rebuild_effort};
+use dev_sweep::scanner::{ProjectKind, RebuildCost, ScannedProject};
+use dev_sweep::tui::display::format_effort_ranking;
+use dev_sweep::util::visible_len;
+
+const MB: u64 = 1024 * 1024;
+
+fn project(name: &str, kind: ProjectKind, bytes: u64) -> ScannedProject {
+    ScannedProject {
+        path: PathBuf::from(format!("/projects/{name}")),
+        kind,
+        other_kinds: Vec::new(),
+        name: name.to_string(),
+        last_modified: Local::now(),
+        clean_targets: Vec::new(),
+        total_cleanable_bytes: bytes,
+        largest_file: None,
+        kind_alias: None,
+        rebuild_cost: None,
+        owner: None,
+        device: None,
+        workspace_members: Vec::new(),
+    }
+}
+
+fn names(projects: Vec<ScannedProject>, efforts: &BTreeMap<RebuildCost, f64>) -> Vec<String> {
+    rank_by_effort(projects, &BTreeMap::new(), efforts)
+        .into_iter()
+        .map(|c| c.project.name)
+        .collect()
+}
+
+#[test]
+fn cheap_rebuilds_outrank_bigger_expensive_ones() {
+    let projects = vec![
+        project("compiled", ProjectKind::Rust, 500 * MB),
+        project("deps", ProjectKind::Node, 300 * MB),
+        project("modules", ProjectKind::Go, 100 * MB),
+    ];
+    let ranked = rank_by_effort(projects, &BTreeMap::new(), &BTreeMap::new());
+
+    let scores: Vec<(&str, f64)> = ranked
+        .iter()
+        .map(|c| (c.project.name.as_str(), c.score))
+        .collect();
+    // Ties are broken by path.
+    assert_eq!(
+        scores,
+        [("deps", 100.0), ("modules", 100.0), ("compiled", 50.0)]
+    );
+    assert_eq!(ranked[2].project.rebuild_cost, Some(RebuildCost::Expensive));
+}
+
+#[test]
+fn effort_weights_and_cost_labels_are_configurable() {
+    let projects = || {
+        vec![
+            project("compiled", ProjectKind::Rust, 500 * MB),
+            project("deps", ProjectKind::Node, 300 * MB),
+        ]
+    };
+    assert_eq!(names(projects(), &BTreeMap::new()), ["deps", "compiled"]);
+
+    let efforts = BTreeMap::from([(RebuildCost::Expensive, 1.0)]);
+    assert_eq!(names(projects(), &efforts), ["compiled", "deps"]);
+
+    // Relabelling Node as expensive puts it back on par with Rust.
+    let costs = BTreeMap::from([(ProjectKind::Node, RebuildCost::Expensive)]);
+    let ranked = rank_by_effort(projects(), &costs, &BTreeMap::new());
+    assert_eq!(ranked[1].project.name, "deps");
+    assert_eq!(ranked[1].effort, 10.0);
+
+    // Weights that aren't positive fall back to the defaults.
+    let bad = BTreeMap::from([(RebuildCost::Cheap, 0.0), (RebuildCost::Moderate, -2.0)]);
+    assert_eq!(rebuild_effort(RebuildCost::Cheap, &bad), 1.0);
+    assert_eq!(rebuild_effort(RebuildCost::Moderate, &bad), 3.0);
+}
+
+#[test]
+fn ranking_lines_show_score_size_and_cost() {
+    let ranked = rank_by_effort(
+        vec![
+            project("deps", ProjectKind::Node, 300 * MB),
+            project("a-longer-name", ProjectKind::Rust, 20 * MB),
+        ],
+        &BTreeMap::new(),
+        &BTreeMap::new(),
+    );
+    let lines = format_effort_ranking(&ranked);
+
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].contains("Score") && lines[0].contains("Rebuild"));
+    assert!(lines[1].contains("1.") && lines[1].contains("100.0"));
+    assert!(lines[1].contains("300.0 MB") && lines[1].contains("moderate"));
+    assert!(lines[2].contains("2.0") && lines[2].contains("expensive"));
+    // Columns line up whatever the name lengths.
+    let path_at = |line: &str| visible_len(&line[..line.find("/projects").unwrap()]);
+    assert_eq!(path_at(&lines[1]), path_at(&lines[2]));
+}
@@ -0,0 +1,111 @@
+//! Tests for `dev-sweep check`: threshold statuses, exit codes, and the status line.
+
+use std::path::PathBuf;
+
+use chrono::Local;
+use dev_sweep::check::{CheckReport, CheckStatus, CheckThresholds};
+use dev_sweep::scanner::{ProjectKind, ScannedProject};
+
+const MB: u64 = 1024 * 1024;
+
+fn project(name: &str, bytes: u64) -> ScannedProject {
+    ScannedProject {
+        path: PathBuf::from(format!("/projects/{name}")),
+        kind: ProjectKind::Node,
+        other_kinds: Vec::new(),
+        name: name.to_string(),
+        last_modified: Local::now(),
+        clean_targets: Vec::new(),
+        total_cleanable_bytes: bytes,
+        largest_file: None,
+        kind_alias: None,
+        rebuild_cost: None,
+        owner: None,
+        device: None,
+        workspace_members: Vec::new(),
+    }
+}
+
+fn thresholds(warn: Option<u64>, crit: Option<u64>) -> CheckThresholds {
+    CheckThresholds { warn, crit }
+}
+
+// ── Statuses ────────────────────────────────────────────────────────────────
+
+#[test]
+fn status_follows_the_highest_threshold_reached() {
+    let both = thresholds(Some(50 * MB), Some(100 * MB));
+    assert_eq!(both.status(0), CheckStatus::Ok);
+    assert_eq!(both.status(50 * MB - 1), CheckStatus::Ok);
+    assert_eq!(both.status(50 * MB), CheckStatus::Warning);
+    assert_eq!(both.status(100 * MB), CheckStatus::Critical);
+    assert_eq!(both.status(500 * MB), CheckStatus::Critical);
+}
+
+#[test]
+fn a_single_threshold_is_enough() {
+    assert_eq!(
+        thresholds(Some(MB), None).status(10 * MB),
+        CheckStatus::Warning
+    );
+    assert_eq!(
+        thresholds(None, Some(MB)).status(10 * MB),
+        CheckStatus::Critical
+    );
+    assert_eq!(thresholds(None, Some(MB)).status(0), CheckStatus::Ok);
+}
+
+#[test]
+fn exit_codes_follow_nagios_conventions() {
+    assert_eq!(CheckStatus::Ok.exit_code(), 0);
+    assert_eq!(CheckStatus::Warning.exit_code(), 1);
+    assert_eq!(CheckStatus::Critical.exit_code(), 2);
+    assert_eq!(CheckStatus::Unknown.exit_code(), 3);
+}
+
+// ── Report ──────────────────────────────────────────────────────────────────
+
+#[test]
+fn report_totals_every_project() {
+    let projects = [project("a", 40 * MB), project("b", 20 * MB)];
+    let report = CheckReport::new(&projects, thresholds(Some(50 * MB), Some(100 * MB)));
+
+    assert_eq!(report.reclaimable_bytes, 60 * MB);
+    assert_eq!(report.projects, 2);
+    assert_eq!(report.status, CheckStatus::Warning);
+}
+
+#[test]
+fn status_line_has_summary_and_perfdata() {
+    let projects = [project("a", 60 * MB)];
+    let report = CheckReport::new(&projects, thresholds(Some(50 * MB), Some(100 * MB)));
+
+    assert_eq!(
+        report.status_line(),
+        "DEV-SWEEP WARNING - 60.0 MB reclaimable in 1 project (warn at 50.0 MB, crit at 100.0 MB) \
+         | reclaimable=62914560B;52428800;104857600;0;"
+    );
+}
+
+#[test]
+fn status_line_leaves_a_missing_threshold_empty() {
+    let report = CheckReport::new(&[], thresholds(None, Some(MB)));
+
+    assert_eq!(
+        report.status_line(),
+        "DEV-SWEEP OK - 0 B reclaimable in 0 projects (crit at 1.0 MB) | reclaimable=0B;;1048576;0;"
+    );
+}
+
+#[test]
+fn json_report_names_status_and_thresholds() {
+    let projects = [project("a", 2 * MB)];
+    let report = CheckReport::new(&projects, thresholds(None, Some(MB)));
+    let json = serde_json::to_value(&report).unwrap();
+
+    assert_eq!(json["status"], "critical");
+    assert_eq!(json["reclaimable_bytes"], 2 * MB);
+    assert_eq!(json["projects"], 1);
+    assert_eq!(json["warn_bytes"], serde_json::Value::Null);
+    assert_eq!(json["crit_bytes"], MB);
+}
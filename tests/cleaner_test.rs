@@ -1,11 +1,30 @@
-//! Tests for the cleaner module: dry-run, actual deletion, error handling, multi-project cleaning.
+//! Tests for the cleaner module: dry-run, actual deletion, error handling, multi-project cleaning,
+//! the git working-tree guard, the active-editor guard, git gc, stale-target checks, progress
+//! events, and empty-directory pruning.
 
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, SystemTime};
 
-use dev_sweep::cleaner::{clean_project, clean_projects};
-use dev_sweep::scanner::walk::analyze_project;
-use dev_sweep::scanner::ProjectKind;
+use dev_sweep::cleaner::{
+    CLEAN_FAILED_EXIT_CODE, CleanEvent, CleanFailed, CleanOptions, INTERRUPTED_EXIT_CODE,
+    InterruptGuard, archive_file_name, check_archive_space, clean_project, clean_project_with,
+    clean_projects, clean_projects_with, clean_projects_with_progress, elevated_remove_command,
+    expand_hook, find_editor_activity, find_empty_dirs, freed_drift, interrupt_flag,
+    is_editor_marker, modified_within, prune_empty_dirs, purge_quarantine, quarantine,
+    quarantine_batch_dir, stale_reason,
+};
+use dev_sweep::config::DevSweepConfig;
+use dev_sweep::git::{
+    GIT_GC_MIN_BYTES, GitStatus, ObjectCounts, count_objects, parse_count_objects,
+    working_tree_status,
+};
+use dev_sweep::scanner::walk::{
+    analyze_project, analyze_project_with, scan_directory_with,
+};
+use dev_sweep::scanner::{CleanTarget, ProjectKind, ScanOptions, SizeMode, scan_temp_dir};
 
 /// Helper: create a fresh temp dir for a test.
 fn test_dir(name: &str) -> PathBuf {
@@ -73,28 +92,149 @@ fn clean_actually_removes_target() {
     fs::remove_dir_all(&dir).unwrap();
 }
 
+#[test]
+fn clean_measures_bytes_actually_freed() {
+    let dir = test_dir("clean_measured");
+    create_rust_project(&dir);
+    let project = analyze_project(&dir, ProjectKind::Rust).unwrap();
+    let estimated = project.total_cleanable_bytes;
+
+    // A build adds output between the scan and the clean
+    fs::write(dir.join("target/debug/new"), vec![b'x'; 1000]).unwrap();
+    let result = clean_project(&project, false).unwrap();
+
+    assert_eq!(result.bytes_estimated, estimated);
+    assert_eq!(result.bytes_freed, estimated + 1000);
+    assert!(freed_drift(result.bytes_estimated, result.bytes_freed).is_some());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn freed_drift_ignores_small_differences() {
+    assert_eq!(freed_drift(1000, 1000), None);
+    assert_eq!(freed_drift(1000, 1040), None);
+    assert_eq!(freed_drift(1000, 960), None);
+    assert_eq!(freed_drift(1000, 1100), Some(100));
+    assert_eq!(freed_drift(1000, 0), Some(-1000));
+    // Anything freed beyond a zero estimate is drift
+    assert_eq!(freed_drift(0, 1), Some(1));
+}
+
 // ── error handling ──────────────────────────────────────────────────────────
 
 #[test]
-fn clean_nonexistent_target_reports_error() {
+fn clean_nonexistent_target_is_skipped_as_stale() {
     let dir = test_dir("clean_noexist");
     create_rust_project(&dir);
 
     let mut project = analyze_project(&dir, ProjectKind::Rust).unwrap();
 
-    // Manually break the path so deletion fails
+    // Point the target somewhere that no longer exists, as after a rename
     project.clean_targets[0].path = dir.join("target_does_not_exist");
 
     let result = clean_project(&project, false).unwrap();
 
     assert_eq!(result.targets_cleaned, 0);
     assert_eq!(result.bytes_freed, 0);
-    assert_eq!(result.errors.len(), 1);
-    assert!(result.errors[0].contains("target_does_not_exist"));
+    assert!(result.errors.is_empty());
+    assert_eq!(result.skipped_stale, [dir.join("target_does_not_exist")]);
+    assert_eq!(
+        stale_reason(&project.clean_targets[0]),
+        Some("no longer exists")
+    );
 
     fs::remove_dir_all(&dir).unwrap();
 }
 
+#[test]
+fn moved_project_is_not_cleaned_from_stale_results() {
+    let dir = test_dir("clean_moved");
+    create_rust_project(&dir);
+    let project = analyze_project(&dir, ProjectKind::Rust).unwrap();
+
+    // Something else now lives at the old path, with a target/ of its own
+    fs::remove_file(dir.join("Cargo.toml")).unwrap();
+    let result = clean_project(&project, false).unwrap();
+
+    assert_eq!(result.targets_cleaned, 0);
+    assert_eq!(result.skipped_stale, [dir.join("target")]);
+    assert!(dir.join("target/debug/app").exists());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn target_replaced_by_symlink_is_skipped() {
+    let dir = test_dir("clean_symlinked");
+    create_rust_project(&dir);
+    let project = analyze_project(&dir, ProjectKind::Rust).unwrap();
+
+    fs::rename(dir.join("target"), dir.join("elsewhere")).unwrap();
+    std::os::unix::fs::symlink(dir.join("elsewhere"), dir.join("target")).unwrap();
+    let result = clean_project(&project, false).unwrap();
+
+    assert_eq!(result.skipped_stale, [dir.join("target")]);
+    assert_eq!(
+        stale_reason(&project.clean_targets[0]),
+        Some("replaced by a symlink")
+    );
+    assert!(dir.join("elsewhere/debug/app").exists());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn permission_denied_target_is_flagged() {
+    use std::os::unix::fs::PermissionsExt;
+
+    // Root ignores directory permissions, so there's nothing to provoke
+    if unsafe { libc::geteuid() } == 0 {
+        return;
+    }
+
+    let dir = test_dir("clean_denied");
+    create_rust_project(&dir);
+    let project = analyze_project(&dir, ProjectKind::Rust).unwrap();
+
+    let debug = dir.join("target/debug");
+    fs::set_permissions(&debug, fs::Permissions::from_mode(0o555)).unwrap();
+    let result = clean_project(&project, false).unwrap();
+    fs::set_permissions(&debug, fs::Permissions::from_mode(0o755)).unwrap();
+
+    assert_eq!(result.targets_cleaned, 0);
+    assert_eq!(result.permission_denied, [dir.join("target")]);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn elevated_remove_command_quotes_each_path() {
+    let plain = PathBuf::from("/srv/app/target");
+    let quote = PathBuf::from("/srv/bob's app/target");
+    assert_eq!(
+        elevated_remove_command(&[&plain, &quote]),
+        r"sudo rm -rf -- '/srv/app/target' '/srv/bob'\''s app/target'"
+    );
+}
+
+#[test]
+fn clean_failure_has_its_own_exit_code() {
+    assert_ne!(CLEAN_FAILED_EXIT_CODE, 1);
+    assert_ne!(CLEAN_FAILED_EXIT_CODE, INTERRUPTED_EXIT_CODE);
+    assert_eq!(
+        CleanFailed { failures: 1 }.to_string(),
+        "1 target could not be cleaned"
+    );
+    assert_eq!(
+        CleanFailed { failures: 3 }.to_string(),
+        "3 targets could not be cleaned"
+    );
+}
+
 // ── multi-project cleaning ──────────────────────────────────────────────────
 
 #[test]
@@ -175,3 +315,1233 @@ fn clean_project_with_no_targets() {
 
     fs::remove_dir_all(&dir).unwrap();
 }
+
+// ── archiving ───────────────────────────────────────────────────────────────
+
+#[test]
+fn archive_writes_tarball_then_removes_target() {
+    let root = test_dir("clean_archive");
+    let proj = root.join("app");
+    fs::create_dir_all(&proj).unwrap();
+    create_rust_project(&proj);
+    let archive_dir = root.join("archive");
+
+    let project = analyze_project(&proj, ProjectKind::Rust).unwrap();
+    let options = CleanOptions {
+        archive_dir: Some(archive_dir.clone()),
+        ..Default::default()
+    };
+    let result = clean_project_with(&project, &options).unwrap();
+
+    assert!(result.errors.is_empty());
+    assert_eq!(result.targets_cleaned, 1);
+    assert_eq!(result.archives.len(), 1);
+    assert!(!proj.join("target").exists());
+
+    // The archive should contain the target's files under "target/"
+    let archive = &result.archives[0];
+    assert!(archive.starts_with(&archive_dir));
+    let file = fs::File::open(archive).unwrap();
+    let mut tarball = tar::Archive::new(flate2::read::GzDecoder::new(file));
+    let entries: Vec<PathBuf> = tarball
+        .entries()
+        .unwrap()
+        .map(|e| e.unwrap().path().unwrap().into_owned())
+        .collect();
+    assert!(entries.contains(&PathBuf::from("target/debug/app")));
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn archive_dry_run_writes_nothing() {
+    let root = test_dir("clean_archive_dry");
+    let proj = root.join("app");
+    fs::create_dir_all(&proj).unwrap();
+    create_rust_project(&proj);
+    let archive_dir = root.join("archive");
+
+    let project = analyze_project(&proj, ProjectKind::Rust).unwrap();
+    let options = CleanOptions {
+        dry_run: true,
+        archive_dir: Some(archive_dir.clone()),
+        ..Default::default()
+    };
+    let result = clean_project_with(&project, &options).unwrap();
+
+    assert_eq!(result.targets_cleaned, 1);
+    assert!(result.archives.is_empty());
+    assert!(!archive_dir.exists());
+    assert!(proj.join("target").exists());
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn archive_failure_keeps_target() {
+    let root = test_dir("clean_archive_fail");
+    let proj = root.join("app");
+    fs::create_dir_all(&proj).unwrap();
+    create_rust_project(&proj);
+
+    // A regular file where the archive directory should be makes archiving fail
+    let archive_dir = root.join("not_a_dir");
+    fs::write(&archive_dir, "").unwrap();
+
+    let project = analyze_project(&proj, ProjectKind::Rust).unwrap();
+    let options = CleanOptions {
+        archive_dir: Some(archive_dir),
+        ..Default::default()
+    };
+    let result = clean_project_with(&project, &options).unwrap();
+
+    assert_eq!(result.targets_cleaned, 0);
+    assert_eq!(result.errors.len(), 1);
+    assert!(proj.join("target/debug/app").exists());
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn archives_of_same_named_projects_never_overwrite_each_other() {
+    let root = test_dir("clean_archive_unique");
+    let archive_dir = root.join("archive");
+    fs::create_dir_all(&archive_dir).unwrap();
+    // Archives already there under the names the next few seconds would get.
+    let now = chrono::Local::now();
+    let taken: Vec<PathBuf> = (0..3)
+        .map(|s| {
+            let when = now + chrono::TimeDelta::seconds(s);
+            archive_dir.join(archive_file_name("api", "target", when))
+        })
+        .collect();
+    for path in &taken {
+        fs::write(path, "earlier backup").unwrap();
+    }
+
+    let options = CleanOptions {
+        archive_dir: Some(archive_dir.clone()),
+        ..Default::default()
+    };
+    let mut archives = Vec::new();
+    for parent in ["a", "b"] {
+        let proj = root.join(parent).join("api");
+        fs::create_dir_all(&proj).unwrap();
+        create_rust_project(&proj);
+        let project = analyze_project(&proj, ProjectKind::Rust).unwrap();
+        let result = clean_project_with(&project, &options).unwrap();
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        archives.extend(result.archives);
+    }
+
+    assert_eq!(archives.len(), 2);
+    assert_ne!(archives[0], archives[1]);
+    for archive in &archives {
+        assert!(!taken.contains(archive));
+        assert!(archive.to_string_lossy().ends_with(".tar.gz"));
+    }
+    for path in &taken {
+        assert_eq!(fs::read_to_string(path).unwrap(), "earlier backup");
+    }
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn archive_file_name_sanitizes_nested_targets() {
+    let when = chrono::Local::now();
+    let name = archive_file_name("my-app", "src/__pycache__", when);
+    assert!(name.starts_with("my-app-src___pycache__-"));
+    assert!(name.ends_with(".tar.gz"));
+    assert!(!name.contains('/'));
+}
+
+#[test]
+fn archive_space_check_rejects_impossible_size() {
+    let dir = test_dir("clean_archive_space");
+    assert!(check_archive_space(&dir, 0).is_ok());
+    assert!(check_archive_space(&dir, u64::MAX).is_err());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+// ── quarantine ──────────────────────────────────────────────────────────────
+
+#[test]
+fn quarantine_moves_target_aside() {
+    let root = test_dir("clean_quarantine");
+    let proj = root.join("app");
+    fs::create_dir_all(&proj).unwrap();
+    create_rust_project(&proj);
+    let batch = root.join("quarantine").join("batch");
+
+    let project = analyze_project(&proj, ProjectKind::Rust).unwrap();
+    let options = CleanOptions {
+        quarantine_dir: Some(batch.clone()),
+        ..Default::default()
+    };
+    let result = clean_project_with(&project, &options).unwrap();
+
+    assert!(result.errors.is_empty());
+    assert_eq!(result.targets_cleaned, 1);
+    assert_eq!(result.quarantined.len(), 1);
+    let entry = &result.quarantined[0];
+    assert_eq!(entry.original, proj.join("target"));
+    assert_eq!(entry.location, batch.join("app-target"));
+    assert!(entry.size_bytes > 0);
+    assert!(!proj.join("target").exists());
+    assert!(batch.join("app-target/debug/app").exists());
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn quarantine_suffixes_a_taken_name() {
+    let root = test_dir("clean_quarantine_collision");
+    let proj = root.join("app");
+    fs::create_dir_all(&proj).unwrap();
+    create_rust_project(&proj);
+    let batch = root.join("batch");
+    fs::create_dir_all(batch.join("app-target")).unwrap();
+
+    let project = analyze_project(&proj, ProjectKind::Rust).unwrap();
+    let entry = quarantine::quarantine_target(
+        &project,
+        &project.clean_targets[0],
+        &batch,
+        SizeMode::default(),
+    )
+    .unwrap();
+
+    assert_eq!(entry.location, batch.join("app-target-2"));
+    assert!(batch.join("app-target-2/debug/app").exists());
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn quarantine_dry_run_moves_nothing() {
+    let root = test_dir("clean_quarantine_dry");
+    let proj = root.join("app");
+    fs::create_dir_all(&proj).unwrap();
+    create_rust_project(&proj);
+    let batch = root.join("batch");
+
+    let project = analyze_project(&proj, ProjectKind::Rust).unwrap();
+    let options = CleanOptions {
+        dry_run: true,
+        quarantine_dir: Some(batch.clone()),
+        ..Default::default()
+    };
+    let result = clean_project_with(&project, &options).unwrap();
+
+    assert_eq!(result.targets_cleaned, 1);
+    assert!(result.quarantined.is_empty());
+    assert!(!batch.exists());
+    assert!(proj.join("target").exists());
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn quarantine_batch_is_named_for_the_run() {
+    use chrono::TimeZone;
+    let when = chrono::Local.with_ymd_and_hms(2025, 1, 2, 3, 4, 5).unwrap();
+    assert_eq!(
+        quarantine_batch_dir(Path::new("/q"), when),
+        Path::new("/q/20250102-030405")
+    );
+}
+
+/// Quarantine two projects' targets a day apart and record them in `index`.
+fn quarantine_two(root: &Path, index: &Path) -> Vec<quarantine::QuarantineEntry> {
+    let mut entries = Vec::new();
+    for (name, days_ago) in [("old", 2), ("new", 0)] {
+        let proj = root.join(name);
+        fs::create_dir_all(&proj).unwrap();
+        create_rust_project(&proj);
+        let project = analyze_project(&proj, ProjectKind::Rust).unwrap();
+        let batch = root.join("quarantine").join(name);
+        let mut entry = quarantine::quarantine_target(
+            &project,
+            &project.clean_targets[0],
+            &batch,
+            SizeMode::default(),
+        )
+        .unwrap();
+        entry.quarantined_at -= chrono::TimeDelta::days(days_ago);
+        entries.push(entry);
+    }
+    quarantine::append_index(index, &entries).unwrap();
+    entries
+}
+
+#[test]
+fn purge_removes_entries_older_than_cutoff() {
+    let root = test_dir("quarantine_purge");
+    let index = root.join("quarantine.jsonl");
+    let entries = quarantine_two(&root, &index);
+
+    let cutoff = chrono::Local::now() - chrono::TimeDelta::days(1);
+    let result = purge_quarantine(&index, Some(cutoff), false).unwrap();
+
+    assert_eq!(result.purged, entries[..1]);
+    assert_eq!(result.kept, 1);
+    assert!(result.errors.is_empty());
+    assert!(!entries[0].location.exists());
+    // The emptied batch directory goes with it
+    assert!(!root.join("quarantine/old").exists());
+    assert!(entries[1].location.exists());
+    assert_eq!(quarantine::read_index(&index), entries[1..]);
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn purge_dry_run_keeps_everything() {
+    let root = test_dir("quarantine_purge_dry");
+    let index = root.join("quarantine.jsonl");
+    let entries = quarantine_two(&root, &index);
+
+    let result = purge_quarantine(&index, None, true).unwrap();
+
+    assert_eq!(result.purged, entries);
+    assert!(entries.iter().all(|e| e.location.exists()));
+    assert_eq!(quarantine::read_index(&index), entries);
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn purge_forgets_entries_removed_by_hand() {
+    let root = test_dir("quarantine_purge_missing");
+    let index = root.join("quarantine.jsonl");
+    let entries = quarantine_two(&root, &index);
+    fs::remove_dir_all(&entries[0].location).unwrap();
+
+    let cutoff = chrono::Local::now() - chrono::TimeDelta::days(1);
+    let result = purge_quarantine(&index, Some(cutoff), false).unwrap();
+
+    assert!(result.purged.is_empty());
+    assert_eq!(result.kept, 1);
+    assert_eq!(quarantine::read_index(&index), entries[1..]);
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn purge_removes_quarantined_files() {
+    let root = test_dir("quarantine_purge_file");
+    let index = root.join("quarantine.jsonl");
+    let proj = root.join("app");
+    fs::create_dir_all(&proj).unwrap();
+    create_rust_project(&proj);
+    fs::write(proj.join("core"), b"\x7fELF core").unwrap();
+    let project = analyze_project(&proj, ProjectKind::Rust).unwrap();
+    let core = CleanTarget {
+        path: proj.join("core"),
+        name: "core".to_string(),
+        size_bytes: 9,
+        protected: false,
+        last_modified: None,
+        runtime_data: true,
+        large_file: false,
+    };
+    let batch = root.join("quarantine/batch");
+    let entry =
+        quarantine::quarantine_target(&project, &core, &batch, SizeMode::default()).unwrap();
+    quarantine::append_index(&index, std::slice::from_ref(&entry)).unwrap();
+    assert!(entry.location.is_file());
+
+    let result = purge_quarantine(&index, None, false).unwrap();
+
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+    assert_eq!(result.purged, std::slice::from_ref(&entry));
+    assert!(!entry.location.exists());
+    assert!(quarantine::read_index(&index).is_empty());
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn purge_without_index_is_empty() {
+    let root = test_dir("quarantine_purge_none");
+    let index = root.join("quarantine.jsonl");
+
+    let result = purge_quarantine(&index, None, false).unwrap();
+
+    assert!(result.purged.is_empty());
+    assert_eq!(result.kept, 0);
+    assert!(!index.exists());
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+// ── Active build guard ──────────────────────────────────────────────────────
+
+/// Helper: backdate every file and directory under `root` by `age`.
+fn backdate(root: &Path, age: Duration) {
+    let when = SystemTime::now() - age;
+    for entry in walkdir::WalkDir::new(root).contents_first(true) {
+        let entry = entry.unwrap();
+        fs::File::open(entry.path()).unwrap().set_modified(when).unwrap();
+    }
+}
+
+#[test]
+fn active_target_is_skipped() {
+    let root = test_dir("clean_active");
+    create_rust_project(&root);
+
+    let project = analyze_project(&root, ProjectKind::Rust).unwrap();
+    let options = CleanOptions {
+        active_window: Some(Duration::from_secs(60)),
+        ..Default::default()
+    };
+    let result = clean_project_with(&project, &options).unwrap();
+
+    assert_eq!(result.targets_cleaned, 0);
+    assert_eq!(result.bytes_freed, 0);
+    assert!(result.errors.is_empty());
+    assert_eq!(result.skipped_active, [root.join("target")]);
+    assert!(root.join("target/debug/app").exists());
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn idle_target_is_cleaned_despite_guard() {
+    let root = test_dir("clean_idle");
+    create_rust_project(&root);
+    backdate(&root.join("target"), Duration::from_secs(3600));
+
+    let project = analyze_project(&root, ProjectKind::Rust).unwrap();
+    let options = CleanOptions {
+        active_window: Some(Duration::from_secs(60)),
+        ..Default::default()
+    };
+    let result = clean_project_with(&project, &options).unwrap();
+
+    assert_eq!(result.targets_cleaned, 1);
+    assert!(result.skipped_active.is_empty());
+    assert!(!root.join("target").exists());
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn temp_entry_modified_since_the_scan_is_kept_without_guard() {
+    let root = test_dir("clean_temp_recent");
+    fs::create_dir_all(root.join("tmp.scratch")).unwrap();
+    fs::write(root.join("tmp.scratch/data"), "x").unwrap();
+    backdate(&root, Duration::from_secs(30 * 24 * 3600));
+
+    let week = Duration::from_secs(7 * 24 * 3600);
+    let project = scan_temp_dir(&root, week, &ScanOptions::default())
+        .unwrap()
+        .unwrap();
+    fs::write(root.join("tmp.scratch/data"), "in use again").unwrap();
+    // --force turns the active-build guard off; temp entries are still checked.
+    let result = clean_project_with(&project, &CleanOptions::default()).unwrap();
+
+    assert_eq!(result.targets_cleaned, 0);
+    assert_eq!(result.skipped_active, [root.join("tmp.scratch")]);
+    assert!(root.join("tmp.scratch/data").exists());
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn stale_temp_entry_is_cleaned() {
+    let root = test_dir("clean_temp_stale");
+    fs::create_dir_all(root.join("cargo-install9/release")).unwrap();
+    fs::write(root.join("cargo-install9/release/app"), "binary").unwrap();
+    backdate(&root, Duration::from_secs(30 * 24 * 3600));
+
+    let week = Duration::from_secs(7 * 24 * 3600);
+    let project = scan_temp_dir(&root, week, &ScanOptions::default())
+        .unwrap()
+        .unwrap();
+    let result = clean_project_with(&project, &CleanOptions::default()).unwrap();
+
+    assert_eq!(result.targets_cleaned, 1);
+    assert!(!root.join("cargo-install9").exists());
+    assert!(root.exists());
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn modified_within_sees_deeply_nested_changes() {
+    let root = test_dir("clean_active_nested");
+    fs::create_dir_all(root.join("a/b/c")).unwrap();
+    fs::write(root.join("a/b/c/old.o"), "x").unwrap();
+    backdate(&root, Duration::from_secs(3600));
+    assert!(!modified_within(&root, Duration::from_secs(60)));
+
+    fs::write(root.join("a/b/c/new.o"), "x").unwrap();
+    assert!(modified_within(&root, Duration::from_secs(60)));
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+// ── Git working-tree guard ──────────────────────────────────────────────────
+
+/// Helper: run git in `dir`, with an identity so commits work anywhere.
+fn git(dir: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .unwrap()
+        .status;
+    assert!(status.success(), "git {args:?} failed");
+}
+
+/// Helper: a Rust project committed to a fresh repo that ignores `target/`.
+fn committed_rust_project(root: &Path) {
+    create_rust_project(root);
+    fs::write(root.join(".gitignore"), "target/\n").unwrap();
+    git(root, &["init", "-q"]);
+    git(root, &["add", "."]);
+    git(root, &["commit", "-q", "-m", "init"]);
+}
+
+fn git_guard(strict: bool) -> CleanOptions {
+    CleanOptions {
+        require_git_clean: true,
+        strict,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn git_status_ignores_artifacts_but_sees_uncommitted_work() {
+    let root = test_dir("git_status");
+    committed_rust_project(&root);
+    assert_eq!(working_tree_status(&root).unwrap(), GitStatus::Clean);
+
+    fs::write(root.join("src/main.rs"), "fn main() { todo!() }").unwrap();
+    assert_eq!(working_tree_status(&root).unwrap(), GitStatus::Dirty);
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn git_status_only_counts_changes_inside_the_project() {
+    let root = test_dir("git_status_monorepo");
+    fs::create_dir_all(root.join("app")).unwrap();
+    fs::create_dir_all(root.join("lib")).unwrap();
+    create_rust_project(&root.join("lib"));
+    fs::write(root.join(".gitignore"), "target/\n").unwrap();
+    git(&root, &["init", "-q"]);
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-q", "-m", "init"]);
+    fs::write(root.join("app/notes.txt"), "wip").unwrap();
+
+    assert_eq!(working_tree_status(&root.join("lib")).unwrap(), GitStatus::Clean);
+    assert_eq!(working_tree_status(&root.join("app")).unwrap(), GitStatus::Dirty);
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn dirty_project_is_skipped_with_require_git_clean() {
+    let root = test_dir("git_guard_dirty");
+    committed_rust_project(&root);
+    fs::write(root.join("src/new.rs"), "// wip").unwrap();
+
+    let project = analyze_project(&root, ProjectKind::Rust).unwrap();
+    let result = clean_project_with(&project, &git_guard(false)).unwrap();
+
+    assert_eq!(result.targets_cleaned, 0);
+    assert_eq!(result.skipped_git.as_deref(), Some("uncommitted changes"));
+    assert!(root.join("target/debug/app").exists());
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn clean_repo_is_cleaned_with_require_git_clean() {
+    let root = test_dir("git_guard_clean");
+    committed_rust_project(&root);
+
+    let project = analyze_project(&root, ProjectKind::Rust).unwrap();
+    let result = clean_project_with(&project, &git_guard(false)).unwrap();
+
+    assert_eq!(result.targets_cleaned, 1);
+    assert!(result.skipped_git.is_none());
+    assert!(!root.join("target").exists());
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn non_git_project_is_only_skipped_when_strict() {
+    let root = test_dir("git_guard_no_repo");
+    create_rust_project(&root);
+    let project = analyze_project(&root, ProjectKind::Rust).unwrap();
+
+    let result = clean_project_with(&project, &git_guard(true)).unwrap();
+    assert_eq!(result.skipped_git.as_deref(), Some("not a git repository"));
+    assert!(root.join("target").exists());
+
+    let result = clean_project_with(&project, &git_guard(false)).unwrap();
+    assert_eq!(result.targets_cleaned, 1);
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+// ── Active editors ──────────────────────────────────────────────────────────
+
+fn editor_guard() -> CleanOptions {
+    CleanOptions {
+        exclude_active_editors: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn editor_markers_match_swap_lock_and_autosave_files() {
+    for name in [
+        ".main.rs.swp",
+        ".main.rs.swo",
+        ".lib.rs.swn",
+        ".#main.rs",
+        "#notes.org#",
+    ] {
+        assert!(is_editor_marker(name), "{name}");
+    }
+    for name in [
+        "main.rs",
+        ".swp",
+        "swap.swp",
+        "#",
+        "##",
+        "#tag",
+        ".gitignore",
+    ] {
+        assert!(!is_editor_marker(name), "{name}");
+    }
+}
+
+#[test]
+fn project_open_in_an_editor_is_skipped_as_in_use() {
+    let root = test_dir("editor_swap");
+    create_rust_project(&root);
+    let swap = root.join("src/.main.rs.swp");
+    fs::write(&swap, "vim").unwrap();
+    let project = analyze_project(&root, ProjectKind::Rust).unwrap();
+
+    let result = clean_project_with(&project, &editor_guard()).unwrap();
+    assert_eq!(result.skipped_in_use, Some(swap.clone()));
+    assert_eq!(result.targets_cleaned, 0);
+    assert!(root.join("target").exists());
+
+    // Without the flag, the swap file doesn't matter
+    let result = clean_project_with(&project, &CleanOptions::default()).unwrap();
+    assert!(result.skipped_in_use.is_none());
+    assert!(!root.join("target").exists());
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn editor_files_inside_targets_are_ignored() {
+    let root = test_dir("editor_in_target");
+    create_rust_project(&root);
+    fs::write(root.join("target/.app.swp"), "vim").unwrap();
+    let project = analyze_project(&root, ProjectKind::Rust).unwrap();
+
+    let result = clean_project_with(&project, &editor_guard()).unwrap();
+    assert!(result.skipped_in_use.is_none());
+    assert_eq!(result.targets_cleaned, 1);
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn project_with_a_socket_is_in_use() {
+    let root = test_dir("editor_socket");
+    create_rust_project(&root);
+    let socket = root.join("lsp.sock");
+    let _listener = std::os::unix::net::UnixListener::bind(&socket).unwrap();
+    let project = analyze_project(&root, ProjectKind::Rust).unwrap();
+
+    assert_eq!(find_editor_activity(&project), Some(socket));
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+// ── git gc ──────────────────────────────────────────────────────────────────
+
+#[test]
+fn count_objects_output_is_parsed_in_bytes() {
+    let counts = parse_count_objects(
+        "count: 12\nsize: 2048\nin-pack: 40\npacks: 1\nsize-pack: 90\nprune-packable: 0\n\
+         garbage: 1\nsize-garbage: 4\n",
+    );
+    assert_eq!(counts.loose, 12);
+    assert_eq!(counts.loose_bytes, 2048 * 1024);
+    assert_eq!(counts.garbage_bytes, 4 * 1024);
+    assert_eq!(counts.reclaimable(), 2052 * 1024);
+
+    let unknown = parse_count_objects("not git output");
+    assert_eq!(unknown, ObjectCounts::default());
+}
+
+/// Helper: a repo holding `bytes` of incompressible data in an unreachable
+/// loose object, which `git gc --prune=now` removes.
+fn repo_with_garbage(root: &Path, bytes: usize) {
+    committed_rust_project(root);
+    let mut state: u32 = 0x2545_f491;
+    let data: Vec<u8> = (0..bytes)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state as u8
+        })
+        .collect();
+    fs::write(root.join("blob.bin"), data).unwrap();
+    git(root, &["add", "blob.bin"]);
+    git(root, &["rm", "-q", "--cached", "blob.bin"]);
+    fs::remove_file(root.join("blob.bin")).unwrap();
+}
+
+#[test]
+fn git_gc_reports_and_compacts_loose_objects() {
+    let root = test_dir("git_gc");
+    repo_with_garbage(&root, 2 * 1024 * 1024);
+    let options = ScanOptions {
+        git_gc: true,
+        ..Default::default()
+    };
+
+    let projects = scan_directory_with(&root, &options, &DevSweepConfig::default()).unwrap();
+    let repo = projects
+        .iter()
+        .find(|p| p.kind == ProjectKind::GitGc)
+        .expect("repository with loose objects is reported");
+    assert_eq!(repo.path, root.join(".git"));
+    assert_eq!(repo.clean_targets[0].path, root.join(".git/objects"));
+    assert!(repo.total_cleanable_bytes >= GIT_GC_MIN_BYTES);
+
+    let result = clean_project(repo, false).unwrap();
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+    assert_eq!(result.targets_cleaned, 1);
+    assert!(result.bytes_freed >= GIT_GC_MIN_BYTES);
+    assert_eq!(count_objects(&root).unwrap().loose, 0);
+    assert!(root.join(".git/HEAD").exists());
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn git_gc_object_store_is_never_split_for_large_files() {
+    let root = test_dir("git_gc_large_files");
+    repo_with_garbage(&root, 2 * 1024 * 1024);
+    let options = ScanOptions {
+        git_gc: true,
+        skip_files_over: Some(1024 * 1024),
+        ..Default::default()
+    };
+
+    let projects = scan_directory_with(&root, &options, &DevSweepConfig::default()).unwrap();
+    let repo = projects
+        .iter()
+        .find(|p| p.kind == ProjectKind::GitGc)
+        .expect("repository with loose objects is reported");
+    // `git gc` compacts the whole store in place, so it stays one target.
+    assert_eq!(repo.clean_targets.len(), 1);
+    assert_eq!(repo.clean_targets[0].path, root.join(".git/objects"));
+    assert!(!repo.clean_targets[0].large_file);
+    assert!(repo.total_cleanable_bytes >= GIT_GC_MIN_BYTES);
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn git_gc_skips_repos_without_much_to_compact() {
+    let root = test_dir("git_gc_small");
+    committed_rust_project(&root);
+    let options = ScanOptions {
+        git_gc: true,
+        ..Default::default()
+    };
+
+    let projects = scan_directory_with(&root, &options, &DevSweepConfig::default()).unwrap();
+    assert!(projects.iter().all(|p| p.kind != ProjectKind::GitGc));
+
+    let config = DevSweepConfig::default();
+    let without = scan_directory_with(&root, &ScanOptions::default(), &config).unwrap();
+    assert!(without.iter().all(|p| p.kind != ProjectKind::GitGc));
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+// ── Clean hooks ─────────────────────────────────────────────────────────────
+
+fn with_hooks(pre: Option<&str>, post: Option<&str>) -> CleanOptions {
+    CleanOptions {
+        pre_clean_cmd: pre.map(String::from),
+        post_clean_cmd: post.map(String::from),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn hook_path_is_quoted_for_the_shell() {
+    let expanded = expand_hook("make -C {path} clean", Path::new("/w/it's here"));
+    #[cfg(unix)]
+    assert_eq!(expanded, r"make -C '/w/it'\''s here' clean");
+    #[cfg(windows)]
+    assert_eq!(expanded, "make -C \"/w/it's here\" clean");
+}
+
+#[cfg(unix)]
+#[test]
+fn hooks_run_around_the_clean_in_the_project() {
+    let root = test_dir("clean hooks");
+    create_rust_project(&root);
+    let project = analyze_project(&root, ProjectKind::Rust).unwrap();
+
+    let options = with_hooks(
+        Some("test -d target && touch {path}/pre-ran"),
+        Some("test ! -e target && touch post-ran"),
+    );
+    let result = clean_project_with(&project, &options).unwrap();
+
+    assert_eq!(result.targets_cleaned, 1);
+    assert!(result.errors.is_empty() && result.warnings.is_empty());
+    assert!(root.join("pre-ran").exists());
+    assert!(root.join("post-ran").exists());
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn failing_pre_hook_leaves_the_project_alone() {
+    let root = test_dir("clean_hook_pre_fails");
+    create_rust_project(&root);
+    let project = analyze_project(&root, ProjectKind::Rust).unwrap();
+
+    let result = clean_project_with(&project, &with_hooks(Some("exit 3"), None)).unwrap();
+
+    assert_eq!(result.targets_cleaned, 0);
+    assert_eq!(result.errors.len(), 1);
+    assert!(result.errors[0].contains("pre_clean_cmd"));
+    assert!(root.join("target/debug/app").exists());
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn failing_post_hook_is_only_a_warning() {
+    let root = test_dir("clean_hook_post_fails");
+    create_rust_project(&root);
+    let project = analyze_project(&root, ProjectKind::Rust).unwrap();
+
+    let result = clean_project_with(&project, &with_hooks(None, Some("false"))).unwrap();
+
+    assert_eq!(result.targets_cleaned, 1);
+    assert!(result.errors.is_empty());
+    assert_eq!(result.warnings.len(), 1);
+    assert!(result.warnings[0].contains("post_clean_cmd `false`"));
+    assert!(!root.join("target").exists());
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn hooks_are_not_run_when_every_target_is_skipped() {
+    let root = test_dir("clean_hook_all_skipped");
+    create_rust_project(&root);
+    let project = analyze_project(&root, ProjectKind::Rust).unwrap();
+
+    // Just created, so the target is inside the active window.
+    let options = CleanOptions {
+        active_window: Some(Duration::from_secs(60)),
+        ..with_hooks(Some("exit 1"), Some("exit 1"))
+    };
+    let result = clean_project_with(&project, &options).unwrap();
+
+    assert_eq!(result.targets_cleaned, 0);
+    assert_eq!(result.skipped_active.len(), 1);
+    assert!(result.errors.is_empty() && result.warnings.is_empty());
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn hooks_are_not_run_for_dry_runs() {
+    let root = test_dir("clean_hook_dry_run");
+    create_rust_project(&root);
+    let project = analyze_project(&root, ProjectKind::Rust).unwrap();
+
+    let options = CleanOptions {
+        dry_run: true,
+        ..with_hooks(Some("exit 1"), Some("exit 1"))
+    };
+    let result = clean_project_with(&project, &options).unwrap();
+
+    assert_eq!(result.targets_cleaned, 1);
+    assert!(result.errors.is_empty() && result.warnings.is_empty());
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+// ── Safe mode ───────────────────────────────────────────────────────────────
+
+#[test]
+fn safe_mode_only_removes_allowlisted_targets() {
+    let root = test_dir("clean_safe_mode");
+    fs::write(root.join("package.json"), "{}").unwrap();
+    fs::write(root.join("vite.config.js"), "").unwrap();
+    fs::create_dir_all(root.join("node_modules/left-pad")).unwrap();
+    fs::write(root.join("node_modules/left-pad/index.js"), "module.exports = 1;").unwrap();
+    fs::create_dir_all(root.join("dist")).unwrap();
+    fs::write(root.join("dist/bundle.js"), "console.log(1);").unwrap();
+
+    let project = analyze_project(&root, ProjectKind::Node).unwrap();
+    let options = CleanOptions {
+        safe_mode: true,
+        ..Default::default()
+    };
+    let result = clean_project_with(&project, &options).unwrap();
+
+    assert_eq!(result.targets_cleaned, 1);
+    assert_eq!(result.skipped_unsafe, [root.join("dist")]);
+    assert!(!root.join("node_modules").exists());
+    assert!(root.join("dist/bundle.js").exists());
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+// ── Logs and core dumps ─────────────────────────────────────────────────────
+
+#[test]
+fn clean_removes_core_dump_files_and_log_dirs() {
+    let root = test_dir("clean_runtime_data");
+    create_rust_project(&root);
+    fs::create_dir_all(root.join("logs")).unwrap();
+    fs::write(root.join("logs/server.log"), "started").unwrap();
+    fs::write(root.join("core"), b"\x7fELF dumped").unwrap();
+
+    let options = ScanOptions {
+        include_logs: true,
+        ..Default::default()
+    };
+    let project = analyze_project_with(&root, ProjectKind::Rust, &options).unwrap();
+    assert_eq!(project.clean_targets.len(), 3);
+    let result = clean_project(&project, false).unwrap();
+
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+    assert_eq!(result.targets_cleaned, 3);
+    assert!(!root.join("core").exists());
+    assert!(!root.join("logs").exists());
+    assert!(root.join("src/main.rs").exists());
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+// ── Interrupts ──────────────────────────────────────────────────────────────
+
+#[test]
+fn interrupt_stops_before_the_next_project() {
+    static STOP: AtomicBool = AtomicBool::new(false);
+    let root = test_dir("clean_interrupted");
+    for name in ["one", "two"] {
+        fs::create_dir_all(root.join(name)).unwrap();
+        create_rust_project(&root.join(name));
+    }
+    let one = analyze_project(&root.join("one"), ProjectKind::Rust).unwrap();
+    let two = analyze_project(&root.join("two"), ProjectKind::Rust).unwrap();
+    let options = CleanOptions {
+        interrupt: Some(&STOP),
+        ..Default::default()
+    };
+
+    // Ctrl-C while the first project is being cleaned.
+    let results = clean_projects_with_progress(&[&one, &two], &options, |_, _| {
+        STOP.store(true, Ordering::SeqCst);
+    });
+
+    assert!(options.interrupted());
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].targets_cleaned, 1);
+    assert!(!root.join("one/target").exists());
+    assert!(root.join("two/target/debug/app").exists());
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn interrupted_project_keeps_its_remaining_targets() {
+    static STOP: AtomicBool = AtomicBool::new(true);
+    let root = test_dir("clean_interrupted_project");
+    create_rust_project(&root);
+    let project = analyze_project(&root, ProjectKind::Rust).unwrap();
+    let options = CleanOptions {
+        interrupt: Some(&STOP),
+        ..Default::default()
+    };
+
+    let result = clean_project_with(&project, &options).unwrap();
+
+    assert_eq!(result.targets_cleaned, 0);
+    assert!(result.errors.is_empty());
+    assert!(root.join("target/debug/app").exists());
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn interrupt_guard_turns_sigint_into_the_flag() {
+    let guard = InterruptGuard::install();
+    let pid = std::process::id().to_string();
+    assert!(Command::new("kill").args(["-INT", &pid]).status().unwrap().success());
+
+    // The signal arrives asynchronously; without the guard it would end the test run.
+    let deadline = SystemTime::now() + Duration::from_secs(5);
+    while !interrupt_flag().load(Ordering::SeqCst) && SystemTime::now() < deadline {
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    drop(guard);
+    assert!(interrupt_flag().swap(false, Ordering::SeqCst));
+}
+
+// ── Time budget ─────────────────────────────────────────────────────────────
+
+#[test]
+fn time_budget_stops_before_the_next_project() {
+    let root = test_dir("clean_time_budget");
+    for name in ["one", "two"] {
+        fs::create_dir_all(root.join(name)).unwrap();
+        create_rust_project(&root.join(name));
+    }
+    let one = analyze_project(&root.join("one"), ProjectKind::Rust).unwrap();
+    let two = analyze_project(&root.join("two"), ProjectKind::Rust).unwrap();
+    let options = CleanOptions {
+        time_budget: Some(Duration::from_nanos(1)),
+        ..Default::default()
+    };
+
+    let results = clean_projects_with(&[&one, &two], &options);
+
+    // The first project is started however small the budget.
+    assert_eq!(results.len(), 1);
+    assert!(options.ran_out_of_time(&results, 2));
+    assert!(!root.join("one/target").exists());
+    assert!(root.join("two/target/debug/app").exists());
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn time_budget_left_over_cleans_everything() {
+    let root = test_dir("clean_time_budget_left");
+    for name in ["one", "two"] {
+        fs::create_dir_all(root.join(name)).unwrap();
+        create_rust_project(&root.join(name));
+    }
+    let one = analyze_project(&root.join("one"), ProjectKind::Rust).unwrap();
+    let two = analyze_project(&root.join("two"), ProjectKind::Rust).unwrap();
+    let options = CleanOptions {
+        time_budget: Some(Duration::from_secs(3600)),
+        ..Default::default()
+    };
+
+    let results = clean_projects_with(&[&one, &two], &options);
+
+    assert_eq!(results.len(), 2);
+    assert!(!options.ran_out_of_time(&results, 2));
+    assert!(!root.join("two/target").exists());
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+// ── Progress events ─────────────────────────────────────────────────────────
+
+#[test]
+fn progress_callback_sees_each_project_as_it_is_cleaned() {
+    let root = test_dir("progress_callback");
+    for name in ["one", "two"] {
+        fs::create_dir_all(root.join(name)).unwrap();
+        create_rust_project(&root.join(name));
+    }
+    let one = analyze_project(&root.join("one"), ProjectKind::Rust).unwrap();
+    let two = analyze_project(&root.join("two"), ProjectKind::Rust).unwrap();
+
+    let mut seen = Vec::new();
+    let results = clean_projects_with_progress(&[&one, &two], &CleanOptions::default(), |p, r| {
+        // The project is already gone by the time its result is reported.
+        assert!(!p.path.join("target").exists());
+        seen.push((p.name.clone(), r.bytes_freed));
+    });
+
+    assert_eq!(seen, [("one".to_string(), 23), ("two".to_string(), 23)]);
+    assert_eq!(results.len(), 2);
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn clean_events_serialize_as_tagged_ndjson() {
+    let root = test_dir("clean_events");
+    create_rust_project(&root);
+    let project = analyze_project(&root, ProjectKind::Rust).unwrap();
+    let result = clean_project(&project, true).unwrap();
+
+    let start = serde_json::to_value(CleanEvent::start(&[&project], true)).unwrap();
+    assert_eq!(start["event"], "start");
+    assert_eq!(start["targets"], 1);
+    assert_eq!(start["bytes_estimated"], 23);
+
+    let events = CleanEvent::for_result(&project, &result);
+    assert_eq!(events.len(), 1);
+    let cleaned = serde_json::to_string(&events[0]).unwrap();
+    assert!(!cleaned.contains('\n'));
+    let cleaned: serde_json::Value = serde_json::from_str(&cleaned).unwrap();
+    assert_eq!(cleaned["event"], "cleaned");
+    assert_eq!(cleaned["bytes_freed"], 23);
+
+    let done = CleanEvent::done(std::slice::from_ref(&result), false, false);
+    let done = serde_json::to_value(done).unwrap();
+    assert_eq!(done["event"], "done");
+    assert_eq!(done["projects_cleaned"], 1);
+    assert_eq!(done["errors"], 0);
+    assert_eq!(done["interrupted"], false);
+    assert_eq!(done["time_budget_exhausted"], false);
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn skipped_and_failed_projects_get_their_own_events() {
+    let root = test_dir("clean_events_skipped");
+    committed_rust_project(&root);
+    fs::write(root.join("src/new.rs"), "// wip").unwrap();
+    let project = analyze_project(&root, ProjectKind::Rust).unwrap();
+
+    let result = clean_project_with(&project, &git_guard(false)).unwrap();
+    let events = CleanEvent::for_result(&project, &result);
+    assert!(matches!(
+        &events[..],
+        [CleanEvent::Skipped { reason, .. }] if reason == "uncommitted changes"
+    ));
+
+    let mut failed = clean_project(&project, true).unwrap();
+    failed.errors.push("Failed to remove target".to_string());
+    let events = CleanEvent::for_result(&project, &failed);
+    assert!(matches!(&events[..], [CleanEvent::Error { .. }, CleanEvent::Cleaned { .. }]));
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+// ── Empty directory pruning ─────────────────────────────────────────────────
+
+#[test]
+fn prune_finds_nested_empty_dirs_deepest_first() {
+    let root = test_dir("prune_nested");
+    fs::create_dir_all(root.join("a/b/c")).unwrap();
+    fs::create_dir_all(root.join("keep/empty")).unwrap();
+    fs::write(root.join("keep/file.txt"), "x").unwrap();
+    // Hidden and VCS directories are never touched
+    fs::create_dir_all(root.join(".git/refs/tags")).unwrap();
+    fs::create_dir_all(root.join("node_modules/pkg")).unwrap();
+
+    let dirs = find_empty_dirs(&root, &ScanOptions::default(), &DevSweepConfig::default());
+    let mut expected = vec![root.join("a/b/c"), root.join("a/b"), root.join("a")];
+    expected.push(root.join("keep/empty"));
+    let mut sorted = dirs.clone();
+    sorted.sort();
+    expected.sort();
+    assert_eq!(sorted, expected);
+    // Children always come before their parent
+    let pos = |p: &Path| dirs.iter().position(|d| d == p).unwrap();
+    assert!(pos(&root.join("a/b/c")) < pos(&root.join("a/b")));
+    assert!(pos(&root.join("a/b")) < pos(&root.join("a")));
+
+    let result = prune_empty_dirs(&root, &dirs, false);
+    assert_eq!(result.pruned.len(), 4);
+    assert!(result.errors.is_empty());
+    assert!(!root.join("a").exists());
+    assert!(root.join("keep/file.txt").exists());
+    assert!(root.join(".git/refs/tags").exists());
+    assert!(root.join("node_modules/pkg").exists());
+    assert!(root.exists());
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn prune_never_removes_root_ignored_or_refilled_dirs() {
+    let root = test_dir("prune_guard");
+    fs::create_dir_all(root.join("ignored/empty")).unwrap();
+    fs::create_dir_all(root.join("refilled")).unwrap();
+    let config = DevSweepConfig {
+        ignore_paths: vec![root.join("ignored")],
+        ..Default::default()
+    };
+
+    let dirs = find_empty_dirs(&root, &ScanOptions::default(), &config);
+    assert_eq!(dirs, [root.join("refilled")]);
+
+    // Something lands in the directory between finding and pruning
+    fs::write(root.join("refilled/new.txt"), "x").unwrap();
+    let mut with_root = dirs.clone();
+    with_root.push(root.clone());
+    let result = prune_empty_dirs(&root, &with_root, false);
+    assert!(result.pruned.is_empty());
+    assert_eq!(result.errors.len(), 1);
+    assert!(root.join("refilled/new.txt").exists());
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn prune_dry_run_removes_nothing() {
+    let root = test_dir("prune_dry");
+    fs::create_dir_all(root.join("a/b")).unwrap();
+
+    let dirs = find_empty_dirs(&root, &ScanOptions::default(), &DevSweepConfig::default());
+    let result = prune_empty_dirs(&root, &dirs, true);
+    assert_eq!(result.pruned.len(), 2);
+    assert!(root.join("a/b").exists());
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+// ── clean --format json ─────────────────────────────────────────────────────
+
+#[test]
+fn json_clean_prints_only_the_report_on_stdout() {
+    let root = test_dir("clean_json_stdout");
+    let projects = root.join("projects");
+    fs::create_dir_all(projects.join("app")).unwrap();
+    create_rust_project(&projects.join("app"));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_dev-sweep"))
+        .args(["clean", "--all", "--dry-run", "--format", "json"])
+        .arg(&projects)
+        .env("HOME", &root)
+        .env("XDG_CONFIG_HOME", root.join("config"))
+        .env("XDG_CACHE_HOME", root.join("cache"))
+        .env("XDG_DATA_HOME", root.join("data"))
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["dry_run"], true);
+    assert_eq!(report["projects_cleaned"], 1);
+    assert!(projects.join("app/target").exists());
+
+    fs::remove_dir_all(&root).unwrap();
+}
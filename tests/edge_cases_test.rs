@@ -11,7 +11,10 @@ use dev_sweep::scanner::walk::{
     analyze_project, detect_project_kind, dir_size, scan_directory,
 };
 use dev_sweep::cleaner::{clean_project, clean_projects};
-use dev_sweep::util::{format_bytes, parse_age};
+use dev_sweep::util::{
+    common_ancestor, extended_length_path, format_bytes, hostname, outermost_paths, parse_age,
+    to_json,
+};
 
 /// Helper: create a fresh temp dir for a test.
 fn test_dir(name: &str) -> PathBuf {
@@ -439,8 +442,8 @@ fn clean_projects_with_mix_of_good_and_bad_targets() {
     // Good project should succeed
     assert!(results[0].errors.is_empty());
     assert!(results[0].bytes_freed > 0);
-    // Bad project should report an error
-    assert!(!results[1].errors.is_empty());
+    // Bad project's vanished target is skipped as stale
+    assert_eq!(results[1].skipped_stale, [root.join("nonexistent")]);
     assert_eq!(results[1].bytes_freed, 0);
 
     fs::remove_dir_all(&root).unwrap();
@@ -698,3 +701,97 @@ fn scala_project_target_and_nested_target() {
     assert_eq!(project.clean_targets.len(), 2);
     fs::remove_dir_all(&dir).unwrap();
 }
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Host name for JSON reports
+// ══════════════════════════════════════════════════════════════════════════════
+
+#[test]
+fn hostname_is_a_clean_non_empty_string() {
+    let host = hostname().expect("unix hosts always have a name");
+    assert!(!host.is_empty());
+    assert!(!host.contains('\0'));
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Several scan roots
+// ══════════════════════════════════════════════════════════════════════════════
+
+#[test]
+fn outermost_paths_drops_duplicates_and_nested_roots() {
+    let roots = ["/src/work", "/src/play", "/src/work/app", "/src/play", "/src/workshop"];
+    let roots: Vec<PathBuf> = roots.iter().map(PathBuf::from).collect();
+    let kept = outermost_paths(roots);
+    assert_eq!(
+        kept,
+        [PathBuf::from("/src/work"), PathBuf::from("/src/play"), PathBuf::from("/src/workshop")]
+    );
+}
+
+#[test]
+fn common_ancestor_of_scan_roots() {
+    let paths = |ps: &[&str]| ps.iter().map(PathBuf::from).collect::<Vec<_>>();
+    assert_eq!(common_ancestor(&paths(&["/src/work"])), PathBuf::from("/src/work"));
+    assert_eq!(common_ancestor(&paths(&["/src/work/a", "/src/play"])), PathBuf::from("/src"));
+    assert_eq!(common_ancestor(&paths(&["/src/work", "/srv"])), PathBuf::from("/"));
+    assert_eq!(common_ancestor(&paths(&["src", "lib"])), PathBuf::new());
+    assert_eq!(common_ancestor(&[]), PathBuf::new());
+}
+
+#[test]
+fn compact_json_is_one_line_with_the_same_content() {
+    let report = serde_json::json!({ "host": "laptop", "projects": [{ "name": "a" }] });
+    let compact = to_json(&report, true).unwrap();
+    let pretty = to_json(&report, false).unwrap();
+
+    assert_eq!(compact, r#"{"host":"laptop","projects":[{"name":"a"}]}"#);
+    assert!(pretty.contains("\n  \"host\": \"laptop\""));
+    let parse = |s: &str| serde_json::from_str::<serde_json::Value>(s).unwrap();
+    assert_eq!(parse(&compact), parse(&pretty));
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Paths longer than Windows' MAX_PATH
+// ══════════════════════════════════════════════════════════════════════════════
+
+#[test]
+fn extended_length_path_prefixes_absolute_windows_paths() {
+    assert_eq!(extended_length_path(r"C:\src\app"), r"\\?\C:\src\app");
+    assert_eq!(extended_length_path("C:/src/app"), r"\\?\C:\src\app");
+    assert_eq!(
+        extended_length_path(r"\\server\share\app"),
+        r"\\?\UNC\server\share\app"
+    );
+}
+
+#[test]
+fn extended_length_path_leaves_other_paths_alone() {
+    for path in [r"\\?\C:\src", r"\\.\pipe\x", r"src\app", "C:", "/home/me/app", ""] {
+        assert_eq!(extended_length_path(path), path);
+    }
+}
+
+#[test]
+fn paths_past_max_path_are_sized_and_cleaned() {
+    let dir = test_dir("edge_long_paths");
+    fs::write(dir.join("package.json"), "{}").unwrap();
+    let mut deep = dir.join("node_modules");
+    while deep.as_os_str().len() < 300 {
+        deep = deep.join("node_modules").join("some-long-package-name");
+    }
+    fs::create_dir_all(&deep).unwrap();
+    fs::write(deep.join("index.js"), "module.exports = {}").unwrap();
+    assert!(deep.join("index.js").as_os_str().len() > 260);
+
+    let project = analyze_project(&dir, ProjectKind::Node).unwrap();
+    assert_eq!(project.total_cleanable_bytes, 19);
+    assert_eq!(
+        project.largest_file.as_ref().map(|f| f.path.clone()),
+        Some(deep.join("index.js"))
+    );
+
+    let result = clean_project(&project, false).unwrap();
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+    assert!(!dir.join("node_modules").exists());
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,806 @@
+//! Tests for result filters (age, kind, substring, regex, recent, target, excluded targets), filter
+//! decisions, ordering, kind aliases, safe mode, last-build protection, and path-tree aggregation.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use chrono::{Duration, Local};
+use dev_sweep::config::{KeepLastBuild, LAST_GOOD_MARKER};
+use dev_sweep::filter::{
+    CleanOrder, ProjectFilter, SortKey, age_cutoff, apply_filters, apply_kind_aliases,
+    apply_rebuild_costs, build_path_tree, build_regex, decide_filters, drop_recent_targets,
+    exclude_targets, filter_by_age, filter_by_kind, filter_by_regex, filter_by_substring,
+    filter_by_target, group_by_filesystem, group_by_kind, group_by_owner, is_older_than,
+    kinds_present, matches_substring, most_recent_per_kind, order_for_cleaning,
+    protect_last_builds, protect_unsafe_targets, sort_projects,
+};
+use dev_sweep::scanner::walk::analyze_project;
+use dev_sweep::scanner::{CleanTarget, ProjectKind, RebuildCost, ScannedProject, SizeMode};
+use dev_sweep::util::{glob_match, user_name};
+
+/// Helper: build a project without touching the filesystem.
+fn project(path: &str, kind: ProjectKind, age_days: i64) -> ScannedProject {
+    let path = PathBuf::from(path);
+    ScannedProject {
+        name: path.file_name().unwrap().to_string_lossy().to_string(),
+        path,
+        kind,
+        other_kinds: Vec::new(),
+        last_modified: Local::now() - Duration::days(age_days),
+        clean_targets: Vec::new(),
+        total_cleanable_bytes: 1,
+        largest_file: None,
+        kind_alias: None,
+        rebuild_cost: None,
+        owner: None,
+        device: None,
+        workspace_members: Vec::new(),
+    }
+}
+
+fn names(projects: &[ScannedProject]) -> Vec<&str> {
+    projects.iter().map(|p| p.name.as_str()).collect()
+}
+
+// ── age ─────────────────────────────────────────────────────────────────────
+
+#[test]
+fn age_filter_keeps_only_stale_projects() {
+    let mut projects = vec![
+        project("/w/fresh", ProjectKind::Rust, 1),
+        project("/w/stale", ProjectKind::Rust, 100),
+    ];
+    filter_by_age(&mut projects, Some("30d")).unwrap();
+    assert_eq!(names(&projects), ["stale"]);
+}
+
+#[test]
+fn age_filter_none_keeps_everything() {
+    let mut projects = vec![project("/w/a", ProjectKind::Rust, 1)];
+    filter_by_age(&mut projects, None).unwrap();
+    assert_eq!(projects.len(), 1);
+}
+
+#[test]
+fn age_filter_rejects_invalid_age() {
+    let mut projects = vec![project("/w/a", ProjectKind::Rust, 1)];
+    assert!(filter_by_age(&mut projects, Some("soon")).is_err());
+}
+
+// ── kind ────────────────────────────────────────────────────────────────────
+
+#[test]
+fn kind_filter_keeps_listed_kinds() {
+    let mut projects = vec![
+        project("/w/a", ProjectKind::Rust, 1),
+        project("/w/b", ProjectKind::Node, 1),
+        project("/w/c", ProjectKind::Python, 1),
+    ];
+    filter_by_kind(&mut projects, &[ProjectKind::Rust, ProjectKind::Python]);
+    assert_eq!(names(&projects), ["a", "c"]);
+}
+
+#[test]
+fn kind_filter_matches_any_kind_of_a_polyglot_project() {
+    let mut addon = project("/w/addon", ProjectKind::Rust, 1);
+    addon.other_kinds = vec![ProjectKind::Node];
+    let mut projects = vec![addon, project("/w/b", ProjectKind::Python, 1)];
+    filter_by_kind(&mut projects, &[ProjectKind::Node]);
+    assert_eq!(names(&projects), ["addon"]);
+}
+
+#[test]
+fn kind_filter_with_no_kinds_matches_nothing() {
+    let mut projects = vec![project("/w/a", ProjectKind::Rust, 1)];
+    filter_by_kind(&mut projects, &[]);
+    assert!(projects.is_empty());
+}
+
+// ── substring ───────────────────────────────────────────────────────────────
+
+#[test]
+fn substring_matches_path_case_insensitively() {
+    let mut projects = vec![
+        project("/home/me/Work/api", ProjectKind::Rust, 1),
+        project("/home/me/play/game", ProjectKind::Rust, 1),
+    ];
+    filter_by_substring(&mut projects, "work");
+    assert_eq!(names(&projects), ["api"]);
+}
+
+#[test]
+fn substring_matches_name() {
+    let mut projects = vec![
+        project("/w/MyService", ProjectKind::Node, 1),
+        project("/w/other", ProjectKind::Node, 1),
+    ];
+    filter_by_substring(&mut projects, "SERVICE");
+    assert_eq!(names(&projects), ["MyService"]);
+}
+
+#[test]
+fn substring_no_match_empties_results() {
+    let mut projects = vec![project("/w/a", ProjectKind::Rust, 1)];
+    filter_by_substring(&mut projects, "zzz");
+    assert!(projects.is_empty());
+}
+
+// ── regex ───────────────────────────────────────────────────────────────────
+
+#[test]
+fn regex_matches_path() {
+    let mut projects = vec![
+        project("/w/client-v1", ProjectKind::Node, 1),
+        project("/w/client-v2", ProjectKind::Node, 1),
+        project("/w/server", ProjectKind::Node, 1),
+    ];
+    let re = build_regex(r"client-v\d$").unwrap();
+    filter_by_regex(&mut projects, &re);
+    assert_eq!(names(&projects), ["client-v1", "client-v2"]);
+}
+
+#[test]
+fn regex_is_case_insensitive() {
+    let mut projects = vec![project("/w/Backend", ProjectKind::Go, 1)];
+    let re = build_regex("^backend$").unwrap();
+    filter_by_regex(&mut projects, &re);
+    assert_eq!(projects.len(), 1);
+}
+
+#[test]
+fn invalid_regex_is_an_error() {
+    let err = build_regex("(unclosed").unwrap_err();
+    assert!(err.to_string().contains("Invalid regex"));
+}
+
+// ── keep recent ─────────────────────────────────────────────────────────────
+
+#[test]
+fn most_recent_per_kind_picks_newest_of_each_kind() {
+    let projects = vec![
+        project("/w/old", ProjectKind::Rust, 300),
+        project("/w/newest", ProjectKind::Rust, 1),
+        project("/w/newer", ProjectKind::Rust, 10),
+        project("/w/web", ProjectKind::Node, 200),
+    ];
+    let recent = most_recent_per_kind(&projects, 2);
+    assert_eq!(recent.len(), 3);
+    assert!(recent.contains(Path::new("/w/newest")));
+    assert!(recent.contains(Path::new("/w/newer")));
+    assert!(recent.contains(Path::new("/w/web")));
+
+    assert!(most_recent_per_kind(&projects, 0).is_empty());
+}
+
+// ── composition ─────────────────────────────────────────────────────────────
+
+#[test]
+fn filters_compose_with_and() {
+    let mut projects = vec![
+        project("/w/work/api", ProjectKind::Rust, 100),
+        project("/w/work/web", ProjectKind::Node, 100),
+        project("/w/work/new", ProjectKind::Rust, 1),
+        project("/w/play/old", ProjectKind::Rust, 100),
+    ];
+    filter_by_age(&mut projects, Some("30d")).unwrap();
+    filter_by_kind(&mut projects, &[ProjectKind::Rust]);
+    filter_by_substring(&mut projects, "/work/");
+    assert_eq!(names(&projects), ["api"]);
+}
+
+// ── decisions ───────────────────────────────────────────────────────────────
+
+#[test]
+fn decisions_check_every_filter_independently() {
+    let projects = vec![
+        project("/w/work/api", ProjectKind::Rust, 100),
+        project("/w/work/web", ProjectKind::Node, 1),
+    ];
+    let cutoff = age_cutoff("30d").unwrap();
+    let filters = vec![
+        ProjectFilter::new("older-than 30d", move |p| is_older_than(p, cutoff)),
+        ProjectFilter::new("kind Rust", |p| p.kind == ProjectKind::Rust),
+        ProjectFilter::new("filter \"work\"", |p| matches_substring(p, "WORK")),
+    ];
+
+    let decisions = decide_filters(&projects, &filters);
+    let verdicts =
+        |i: usize| -> Vec<bool> { decisions[i].checks.iter().map(|c| c.passed).collect() };
+    assert_eq!(decisions[0].checks[0].filter, "older-than 30d");
+    assert_eq!(verdicts(0), [true, true, true]);
+    assert!(decisions[0].kept());
+    // Failing the age filter doesn't hide the kind filter's verdict
+    assert_eq!(verdicts(1), [false, false, true]);
+    assert!(!decisions[1].kept());
+
+    let mut kept = projects.clone();
+    apply_filters(&mut kept, &filters);
+    assert_eq!(names(&kept), ["api"]);
+}
+
+#[test]
+fn decisions_without_filters_keep_everything() {
+    let projects = vec![project("/w/api", ProjectKind::Rust, 1)];
+    let decisions = decide_filters(&projects, &[]);
+    assert!(decisions[0].checks.is_empty());
+    assert!(decisions[0].kept());
+}
+
+// ── ordering ────────────────────────────────────────────────────────────────
+
+fn sized(path: &str, bytes: u64, age_days: i64) -> ScannedProject {
+    let mut p = project(path, ProjectKind::Rust, age_days);
+    p.total_cleanable_bytes = bytes;
+    p
+}
+
+#[test]
+fn sort_by_size_largest_first() {
+    let mut projects = vec![
+        sized("/w/a", 1, 1),
+        sized("/w/b", 3, 1),
+        sized("/w/c", 2, 1),
+    ];
+    sort_projects(&mut projects, SortKey::Size);
+    assert_eq!(names(&projects), ["b", "c", "a"]);
+}
+
+#[test]
+fn sort_by_size_ties_broken_by_path() {
+    // Same size everywhere: input order must not leak into the output
+    let mut forward = vec![
+        sized("/w/c", 5, 1),
+        sized("/w/a", 5, 1),
+        sized("/w/b", 5, 1),
+    ];
+    let mut backward: Vec<_> = forward.iter().rev().cloned().collect();
+    sort_projects(&mut forward, SortKey::Size);
+    sort_projects(&mut backward, SortKey::Size);
+    assert_eq!(names(&forward), ["a", "b", "c"]);
+    assert_eq!(names(&forward), names(&backward));
+}
+
+#[test]
+fn sort_by_age_oldest_first() {
+    let mut projects = vec![
+        sized("/w/new", 1, 1),
+        sized("/w/old", 1, 300),
+        sized("/w/mid", 1, 30),
+    ];
+    sort_projects(&mut projects, SortKey::Age);
+    assert_eq!(names(&projects), ["old", "mid", "new"]);
+}
+
+#[test]
+fn sort_by_name_is_case_insensitive_with_path_tiebreak() {
+    let mut projects = vec![
+        sized("/z/app", 1, 1),
+        sized("/w/Beta", 1, 1),
+        sized("/a/app", 1, 1),
+        sized("/w/alpha", 1, 1),
+    ];
+    sort_projects(&mut projects, SortKey::Name);
+    let paths: Vec<_> = projects.iter().map(|p| p.path.to_str().unwrap()).collect();
+    assert_eq!(paths, ["/w/alpha", "/a/app", "/z/app", "/w/Beta"]);
+}
+
+#[test]
+fn sort_by_path() {
+    let mut projects = vec![sized("/w/b", 9, 1), sized("/w/a", 1, 1)];
+    sort_projects(&mut projects, SortKey::Path);
+    assert_eq!(names(&projects), ["a", "b"]);
+}
+
+#[test]
+fn group_by_kind_is_sorted_and_stable() {
+    let mut node = sized("/w/n", 4, 1);
+    node.kind = ProjectKind::Node;
+    let mut go = sized("/w/g", 4, 1);
+    go.kind = ProjectKind::Go;
+    let projects = vec![sized("/w/r1", 5, 1), node, sized("/w/r2", 5, 1), go];
+
+    let groups = group_by_kind(&projects);
+    assert_eq!(
+        groups,
+        vec![
+            ("Rust".to_string(), (2, 10)),
+            ("Go".to_string(), (1, 4)),
+            ("Node.js".to_string(), (1, 4)),
+        ]
+    );
+}
+
+#[test]
+fn group_by_owner_sums_per_uid_largest_first() {
+    let mut alice = sized("/w/a", 3, 1);
+    alice.owner = Some(1000);
+    let mut bob = sized("/w/b", 8, 1);
+    bob.owner = Some(1001);
+    let mut alice2 = sized("/w/c", 4, 1);
+    alice2.owner = Some(1000);
+    let projects = vec![alice, bob, alice2, sized("/w/d", 1, 1)];
+
+    let groups = group_by_owner(&projects);
+    assert_eq!(
+        groups,
+        vec![(Some(1001), (1, 8)), (Some(1000), (2, 7)), (None, (1, 1))]
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn scanned_projects_record_their_owner() {
+    let dir = std::env::temp_dir().join("dev_sweep_test_filter_owner");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("target")).unwrap();
+    fs::write(dir.join("Cargo.toml"), "[package]\nname = \"x\"").unwrap();
+    fs::write(dir.join("target/out"), "x").unwrap();
+
+    let project = analyze_project(&dir, ProjectKind::Rust).unwrap();
+    assert_eq!(project.owner, Some(unsafe { libc::getuid() }));
+    assert_eq!(user_name(0).as_deref(), Some("root"));
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[cfg(unix)]
+#[test]
+fn group_by_filesystem_buckets_by_device_under_its_mount_point() {
+    let dir = std::env::temp_dir().join("dev_sweep_test_filter_filesystem");
+    let _ = fs::remove_dir_all(&dir);
+    for name in ["a", "b"] {
+        fs::create_dir_all(dir.join(name).join("target")).unwrap();
+        fs::write(dir.join(name).join("Cargo.toml"), "[package]").unwrap();
+        fs::write(dir.join(name).join("target/out"), "x").unwrap();
+    }
+    let a = analyze_project(&dir.join("a"), ProjectKind::Rust).unwrap();
+    let b = analyze_project(&dir.join("b"), ProjectKind::Rust).unwrap();
+    assert!(a.device.is_some());
+    let bytes = a.total_cleanable_bytes + b.total_cleanable_bytes;
+    // Another device is a bucket of its own, even at the same path.
+    let mut other = b.clone();
+    other.device = a.device.map(|dev| dev + 1);
+
+    let groups = group_by_filesystem(&[a, b, other]);
+    assert_eq!(groups.len(), 2);
+    let (mount, (count, total)) = &groups[0];
+    assert_eq!((*count, *total), (2, bytes));
+    assert!(dir.starts_with(mount), "{}", mount.display());
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn clean_order_parses_modes_and_kind_lists() {
+    assert_eq!("size".parse::<CleanOrder>().unwrap(), CleanOrder::Size);
+    assert_eq!("AGE".parse::<CleanOrder>().unwrap(), CleanOrder::Age);
+    assert_eq!(
+        "node, rust".parse::<CleanOrder>().unwrap(),
+        CleanOrder::Kinds(vec![ProjectKind::Node, ProjectKind::Rust])
+    );
+    assert!("newest".parse::<CleanOrder>().is_err());
+    assert!(",".parse::<CleanOrder>().is_err());
+}
+
+#[test]
+fn order_for_cleaning_by_size_age_and_kind() {
+    let mut node = sized("/w/n", 2, 1);
+    node.kind = ProjectKind::Node;
+    let projects = [sized("/w/a", 5, 10), sized("/w/b", 1, 30), node];
+
+    let ordered = |order: CleanOrder| {
+        let mut refs: Vec<&ScannedProject> = projects.iter().collect();
+        order_for_cleaning(&mut refs, &order);
+        refs.iter().map(|p| p.name.as_str()).collect::<Vec<_>>()
+    };
+    assert_eq!(ordered(CleanOrder::Size), ["a", "n", "b"]);
+    assert_eq!(ordered(CleanOrder::Age), ["b", "a", "n"]);
+    assert_eq!(ordered(CleanOrder::Kinds(vec![ProjectKind::Node])), ["n", "a", "b"]);
+}
+
+// ── target ──────────────────────────────────────────────────────────────────
+
+fn with_targets(path: &str, kind: ProjectKind, targets: &[(&str, u64)]) -> ScannedProject {
+    let mut p = project(path, kind, 1);
+    p.clean_targets = targets
+        .iter()
+        .map(|(name, size)| CleanTarget {
+            path: p.path.join(name),
+            name: name.to_string(),
+            size_bytes: *size,
+            protected: false,
+            last_modified: None,
+            runtime_data: false,
+            large_file: false,
+        })
+        .collect();
+    p.total_cleanable_bytes = targets.iter().map(|(_, size)| size).sum();
+    p
+}
+
+#[test]
+fn target_filter_keeps_only_matching_targets() {
+    let mut projects = vec![
+        with_targets(
+            "/w/web",
+            ProjectKind::Node,
+            &[("node_modules", 10), ("dist", 5)],
+        ),
+        with_targets("/w/api", ProjectKind::Rust, &[("target", 7)]),
+    ];
+    filter_by_target(&mut projects, "node_modules");
+
+    assert_eq!(names(&projects), ["web"]);
+    assert_eq!(projects[0].clean_targets.len(), 1);
+    assert_eq!(projects[0].total_cleanable_bytes, 10);
+}
+
+#[test]
+fn target_filter_matches_nested_last_component() {
+    let mut projects = vec![with_targets(
+        "/w/py",
+        ProjectKind::Python,
+        &[("__pycache__", 1), ("src/__pycache__", 2), (".venv", 4)],
+    )];
+    filter_by_target(&mut projects, "__pycache__/");
+
+    assert_eq!(projects[0].clean_targets.len(), 2);
+    assert_eq!(projects[0].total_cleanable_bytes, 3);
+}
+
+#[test]
+fn target_filter_can_span_kinds() {
+    let mut projects = vec![
+        with_targets("/w/rs", ProjectKind::Rust, &[("target", 1)]),
+        with_targets("/w/mvn", ProjectKind::Java, &[("target", 1), ("build", 1)]),
+        with_targets("/w/web", ProjectKind::Node, &[("dist", 1)]),
+    ];
+    filter_by_target(&mut projects, "target");
+
+    assert_eq!(names(&projects), ["rs", "mvn"]);
+    assert_eq!(
+        kinds_present(&projects),
+        [ProjectKind::Java, ProjectKind::Rust]
+    );
+}
+
+// ── excluded targets ────────────────────────────────────────────────────────
+
+#[test]
+fn glob_match_supports_star_and_question_mark() {
+    assert!(glob_match("target", "target"));
+    assert!(glob_match("*.egg-info", "mypkg.egg-info"));
+    assert!(glob_match("*", ""));
+    assert!(glob_match("cmake-build-*", "cmake-build-debug"));
+    assert!(glob_match("?ist", "dist"));
+    assert!(glob_match("a*b*c", "aXXbYYc"));
+    assert!(!glob_match("a*b*c", "aXXbYY"));
+    assert!(!glob_match("?ist", "ist"));
+    assert!(!glob_match("target", "targets"));
+}
+
+#[test]
+fn exclude_targets_drops_matches_and_emptied_projects() {
+    let mut projects = vec![
+        with_targets(
+            "/w/web",
+            ProjectKind::Node,
+            &[("node_modules", 10), ("dist", 5), ("src/__pycache__", 2)],
+        ),
+        with_targets("/w/py", ProjectKind::Python, &[("__pycache__", 1)]),
+    ];
+    exclude_targets(
+        &mut projects,
+        &["node_*".to_string(), "__pycache__/".to_string()],
+        SizeMode::Apparent,
+    );
+
+    assert_eq!(names(&projects), ["web"]);
+    assert_eq!(projects[0].clean_targets.len(), 1);
+    assert_eq!(projects[0].clean_targets[0].name, "dist");
+    assert_eq!(projects[0].total_cleanable_bytes, 5);
+}
+
+#[test]
+fn exclude_targets_keeps_part_of_a_target() {
+    let root = std::env::temp_dir().join("dev_sweep_test_exclude_nested_target");
+    let _ = fs::remove_dir_all(&root);
+    for dir in ["target/debug/deps", "target/release/deps", "target/doc"] {
+        fs::create_dir_all(root.join(dir)).unwrap();
+        fs::write(root.join(dir).join("data"), "0123456789").unwrap();
+    }
+    fs::write(root.join("target/.rustc_info.json"), "{}").unwrap();
+    fs::write(root.join("Cargo.toml"), "[package]").unwrap();
+    let mut projects = vec![analyze_project(&root, ProjectKind::Rust).unwrap()];
+
+    exclude_targets(
+        &mut projects,
+        &["target/release".to_string()],
+        SizeMode::Apparent,
+    );
+    let remaining: Vec<&str> = projects[0]
+        .clean_targets
+        .iter()
+        .map(|t| t.name.as_str())
+        .collect();
+    assert_eq!(remaining, ["target/debug", "target/doc"]);
+    assert_eq!(projects[0].total_cleanable_bytes, 20);
+
+    exclude_targets(&mut projects, &["target/*".to_string()], SizeMode::Apparent);
+    assert!(projects.is_empty());
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+// ── target age ──────────────────────────────────────────────────────────────
+
+#[test]
+fn targets_record_their_newest_file_and_recent_ones_are_dropped() {
+    let root = std::env::temp_dir().join("dev_sweep_test_target_older_than");
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join("node_modules/pkg")).unwrap();
+    fs::create_dir_all(root.join("dist")).unwrap();
+    fs::write(root.join("package.json"), "{}").unwrap();
+    fs::write(root.join("vite.config.js"), "").unwrap();
+    fs::write(root.join("node_modules/pkg/index.js"), "old").unwrap();
+    fs::write(root.join("dist/bundle.js"), "fresh").unwrap();
+    let ninety_days_ago = SystemTime::now() - std::time::Duration::from_secs(90 * 86_400);
+    fs::File::options()
+        .write(true)
+        .open(root.join("node_modules/pkg/index.js"))
+        .unwrap()
+        .set_modified(ninety_days_ago)
+        .unwrap();
+
+    let mut projects = vec![analyze_project(&root, ProjectKind::Node).unwrap()];
+    let stale = &projects[0].clean_targets[0];
+    assert_eq!(stale.name, "node_modules");
+    assert_eq!(stale.last_modified, Some(ninety_days_ago.into()));
+
+    drop_recent_targets(&mut projects, age_cutoff("30d").unwrap());
+    assert_eq!(projects[0].clean_targets.len(), 1);
+    assert_eq!(projects[0].clean_targets[0].name, "node_modules");
+    assert_eq!(projects[0].total_cleanable_bytes, 3);
+
+    drop_recent_targets(&mut projects, age_cutoff("1y").unwrap());
+    assert!(projects.is_empty());
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn targets_of_unknown_age_are_dropped() {
+    let mut projects = vec![with_targets("/w/a", ProjectKind::Rust, &[("target", 10)])];
+    drop_recent_targets(&mut projects, Local::now());
+    assert!(projects.is_empty());
+}
+
+// ── kind aliases ────────────────────────────────────────────────────────────
+
+// ── safe mode ───────────────────────────────────────────────────────────────
+
+#[test]
+fn safe_mode_protects_targets_outside_the_allowlist() {
+    let mut projects = vec![
+        with_targets(
+            "/w/web",
+            ProjectKind::Node,
+            &[("node_modules", 10), ("dist", 5), ("src/__pycache__", 2)],
+        ),
+        with_targets("/w/gem", ProjectKind::Ruby, &[("vendor/bundle", 3)]),
+    ];
+    protect_unsafe_targets(&mut projects);
+
+    let protected: Vec<_> = projects[0]
+        .clean_targets
+        .iter()
+        .map(|t| (t.name.as_str(), t.protected))
+        .collect();
+    assert_eq!(
+        protected,
+        [("node_modules", false), ("dist", true), ("src/__pycache__", false)]
+    );
+    assert_eq!(projects[0].total_cleanable_bytes, 12);
+    // Fully protected projects stay listed, with nothing to reclaim.
+    assert_eq!(names(&projects), ["web", "gem"]);
+    assert_eq!(projects[1].total_cleanable_bytes, 0);
+}
+
+// ── keep last build ─────────────────────────────────────────────────────────
+
+/// A Rust project whose `target` holds `debug` and `release` builds.
+fn rust_builds(name: &str) -> PathBuf {
+    let root = std::env::temp_dir().join(format!("dev_sweep_test_{name}"));
+    let _ = fs::remove_dir_all(&root);
+    for dir in ["target/debug", "target/release"] {
+        fs::create_dir_all(root.join(dir)).unwrap();
+        fs::write(root.join(dir).join("app"), "0123456789").unwrap();
+    }
+    fs::write(root.join("Cargo.toml"), "[package]").unwrap();
+    root
+}
+
+fn protected_targets(project: &ScannedProject) -> Vec<(&str, bool)> {
+    project
+        .clean_targets
+        .iter()
+        .map(|t| (t.name.as_str(), t.protected))
+        .collect()
+}
+
+#[test]
+fn recent_release_build_is_split_out_and_protected() {
+    let root = rust_builds("keep_last_build_recent");
+    let mut projects = vec![analyze_project(&root, ProjectKind::Rust).unwrap()];
+    let rules = BTreeMap::from([(
+        ProjectKind::Rust,
+        KeepLastBuild {
+            dirs: Vec::new(),
+            within: Some("7d".to_string()),
+        },
+    )]);
+
+    let kept = protect_last_builds(&mut projects, &rules, SizeMode::Apparent).unwrap();
+    assert_eq!(kept, 1);
+    assert_eq!(
+        protected_targets(&projects[0]),
+        [("target/debug", false), ("target/release", true)]
+    );
+    assert_eq!(projects[0].total_cleanable_bytes, 10);
+
+    // Other kinds have no rule, and a bad age is an error.
+    let mut node = vec![with_targets("/w/web", ProjectKind::Node, &[("dist", 5)])];
+    assert_eq!(
+        protect_last_builds(&mut node, &rules, SizeMode::Apparent).unwrap(),
+        0
+    );
+    let bad = BTreeMap::from([(
+        ProjectKind::Rust,
+        KeepLastBuild {
+            dirs: Vec::new(),
+            within: Some("soon".to_string()),
+        },
+    )]);
+    assert!(protect_last_builds(&mut projects, &bad, SizeMode::Apparent).is_err());
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn marked_build_is_kept_without_a_window() {
+    let root = rust_builds("keep_last_build_marker");
+    let mut projects = vec![analyze_project(&root, ProjectKind::Rust).unwrap()];
+    let rules = BTreeMap::from([(
+        ProjectKind::Rust,
+        KeepLastBuild {
+            dirs: vec!["target/debug".to_string(), "target/release/".to_string()],
+            within: None,
+        },
+    )]);
+
+    // Without a window, nothing unmarked is kept.
+    assert_eq!(
+        protect_last_builds(&mut projects, &rules, SizeMode::Apparent).unwrap(),
+        0
+    );
+    fs::write(root.join("target/debug").join(LAST_GOOD_MARKER), "").unwrap();
+    assert_eq!(
+        protect_last_builds(&mut projects, &rules, SizeMode::Apparent).unwrap(),
+        1
+    );
+    assert_eq!(
+        protected_targets(&projects[0]),
+        [("target/debug", true), ("target/release", false)]
+    );
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn kind_aliases_roll_up_in_groups() {
+    let aliases = BTreeMap::from([
+        (ProjectKind::Java, "JVM".to_string()),
+        (ProjectKind::Scala, "JVM".to_string()),
+    ]);
+    let mut projects = vec![
+        sized("/w/mvn", 3, 1),
+        sized("/w/sbt", 4, 1),
+        sized("/w/rs", 5, 1),
+    ];
+    projects[0].kind = ProjectKind::Java;
+    projects[1].kind = ProjectKind::Scala;
+    apply_kind_aliases(&mut projects, &aliases);
+
+    assert_eq!(projects[0].display_kind(), "JVM");
+    assert_eq!(projects[2].display_kind(), "Rust");
+    // The detected kind is kept untouched
+    assert_eq!(projects[1].kind, ProjectKind::Scala);
+    assert_eq!(
+        group_by_kind(&projects),
+        vec![("JVM".to_string(), (2, 7)), ("Rust".to_string(), (1, 5))]
+    );
+}
+
+#[test]
+fn rebuild_costs_default_per_kind_and_can_be_overridden() {
+    let mut projects = vec![
+        project("/w/rs", ProjectKind::Rust, 1),
+        project("/w/web", ProjectKind::Node, 1),
+        project("/w/infra", ProjectKind::Terraform, 1),
+    ];
+    let plain = serde_json::to_value(&projects[0]).unwrap();
+    assert!(plain.get("rebuild_cost").is_none());
+
+    apply_rebuild_costs(
+        &mut projects,
+        &BTreeMap::from([(ProjectKind::Node, RebuildCost::Cheap)]),
+    );
+    let costs: Vec<_> = projects.iter().map(|p| p.rebuild_cost).collect();
+    assert_eq!(
+        costs,
+        [
+            Some(RebuildCost::Expensive),
+            Some(RebuildCost::Cheap),
+            Some(RebuildCost::Cheap)
+        ]
+    );
+    let json = serde_json::to_value(&projects[0]).unwrap();
+    assert_eq!(json["rebuild_cost"], "expensive");
+}
+
+#[test]
+fn kind_alias_is_serialized_next_to_raw_kind() {
+    let mut projects = vec![project("/w/mvn", ProjectKind::Java, 1)];
+    apply_kind_aliases(
+        &mut projects,
+        &BTreeMap::from([(ProjectKind::Java, "JVM".into())]),
+    );
+    let json = serde_json::to_value(&projects[0]).unwrap();
+    assert_eq!(json["kind"], "Java");
+    assert_eq!(json["kind_alias"], "JVM");
+
+    // Unaliased projects don't carry the field at all
+    let plain = serde_json::to_value(project("/w/rs", ProjectKind::Rust, 1)).unwrap();
+    assert!(plain.get("kind_alias").is_none());
+}
+
+// ── path tree ───────────────────────────────────────────────────────────────
+
+#[test]
+fn path_tree_aggregates_up_the_hierarchy() {
+    let projects = [
+        sized("/w/clients/acme/api", 300, 1),
+        sized("/w/clients/acme/web", 500, 1),
+        sized("/w/clients/globex", 100, 1),
+        sized("/w/personal/blog", 1000, 1),
+    ];
+    let tree = build_path_tree(Path::new("/w"), &projects);
+
+    assert_eq!(tree.name, "/w");
+    assert_eq!((tree.bytes, tree.projects), (1900, 4));
+
+    let top: Vec<(&str, u64)> = tree.children.iter().map(|c| (c.name.as_str(), c.bytes)).collect();
+    assert_eq!(top, [("personal", 1000), ("clients", 900)]);
+
+    let clients = &tree.children[1];
+    assert_eq!(clients.projects, 3);
+    let acme = &clients.children[0];
+    assert_eq!((acme.name.as_str(), acme.bytes), ("acme", 800));
+    let leaves: Vec<&str> = acme.children.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(leaves, ["web", "api"]);
+}
+
+#[test]
+fn path_tree_ties_are_alphabetical_and_outsiders_kept_whole() {
+    let projects = [
+        sized("/w/b", 10, 1),
+        sized("/w/a", 10, 1),
+        sized("/home/me/.julia", 5, 1),
+    ];
+    let tree = build_path_tree(Path::new("/w"), &projects);
+
+    let top: Vec<&str> = tree.children.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(top, ["a", "b", "/home/me/.julia"]);
+    assert_eq!(tree.bytes, 25);
+}
@@ -0,0 +1,486 @@
+//! Tests for opt-in global caches, relocated build output, and the temp
+//! directory: location resolution, labeling, and targets.
+
+use std::collections::BTreeMap;
+use std::ffi::{OsStr, OsString};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use dev_sweep::config::DevSweepConfig;
+use dev_sweep::scanner::global::{
+    resolve_android_user_home, resolve_cargo_home, resolve_conda_envs, resolve_gradle_caches,
+    resolve_julia_depot, resolve_poetry_virtualenvs, resolve_sccache_dir, scan_global_cache_at,
+    scan_global_caches, scan_sccache_at,
+};
+use dev_sweep::scanner::relocated::scan_relocated_dirs;
+use dev_sweep::scanner::walk::{detect_project_kind, scan_directory_with};
+use dev_sweep::scanner::{
+    ProjectKind, RelocatedDir, ScanOptions, resolve_artifact_dirs, scan_temp_dir,
+};
+
+/// Helper: create a fresh temp dir for a test.
+fn test_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("dev_sweep_test_{name}"));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+// ── Julia depot ────────────────────────────────────────────────────────────
+
+#[test]
+fn julia_depot_defaults_to_home() {
+    let depot = resolve_julia_depot(None, Some(Path::new("/home/me")));
+    assert_eq!(depot, Some(PathBuf::from("/home/me/.julia")));
+}
+
+#[test]
+fn julia_depot_uses_first_env_entry() {
+    let env = OsString::from("/opt/depot:/usr/share/julia");
+    let depot = resolve_julia_depot(Some(&env), Some(Path::new("/home/me")));
+    assert_eq!(depot, Some(PathBuf::from("/opt/depot")));
+}
+
+#[test]
+fn julia_depot_skips_empty_env_entries() {
+    // A leading separator means "default + ..." in Julia; fall through to a real path
+    let env = OsString::from(":/opt/depot");
+    let depot = resolve_julia_depot(Some(&env), None);
+    assert_eq!(depot, Some(PathBuf::from("/opt/depot")));
+}
+
+#[test]
+fn julia_depot_targets_are_separate() {
+    let depot = test_dir("global_julia");
+    for sub in ["compiled/v1.10", "artifacts/abc", "packages/JSON", "registries/General"] {
+        fs::create_dir_all(depot.join(sub)).unwrap();
+        fs::write(depot.join(sub).join("file"), "data").unwrap();
+    }
+
+    let project = scan_global_cache_at(ProjectKind::Julia, &depot).unwrap();
+    let names: Vec<&str> = project.clean_targets.iter().map(|t| t.name.as_str()).collect();
+    assert_eq!(names, ["compiled", "artifacts", "packages"]);
+    assert_eq!(project.total_cleanable_bytes, 12); // registries/ isn't touched
+    assert_eq!(project.kind, ProjectKind::Julia);
+    assert!(project.name.contains("global cache"));
+    fs::remove_dir_all(&depot).unwrap();
+}
+
+// ── Poetry virtualenvs ──────────────────────────────────────────────────────
+
+#[test]
+fn poetry_virtualenvs_default_to_cache_dir() {
+    let cache = Path::new("/home/me/.cache/pypoetry");
+    let path = resolve_poetry_virtualenvs(None, None, None, Some(cache));
+    assert_eq!(path, Some(cache.join("virtualenvs")));
+}
+
+#[test]
+fn poetry_virtualenvs_honor_env_then_config() {
+    let cache = Path::new("/home/me/.cache/pypoetry");
+    let env = OsString::from("/fast/venvs");
+    let config = "cache-dir = \"/srv/poetry\"\n[virtualenvs]\npath = \"{cache-dir}/envs\"\n";
+
+    let path = resolve_poetry_virtualenvs(Some(&env), None, Some(config), Some(cache));
+    assert_eq!(path, Some(PathBuf::from("/fast/venvs")));
+
+    let path = resolve_poetry_virtualenvs(None, None, Some(config), Some(cache));
+    assert_eq!(path, Some(PathBuf::from("/srv/poetry/envs")));
+
+    // POETRY_CACHE_DIR beats the config's cache-dir
+    let cache_env = OsString::from("/tmp/pc");
+    let path = resolve_poetry_virtualenvs(None, Some(&cache_env), Some(config), Some(cache));
+    assert_eq!(path, Some(PathBuf::from("/tmp/pc/envs")));
+}
+
+#[test]
+fn poetry_lists_each_virtualenv_as_a_target() {
+    let venvs = test_dir("global_poetry");
+    for env in ["api-Xy12-py3.11", "web-Ab34-py3.12"] {
+        fs::create_dir_all(venvs.join(env).join("lib")).unwrap();
+        fs::write(venvs.join(env).join("lib/site.py"), "data").unwrap();
+    }
+
+    let project = scan_global_cache_at(ProjectKind::Poetry, &venvs).unwrap();
+    let mut names: Vec<&str> = project.clean_targets.iter().map(|t| t.name.as_str()).collect();
+    names.sort();
+    assert_eq!(names, ["api-Xy12-py3.11", "web-Ab34-py3.12"]);
+    assert_eq!(project.total_cleanable_bytes, 8);
+    assert_eq!(project.name, "Poetry virtualenvs (global cache)");
+    fs::remove_dir_all(&venvs).unwrap();
+}
+
+// ── Conda environments ──────────────────────────────────────────────────────
+
+#[test]
+fn conda_envs_from_env_then_condarc_then_conda_exe() {
+    let home = Path::new("/home/me");
+    let env = OsString::from("/data/envs:/other");
+    let condarc = "envs_dirs:\n  - ~/conda-envs\n  - /opt/envs\n";
+    let exe = Path::new("/opt/miniconda3/bin/conda");
+
+    let envs = resolve_conda_envs(Some(&env), Some(condarc), Some(exe), Some(home));
+    assert_eq!(envs, Some(PathBuf::from("/data/envs")));
+
+    let envs = resolve_conda_envs(None, Some(condarc), Some(exe), Some(home));
+    assert_eq!(envs, Some(PathBuf::from("/home/me/conda-envs")));
+
+    let envs = resolve_conda_envs(None, Some("channels: [defaults]\n"), Some(exe), Some(home));
+    assert_eq!(envs, Some(PathBuf::from("/opt/miniconda3/envs")));
+}
+
+#[test]
+fn conda_envs_fall_back_to_an_existing_install() {
+    let home = test_dir("global_conda_home");
+    assert_eq!(resolve_conda_envs(None, None, None, Some(&home)), None);
+
+    fs::create_dir_all(home.join("miniforge3/envs/ml")).unwrap();
+    let envs = resolve_conda_envs(None, None, None, Some(&home));
+    assert_eq!(envs, Some(home.join("miniforge3/envs")));
+    fs::remove_dir_all(&home).unwrap();
+}
+
+// ── Cargo caches ────────────────────────────────────────────────────────────
+
+#[test]
+fn cargo_home_honors_env_then_home() {
+    let home = Path::new("/home/me");
+    let env = OsString::from("/opt/cargo");
+    assert_eq!(
+        resolve_cargo_home(Some(&env), Some(home)),
+        Some(PathBuf::from("/opt/cargo"))
+    );
+    assert_eq!(
+        resolve_cargo_home(Some(OsStr::new("")), Some(home)),
+        Some(PathBuf::from("/home/me/.cargo"))
+    );
+}
+
+#[test]
+fn sccache_dir_honors_env() {
+    let env = OsString::from("/fast/sccache");
+    let dir = resolve_sccache_dir(Some(&env), Some(Path::new("/home/me/.cache")));
+    assert_eq!(dir, Some(PathBuf::from("/fast/sccache")));
+    assert!(resolve_sccache_dir(None, None).is_none());
+}
+
+#[test]
+fn cargo_home_lists_registry_and_git_caches_but_not_binaries() {
+    let cargo = test_dir("global_cargo_home");
+    for sub in ["registry/cache/index", "registry/src/index", "git/db/dep", "bin"] {
+        fs::create_dir_all(cargo.join(sub)).unwrap();
+        fs::write(cargo.join(sub).join("file"), "data").unwrap();
+    }
+
+    let project = scan_global_cache_at(ProjectKind::CargoCache, &cargo).unwrap();
+    let names: Vec<&str> = project.clean_targets.iter().map(|t| t.name.as_str()).collect();
+    assert_eq!(names, ["registry/cache", "registry/src", "git/db"]);
+    assert_eq!(project.total_cleanable_bytes, 12); // installed binaries stay
+    assert_eq!(project.name, "Cargo registry and git caches (global cache)");
+    fs::remove_dir_all(&cargo).unwrap();
+}
+
+#[test]
+fn sccache_lists_each_shard_as_a_target() {
+    let dir = test_dir("global_sccache");
+    for shard in ["0/a", "f/b"] {
+        fs::create_dir_all(dir.join(shard)).unwrap();
+        fs::write(dir.join(shard).join("entry"), "data").unwrap();
+    }
+
+    let project = scan_sccache_at(&dir).unwrap();
+    let names: Vec<&str> = project.clean_targets.iter().map(|t| t.name.as_str()).collect();
+    assert_eq!(names, ["sccache/0", "sccache/f"]);
+    assert_eq!(project.kind, ProjectKind::CargoCache);
+    assert_eq!(project.total_cleanable_bytes, 8);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+// ── Android caches ──────────────────────────────────────────────────────────
+
+#[test]
+fn gradle_caches_honor_env_then_home() {
+    let home = Path::new("/home/me");
+    let env = OsString::from("/opt/gradle");
+    assert_eq!(
+        resolve_gradle_caches(Some(&env), Some(home)),
+        Some(PathBuf::from("/opt/gradle/caches"))
+    );
+    assert_eq!(
+        resolve_gradle_caches(None, Some(home)),
+        Some(PathBuf::from("/home/me/.gradle/caches"))
+    );
+}
+
+#[test]
+fn android_user_home_prefers_its_own_env_over_the_sdk_home() {
+    let home = Some(Path::new("/home/me"));
+    let user_home = OsString::from("/opt/android-prefs");
+    let sdk_home = OsString::from("/opt/sdk-home");
+    assert_eq!(
+        resolve_android_user_home(Some(&user_home), Some(&sdk_home), home),
+        Some(PathBuf::from("/opt/android-prefs"))
+    );
+    assert_eq!(
+        resolve_android_user_home(Some(OsStr::new("")), Some(&sdk_home), home),
+        Some(PathBuf::from("/opt/sdk-home/.android"))
+    );
+    assert_eq!(
+        resolve_android_user_home(None, None, home),
+        Some(PathBuf::from("/home/me/.android"))
+    );
+}
+
+#[test]
+fn android_caches_list_each_transforms_dir_and_the_build_cache() {
+    let dir = test_dir("global_android");
+    let (caches, android) = (dir.join("gradle/caches"), dir.join("android"));
+    for sub in ["transforms-3/abc", "transforms-4/def", "modules-2/files"] {
+        fs::create_dir_all(caches.join(sub)).unwrap();
+        fs::write(caches.join(sub).join("file"), "data").unwrap();
+    }
+    for sub in ["build-cache/0a1b", "avd"] {
+        fs::create_dir_all(android.join(sub)).unwrap();
+        fs::write(android.join(sub).join("file"), "data").unwrap();
+    }
+
+    let project = scan_global_cache_at(ProjectKind::AndroidCache, &caches).unwrap();
+    let mut names: Vec<&str> = project.clean_targets.iter().map(|t| t.name.as_str()).collect();
+    names.sort();
+    assert_eq!(names, ["transforms-3", "transforms-4"]);
+    assert_eq!(project.total_cleanable_bytes, 8); // downloaded modules stay
+    assert_eq!(project.name, "Gradle transform caches (global cache)");
+
+    let project = scan_global_cache_at(ProjectKind::AndroidCache, &android).unwrap();
+    let names: Vec<&str> = project.clean_targets.iter().map(|t| t.name.as_str()).collect();
+    assert_eq!(names, ["build-cache"]); // emulator images stay
+    assert!(ProjectKind::AndroidCache.is_global());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+// ── opt-in behavior ─────────────────────────────────────────────────────────
+
+#[test]
+fn julia_is_never_detected_by_walking() {
+    let dir = test_dir("global_julia_walk");
+    fs::write(dir.join("Project.toml"), "").unwrap();
+    fs::write(dir.join("Manifest.toml"), "").unwrap();
+    fs::create_dir_all(dir.join("compiled")).unwrap();
+    assert_eq!(detect_project_kind(&dir), None);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn no_global_caches_requested_means_none_scanned() {
+    assert!(scan_global_caches(&[], true).unwrap().is_empty());
+}
+
+#[test]
+fn non_global_kind_is_rejected() {
+    let err = scan_global_caches(&[ProjectKind::Rust], false).unwrap_err();
+    assert!(err.to_string().contains("not a global cache"));
+}
+
+#[test]
+fn global_kinds_have_no_marker_files() {
+    // Global caches must only ever be reported on request, never by walking.
+    for kind in ProjectKind::all().iter().filter(|k| k.is_global()) {
+        assert!(kind.marker_files().is_empty(), "{kind} has marker files");
+    }
+}
+
+// ── Relocated build output ──────────────────────────────────────────────────
+
+#[test]
+fn cargo_target_dir_is_reported_as_rust_output() {
+    let dirs = resolve_artifact_dirs(&BTreeMap::new(), Some(OsStr::new("/mnt/cargo-target")));
+    assert_eq!(
+        dirs,
+        vec![RelocatedDir {
+            kind: ProjectKind::Rust,
+            path: PathBuf::from("/mnt/cargo-target"),
+            source: "CARGO_TARGET_DIR".to_string(),
+        }]
+    );
+}
+
+#[test]
+fn relative_cargo_target_dir_is_ignored() {
+    let dirs = resolve_artifact_dirs(&BTreeMap::new(), Some(OsStr::new("build/target")));
+    assert!(dirs.is_empty());
+}
+
+#[test]
+fn artifact_dirs_config_overrides_cargo_target_dir() {
+    let configured = BTreeMap::from([
+        (ProjectKind::Rust, PathBuf::from("/data/rust-out")),
+        (ProjectKind::Node, PathBuf::from("/data/node-out")),
+    ]);
+    let dirs = resolve_artifact_dirs(&configured, Some(OsStr::new("/mnt/cargo-target")));
+    assert_eq!(dirs.len(), 2);
+    assert!(dirs.iter().all(|d| d.source == "artifact_dirs"));
+    assert!(
+        dirs.iter()
+            .any(|d| d.kind == ProjectKind::Rust && d.path == Path::new("/data/rust-out"))
+    );
+}
+
+#[test]
+fn relocated_dir_is_one_project_with_one_target() {
+    let dir = test_dir("relocated_single");
+    let out = dir.join("cargo-target");
+    fs::create_dir_all(out.join("debug")).unwrap();
+    fs::write(out.join("debug/app"), "binary").unwrap();
+
+    let relocated = vec![RelocatedDir {
+        kind: ProjectKind::Rust,
+        path: out.clone(),
+        source: "CARGO_TARGET_DIR".to_string(),
+    }];
+    let projects = scan_relocated_dirs(&relocated, &[], &ScanOptions::default());
+    assert_eq!(projects.len(), 1);
+    assert_eq!(projects[0].kind, ProjectKind::Rust);
+    assert_eq!(projects[0].name, "Rust build output (CARGO_TARGET_DIR)");
+    assert_eq!(projects[0].clean_targets.len(), 1);
+    assert_eq!(projects[0].clean_targets[0].path, out);
+    assert!(projects[0].total_cleanable_bytes > 0);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn missing_or_empty_relocated_dir_is_skipped() {
+    let dir = test_dir("relocated_missing");
+    fs::create_dir_all(dir.join("empty")).unwrap();
+
+    let relocated = vec![
+        RelocatedDir {
+            kind: ProjectKind::Rust,
+            path: dir.join("missing"),
+            source: "CARGO_TARGET_DIR".to_string(),
+        },
+        RelocatedDir {
+            kind: ProjectKind::Node,
+            path: dir.join("empty"),
+            source: "artifact_dirs".to_string(),
+        },
+    ];
+    assert!(scan_relocated_dirs(&relocated, &[], &ScanOptions::default()).is_empty());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn relocated_dir_inside_a_found_target_is_not_counted_twice() {
+    let root = test_dir("relocated_dedupe");
+    let proj = root.join("app");
+    fs::create_dir_all(proj.join("target/debug")).unwrap();
+    fs::write(proj.join("Cargo.toml"), "[package]").unwrap();
+    fs::write(proj.join("target/debug/app"), "binary").unwrap();
+
+    let options = ScanOptions {
+        artifact_dirs: vec![RelocatedDir {
+            kind: ProjectKind::Rust,
+            path: proj.join("target"),
+            source: "CARGO_TARGET_DIR".to_string(),
+        }],
+        ..Default::default()
+    };
+    let projects = scan_directory_with(&root, &options, &DevSweepConfig::default()).unwrap();
+    assert_eq!(projects.len(), 1);
+    assert_eq!(projects[0].path, proj);
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn excluded_kind_skips_its_relocated_dir() {
+    let root = test_dir("relocated_excluded");
+    let out = test_dir("relocated_excluded_out");
+    fs::write(out.join("artifact"), "data").unwrap();
+
+    let options = ScanOptions {
+        artifact_dirs: vec![RelocatedDir {
+            kind: ProjectKind::Rust,
+            path: out.clone(),
+            source: "CARGO_TARGET_DIR".to_string(),
+        }],
+        ..Default::default()
+    };
+    let config = DevSweepConfig {
+        exclude_kinds: vec![ProjectKind::Rust],
+        ..Default::default()
+    };
+    assert!(scan_directory_with(&root, &options, &config).unwrap().is_empty());
+    let config = DevSweepConfig::default();
+    assert_eq!(scan_directory_with(&root, &options, &config).unwrap().len(), 1);
+
+    fs::remove_dir_all(&root).unwrap();
+    fs::remove_dir_all(&out).unwrap();
+}
+
+// ── Temp directory ──────────────────────────────────────────────────────────
+
+const WEEK: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Helper: backdate every file and directory under `root` by `age`.
+fn backdate(root: &Path, age: Duration) {
+    let when = SystemTime::now() - age;
+    for entry in walkdir::WalkDir::new(root).contents_first(true) {
+        let entry = entry.unwrap();
+        fs::File::open(entry.path()).unwrap().set_modified(when).unwrap();
+    }
+}
+
+#[test]
+fn temp_scan_reports_only_stale_directories() {
+    let root = test_dir("temp_scan_stale");
+    for dir in ["cargo-install1", "tmp.fresh", ".hidden"] {
+        fs::create_dir_all(root.join(dir)).unwrap();
+        fs::write(root.join(dir).join("data"), "scratch").unwrap();
+    }
+    fs::write(root.join("download.zip"), "zip").unwrap();
+    backdate(&root.join("cargo-install1"), 2 * WEEK);
+    backdate(&root.join(".hidden"), 2 * WEEK);
+    backdate(&root.join("download.zip"), 2 * WEEK);
+
+    let project = scan_temp_dir(&root, WEEK, &ScanOptions::default())
+        .unwrap()
+        .unwrap();
+    assert_eq!(project.kind, ProjectKind::Temp);
+    assert_eq!(project.path, root);
+    let names: Vec<_> = project.clean_targets.iter().map(|t| t.name.as_str()).collect();
+    assert_eq!(names, ["cargo-install1"]);
+    assert_eq!(project.total_cleanable_bytes, 7);
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn temp_scan_skips_old_directories_with_recent_files() {
+    let root = test_dir("temp_scan_recent_inside");
+    fs::create_dir_all(root.join("build-cache/deep")).unwrap();
+    fs::write(root.join("build-cache/deep/old"), "old").unwrap();
+    backdate(&root.join("build-cache"), 2 * WEEK);
+    // Rewriting a file deep down leaves the entry's own mtime alone.
+    fs::write(root.join("build-cache/deep/old"), "new").unwrap();
+
+    assert!(
+        scan_temp_dir(&root, WEEK, &ScanOptions::default())
+            .unwrap()
+            .is_none()
+    );
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn temp_scan_is_opt_in() {
+    let root = test_dir("temp_scan_opt_in");
+    let projects =
+        scan_directory_with(&root, &ScanOptions::default(), &DevSweepConfig::default()).unwrap();
+    assert!(projects.iter().all(|p| p.kind != ProjectKind::Temp));
+
+    fs::remove_dir_all(&root).unwrap();
+}
@@ -0,0 +1,71 @@
+//! Tests for `--ascii`. Once switched on, ASCII symbols apply to the whole
+//! process, so these tests live in their own binary.
+
+use std::ffi::OsString;
+
+use dev_sweep::tui::colors::{self, yellow};
+use dev_sweep::tui::display::format_size_legend;
+use dev_sweep::tui::glyphs::{detect_ascii, set_ascii, to_ascii};
+use dev_sweep::util::{truncate, visible_len};
+
+fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<OsString> {
+    let vars: Vec<(String, String)> = vars
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    move |name| vars.iter().find(|(k, _)| k == name).map(|(_, v)| v.into())
+}
+
+// ── Detection ───────────────────────────────────────────────────────────────
+
+#[test]
+fn utf8_locales_keep_unicode() {
+    assert!(!detect_ascii(env(&[("LANG", "en_US.UTF-8")])));
+    assert!(!detect_ascii(env(&[("LC_ALL", "C.utf8"), ("LANG", "C")])));
+    // Nothing to go on: keep Unicode.
+    assert!(!detect_ascii(env(&[])));
+}
+
+#[test]
+fn other_locales_and_dumb_terminals_get_ascii() {
+    assert!(detect_ascii(env(&[("LANG", "C")])));
+    assert!(detect_ascii(env(&[
+        ("LC_CTYPE", "POSIX"),
+        ("LANG", "en_US.UTF-8")
+    ])));
+    assert!(detect_ascii(env(&[("LC_ALL", "en_US.ISO-8859-1")])));
+    assert!(detect_ascii(env(&[
+        ("TERM", "dumb"),
+        ("LANG", "en_US.UTF-8")
+    ])));
+}
+
+#[test]
+fn empty_locale_variables_are_skipped() {
+    assert!(!detect_ascii(env(&[
+        ("LC_ALL", ""),
+        ("LANG", "de_DE.UTF-8")
+    ])));
+}
+
+// ── Output ──────────────────────────────────────────────────────────────────
+
+#[test]
+fn symbols_become_ascii_and_names_are_left_alone() {
+    assert_eq!(to_ascii("✓ café → done…"), "+ café -> done...");
+    let border = "╭───┬──╮";
+    assert_eq!(to_ascii(border), "+---+--+");
+    assert_eq!(to_ascii(border).len(), border.chars().count());
+}
+
+#[test]
+fn ascii_mode_reaches_colored_text_and_truncation() {
+    set_ascii(true);
+    colors::set_enabled(false);
+    assert_eq!(yellow("⚠"), "!");
+    assert!(format_size_legend().is_ascii());
+
+    let cut = truncate("a-very-long-project-name", 10);
+    assert_eq!(cut, "a-very-...");
+    assert_eq!(visible_len(&cut), 10);
+}
@@ -0,0 +1,78 @@
+//! Tests for the standalone HTML report behind `scan --format html`.
+
+use std::path::PathBuf;
+
+use chrono::{Local, TimeZone};
+use dev_sweep::scanner::{CleanTarget, ProjectKind, ScannedProject};
+use dev_sweep::tui::html::{escape_html, render_html_report};
+
+fn project(name: &str, kind: ProjectKind, bytes: u64) -> ScannedProject {
+    let path = PathBuf::from(format!("/work/{name}"));
+    ScannedProject {
+        clean_targets: vec![CleanTarget {
+            path: path.join("target"),
+            name: "target".to_string(),
+            size_bytes: bytes,
+            protected: false,
+            last_modified: None,
+            runtime_data: false,
+            large_file: false,
+        }],
+        path,
+        kind,
+        other_kinds: Vec::new(),
+        name: name.to_string(),
+        last_modified: Local.with_ymd_and_hms(2026, 3, 14, 12, 0, 0).unwrap(),
+        total_cleanable_bytes: bytes,
+        largest_file: None,
+        kind_alias: None,
+        rebuild_cost: None,
+        owner: None,
+        device: None,
+        workspace_members: Vec::new(),
+    }
+}
+
+fn report(projects: &[ScannedProject]) -> String {
+    let generated = Local.with_ymd_and_hms(2026, 10, 1, 9, 30, 0).unwrap();
+    render_html_report(projects, &[PathBuf::from("/work")], generated)
+}
+
+#[test]
+fn report_is_a_single_self_contained_page() {
+    let html = report(&[project("api", ProjectKind::Rust, 2048)]);
+
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.trim_end().ends_with("</html>"));
+    assert!(html.contains("<style>") && html.contains("<script>"));
+    assert!(!html.contains("<link") && !html.contains("src="));
+    assert!(html.contains("Scanned /work on 2026-10-01 09:30"));
+}
+
+#[test]
+fn report_lists_projects_and_kinds() {
+    let html = report(&[
+        project("api", ProjectKind::Rust, 2048),
+        project("web", ProjectKind::Node, 1024),
+        project("cli", ProjectKind::Rust, 1024),
+    ]);
+
+    assert!(html.contains("3 projects with <strong>4.0 KB</strong>"));
+    assert!(
+        html.contains(
+            "<tr><td>Rust</td><td class=\"size\">2</td><td class=\"size\">3.0 KB</td></tr>"
+        )
+    );
+    assert!(html.contains("<td class=\"size\" data-sort=\"2048\">2.0 KB</td>"));
+    assert!(html.contains(">2026-03-14</td>"));
+    assert!(html.contains("<td class=\"path\">/work/web</td>"));
+}
+
+#[test]
+fn report_escapes_project_names_and_paths() {
+    let html = report(&[project("<b>&co</b>", ProjectKind::Rust, 1)]);
+
+    assert!(!html.contains("<b>&co</b>"));
+    assert!(html.contains("&lt;b&gt;&amp;co&lt;/b&gt;"));
+    assert_eq!(escape_html(r#"a"b'c"#), "a&quot;b&#39;c");
+}
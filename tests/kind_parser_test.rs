@@ -0,0 +1,57 @@
+//! Tests for parsing project kind names (e.g. "Rust", "node.js", "DotNet").
+
+use dev_sweep::scanner::ProjectKind;
+
+// ── valid inputs ────────────────────────────────────────────────────────────
+
+#[test]
+fn parse_config_name() {
+    assert_eq!("Rust".parse::<ProjectKind>().unwrap(), ProjectKind::Rust);
+    assert_eq!("DotNet".parse::<ProjectKind>().unwrap(), ProjectKind::DotNet);
+}
+
+#[test]
+fn parse_display_name() {
+    assert_eq!("Node.js".parse::<ProjectKind>().unwrap(), ProjectKind::Node);
+    assert_eq!(".NET".parse::<ProjectKind>().unwrap(), ProjectKind::DotNet);
+}
+
+#[test]
+fn parse_is_case_insensitive() {
+    assert_eq!("rust".parse::<ProjectKind>().unwrap(), ProjectKind::Rust);
+    assert_eq!("PYTHON".parse::<ProjectKind>().unwrap(), ProjectKind::Python);
+    assert_eq!("node".parse::<ProjectKind>().unwrap(), ProjectKind::Node);
+}
+
+#[test]
+fn parse_trims_whitespace() {
+    assert_eq!("  go ".parse::<ProjectKind>().unwrap(), ProjectKind::Go);
+}
+
+#[test]
+fn parse_round_trips_every_kind() {
+    for kind in ProjectKind::all() {
+        assert_eq!(format!("{kind:?}").parse::<ProjectKind>().unwrap(), *kind);
+        assert_eq!(kind.to_string().parse::<ProjectKind>().unwrap(), *kind);
+    }
+}
+
+// ── invalid inputs ──────────────────────────────────────────────────────────
+
+#[test]
+fn parse_unknown_kind_fails() {
+    let err = "Cargo".parse::<ProjectKind>().unwrap_err();
+    assert!(err.to_string().contains("Unknown project kind 'Cargo'"));
+}
+
+#[test]
+fn parse_unknown_kind_lists_valid_names() {
+    let err = "nope".parse::<ProjectKind>().unwrap_err();
+    assert!(err.to_string().contains("Rust"));
+    assert!(err.to_string().contains("Terraform"));
+}
+
+#[test]
+fn parse_empty_fails() {
+    assert!("".parse::<ProjectKind>().is_err());
+}
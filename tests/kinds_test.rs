@@ -0,0 +1,79 @@
+//! Tests for `dev-sweep kinds`: the listed kinds and how the config changes them.
+
+use dev_sweep::config::DevSweepConfig;
+use dev_sweep::kinds::list_kinds;
+use dev_sweep::scanner::ProjectKind;
+
+#[test]
+fn every_kind_is_listed_in_detection_order() {
+    let kinds: Vec<ProjectKind> = list_kinds(&DevSweepConfig::default())
+        .iter()
+        .map(|k| k.kind)
+        .collect();
+    assert_eq!(kinds, ProjectKind::all());
+}
+
+#[test]
+fn detection_priority_moves_kinds_first() {
+    let config = DevSweepConfig {
+        detection_priority: vec![ProjectKind::Node],
+        ..Default::default()
+    };
+    let kinds = list_kinds(&config);
+    assert_eq!(kinds[0].kind, ProjectKind::Node);
+    assert_eq!(kinds[1].kind, ProjectKind::Rust);
+    assert_eq!(kinds.len(), ProjectKind::all().len());
+}
+
+#[test]
+fn markers_and_targets_come_from_the_detectors() {
+    let kinds = list_kinds(&DevSweepConfig::default());
+    let node = kinds.iter().find(|k| k.kind == ProjectKind::Node).unwrap();
+
+    assert_eq!(node.display_name, "Node.js");
+    assert_eq!(node.markers, ["package.json"]);
+    assert!(node.targets.iter().any(|t| t == "node_modules"));
+    let dist = node
+        .gated_targets
+        .iter()
+        .find(|g| g.target == "dist")
+        .unwrap();
+    assert!(dist.markers.iter().any(|m| m == "vite.config.ts"));
+    assert!(!node.global);
+
+    let julia = kinds.iter().find(|k| k.kind == ProjectKind::Julia).unwrap();
+    assert!(julia.global);
+    assert!(julia.markers.is_empty());
+}
+
+#[test]
+fn aliases_and_exclusions_come_from_the_config() {
+    let config = DevSweepConfig {
+        kind_aliases: [(ProjectKind::Java, "JVM".to_string())].into(),
+        exclude_kinds: vec![ProjectKind::Go],
+        ..Default::default()
+    };
+    let kinds = list_kinds(&config);
+    let find = |kind| kinds.iter().find(|k| k.kind == kind).unwrap();
+
+    assert_eq!(find(ProjectKind::Java).alias.as_deref(), Some("JVM"));
+    assert_eq!(find(ProjectKind::Scala).alias, None);
+    assert!(find(ProjectKind::Go).excluded);
+    assert!(!find(ProjectKind::Rust).excluded);
+}
+
+#[test]
+fn json_uses_config_names_and_skips_missing_alias() {
+    let kinds = list_kinds(&DevSweepConfig::default());
+    let dotnet = kinds
+        .iter()
+        .find(|k| k.kind == ProjectKind::DotNet)
+        .unwrap();
+    let json = serde_json::to_value(dotnet).unwrap();
+
+    assert_eq!(json["kind"], "DotNet");
+    assert_eq!(json["display_name"], ".NET");
+    assert!(json.get("alias").is_none());
+    assert_eq!(json["gated_targets"][0]["target"], "bin");
+    assert_eq!(json["gated_targets"][0]["markers"][0], "*.csproj");
+}
@@ -0,0 +1,41 @@
+//! Tests for when structured logging is turned on and with which filter.
+
+use dev_sweep::logging::{DEFAULT_LOG_FILTER, LogFormat, log_filter};
+
+#[test]
+fn logging_is_off_by_default() {
+    assert_eq!(log_filter(None, None), None);
+}
+
+#[test]
+fn rust_log_turns_logging_on() {
+    assert_eq!(
+        log_filter(Some("dev_sweep=debug"), None),
+        Some("dev_sweep=debug".to_string())
+    );
+}
+
+#[test]
+fn log_format_alone_uses_the_default_filter() {
+    assert_eq!(
+        log_filter(None, Some(LogFormat::Json)),
+        Some(DEFAULT_LOG_FILTER.to_string())
+    );
+}
+
+#[test]
+fn rust_log_wins_over_the_default_filter() {
+    assert_eq!(
+        log_filter(Some("warn"), Some(LogFormat::Text)),
+        Some("warn".to_string())
+    );
+}
+
+#[test]
+fn empty_rust_log_counts_as_unset() {
+    assert_eq!(log_filter(Some("  "), None), None);
+    assert_eq!(
+        log_filter(Some(""), Some(LogFormat::Json)),
+        Some(DEFAULT_LOG_FILTER.to_string())
+    );
+}
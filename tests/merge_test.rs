@@ -0,0 +1,188 @@
+//! Tests for `dev-sweep merge`: reading reports and combining their totals.
+
+use std::path::{Path, PathBuf};
+
+use chrono::Local;
+use dev_sweep::merge::{JSON_SCHEMA_VERSION, ReportTotals, merge_reports, read_report};
+use dev_sweep::scanner::{ProjectKind, ScannedProject};
+use serde_json::json;
+
+fn project(name: &str, kind: ProjectKind, bytes: u64) -> ScannedProject {
+    ScannedProject {
+        path: PathBuf::from(format!("/projects/{name}")),
+        kind,
+        other_kinds: Vec::new(),
+        name: name.to_string(),
+        last_modified: Local::now(),
+        clean_targets: Vec::new(),
+        total_cleanable_bytes: bytes,
+        largest_file: None,
+        kind_alias: None,
+        rebuild_cost: None,
+        owner: None,
+        device: None,
+        workspace_members: Vec::new(),
+    }
+}
+
+/// A `scan --json` report from `host` listing `projects`.
+fn scan_report(host: &str, projects: &[ScannedProject]) -> String {
+    json!({
+        "schema_version": JSON_SCHEMA_VERSION,
+        "host": host,
+        "scanned_at": "2026-01-02T03:04:05+00:00",
+        "projects": projects,
+    })
+    .to_string()
+}
+
+/// A `summary --json` report with the given schema version.
+fn summary_report(schema_version: u32) -> String {
+    json!({
+        "schema_version": schema_version,
+        "host": null,
+        "total_projects": 3,
+        "by_kind": [
+            { "kind": "Rust", "projects": 1, "reclaimable_bytes": 1000 },
+            { "kind": "Node.js", "projects": 2, "reclaimable_bytes": 500 },
+        ],
+    })
+    .to_string()
+}
+
+fn read(name: &str, contents: &str) -> ReportTotals {
+    read_report(Path::new(name), contents).unwrap()
+}
+
+// ── Reading reports ─────────────────────────────────────────────────────────
+
+#[test]
+fn scan_report_is_totaled_by_kind() {
+    let report = read(
+        "laptop.json",
+        &scan_report(
+            "laptop",
+            &[
+                project("a", ProjectKind::Rust, 100),
+                project("b", ProjectKind::Rust, 50),
+                project("c", ProjectKind::Node, 10),
+            ],
+        ),
+    );
+
+    assert_eq!(report.host.as_deref(), Some("laptop"));
+    assert!(report.scanned_at.is_some());
+    assert_eq!(report.projects, 3);
+    assert_eq!(report.reclaimable_bytes, 160);
+    assert_eq!(report.by_kind["Rust"], (2, 150));
+    assert_eq!(report.by_kind["Node.js"], (1, 10));
+}
+
+#[test]
+fn summary_report_keeps_its_totals() {
+    let report = read("summary.json", &summary_report(1));
+
+    assert_eq!(report.host, None);
+    assert_eq!(report.projects, 3);
+    assert_eq!(report.reclaimable_bytes, 1500);
+    assert_eq!(report.by_kind["Node.js"], (2, 500));
+}
+
+#[test]
+fn report_without_a_version_is_version_one() {
+    let contents = json!({ "projects": [project("a", ProjectKind::Go, 1)] }).to_string();
+    assert_eq!(read("old.json", &contents).schema_version, 1);
+}
+
+#[test]
+fn other_reports_are_rejected() {
+    let clean = json!({ "schema_version": 1, "cleaned": [] }).to_string();
+    let err = read_report(Path::new("clean.json"), &clean).unwrap_err();
+    assert!(err.to_string().contains("clean.json"));
+
+    assert!(read_report(Path::new("junk.json"), "not json").is_err());
+}
+
+// ── Merging ─────────────────────────────────────────────────────────────────
+
+#[test]
+fn merge_adds_up_kinds_and_hosts() {
+    let reports = [
+        read(
+            "laptop.json",
+            &scan_report("laptop", &[project("a", ProjectKind::Rust, 100)]),
+        ),
+        read(
+            "server.json",
+            &scan_report(
+                "server",
+                &[
+                    project("b", ProjectKind::Rust, 300),
+                    project("c", ProjectKind::Node, 5),
+                ],
+            ),
+        ),
+        read(
+            "laptop-2.json",
+            &scan_report("laptop", &[project("d", ProjectKind::Node, 20)]),
+        ),
+    ];
+    let merged = merge_reports(&reports).unwrap();
+
+    assert_eq!(merged.sources.len(), 3);
+    assert_eq!(merged.total_projects, 4);
+    assert_eq!(merged.total_reclaimable_bytes, 425);
+
+    let kinds: Vec<(&str, u64, u64, u64)> = merged
+        .by_kind
+        .iter()
+        .map(|k| (k.kind.as_str(), k.reports, k.projects, k.reclaimable_bytes))
+        .collect();
+    assert_eq!(kinds, [("Rust", 2, 2, 400), ("Node.js", 2, 2, 25)]);
+
+    let hosts: Vec<(&str, u64, u64, u64)> = merged
+        .by_host
+        .iter()
+        .map(|h| (h.host.as_str(), h.reports, h.projects, h.reclaimable_bytes))
+        .collect();
+    assert_eq!(hosts, [("server", 1, 2, 305), ("laptop", 2, 2, 120)]);
+}
+
+#[test]
+fn reports_without_hosts_have_no_host_breakdown() {
+    let reports = [
+        read("a.json", &summary_report(1)),
+        read("b.json", &summary_report(1)),
+    ];
+    let merged = merge_reports(&reports).unwrap();
+
+    assert!(merged.by_host.is_empty());
+    assert_eq!(merged.total_reclaimable_bytes, 3000);
+    assert_eq!(merged.first_scanned_at, None);
+}
+
+#[test]
+fn mismatched_schema_versions_are_not_merged() {
+    let reports = [
+        read("a.json", &summary_report(1)),
+        read("b.json", &summary_report(2)),
+    ];
+    let err = merge_reports(&reports).unwrap_err().to_string();
+    assert!(err.contains("a.json (v1)"), "{err}");
+    assert!(err.contains("b.json (v2)"), "{err}");
+}
+
+#[test]
+fn newer_schema_versions_are_not_merged() {
+    let reports = [read(
+        "future.json",
+        &summary_report(JSON_SCHEMA_VERSION + 1),
+    )];
+    let err = merge_reports(&reports).unwrap_err().to_string();
+    assert!(err.contains("future.json"), "{err}");
+}
+
+#[test]
+fn merging_nothing_is_an_error() {
+    assert!(merge_reports(&[]).is_err());
+}
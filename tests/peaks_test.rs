@@ -0,0 +1,83 @@
+//! Tests for the peak history behind `scan --record-max`.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{Duration, Local};
+use dev_sweep::peaks::{self, PeakHistory};
+use dev_sweep::scanner::walk::analyze_project;
+use dev_sweep::scanner::{ProjectKind, ScannedProject};
+
+/// Helper: create a fresh temp dir for a test.
+fn test_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("dev_sweep_test_{name}"));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn rust_project(root: &Path, artifact_bytes: usize) -> ScannedProject {
+    fs::create_dir_all(root.join("target")).unwrap();
+    fs::write(root.join("Cargo.toml"), "[package]").unwrap();
+    fs::write(root.join("target/bin"), vec![0u8; artifact_bytes]).unwrap();
+    analyze_project(root, ProjectKind::Rust).unwrap()
+}
+
+#[test]
+fn peaks_only_ever_rise() {
+    let dir = test_dir("peaks_rise");
+    let earlier = Local::now() - Duration::days(30);
+    let now = Local::now();
+
+    let mut history = PeakHistory::default();
+    let small = rust_project(&dir.join("a"), 100);
+    history.record(std::slice::from_ref(&small), earlier);
+    let first = history.peak(&small).unwrap().clone();
+    assert_eq!(first.bytes, small.total_cleanable_bytes);
+
+    // Growing sets a new peak, stamped with the scan that saw it.
+    let big = rust_project(&dir.join("a"), 5000);
+    history.record(std::slice::from_ref(&big), now);
+    let peak = history.peak(&big).unwrap().clone();
+    assert_eq!(peak.bytes, big.total_cleanable_bytes);
+    assert_eq!(peak.recorded_at, now);
+
+    // Shrinking (say, after a clean) keeps the peak.
+    let shrunk = rust_project(&dir.join("a"), 10);
+    history.record(std::slice::from_ref(&shrunk), Local::now());
+    assert_eq!(history.peak(&shrunk), Some(&peak));
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn projects_not_scanned_keep_their_peaks() {
+    let dir = test_dir("peaks_kept");
+    let a = rust_project(&dir.join("a"), 100);
+    let b = rust_project(&dir.join("b"), 200);
+
+    let mut history = PeakHistory::default();
+    history.record(&[a.clone(), b], Local::now());
+    history.record(&[], Local::now());
+    assert!(history.peak(&a).is_some());
+    assert_eq!(history.projects.len(), 2);
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn history_round_trips_through_its_file() {
+    let dir = test_dir("peaks_file");
+    let path = dir.join("nested/peaks.json");
+    assert_eq!(peaks::load(&path), PeakHistory::default());
+
+    let mut history = PeakHistory::default();
+    history.record(&[rust_project(&dir.join("a"), 100)], Local::now());
+    peaks::save(&path, &history).unwrap();
+    assert_eq!(peaks::load(&path), history);
+
+    fs::write(&path, "not json").unwrap();
+    assert_eq!(peaks::load(&path), PeakHistory::default());
+
+    let _ = fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,123 @@
+//! Tests for `scan --read-only`: once enabled, every write fails and leaves
+//! the filesystem as it was. The mode can't be turned off again, so these
+//! tests live in their own binary.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use dev_sweep::cleaner::{CleanOptions, CleanResult, clean_project_with, prune_empty_dirs};
+use dev_sweep::cleanlog::{self, entries_for};
+use dev_sweep::config::DevSweepConfig;
+use dev_sweep::readonly::{enable_read_only, ensure_writable, is_read_only};
+use dev_sweep::scancache::{self, ScanCache};
+use dev_sweep::scanner::ProjectKind;
+use dev_sweep::scanner::walk::analyze_project;
+
+/// Helper: create a fresh temp dir for a test.
+fn test_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("dev_sweep_test_{name}"));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn rust_project(root: &Path) {
+    fs::create_dir_all(root.join("target/debug")).unwrap();
+    fs::write(root.join("Cargo.toml"), "[package]").unwrap();
+    fs::write(root.join("target/debug/bin"), vec![0u8; 1024]).unwrap();
+}
+
+#[test]
+fn read_only_refuses_writes() {
+    enable_read_only();
+    assert!(is_read_only());
+    let err = ensure_writable("write things").unwrap_err();
+    assert!(err.to_string().contains("Refusing to write things"));
+    assert!(err.to_string().contains("--read-only"));
+}
+
+#[test]
+fn read_only_clean_leaves_targets_in_place() {
+    enable_read_only();
+    let root = test_dir("readonly_clean");
+    rust_project(&root);
+    let project = analyze_project(&root, ProjectKind::Rust).unwrap();
+
+    let archive_dir = root.join("archives");
+    let options = CleanOptions {
+        archive_dir: Some(archive_dir.clone()),
+        ..Default::default()
+    };
+    let result = clean_project_with(&project, &options).unwrap();
+    assert_eq!(result.targets_cleaned, 0);
+    assert_eq!(result.bytes_freed, 0);
+    assert!(result.errors[0].contains("read-only"));
+    assert!(root.join("target/debug/bin").exists());
+    assert!(!archive_dir.exists());
+
+    let result = clean_project_with(&project, &CleanOptions::default()).unwrap();
+    assert!(result.errors[0].contains("read-only"));
+    assert!(root.join("target/debug/bin").exists());
+
+    let empty = root.join("empty");
+    fs::create_dir(&empty).unwrap();
+    let pruned = prune_empty_dirs(&root, std::slice::from_ref(&empty), false);
+    assert!(pruned.pruned.is_empty());
+    assert!(empty.exists());
+
+    let log = root.join("clean-log.jsonl");
+    let cleaned = CleanResult {
+        targets_cleaned: 1,
+        bytes_freed: 1024,
+        ..result
+    };
+    let entries = entries_for(&[&project], &[cleaned]);
+    assert_eq!(entries.len(), 1);
+    assert!(cleanlog::append(&log, &entries).is_err());
+    assert!(!log.exists());
+
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn read_only_skips_cache_and_config_writes() {
+    enable_read_only();
+    let root = test_dir("readonly_files");
+
+    let cache = root.join("cache/scan-cache.json");
+    assert!(scancache::save(&cache, &ScanCache::default()).is_err());
+    assert!(!cache.exists());
+
+    let config = root.join("config/config.json");
+    assert!(DevSweepConfig::default().save_to(&config).is_err());
+    assert!(!config.exists());
+
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[cfg(unix)]
+#[test]
+fn read_only_scan_does_not_back_up_a_broken_config() {
+    let root = test_dir("readonly_broken_config");
+    let config_dir = root.join("xdg/dev-sweep");
+    fs::create_dir_all(&config_dir).unwrap();
+    fs::write(config_dir.join("config.json"), "{ not json").unwrap();
+    let projects = root.join("projects");
+    fs::create_dir(&projects).unwrap();
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_dev-sweep"))
+        .args(["scan", "--read-only", "--json"])
+        .arg(&projects)
+        .env("HOME", &root)
+        .env("XDG_CONFIG_HOME", root.join("xdg"))
+        .env("XDG_CACHE_HOME", root.join("cache"))
+        .env("XDG_DATA_HOME", root.join("data"))
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("invalid"));
+    assert!(!config_dir.join("config.json.bak").exists());
+    assert!(!config_dir.join("config.bak").exists());
+
+    let _ = fs::remove_dir_all(&root);
+}
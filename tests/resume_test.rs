@@ -0,0 +1,117 @@
+//! Tests for the checkpoint behind `--resume`.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use dev_sweep::config::DevSweepConfig;
+use dev_sweep::resume::{self, ScanCheckpoint};
+use dev_sweep::scanner::walk::analyze_project;
+use dev_sweep::scanner::{ProjectKind, ScanOptions, scan_directory_with};
+
+/// Helper: create a fresh temp dir for a test.
+fn test_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("dev_sweep_test_{name}"));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// A scan root holding a Rust project at the root and in `a/`, and a Node
+/// project in `b/`.
+fn tree(root: &Path) {
+    for dir in [root.to_path_buf(), root.join("a")] {
+        fs::create_dir_all(dir.join("target")).unwrap();
+        fs::write(dir.join("Cargo.toml"), "[package]").unwrap();
+        fs::write(dir.join("target/bin"), "x".repeat(100)).unwrap();
+    }
+    fs::create_dir_all(root.join("b/node_modules")).unwrap();
+    fs::write(root.join("b/package.json"), "{}").unwrap();
+    fs::write(root.join("b/node_modules/index.js"), "x".repeat(100)).unwrap();
+}
+
+fn paths(options: &ScanOptions, root: &Path) -> Vec<PathBuf> {
+    let projects = scan_directory_with(root, options, &DevSweepConfig::default()).unwrap();
+    let mut paths: Vec<PathBuf> = projects.into_iter().map(|p| p.path).collect();
+    paths.sort();
+    paths
+}
+
+#[test]
+fn resumable_scan_finds_what_a_full_scan_does() {
+    let dir = test_dir("resume_full");
+    let root = dir.join("root");
+    tree(&root);
+    let checkpoint = dir.join("resume.json");
+    let options = ScanOptions {
+        resume: Some(checkpoint.clone()),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        paths(&options, &root),
+        paths(&ScanOptions::default(), &root)
+    );
+    assert_eq!(paths(&options, &root).len(), 3);
+    // A finished scan leaves no checkpoint behind.
+    assert!(!checkpoint.exists());
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn resumed_scan_skips_directories_already_done() {
+    let dir = test_dir("resume_skip");
+    let root = dir.join("root");
+    tree(&root);
+    let checkpoint = dir.join("resume.json");
+
+    // An earlier scan got through `a/` before it was cut short.
+    let mut earlier = analyze_project(&root.join("a"), ProjectKind::Rust).unwrap();
+    earlier.name = "from-checkpoint".to_string();
+    let mut progress = ScanCheckpoint::default();
+    progress.record(&root, &root.join("a"), vec![earlier]);
+    resume::save(&checkpoint, &progress).unwrap();
+
+    let options = ScanOptions {
+        resume: Some(checkpoint.clone()),
+        ..Default::default()
+    };
+    let projects = scan_directory_with(&root, &options, &DevSweepConfig::default()).unwrap();
+    let mut names: Vec<&str> = projects.iter().map(|p| p.name.as_str()).collect();
+    names.sort();
+    assert_eq!(names, ["b", "from-checkpoint", "root"]);
+    assert!(!checkpoint.exists());
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn checkpoint_keeps_other_roots_and_round_trips() {
+    let dir = test_dir("resume_file");
+    let path = dir.join("nested/resume.json");
+    assert!(resume::load(&path).roots.is_empty());
+
+    let (root, other) = (dir.join("root"), dir.join("other"));
+    tree(&root);
+    let project = analyze_project(&root.join("a"), ProjectKind::Rust).unwrap();
+    let mut checkpoint = ScanCheckpoint::default();
+    checkpoint.record(&root, &root.join("a"), vec![project]);
+    checkpoint.record(&other, &other, Vec::new());
+    resume::save(&path, &checkpoint).unwrap();
+
+    let loaded = resume::load(&path);
+    let found = |root: &Path, dir: &Path| loaded.done(root, dir).map(<[_]>::len);
+    assert_eq!(found(&root, &root.join("a")), Some(1));
+    assert_eq!(found(&other, &other), Some(0));
+    assert_eq!(found(&root, &root.join("b")), None);
+
+    // Finishing one root keeps the other's progress; finishing both removes the file.
+    checkpoint.finish(&root);
+    resume::save(&path, &checkpoint).unwrap();
+    assert!(resume::load(&path).done(&other, &other).is_some());
+    checkpoint.finish(&other);
+    resume::save(&path, &checkpoint).unwrap();
+    assert!(!path.exists());
+
+    let _ = fs::remove_dir_all(&dir);
+}
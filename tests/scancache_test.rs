@@ -0,0 +1,304 @@
+//! Tests for the scan cache behind `scan --since-last`.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use dev_sweep::cli::commands::ScanArgs;
+use dev_sweep::config::DevSweepConfig;
+use dev_sweep::scancache::{self, ChangeStatus, ScanCache};
+use dev_sweep::scanner::walk::analyze_project;
+use dev_sweep::scanner::{
+    ChangedSince, ProjectKind, ScanOptions, ScannedProject, scan_directory_with,
+};
+
+/// Helper: create a fresh temp dir for a test.
+fn test_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("dev_sweep_test_{name}"));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn rust_project(root: &Path, artifact_bytes: usize) -> ScannedProject {
+    fs::create_dir_all(root.join("target")).unwrap();
+    fs::write(root.join("Cargo.toml"), "[package]").unwrap();
+    fs::write(root.join("target/bin"), vec![0u8; artifact_bytes]).unwrap();
+    analyze_project(root, ProjectKind::Rust).unwrap()
+}
+
+// ── diff ────────────────────────────────────────────────────────────────────
+
+#[test]
+fn diff_reports_new_changed_and_removed() {
+    let dir = test_dir("scancache_diff");
+    let a = rust_project(&dir.join("a"), 100);
+    let b = rust_project(&dir.join("b"), 200);
+    let c = rust_project(&dir.join("c"), 300);
+
+    let mut cache = ScanCache::default();
+    cache.update(std::slice::from_ref(&dir), &[a.clone(), b], true);
+
+    let grown = rust_project(&dir.join("b"), 5000);
+    let changes = cache.diff(std::slice::from_ref(&dir), &[a, grown, c], true);
+
+    // a is unchanged and left out; b grew the most, then c is new.
+    assert_eq!(changes.len(), 2);
+    assert_eq!(changes[0].status, ChangeStatus::Changed);
+    assert_eq!(changes[0].name, "b");
+    assert_eq!(changes[0].delta(), 4800);
+    assert_eq!(changes[1].status, ChangeStatus::New);
+    assert_eq!(changes[1].previous_bytes, 0);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn diff_only_reports_removed_when_unfiltered() {
+    let dir = test_dir("scancache_removed");
+    let a = rust_project(&dir.join("a"), 100);
+    let gone = rust_project(&dir.join("gone"), 400);
+
+    let mut cache = ScanCache::default();
+    cache.update(std::slice::from_ref(&dir), &[a.clone(), gone], true);
+
+    let changes = cache.diff(std::slice::from_ref(&dir), std::slice::from_ref(&a), true);
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].status, ChangeStatus::Removed);
+    assert_eq!(changes[0].delta(), -400);
+
+    assert!(cache.diff(std::slice::from_ref(&dir), &[a], false).is_empty());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn results_narrowed_by_any_filter_are_not_complete() {
+    assert!(!ScanArgs::default().is_filtered());
+    let narrowed = [
+        ScanArgs {
+            keep_recent: Some(2),
+            ..Default::default()
+        },
+        ScanArgs {
+            min_size: Some(1),
+            ..Default::default()
+        },
+        ScanArgs {
+            since_clean: true,
+            ..Default::default()
+        },
+    ];
+    // A cache written from these would report the hidden projects as removed.
+    for args in narrowed {
+        assert!(args.is_filtered(), "{args:?}");
+    }
+}
+
+#[test]
+fn diff_ignores_cached_projects_outside_root() {
+    let dir = test_dir("scancache_outside_root");
+    let a = rust_project(&dir.join("one/a"), 100);
+    let b = rust_project(&dir.join("two/b"), 100);
+
+    let mut cache = ScanCache::default();
+    cache.update(std::slice::from_ref(&dir), &[a, b.clone()], true);
+
+    let changes = cache.diff(&[dir.join("two")], &[b], true);
+    assert!(changes.is_empty());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn diff_reports_removed_under_every_root() {
+    let dir = test_dir("scancache_many_roots");
+    let a = rust_project(&dir.join("one/a"), 100);
+    let b = rust_project(&dir.join("two/b"), 200);
+    let c = rust_project(&dir.join("three/c"), 300);
+    let roots = [dir.join("one"), dir.join("two")];
+
+    let mut cache = ScanCache::default();
+    cache.update(std::slice::from_ref(&dir), &[a, b, c], true);
+
+    // Both scanned roots lost their project; "three" wasn't scanned.
+    let changes = cache.diff(&roots, &[], true);
+    let names: Vec<&str> = changes.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, ["b", "a"]);
+    assert!(changes.iter().all(|c| c.status == ChangeStatus::Removed));
+
+    cache.update(&roots, &[], true);
+    assert_eq!(cache.projects.len(), 1);
+    assert_eq!(cache.projects[0].name, "c");
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+// ── update ──────────────────────────────────────────────────────────────────
+
+#[test]
+fn update_drops_missing_projects_only_when_complete() {
+    let dir = test_dir("scancache_update");
+    let a = rust_project(&dir.join("a"), 100);
+    let b = rust_project(&dir.join("b"), 200);
+
+    let mut cache = ScanCache::default();
+    cache.update(std::slice::from_ref(&dir), &[a.clone(), b], true);
+    assert_eq!(cache.projects.len(), 2);
+
+    cache.update(std::slice::from_ref(&dir), std::slice::from_ref(&a), false);
+    assert_eq!(cache.projects.len(), 2);
+
+    cache.update(std::slice::from_ref(&dir), &[a], true);
+    assert_eq!(cache.projects.len(), 1);
+    assert_eq!(cache.projects[0].name, "a");
+    assert!(cache.timestamp.is_some());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn update_keeps_projects_sorted_and_replaces_rescanned_ones() {
+    let dir = test_dir("scancache_sorted");
+    let b = rust_project(&dir.join("b"), 200);
+    let a = rust_project(&dir.join("a"), 100);
+    let nested = rust_project(&dir.join("a/tools"), 50);
+    let outside = test_dir("scancache_sorted_outside");
+    let other = rust_project(&outside.join("z"), 10);
+
+    let mut cache = ScanCache::default();
+    cache.update(std::slice::from_ref(&outside), &[other], true);
+    cache.update(std::slice::from_ref(&dir), &[b, nested, a.clone()], true);
+    let names: Vec<&str> = cache.projects.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, ["a", "tools", "b", "z"]);
+
+    // A rescan replaces the entry, and a complete one drops only this root's projects
+    let grown = rust_project(&dir.join("a"), 400);
+    cache.update(std::slice::from_ref(&dir), &[grown], true);
+    let names: Vec<&str> = cache.projects.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, ["a", "z"]);
+    assert!(cache.projects[0].bytes > a.total_cleanable_bytes);
+
+    fs::remove_dir_all(&dir).unwrap();
+    fs::remove_dir_all(&outside).unwrap();
+}
+
+// ── persistence ─────────────────────────────────────────────────────────────
+
+#[test]
+fn save_and_load_round_trip() {
+    let dir = test_dir("scancache_round_trip");
+    let a = rust_project(&dir.join("a"), 100);
+    let path = dir.join("nested/scan-cache.json");
+
+    let mut cache = ScanCache::default();
+    cache.update(std::slice::from_ref(&dir), &[a], true);
+    scancache::save(&path, &cache).unwrap();
+
+    assert_eq!(scancache::load(&path), cache);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn load_treats_missing_or_corrupt_cache_as_empty() {
+    let dir = test_dir("scancache_corrupt");
+    let path = dir.join("scan-cache.json");
+
+    assert_eq!(scancache::load(&path), ScanCache::default());
+    fs::write(&path, "not json").unwrap();
+    assert_eq!(scancache::load(&path), ScanCache::default());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+// ── incremental scans ───────────────────────────────────────────────────────
+
+/// Helper: scan `root` with `--changed-since-file` semantics, cutoff a minute ago.
+fn scan_changed_since(root: &Path, cache: ScanCache) -> Vec<ScannedProject> {
+    let options = ScanOptions {
+        changed_since: Some(ChangedSince {
+            time: SystemTime::now() - Duration::from_secs(60),
+            cache,
+        }),
+        ..Default::default()
+    };
+    scan_directory_with(root, &options, &DevSweepConfig::default()).unwrap()
+}
+
+fn bytes_of(projects: &[ScannedProject], name: &str) -> Option<u64> {
+    projects
+        .iter()
+        .find(|p| p.name == name)
+        .map(|p| p.total_cleanable_bytes)
+}
+
+/// Helper: set a directory's own mtime an hour back.
+fn backdate_dir(dir: &Path) {
+    let when = SystemTime::now() - Duration::from_secs(3600);
+    fs::File::open(dir).unwrap().set_modified(when).unwrap();
+}
+
+#[test]
+fn unchanged_directories_reuse_cached_projects() {
+    let dir = test_dir("scancache_changed_since");
+    let old = rust_project(&dir.join("old"), 100);
+    let mut cache = ScanCache::default();
+    cache.update(std::slice::from_ref(&dir), &[old], true);
+
+    // Rewriting a file deep down leaves the project directory's mtime alone.
+    fs::write(dir.join("old/target/bin"), vec![0u8; 500]).unwrap();
+    backdate_dir(&dir.join("old"));
+    rust_project(&dir.join("new"), 200);
+
+    let projects = scan_changed_since(&dir, cache);
+    assert_eq!(bytes_of(&projects, "old"), Some(100));
+    assert_eq!(bytes_of(&projects, "new"), Some(200));
+    let old = projects.iter().find(|p| p.name == "old").unwrap();
+    assert_eq!(old.clean_targets[0].path, dir.join("old/target"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn cached_targets_removed_since_are_dropped() {
+    let dir = test_dir("scancache_changed_since_removed");
+    let proj = dir.join("old");
+    let old = rust_project(&proj, 100);
+    let mut cache = ScanCache::default();
+    cache.update(std::slice::from_ref(&dir), &[old], true);
+    // A second target, as if recorded by an earlier scan, then removed by hand.
+    let mut gone = cache.projects[0].targets[0].clone();
+    gone.path = proj.join("target-old");
+    gone.name = "target-old".to_string();
+    gone.size_bytes = 5000;
+    cache.projects[0].targets.push(gone);
+
+    let project = cache.projects[0].to_project(&proj).unwrap();
+    assert_eq!(project.clean_targets.len(), 1);
+    assert_eq!(project.total_cleanable_bytes, 100);
+
+    // With every target gone, the project is sized again instead.
+    fs::remove_dir_all(proj.join("target")).unwrap();
+    assert!(cache.projects[0].to_project(&proj).is_none());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn unchanged_directories_without_cached_targets_are_sized_again() {
+    let dir = test_dir("scancache_changed_since_old_cache");
+    let old = rust_project(&dir.join("old"), 100);
+    let mut cache = ScanCache::default();
+    cache.update(std::slice::from_ref(&dir), &[old], true);
+    // As written before the cache recorded targets.
+    cache.projects[0].targets.clear();
+
+    fs::write(dir.join("old/target/bin"), vec![0u8; 500]).unwrap();
+    backdate_dir(&dir.join("old"));
+
+    let projects = scan_changed_since(&dir, cache);
+    assert_eq!(bytes_of(&projects, "old"), Some(500));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -1,13 +1,17 @@
 //! Tests for project analysis, directory sizing, pycache discovery, and full scanning.
 
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use dev_sweep::config::DevSweepConfig;
-use dev_sweep::scanner::ProjectKind;
 use dev_sweep::scanner::walk::{
-    analyze_project, dir_size, find_pycache_recursive, scan_directory, should_visit,
+    ScanOptions, analyze_project, analyze_project_kinds, analyze_project_with, dir_size,
+    dir_size_with, estimate_dir_size, find_pycache_recursive, has_walkable_subdirs, scan_directory,
+    scan_directory_with, should_visit, slowest_dirs,
 };
+use dev_sweep::scanner::workspace::workspace_members;
+use dev_sweep::scanner::{ProjectKind, ScannedProject, SizeMode, is_core_dump_name};
 
 /// Helper: create a fresh temp dir for a test.
 fn test_dir(name: &str) -> PathBuf {
@@ -46,6 +50,90 @@ fn dir_size_nested() {
     fs::remove_dir_all(&dir).unwrap();
 }
 
+#[cfg(unix)]
+#[test]
+fn disk_usage_counts_only_allocated_blocks_of_sparse_files() {
+    let dir = test_dir("size_sparse");
+    let sparse = fs::File::create(dir.join("core")).unwrap();
+    sparse.set_len(64 * 1024 * 1024).unwrap();
+    drop(sparse);
+    fs::write(dir.join("small.txt"), "hello").unwrap();
+
+    assert_eq!(
+        dir_size_with(&dir, SizeMode::Apparent).unwrap(),
+        64 * 1024 * 1024 + 5
+    );
+    let on_disk = dir_size_with(&dir, SizeMode::DiskUsage).unwrap();
+    // The hole takes no blocks; the small file takes at least one.
+    assert!(on_disk > 0);
+    assert!(on_disk < 1024 * 1024);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn dir_size_counts_hard_linked_file_once() {
+    let dir = test_dir("size_hard_link");
+    fs::write(dir.join("a"), "0123456789").unwrap();
+    fs::hard_link(dir.join("a"), dir.join("b")).unwrap();
+    fs::write(dir.join("c"), "xyz").unwrap();
+
+    assert_eq!(dir_size(&dir).unwrap(), 13);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn project_counts_file_linked_into_two_targets_once() {
+    let dir = test_dir("size_hard_link_targets");
+    fs::write(dir.join("package.json"), "{}").unwrap();
+    fs::create_dir_all(dir.join("node_modules/pkg")).unwrap();
+    fs::create_dir_all(dir.join(".cache")).unwrap();
+    fs::write(dir.join("node_modules/pkg/index.js"), "0123456789").unwrap();
+    fs::hard_link(
+        dir.join("node_modules/pkg/index.js"),
+        dir.join(".cache/index.js"),
+    )
+    .unwrap();
+
+    let project = analyze_project(&dir, ProjectKind::Node).unwrap();
+    assert_eq!(project.total_cleanable_bytes, 10);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn nested_target_keeps_its_hard_linked_files() {
+    // A package store outside the project links files into node_modules/.vite
+    let root = test_dir("size_hard_link_nested");
+    let store = root.join("store");
+    let dir = root.join("app");
+    fs::create_dir_all(&store).unwrap();
+    fs::create_dir_all(dir.join("node_modules/.vite")).unwrap();
+    fs::write(dir.join("package.json"), "{}").unwrap();
+    fs::write(store.join("chunk.js"), "0123456789").unwrap();
+    fs::hard_link(
+        store.join("chunk.js"),
+        dir.join("node_modules/.vite/chunk.js"),
+    )
+    .unwrap();
+    fs::write(dir.join("node_modules/dep.js"), "abc").unwrap();
+
+    let project = analyze_project(&dir, ProjectKind::Node).unwrap();
+    let size = |name: &str| {
+        project
+            .clean_targets
+            .iter()
+            .find(|t| t.name == name)
+            .unwrap()
+            .size_bytes
+    };
+    assert_eq!(size("node_modules/.vite"), 10);
+    assert_eq!(size("node_modules"), 3);
+    assert_eq!(project.total_cleanable_bytes, 13);
+    fs::remove_dir_all(&root).unwrap();
+}
+
 // ── should_visit ────────────────────────────────────────────────────────────
 
 #[test]
@@ -139,6 +227,7 @@ fn analyze_node_with_node_modules() {
 fn analyze_node_with_multiple_targets() {
     let dir = test_dir("analyze_node_multi");
     fs::write(dir.join("package.json"), "{}").unwrap();
+    fs::write(dir.join("vite.config.js"), "").unwrap();
     fs::create_dir_all(dir.join("node_modules")).unwrap();
     fs::write(dir.join("node_modules/mod.js"), "x").unwrap();
     fs::create_dir_all(dir.join("dist")).unwrap();
@@ -156,6 +245,195 @@ fn analyze_node_with_multiple_targets() {
     fs::remove_dir_all(&dir).unwrap();
 }
 
+// ── Node framework caches ───────────────────────────────────────────────────
+
+#[test]
+fn analyze_node_vite_cache_is_separate_target() {
+    let dir = test_dir("analyze_node_vite");
+    fs::write(dir.join("package.json"), "{}").unwrap();
+    fs::create_dir_all(dir.join("node_modules/.vite/deps")).unwrap();
+    fs::write(dir.join("node_modules/.vite/deps/chunk.js"), "12345").unwrap(); // 5
+    fs::write(dir.join("node_modules/react.js"), "abc").unwrap(); // 3
+
+    let project = analyze_project(&dir, ProjectKind::Node).unwrap();
+    let size_of = |name: &str| {
+        project
+            .clean_targets
+            .iter()
+            .find(|t| t.name == name)
+            .map(|t| t.size_bytes)
+    };
+
+    // Each byte is counted once: .vite on its own, node_modules without it
+    assert_eq!(size_of("node_modules/.vite"), Some(5));
+    assert_eq!(size_of("node_modules"), Some(3));
+    assert_eq!(project.total_cleanable_bytes, 8);
+
+    // The nested target comes first so it can be removed before its parent
+    assert_eq!(project.clean_targets[0].name, "node_modules/.vite");
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn analyze_node_nuxt_output_requires_nuxt() {
+    let dir = test_dir("analyze_node_nuxt");
+    fs::write(dir.join("package.json"), "{}").unwrap();
+    fs::create_dir_all(dir.join(".output/server")).unwrap();
+    fs::write(dir.join(".output/server/index.mjs"), "data").unwrap();
+
+    let project = analyze_project(&dir, ProjectKind::Node).unwrap();
+    assert!(project.clean_targets.iter().all(|t| t.name != ".output"));
+
+    fs::write(dir.join("nuxt.config.ts"), "export default {}").unwrap();
+    let project = analyze_project(&dir, ProjectKind::Node).unwrap();
+    assert!(project.clean_targets.iter().any(|t| t.name == ".output"));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn analyze_node_gatsby_public_requires_cache_sibling() {
+    let dir = test_dir("analyze_node_gatsby");
+    fs::write(dir.join("package.json"), "{}").unwrap();
+    fs::create_dir_all(dir.join("public")).unwrap();
+    fs::write(dir.join("public/index.html"), "<html>").unwrap();
+
+    // A lone public/ is likely hand-written static assets — leave it alone
+    let project = analyze_project(&dir, ProjectKind::Node).unwrap();
+    assert!(project.clean_targets.iter().all(|t| t.name != "public"));
+
+    fs::create_dir_all(dir.join(".cache")).unwrap();
+    fs::write(dir.join(".cache/webpack.json"), "{}").unwrap();
+    let project = analyze_project(&dir, ProjectKind::Node).unwrap();
+    let names: Vec<&str> = project.clean_targets.iter().map(|t| t.name.as_str()).collect();
+    assert!(names.contains(&"public"));
+    assert!(names.contains(&".cache"));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+// ── Frontend and WASM output ────────────────────────────────────────────────
+
+#[test]
+fn analyze_node_dist_and_build_require_bundler_config() {
+    let dir = test_dir("analyze_node_bundler");
+    fs::write(dir.join("package.json"), "{}").unwrap();
+    for out in ["dist", "build"] {
+        fs::create_dir_all(dir.join(out)).unwrap();
+        fs::write(dir.join(out).join("index.html"), "<html>").unwrap();
+    }
+
+    // Without a bundler these may be the project's own static files
+    let project = analyze_project(&dir, ProjectKind::Node).unwrap();
+    assert!(project.clean_targets.is_empty());
+
+    fs::write(dir.join("rollup.config.mjs"), "export default {}").unwrap();
+    let project = analyze_project(&dir, ProjectKind::Node).unwrap();
+    let names: Vec<&str> = project.clean_targets.iter().map(|t| t.name.as_str()).collect();
+    assert!(names.contains(&"dist"));
+    assert!(names.contains(&"build"));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn analyze_node_turbo_cache_requires_turbo_json() {
+    let dir = test_dir("analyze_node_turbo");
+    fs::write(dir.join("package.json"), "{}").unwrap();
+    fs::create_dir_all(dir.join(".turbo/cache")).unwrap();
+    fs::write(dir.join(".turbo/cache/abc.tar.zst"), "data").unwrap();
+
+    let project = analyze_project(&dir, ProjectKind::Node).unwrap();
+    assert!(project.clean_targets.iter().all(|t| t.name != ".turbo"));
+
+    fs::write(dir.join("turbo.json"), "{}").unwrap();
+    let project = analyze_project(&dir, ProjectKind::Node).unwrap();
+    assert!(project.clean_targets.iter().any(|t| t.name == ".turbo"));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn analyze_node_parcel_cache() {
+    let dir = test_dir("analyze_node_parcel");
+    fs::write(dir.join("package.json"), "{}").unwrap();
+    fs::create_dir_all(dir.join(".parcel-cache")).unwrap();
+    fs::write(dir.join(".parcel-cache/data.mdb"), "data").unwrap();
+    fs::create_dir_all(dir.join("dist")).unwrap();
+    fs::write(dir.join("dist/index.js"), "code").unwrap();
+
+    // Parcel needs no config file; its cache is enough to trust dist/
+    let project = analyze_project(&dir, ProjectKind::Node).unwrap();
+    let names: Vec<&str> = project.clean_targets.iter().map(|t| t.name.as_str()).collect();
+    assert!(names.contains(&".parcel-cache"));
+    assert!(names.contains(&"dist"));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn analyze_rust_wasm_pack_output() {
+    let dir = test_dir("analyze_rust_wasm_pack");
+    fs::write(dir.join("Cargo.toml"), "[package]").unwrap();
+    fs::create_dir_all(dir.join("pkg")).unwrap();
+    fs::write(dir.join("pkg/package.json"), "{}").unwrap();
+
+    // A pkg/ without wasm-pack's module may be hand-written
+    let project = analyze_project(&dir, ProjectKind::Rust).unwrap();
+    assert!(project.clean_targets.iter().all(|t| t.name != "pkg"));
+
+    fs::write(dir.join("pkg/my_crate_bg.wasm"), "\0asm").unwrap();
+    let project = analyze_project(&dir, ProjectKind::Rust).unwrap();
+    assert!(project.clean_targets.iter().any(|t| t.name == "pkg"));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+// ── iOS dependency managers ────────────────────────────────────────────────
+
+#[test]
+fn analyze_ios_pods_and_carthage() {
+    let dir = test_dir("analyze_ios_deps");
+    fs::write(dir.join("Podfile"), "").unwrap();
+    fs::write(dir.join("Cartfile"), "").unwrap();
+    fs::create_dir_all(dir.join("Pods/Alamofire")).unwrap();
+    fs::write(dir.join("Pods/Alamofire/lib.swift"), "code").unwrap();
+    fs::create_dir_all(dir.join("Carthage/Build/iOS")).unwrap();
+    fs::write(dir.join("Carthage/Build/iOS/Lib.framework"), "bin").unwrap();
+    // Checkouts are sources Carthage builds from — not a target
+    fs::create_dir_all(dir.join("Carthage/Checkouts/Lib")).unwrap();
+    fs::write(dir.join("Carthage/Checkouts/Lib/src.swift"), "src").unwrap();
+
+    let project = analyze_project(&dir, ProjectKind::IosDeps).unwrap();
+    let names: Vec<&str> = project.clean_targets.iter().map(|t| t.name.as_str()).collect();
+    assert_eq!(names, ["Pods", "Carthage/Build"]);
+    assert_eq!(project.total_cleanable_bytes, 7);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn analyze_ios_targets_require_their_manager() {
+    // A stray Pods/ without a Podfile isn't claimed
+    let dir = test_dir("analyze_ios_stray_pods");
+    fs::write(dir.join("Cartfile"), "").unwrap();
+    fs::create_dir_all(dir.join("Pods")).unwrap();
+    fs::write(dir.join("Pods/file"), "data").unwrap();
+
+    let project = analyze_project(&dir, ProjectKind::IosDeps).unwrap();
+    assert!(project.clean_targets.is_empty());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn analyze_ios_includes_swiftpm_build() {
+    let dir = test_dir("analyze_ios_swiftpm");
+    fs::write(dir.join("Podfile"), "").unwrap();
+    fs::write(dir.join("Package.swift"), "").unwrap();
+    fs::create_dir_all(dir.join("Pods")).unwrap();
+    fs::write(dir.join("Pods/a"), "a").unwrap();
+    fs::create_dir_all(dir.join(".build/debug")).unwrap();
+    fs::write(dir.join(".build/debug/app"), "bb").unwrap();
+
+    let project = analyze_project(&dir, ProjectKind::IosDeps).unwrap();
+    let names: Vec<&str> = project.clean_targets.iter().map(|t| t.name.as_str()).collect();
+    assert_eq!(names, ["Pods", ".build"]);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
 #[test]
 fn analyze_project_no_artifacts() {
     let dir = test_dir("analyze_clean");
@@ -193,6 +471,221 @@ fn analyze_dotnet_bin_obj() {
     fs::remove_dir_all(&dir).unwrap();
 }
 
+#[test]
+fn analyze_maui_includes_publish_and_platform_output() {
+    let dir = test_dir("analyze_maui");
+    fs::write(dir.join("App.csproj"), "<Project>").unwrap();
+    for target in [
+        "bin/Debug/App.dll",
+        "obj/project.assets.json",
+        "publish/App.dll",
+        "Platforms/Android/bin/App.apk",
+        "Platforms/iOS/obj/App.o",
+        "Platforms/Windows/App.xaml",
+    ] {
+        let path = dir.join(target);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, "x").unwrap();
+    }
+
+    let project = analyze_project(&dir, ProjectKind::DotNet).unwrap();
+    let mut names: Vec<&str> = project.clean_targets.iter().map(|t| t.name.as_str()).collect();
+    names.sort_unstable();
+    assert_eq!(
+        names,
+        ["Platforms/Android/bin", "Platforms/iOS/obj", "bin", "obj", "publish"]
+    );
+    assert_eq!(project.total_cleanable_bytes, 5);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn analyze_dotnet_solution_bin_is_not_build_output() {
+    // A bin beside only a solution file is likely checked-in tooling.
+    let dir = test_dir("analyze_dotnet_sln_bin");
+    fs::write(dir.join("App.sln"), "").unwrap();
+    fs::create_dir_all(dir.join("bin")).unwrap();
+    fs::write(dir.join("bin/nuget.exe"), "exe").unwrap();
+
+    let project = analyze_project(&dir, ProjectKind::DotNet).unwrap();
+    assert!(project.clean_targets.is_empty());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+// ── Flutter platform folders ────────────────────────────────────────────────
+
+/// A Flutter app with build output at the top level and in each platform folder.
+fn flutter_app(name: &str) -> PathBuf {
+    let dir = test_dir(name);
+    fs::write(dir.join("pubspec.yaml"), "name: app").unwrap();
+    for (target, data) in [
+        ("build/app.dill", "a"),
+        ("ios/Pods/Firebase/lib.a", "bb"),
+        ("ios/.symlinks/plugins/camera", "ccc"),
+        ("macos/Pods/Sparkle/lib.a", "dddd"),
+        ("android/.gradle/8.0/cache.bin", "eeeee"),
+        ("android/app/build/app.apk", "ffffff"),
+    ] {
+        let path = dir.join(target);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, data).unwrap();
+    }
+    fs::write(dir.join("ios/Podfile"), "").unwrap();
+    fs::write(dir.join("android/app/build.gradle"), "").unwrap();
+    dir
+}
+
+#[test]
+fn analyze_flutter_includes_platform_artifacts() {
+    let dir = flutter_app("analyze_flutter");
+
+    let project = analyze_project(&dir, ProjectKind::Dart).unwrap();
+    let names: Vec<&str> = project.clean_targets.iter().map(|t| t.name.as_str()).collect();
+    assert_eq!(
+        names,
+        [
+            "build",
+            "ios/Pods",
+            "ios/.symlinks",
+            "macos/Pods",
+            "android/.gradle",
+            "android/app/build"
+        ]
+    );
+    assert_eq!(project.total_cleanable_bytes, 21);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn analyze_dart_without_platform_folders() {
+    let dir = test_dir("analyze_dart_plain");
+    fs::write(dir.join("pubspec.yaml"), "name: lib").unwrap();
+    fs::create_dir_all(dir.join(".dart_tool")).unwrap();
+    fs::write(dir.join(".dart_tool/package_config.json"), "{}").unwrap();
+
+    let project = analyze_project(&dir, ProjectKind::Dart).unwrap();
+    let names: Vec<&str> = project.clean_targets.iter().map(|t| t.name.as_str()).collect();
+    assert_eq!(names, [".dart_tool"]);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn scan_counts_flutter_platform_artifacts_once() {
+    // ios/ (Podfile) and android/app (build.gradle) are projects of their own,
+    // but their artifacts belong to the Flutter app around them.
+    let root = flutter_app("scan_flutter");
+    let projects = scan_directory(&root, None, &DevSweepConfig::default()).unwrap();
+
+    assert_eq!(projects.len(), 1);
+    assert_eq!(projects[0].kind, ProjectKind::Dart);
+    assert_eq!(projects[0].total_cleanable_bytes, 21);
+    fs::remove_dir_all(&root).unwrap();
+}
+
+// ── Polyglot projects ───────────────────────────────────────────────────────
+
+/// A Node package with a Rust native addon beside it.
+fn node_rust_addon(name: &str) -> PathBuf {
+    let dir = test_dir(name);
+    fs::write(dir.join("Cargo.toml"), "[package]").unwrap();
+    fs::write(dir.join("package.json"), "{}").unwrap();
+    fs::create_dir_all(dir.join("target/debug")).unwrap();
+    fs::write(dir.join("target/debug/addon.node"), "0123456789").unwrap();
+    fs::create_dir_all(dir.join("node_modules/napi")).unwrap();
+    fs::write(dir.join("node_modules/napi/index.js"), "12345").unwrap();
+    dir
+}
+
+#[test]
+fn polyglot_project_collects_every_kinds_targets() {
+    let dir = node_rust_addon("polyglot_targets");
+    let kinds = [ProjectKind::Rust, ProjectKind::Node];
+    let project = analyze_project_kinds(&dir, &kinds, &ScanOptions::default()).unwrap();
+
+    assert_eq!(project.kind, ProjectKind::Rust);
+    assert_eq!(project.other_kinds, [ProjectKind::Node]);
+    let names: Vec<&str> = project
+        .clean_targets
+        .iter()
+        .map(|t| t.name.as_str())
+        .collect();
+    assert_eq!(names, ["target", "node_modules"]);
+    assert_eq!(project.total_cleanable_bytes, 15);
+    assert_eq!(project.display_kind(), "Rust + Node.js");
+    assert_eq!(project.short_kind(), "rs+js");
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn polyglot_target_shared_by_two_kinds_is_listed_once() {
+    let dir = test_dir("polyglot_shared_target");
+    fs::write(dir.join("pom.xml"), "<project/>").unwrap();
+    fs::write(dir.join("build.sbt"), "").unwrap();
+    fs::create_dir_all(dir.join("target")).unwrap();
+    fs::write(dir.join("target/app.jar"), "1234").unwrap();
+    let kinds = [ProjectKind::Java, ProjectKind::Scala];
+    let project = analyze_project_kinds(&dir, &kinds, &ScanOptions::default()).unwrap();
+
+    let names: Vec<&str> = project
+        .clean_targets
+        .iter()
+        .map(|t| t.name.as_str())
+        .collect();
+    assert_eq!(names, ["target"]);
+    assert_eq!(project.total_cleanable_bytes, 4);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn scan_reports_polyglot_directory_as_one_project() {
+    let root = test_dir("polyglot_scan");
+    let dir = node_rust_addon("polyglot_scan/addon");
+    let projects = scan_directory(&root, None, &DevSweepConfig::default()).unwrap();
+
+    assert_eq!(projects.len(), 1);
+    assert_eq!(projects[0].path, dir);
+    assert_eq!(projects[0].kind, ProjectKind::Rust);
+    assert_eq!(projects[0].other_kinds, [ProjectKind::Node]);
+    assert_eq!(projects[0].total_cleanable_bytes, 15);
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn scan_uses_configured_detection_priority() {
+    let root = test_dir("polyglot_priority");
+    node_rust_addon("polyglot_priority/addon");
+    let config = DevSweepConfig {
+        detection_priority: vec![ProjectKind::Node],
+        ..Default::default()
+    };
+    let projects = scan_directory(&root, None, &config).unwrap();
+
+    assert_eq!(projects[0].kind, ProjectKind::Node);
+    assert_eq!(projects[0].other_kinds, [ProjectKind::Rust]);
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn scan_drops_excluded_kind_from_polyglot_project() {
+    let root = test_dir("polyglot_excluded");
+    node_rust_addon("polyglot_excluded/addon");
+    let config = DevSweepConfig {
+        exclude_kinds: vec![ProjectKind::Rust],
+        ..Default::default()
+    };
+    let projects = scan_directory(&root, None, &config).unwrap();
+
+    assert_eq!(projects[0].kind, ProjectKind::Node);
+    assert!(projects[0].other_kinds.is_empty());
+    let names: Vec<&str> = projects[0]
+        .clean_targets
+        .iter()
+        .map(|t| t.name.as_str())
+        .collect();
+    assert_eq!(names, ["node_modules"]);
+    fs::remove_dir_all(&root).unwrap();
+}
+
 // ── find_pycache_recursive ──────────────────────────────────────────────────
 
 #[test]
@@ -236,6 +729,98 @@ fn pycache_skips_empty_cache_dirs() {
     fs::remove_dir_all(&dir).unwrap();
 }
 
+// ── Python packaging output ─────────────────────────────────────────────────
+
+#[test]
+fn analyze_python_build_and_dist_require_packaging_metadata() {
+    let dir = test_dir("analyze_python_packaging");
+    fs::write(dir.join("requirements.txt"), "").unwrap();
+    for out in ["build/lib/pkg", "dist", ".eggs/dep.egg", "pkg.egg-info"] {
+        fs::create_dir_all(dir.join(out)).unwrap();
+        fs::write(dir.join(out).join("file"), "data").unwrap();
+    }
+
+    // Without setup.py or pyproject.toml these may be the project's own files
+    let project = analyze_project(&dir, ProjectKind::Python).unwrap();
+    let names: Vec<&str> = project.clean_targets.iter().map(|t| t.name.as_str()).collect();
+    assert_eq!(names, ["pkg.egg-info", ".eggs"]);
+
+    fs::write(dir.join("pyproject.toml"), "[project]").unwrap();
+    let project = analyze_project(&dir, ProjectKind::Python).unwrap();
+    let names: Vec<&str> = project.clean_targets.iter().map(|t| t.name.as_str()).collect();
+    assert_eq!(names, ["pkg.egg-info", ".eggs", "build", "dist"]);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn analyze_python_counts_caches_in_other_targets_once() {
+    let dir = test_dir("analyze_python_nested_caches");
+    fs::write(dir.join("setup.py"), "").unwrap();
+    for cache in [
+        ".venv/lib/site-packages/dep/__pycache__",
+        "build/lib/pkg/__pycache__",
+    ] {
+        fs::create_dir_all(dir.join(cache)).unwrap();
+        fs::write(dir.join(cache).join("mod.pyc"), "bytecode").unwrap();
+    }
+    fs::create_dir_all(dir.join(".venv/lib/site-packages/dep-1.0.egg-info")).unwrap();
+
+    let project = analyze_project(&dir, ProjectKind::Python).unwrap();
+    let names: Vec<&str> = project.clean_targets.iter().map(|t| t.name.as_str()).collect();
+    assert_eq!(names, [".venv", "build"]);
+    assert_eq!(project.total_cleanable_bytes, 16);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+// ── Version control metadata ────────────────────────────────────────────────
+
+#[test]
+fn git_metadata_is_never_a_clean_target() {
+    let root = test_dir("vcs_never_target");
+    fs::write(root.join("Cargo.toml"), "[package]").unwrap();
+    fs::create_dir_all(root.join("target")).unwrap();
+    fs::write(root.join("target/bin"), "x".repeat(10)).unwrap();
+    for vcs in [".git/objects/pack", ".hg/store", ".svn/pristine"] {
+        fs::create_dir_all(root.join(vcs)).unwrap();
+        fs::write(root.join(vcs).join("data"), "y".repeat(1000)).unwrap();
+    }
+    // A pattern matching every directory (Poetry's environments) skips them too.
+    fs::create_dir_all(root.join("env/bin")).unwrap();
+    fs::write(root.join("env/bin/python"), "z".repeat(5)).unwrap();
+
+    let project = analyze_project(&root, ProjectKind::Rust).unwrap();
+    assert_eq!(project.total_cleanable_bytes, 10);
+    let envs = analyze_project(&root, ProjectKind::Poetry).unwrap();
+    let mut names: Vec<&str> = envs.clean_targets.iter().map(|t| t.name.as_str()).collect();
+    names.sort();
+    assert_eq!(names, ["env", "target"]);
+    for project in [&project, &envs] {
+        assert!(project.clean_targets.iter().all(|t| !t.name.starts_with('.')));
+    }
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn target_that_is_a_working_copy_is_skipped() {
+    let root = test_dir("vcs_working_copy");
+    fs::write(root.join("package.json"), "{}").unwrap();
+    fs::write(root.join("vite.config.js"), "").unwrap();
+    // `dist/` checked out as a deploy branch worktree, and a plain `build/`.
+    fs::create_dir_all(root.join("dist")).unwrap();
+    fs::write(root.join("dist/.git"), "gitdir: ../.git/worktrees/dist").unwrap();
+    fs::write(root.join("dist/index.html"), "<html>").unwrap();
+    fs::create_dir_all(root.join("build")).unwrap();
+    fs::write(root.join("build/app.js"), "app").unwrap();
+
+    let project = analyze_project(&root, ProjectKind::Node).unwrap();
+    let names: Vec<&str> = project.clean_targets.iter().map(|t| t.name.as_str()).collect();
+    assert_eq!(names, ["build"]);
+    assert_eq!(project.total_cleanable_bytes, 3);
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
 // ── scan_directory (integration) ────────────────────────────────────────────
 
 #[test]
@@ -442,3 +1027,654 @@ fn scan_config_empty_has_no_effect() {
 
     fs::remove_dir_all(&root).unwrap();
 }
+
+// ── Strict mode ────────────────────────────────────────────────────────────
+
+#[test]
+fn scan_lenient_ignores_missing_ignore_path() {
+    let root = test_dir("scan_lenient_ignore");
+    let config = DevSweepConfig {
+        ignore_paths: vec![root.join("does_not_exist")],
+        ..Default::default()
+    };
+    assert!(scan_directory(&root, None, &config).is_ok());
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn scan_strict_rejects_missing_ignore_path() {
+    let root = test_dir("scan_strict_ignore");
+    let config = DevSweepConfig {
+        ignore_paths: vec![root.join("does_not_exist")],
+        ..Default::default()
+    };
+    let options = ScanOptions {
+        strict: true,
+        ..Default::default()
+    };
+    let err = scan_directory_with(&root, &options, &config).unwrap_err();
+    assert!(err.to_string().contains("does_not_exist"));
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn scan_strict_finds_same_projects_when_nothing_is_wrong() {
+    let root = test_dir("scan_strict_ok");
+    let proj = root.join("app");
+    fs::create_dir_all(proj.join("target")).unwrap();
+    fs::write(proj.join("Cargo.toml"), "[package]").unwrap();
+    fs::write(proj.join("target/bin"), "data").unwrap();
+
+    let options = ScanOptions {
+        strict: true,
+        ..Default::default()
+    };
+    let projects = scan_directory_with(&root, &options, &DevSweepConfig::default()).unwrap();
+    assert_eq!(projects.len(), 1);
+    fs::remove_dir_all(&root).unwrap();
+}
+
+// ── one file system ─────────────────────────────────────────────────────────
+
+#[test]
+fn scan_one_file_system_keeps_same_device_projects() {
+    let root = test_dir("scan_one_fs_same");
+    let proj = root.join("app");
+    fs::create_dir_all(proj.join("target")).unwrap();
+    fs::write(proj.join("Cargo.toml"), "[package]").unwrap();
+    fs::write(proj.join("target/bin"), "data").unwrap();
+
+    let options = ScanOptions {
+        one_file_system: true,
+        ..Default::default()
+    };
+    let projects = scan_directory_with(&root, &options, &DevSweepConfig::default()).unwrap();
+    assert_eq!(projects.len(), 1);
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn scan_one_file_system_stops_at_mount_boundary() {
+    use std::os::unix::fs::MetadataExt;
+
+    // /dev/shm is normally a tmpfs mounted inside /dev; skip where it isn't.
+    let (Ok(dev), Ok(shm)) = (fs::metadata("/dev"), fs::metadata("/dev/shm")) else {
+        return;
+    };
+    if dev.dev() == shm.dev() {
+        return;
+    }
+    let proj = PathBuf::from("/dev/shm/dev_sweep_test_one_fs/app");
+    let _ = fs::remove_dir_all(proj.parent().unwrap());
+    if fs::create_dir_all(proj.join("target")).is_err() {
+        return;
+    }
+    fs::write(proj.join("Cargo.toml"), "[package]").unwrap();
+    fs::write(proj.join("target/bin"), "data").unwrap();
+
+    let found = |one_file_system: bool| {
+        let options = ScanOptions {
+            max_depth: Some(3),
+            one_file_system,
+            ..Default::default()
+        };
+        scan_directory_with("/dev".as_ref(), &options, &DevSweepConfig::default())
+            .unwrap()
+            .iter()
+            .any(|p| p.path == proj)
+    };
+    assert!(found(false));
+    assert!(!found(true));
+
+    fs::remove_dir_all(proj.parent().unwrap()).unwrap();
+}
+
+// ── largest file ────────────────────────────────────────────────────────────
+
+#[test]
+fn analyze_tracks_largest_file_across_targets() {
+    let root = test_dir("largest_file");
+    fs::write(root.join("package.json"), "{}").unwrap();
+    fs::write(root.join("webpack.config.js"), "").unwrap();
+    fs::create_dir_all(root.join("node_modules/pkg")).unwrap();
+    fs::create_dir_all(root.join("dist")).unwrap();
+    fs::write(root.join("node_modules/pkg/index.js"), "x".repeat(100)).unwrap();
+    fs::write(root.join("dist/bundle.js"), "x".repeat(500)).unwrap();
+    fs::write(root.join("dist/small.js"), "x").unwrap();
+
+    let project = analyze_project(&root, ProjectKind::Node).unwrap();
+    let largest = project.largest_file.unwrap();
+    assert_eq!(largest.path, root.join("dist/bundle.js"));
+    assert_eq!(largest.size_bytes, 500);
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn analyze_largest_file_includes_nested_pycache() {
+    let root = test_dir("largest_file_pycache");
+    fs::write(root.join("setup.py"), "").unwrap();
+    fs::create_dir_all(root.join("pkg/__pycache__")).unwrap();
+    fs::write(root.join("pkg/__pycache__/mod.pyc"), "x".repeat(64)).unwrap();
+
+    let project = analyze_project(&root, ProjectKind::Python).unwrap();
+    let largest = project.largest_file.unwrap();
+    assert_eq!(largest.path, root.join("pkg/__pycache__/mod.pyc"));
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn analyze_python_lists_root_pycache_once() {
+    let root = test_dir("pycache_root_once");
+    fs::write(root.join("requirements.txt"), "").unwrap();
+    fs::create_dir_all(root.join("__pycache__")).unwrap();
+    fs::write(root.join("__pycache__/main.pyc"), "bytecode").unwrap();
+
+    let project = analyze_project(&root, ProjectKind::Python).unwrap();
+    let pycaches: Vec<_> = project
+        .clean_targets
+        .iter()
+        .filter(|t| t.path == root.join("__pycache__"))
+        .collect();
+    assert_eq!(pycaches.len(), 1);
+    assert_eq!(project.total_cleanable_bytes, 8);
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+// ── min depth ───────────────────────────────────────────────────────────────
+
+#[test]
+fn scan_min_depth_skips_shallow_umbrella_projects() {
+    let root = test_dir("scan_min_depth");
+    // An umbrella workspace at depth 1 that also looks like a Node project
+    let umbrella = root.join("org");
+    fs::create_dir_all(umbrella.join("dist")).unwrap();
+    fs::write(umbrella.join("package.json"), "{}").unwrap();
+    fs::write(umbrella.join("vite.config.ts"), "").unwrap();
+    fs::write(umbrella.join("dist/x.js"), "x").unwrap();
+    // A real project further down
+    let app = umbrella.join("team/app");
+    fs::create_dir_all(app.join("target")).unwrap();
+    fs::write(app.join("Cargo.toml"), "[package]").unwrap();
+    fs::write(app.join("target/bin"), "data").unwrap();
+
+    let found = |min_depth| {
+        let options = ScanOptions {
+            min_depth,
+            ..Default::default()
+        };
+        let mut paths: Vec<_> = scan_directory_with(&root, &options, &DevSweepConfig::default())
+            .unwrap()
+            .into_iter()
+            .map(|p| p.path)
+            .collect();
+        paths.sort();
+        paths
+    };
+    assert_eq!(found(None), [umbrella, app.clone()]);
+    assert_eq!(found(Some(2)), vec![app.clone()]);
+    assert_eq!(found(Some(3)), vec![app]);
+    assert!(found(Some(4)).is_empty());
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+// ── depth limit ─────────────────────────────────────────────────────────────
+
+#[test]
+fn walkable_subdirs_ignore_files_and_skipped_dirs() {
+    let dir = test_dir("walkable_subdirs");
+    fs::write(dir.join("README.md"), "x").unwrap();
+    fs::create_dir_all(dir.join("node_modules/pkg")).unwrap();
+    fs::create_dir_all(dir.join(".cache")).unwrap();
+    assert!(!has_walkable_subdirs(&dir));
+
+    fs::create_dir_all(dir.join("services")).unwrap();
+    assert!(has_walkable_subdirs(&dir));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn walkable_subdirs_of_missing_dir_is_false() {
+    assert!(!has_walkable_subdirs(
+        &std::env::temp_dir().join("dev_sweep_test_walkable_subdirs_missing")
+    ));
+}
+
+#[test]
+fn scan_with_depth_warning_still_finds_shallow_projects() {
+    let root = test_dir("scan_depth_warning");
+    let shallow = root.join("app");
+    fs::create_dir_all(shallow.join("target")).unwrap();
+    fs::write(shallow.join("Cargo.toml"), "[package]").unwrap();
+    fs::write(shallow.join("target/bin"), "data").unwrap();
+    let deep = root.join("group/team/app");
+    fs::create_dir_all(deep.join("target")).unwrap();
+    fs::write(deep.join("Cargo.toml"), "[package]").unwrap();
+    fs::write(deep.join("target/bin"), "data").unwrap();
+
+    let options = ScanOptions {
+        max_depth: Some(1),
+        warn_depth_limit: true,
+        ..Default::default()
+    };
+    let found = scan_directory_with(&root, &options, &DevSweepConfig::default()).unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].path, shallow);
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+// ── scan profiling ──────────────────────────────────────────────────────────
+
+#[test]
+fn slowest_dirs_merges_walk_and_sizing() {
+    let ms = Duration::from_millis;
+    let (a, b, c) = (PathBuf::from("/r/a"), PathBuf::from("/r/b"), PathBuf::from("/r/c"));
+    let walk = [(a.clone(), ms(5)), (b.clone(), ms(20)), (c.clone(), ms(1))];
+    // Two projects sized under `a`, pushing it past `b`
+    let sizing = [(a.clone(), ms(10)), (a.clone(), ms(10)), (c.clone(), ms(4))];
+
+    let timings = slowest_dirs(&walk, &sizing, 2);
+    assert_eq!(timings.len(), 2);
+    assert_eq!(timings[0].path, a);
+    assert_eq!((timings[0].walk, timings[0].sizing), (ms(5), ms(20)));
+    assert_eq!(timings[1].path, b);
+    assert_eq!(timings[1].total(), ms(20));
+}
+
+#[test]
+fn slowest_dirs_ties_broken_by_path() {
+    let ms = Duration::from_millis;
+    let walk = [(PathBuf::from("/r/z"), ms(3)), (PathBuf::from("/r/m"), ms(3))];
+    let paths: Vec<_> = slowest_dirs(&walk, &[], 10)
+        .into_iter()
+        .map(|t| t.path)
+        .collect();
+    assert_eq!(paths, [PathBuf::from("/r/m"), PathBuf::from("/r/z")]);
+}
+
+#[test]
+fn scan_with_profiling_finds_the_same_projects() {
+    let root = test_dir("scan_profile");
+    for name in ["one", "two"] {
+        let proj = root.join(name).join("app");
+        fs::create_dir_all(proj.join("target")).unwrap();
+        fs::write(proj.join("Cargo.toml"), "[package]").unwrap();
+        fs::write(proj.join("target/bin"), "data").unwrap();
+    }
+
+    let found = |profile| {
+        let options = ScanOptions {
+            profile,
+            ..Default::default()
+        };
+        let mut paths: Vec<_> = scan_directory_with(&root, &options, &DevSweepConfig::default())
+            .unwrap()
+            .into_iter()
+            .map(|p| p.path)
+            .collect();
+        paths.sort();
+        paths
+    };
+    assert_eq!(found(Some(1)), found(None));
+    assert_eq!(found(Some(1)).len(), 2);
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+// ── IDE caches ──────────────────────────────────────────────────────────────
+
+#[test]
+fn ide_caches_are_opt_in_and_spare_user_state() {
+    let root = test_dir("ide_caches");
+    fs::write(root.join("package.json"), "{}").unwrap();
+    for dir in [".vs/app/v17", ".idea/caches", ".idea/shelf", ".vscode/ipch", ".vscode/.history"] {
+        fs::create_dir_all(root.join(dir)).unwrap();
+        fs::write(root.join(dir).join("data"), "cached").unwrap();
+    }
+    fs::write(root.join(".idea/workspace.xml"), "<project/>").unwrap();
+
+    let off = analyze_project(&root, ProjectKind::Node).unwrap();
+    assert!(off.clean_targets.is_empty());
+
+    let options = ScanOptions {
+        ide_caches: true,
+        ..Default::default()
+    };
+    let on = analyze_project_with(&root, ProjectKind::Node, &options).unwrap();
+    let mut names: Vec<&str> = on.clean_targets.iter().map(|t| t.name.as_str()).collect();
+    names.sort();
+    assert_eq!(names, [".idea/caches", ".vs", ".vscode/ipch"]);
+    assert_eq!(on.total_cleanable_bytes, 18);
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+// ── logs and core dumps ─────────────────────────────────────────────────────
+
+/// A Rust project with build output, a log directory, a core dump, and
+/// lookalikes that must never be offered: a `log` source module, a
+/// `core.rs`, and a `core.1` that isn't a core dump.
+fn project_with_runtime_data(root: &Path) {
+    fs::write(root.join("Cargo.toml"), "[package]").unwrap();
+    fs::create_dir_all(root.join("target")).unwrap();
+    fs::write(root.join("target/app"), "binary").unwrap();
+    fs::create_dir_all(root.join("logs")).unwrap();
+    fs::write(root.join("logs/app.log"), "x".repeat(1000)).unwrap();
+    fs::write(root.join("logs/app.log.1"), "x".repeat(1000)).unwrap();
+    fs::create_dir_all(root.join("log")).unwrap();
+    fs::write(root.join("log/mod.rs"), "pub fn log() {}").unwrap();
+    let mut dump = b"\x7fELF".to_vec();
+    dump.extend(vec![0u8; 4000]);
+    fs::write(root.join("core.4242"), dump).unwrap();
+    fs::write(root.join("core.rs"), "fn main() {}").unwrap();
+    fs::write(root.join("core.1"), "not a dump").unwrap();
+}
+
+#[test]
+fn logs_and_core_dumps_are_opt_in_and_marked_as_runtime_data() {
+    let root = test_dir("include_logs");
+    project_with_runtime_data(&root);
+
+    let off = analyze_project(&root, ProjectKind::Rust).unwrap();
+    let names: Vec<&str> = off.clean_targets.iter().map(|t| t.name.as_str()).collect();
+    assert_eq!(names, ["target"]);
+
+    let options = ScanOptions {
+        include_logs: true,
+        ..Default::default()
+    };
+    let on = analyze_project_with(&root, ProjectKind::Rust, &options).unwrap();
+    let targets: Vec<(&str, bool)> = on
+        .clean_targets
+        .iter()
+        .map(|t| (t.name.as_str(), t.runtime_data))
+        .collect();
+    assert_eq!(
+        targets,
+        [("target", false), ("core.4242", true), ("logs", true)]
+    );
+    assert_eq!(on.total_cleanable_bytes, off.total_cleanable_bytes + 6004);
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn core_dump_names_are_core_or_core_with_a_pid() {
+    assert!(is_core_dump_name("core"));
+    assert!(is_core_dump_name("core.12345"));
+    assert!(!is_core_dump_name("core."));
+    assert!(!is_core_dump_name("core.py"));
+    assert!(!is_core_dump_name("core.12.bak"));
+    assert!(!is_core_dump_name("score"));
+    assert!(!is_core_dump_name("cores"));
+}
+
+// ── large files ─────────────────────────────────────────────────────────────
+
+/// A Rust project whose `target/release/data` holds a 1000-byte dataset next
+/// to 10-byte builds in `target/debug` and `target/release`.
+fn project_with_dataset(root: &Path) {
+    fs::write(root.join("Cargo.toml"), "[package]").unwrap();
+    for dir in ["target/debug", "target/release/data"] {
+        fs::create_dir_all(root.join(dir)).unwrap();
+    }
+    fs::write(root.join("target/debug/app"), "0123456789").unwrap();
+    fs::write(root.join("target/release/app"), "0123456789").unwrap();
+    fs::write(root.join("target/release/data/set.bin"), "x".repeat(1000)).unwrap();
+}
+
+#[test]
+fn files_over_the_limit_are_split_out_and_kept() {
+    let root = test_dir("skip_files_over");
+    project_with_dataset(&root);
+    let all = analyze_project(&root, ProjectKind::Rust).unwrap();
+    assert_eq!(all.total_cleanable_bytes, 1020);
+
+    // The serial walk and the parallel one find the same files.
+    for parallel_min_files in [None, Some(1)] {
+        let options = ScanOptions {
+            size_mode: SizeMode::Apparent,
+            skip_files_over: Some(100),
+            parallel_min_files,
+            ..Default::default()
+        };
+        let project = analyze_project_with(&root, ProjectKind::Rust, &options).unwrap();
+        let targets: Vec<(&str, u64, bool)> = project
+            .clean_targets
+            .iter()
+            .map(|t| (t.name.as_str(), t.size_bytes, t.large_file))
+            .collect();
+        assert_eq!(
+            targets,
+            [
+                ("target/debug", 10, false),
+                ("target/release/data/set.bin", 1000, true)
+            ]
+        );
+        assert!(project.clean_targets[1].protected);
+        // The kept file isn't counted, and `target/release/app`, loose next
+        // to the directory leading to it, is left alone too.
+        assert_eq!(project.total_cleanable_bytes, 10);
+    }
+
+    // Nothing over the limit leaves the target whole.
+    let options = ScanOptions {
+        size_mode: SizeMode::Apparent,
+        skip_files_over: Some(1000),
+        ..Default::default()
+    };
+    let project = analyze_project_with(&root, ProjectKind::Rust, &options).unwrap();
+    assert_eq!(project.clean_targets.len(), 1);
+    assert_eq!(project.total_cleanable_bytes, 1020);
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+// ── parallel sizing ─────────────────────────────────────────────────────────
+
+#[test]
+fn parallel_sizing_matches_serial_sizing() {
+    let root = test_dir("parallel_sizing");
+    fs::write(root.join("Cargo.toml"), "[package]").unwrap();
+    for i in 0..8 {
+        let dir = root.join(format!("target/debug/deps{i}"));
+        fs::create_dir_all(&dir).unwrap();
+        for j in 0..5 {
+            fs::write(dir.join(format!("lib{j}.rlib")), vec![0u8; 100 * (i + j + 1)]).unwrap();
+        }
+    }
+    fs::write(root.join("target/.rustc_info.json"), vec![0u8; 50]).unwrap();
+    #[cfg(unix)]
+    std::os::unix::fs::symlink(&root, root.join("target/loop")).unwrap();
+
+    let serial = analyze_project(&root, ProjectKind::Rust).unwrap();
+    for options in [
+        ScanOptions {
+            parallel_min_files: Some(0),
+            ..Default::default()
+        },
+        ScanOptions {
+            parallel_min_bytes: Some(1024),
+            ..Default::default()
+        },
+    ] {
+        let parallel = analyze_project_with(&root, ProjectKind::Rust, &options).unwrap();
+        assert_eq!(parallel.total_cleanable_bytes, serial.total_cleanable_bytes);
+        assert_eq!(
+            parallel.largest_file.as_ref().map(|f| f.size_bytes),
+            serial.largest_file.as_ref().map(|f| f.size_bytes)
+        );
+        assert_eq!(
+            parallel.clean_targets[0].last_modified,
+            serial.clean_targets[0].last_modified
+        );
+    }
+    assert_eq!(serial.total_cleanable_bytes, 50 + 26_000);
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+// ── workspaces ──────────────────────────────────────────────────────────────
+
+fn follow_references() -> ScanOptions {
+    ScanOptions {
+        follow_project_references: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn cargo_workspace_members_are_grouped_under_the_root() {
+    let root = test_dir("workspace_cargo");
+    let ws = root.join("ws");
+    fs::create_dir_all(ws.join("target")).unwrap();
+    fs::write(
+        ws.join("Cargo.toml"),
+        "[workspace]\nmembers = [\"crates/*\"]\nexclude = [\"crates/skip\"]\n",
+    )
+    .unwrap();
+    fs::write(ws.join("target/lib.rlib"), vec![0u8; 4000]).unwrap();
+    for member in ["a", "b", "skip"] {
+        let dir = ws.join("crates").join(member);
+        fs::create_dir_all(dir.join("target")).unwrap();
+        fs::write(dir.join("Cargo.toml"), "[package]").unwrap();
+        fs::write(dir.join("target/bin"), vec![0u8; 1000]).unwrap();
+    }
+
+    let config = DevSweepConfig::default();
+    let separate = scan_directory_with(&root, &ScanOptions::default(), &config).unwrap();
+    assert_eq!(separate.len(), 4);
+
+    let mut grouped = scan_directory_with(&root, &follow_references(), &config).unwrap();
+    grouped.sort_by(|a, b| a.path.cmp(&b.path));
+    assert_eq!(grouped.len(), 2);
+    let workspace = &grouped[0];
+    assert_eq!(workspace.path, ws);
+    assert_eq!(
+        workspace.workspace_members,
+        [ws.join("crates/a"), ws.join("crates/b")]
+    );
+    let mut names: Vec<&str> = workspace
+        .clean_targets
+        .iter()
+        .map(|t| t.name.as_str())
+        .collect();
+    names.sort();
+    assert_eq!(names, ["crates/a/target", "crates/b/target", "target"]);
+    // Excluded members stay projects of their own.
+    assert_eq!(grouped[1].path, ws.join("crates/skip"));
+
+    let total = |projects: &[ScannedProject]| -> u64 {
+        projects.iter().map(|p| p.total_cleanable_bytes).sum()
+    };
+    assert_eq!(total(&grouped), total(&separate));
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn node_workspace_without_root_artifacts_still_groups_members() {
+    let root = test_dir("workspace_node");
+    fs::write(
+        root.join("package.json"),
+        r#"{ "workspaces": { "packages": ["packages/**"] } }"#,
+    )
+    .unwrap();
+    for member in ["packages/ui", "packages/nested/api", "tools/lint"] {
+        let dir = root.join(member);
+        fs::create_dir_all(dir.join("node_modules")).unwrap();
+        fs::write(dir.join("package.json"), "{}").unwrap();
+        fs::write(dir.join("node_modules/dep.js"), "x".repeat(2000)).unwrap();
+    }
+    // A Python project among the packages isn't an npm member.
+    fs::create_dir_all(root.join("packages/py/__pycache__")).unwrap();
+    fs::write(root.join("packages/py/setup.py"), "").unwrap();
+    fs::write(root.join("packages/py/__pycache__/m.pyc"), "x".repeat(500)).unwrap();
+
+    let mut projects =
+        scan_directory_with(&root, &follow_references(), &DevSweepConfig::default()).unwrap();
+    projects.sort_by(|a, b| a.path.cmp(&b.path));
+    let paths: Vec<_> = projects.iter().map(|p| p.path.clone()).collect();
+    assert_eq!(
+        paths,
+        [
+            root.clone(),
+            root.join("packages/py"),
+            root.join("tools/lint")
+        ]
+    );
+    assert_eq!(projects[0].workspace_members.len(), 2);
+    assert_eq!(projects[0].clean_targets.len(), 2);
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn pnpm_workspace_patterns_and_exclusions() {
+    let root = test_dir("workspace_pnpm");
+    fs::write(root.join("package.json"), "{}").unwrap();
+    fs::write(
+        root.join("pnpm-workspace.yaml"),
+        "packages:\n  - 'apps/*'\n  - '!apps/legacy'\n",
+    )
+    .unwrap();
+
+    let members = workspace_members(&root, ProjectKind::Node).unwrap();
+    assert!(members.contains(Path::new("apps/web")));
+    assert!(!members.contains(Path::new("apps/legacy")));
+    assert!(!members.contains(Path::new("apps/web/deep")));
+    assert!(!members.contains(Path::new("")));
+    assert_eq!(workspace_members(&root, ProjectKind::Rust), None);
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+// ── largest first ───────────────────────────────────────────────────────────
+
+/// A scan root with a small Rust project in `a/` and a big one in `b/`.
+fn small_and_big_projects(root: &Path) {
+    for (name, bytes) in [("a", 10), ("b", 200_000)] {
+        let project = root.join(name);
+        fs::create_dir_all(project.join("target")).unwrap();
+        fs::write(project.join("Cargo.toml"), "[package]").unwrap();
+        fs::write(project.join("Cargo.lock"), "x".repeat(bytes)).unwrap();
+        fs::write(project.join("target/bin"), "x".repeat(bytes)).unwrap();
+    }
+}
+
+#[test]
+fn estimate_dir_size_counts_only_direct_entries() {
+    let root = test_dir("estimate_dir_size");
+    fs::create_dir_all(root.join("sub/deeper")).unwrap();
+    fs::write(root.join("file"), "x".repeat(5000)).unwrap();
+    fs::write(root.join("sub/deeper/file"), "x".repeat(100_000)).unwrap();
+
+    let estimate = estimate_dir_size(&root);
+    assert!((5000..100_000).contains(&estimate), "{estimate}");
+    assert_eq!(estimate_dir_size(&root.join("missing")), 0);
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn largest_first_walks_big_directories_first() {
+    let root = test_dir("largest_first");
+    small_and_big_projects(&root);
+
+    let options = ScanOptions {
+        largest_first: true,
+        resume: Some(root.join("resume.json")),
+        ..Default::default()
+    };
+    let projects = scan_directory_with(&root, &options, &DevSweepConfig::default()).unwrap();
+    let names: Vec<&str> = projects.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, ["b", "a"]);
+
+    fs::remove_dir_all(&root).unwrap();
+}
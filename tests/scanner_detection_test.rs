@@ -1,10 +1,10 @@
-//! Tests for project kind detection across all 17 supported project types.
+//! Tests for project kind detection across every supported project type.
 
 use std::fs;
 use std::path::PathBuf;
 
 use dev_sweep::scanner::ProjectKind;
-use dev_sweep::scanner::walk::detect_project_kind;
+use dev_sweep::scanner::walk::{detect_project_kind, detect_project_kinds};
 
 /// Helper: create a fresh temp dir for a test.
 fn test_dir(name: &str) -> PathBuf {
@@ -112,6 +112,33 @@ fn detect_swift() {
     fs::remove_dir_all(&dir).unwrap();
 }
 
+#[test]
+fn detect_cocoapods() {
+    let dir = test_dir("detect_cocoapods");
+    fs::write(dir.join("Podfile"), "platform :ios").unwrap();
+    assert_eq!(detect_project_kind(&dir), Some(ProjectKind::IosDeps));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn detect_carthage() {
+    let dir = test_dir("detect_carthage");
+    fs::write(dir.join("Cartfile"), "github \"Alamofire/Alamofire\"").unwrap();
+    assert_eq!(detect_project_kind(&dir), Some(ProjectKind::IosDeps));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn detect_podfile_wins_over_swift_package() {
+    // An app using both SwiftPM and CocoaPods is reported once, as iOS deps,
+    // so both dependency managers' artifacts are captured together.
+    let dir = test_dir("detect_pods_and_swiftpm");
+    fs::write(dir.join("Package.swift"), "").unwrap();
+    fs::write(dir.join("Podfile"), "").unwrap();
+    assert_eq!(detect_project_kind(&dir), Some(ProjectKind::IosDeps));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
 #[test]
 fn detect_elixir() {
     let dir = test_dir("detect_elixir");
@@ -248,3 +275,36 @@ fn detect_priority_rust_over_node() {
     assert_eq!(detect_project_kind(&dir), Some(ProjectKind::Rust));
     fs::remove_dir_all(&dir).unwrap();
 }
+
+// ── Polyglot projects ───────────────────────────────────────────────────────
+
+#[test]
+fn detect_all_kinds_in_polyglot_directory() {
+    let dir = test_dir("detect_polyglot");
+    fs::write(dir.join("Cargo.toml"), "[package]").unwrap();
+    fs::write(dir.join("package.json"), "{}").unwrap();
+    let kinds = detect_project_kinds(&dir, ProjectKind::all());
+    assert_eq!(kinds, [ProjectKind::Rust, ProjectKind::Node]);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn detect_polyglot_follows_priority() {
+    let dir = test_dir("detect_polyglot_priority");
+    fs::write(dir.join("Cargo.toml"), "[package]").unwrap();
+    fs::write(dir.join("package.json"), "{}").unwrap();
+    let priority = ProjectKind::by_priority(&[ProjectKind::Node]);
+    let kinds = detect_project_kinds(&dir, &priority);
+    assert_eq!(kinds, [ProjectKind::Node, ProjectKind::Rust]);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn priority_puts_preferred_kinds_first_and_keeps_the_rest() {
+    let priority = ProjectKind::by_priority(&[ProjectKind::Go, ProjectKind::Node]);
+    assert_eq!(
+        &priority[..3],
+        [ProjectKind::Go, ProjectKind::Node, ProjectKind::Rust]
+    );
+    assert_eq!(priority.len(), ProjectKind::all().len());
+}
@@ -1,6 +1,6 @@
-//! Tests for the multi-select input parser (e.g. "1,3,5-8").
+//! Tests for the multi-select input parser (e.g. "1,3,5-8") and the filterable picker.
 
-use dev_sweep::tui::display::parse_selection;
+use dev_sweep::tui::display::{Picker, fuzzy_match, parse_selection};
 
 // ── valid inputs ────────────────────────────────────────────────────────────
 
@@ -95,3 +95,93 @@ fn parse_non_number_is_error() {
 fn parse_range_with_non_number_is_error() {
     assert!(parse_selection("a-5", 10).is_err());
 }
+
+// ── fuzzy filter ────────────────────────────────────────────────────────────
+
+#[test]
+fn fuzzy_match_is_an_ordered_subsequence() {
+    assert!(fuzzy_match("dsw", "dev-sweep (Rust)"));
+    assert!(fuzzy_match("DEV rust", "dev-sweep (Rust)"));
+    assert!(fuzzy_match("", "anything"));
+    assert!(!fuzzy_match("wsd", "dev-sweep"));
+    assert!(!fuzzy_match("devx", "dev-sweep"));
+}
+
+// ── picker ──────────────────────────────────────────────────────────────────
+
+fn items() -> Vec<String> {
+    ["api (Rust)", "web (Node.js)", "admin (Node.js)", "cli (Rust)"]
+        .map(String::from)
+        .to_vec()
+}
+
+#[test]
+fn picker_numbers_first_finish_immediately() {
+    let items = items();
+    let mut picker = Picker::new(&items);
+    assert!(picker.apply("1,3\n").unwrap());
+    assert_eq!(picker.selection(), [0, 2]);
+}
+
+#[test]
+fn picker_all_without_filter_finishes() {
+    let items = items();
+    let mut picker = Picker::new(&items);
+    assert!(picker.apply("all").unwrap());
+    assert_eq!(picker.selection(), [0, 1, 2, 3]);
+}
+
+#[test]
+fn picker_filter_numbers_refer_to_the_view() {
+    let items = items();
+    let mut picker = Picker::new(&items);
+    assert!(!picker.apply("/node").unwrap());
+    assert_eq!(picker.visible(), [1, 2]);
+
+    // "2" is the second visible item, admin, at index 2 of the full list
+    assert!(!picker.apply("2").unwrap());
+    assert_eq!(picker.selection(), [2]);
+    // Toggling again deselects
+    assert!(!picker.apply("2").unwrap());
+    assert!(picker.selection().is_empty());
+}
+
+#[test]
+fn picker_select_all_and_none_apply_to_the_view() {
+    let items = items();
+    let mut picker = Picker::new(&items);
+    picker.apply("/rust").unwrap();
+    picker.apply("+").unwrap();
+    picker.apply("/").unwrap();
+    assert_eq!(picker.visible().len(), 4);
+    assert_eq!(picker.selection(), [0, 3]);
+
+    picker.apply("/cli").unwrap();
+    picker.apply("-").unwrap();
+    // Enter finishes with what's left
+    assert!(picker.apply("").unwrap());
+    assert_eq!(picker.selection(), [0]);
+}
+
+#[test]
+fn picker_rejects_numbers_outside_the_view() {
+    let items = items();
+    let mut picker = Picker::new(&items);
+    picker.apply("/cli").unwrap();
+    assert!(picker.apply("2").is_err());
+    assert!(picker.selection().is_empty());
+}
+
+#[test]
+fn picker_renders_checkboxes_once_interactive() {
+    let items = items();
+    let mut picker = Picker::new(&items);
+    assert!(!picker.render()[0].contains("[ ]"));
+
+    picker.apply("/web").unwrap();
+    picker.apply("1").unwrap();
+    let lines = picker.render();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].contains("[x]") && lines[0].contains("web"));
+    assert!(lines[1].contains("1 of 4 shown, 1 selected"));
+}
@@ -0,0 +1,39 @@
+//! Tests for the self-test command: it should pass end to end on a healthy machine.
+
+use std::path::PathBuf;
+
+use dev_sweep::selftest::run_selftest;
+
+fn sandbox(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("dev_sweep_test_{name}"))
+}
+
+#[test]
+fn selftest_passes_every_check() {
+    let root = sandbox("selftest");
+    let checks = run_selftest(&root);
+
+    let failed: Vec<_> = checks.iter().filter(|c| !c.passed).collect();
+    assert!(failed.is_empty(), "failed checks: {failed:?}");
+    assert!(checks.iter().any(|c| c.name == "detect project kinds"));
+    assert!(checks.iter().any(|c| c.name == "dry-run clean"));
+}
+
+#[test]
+fn selftest_cleans_up_after_itself() {
+    let root = sandbox("selftest_cleanup");
+    run_selftest(&root);
+    assert!(!root.exists());
+}
+
+#[test]
+fn selftest_replaces_a_stale_sandbox() {
+    let root = sandbox("selftest_stale");
+    std::fs::create_dir_all(root.join("leftover/node_modules")).unwrap();
+    std::fs::write(root.join("leftover/package.json"), "{}").unwrap();
+    std::fs::write(root.join("leftover/node_modules/x.js"), "x").unwrap();
+
+    let checks = run_selftest(&root);
+    assert!(checks.iter().all(|c| c.passed), "{checks:?}");
+    assert!(!root.exists());
+}
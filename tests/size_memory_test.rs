@@ -0,0 +1,95 @@
+//! Tests that sizing a directory streams its entries, so peak memory doesn't
+//! grow with the number of files. The allocator counting heap use applies to
+//! the whole process, so these tests live in their own binary.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use dev_sweep::scanner::SizeMode;
+use dev_sweep::scanner::walk::dir_size_with;
+
+/// The system allocator, keeping track of the bytes in use and their peak.
+struct Counting;
+
+static IN_USE: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            let in_use = IN_USE.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            PEAK.fetch_max(in_use, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        IN_USE.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+/// Helper: create a fresh temp dir for a test.
+fn test_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("dev_sweep_test_{name}"));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// A tree of `files` small files, 100 to a directory, every other one a hard
+/// link to the same file (as package managers with a shared store lay out
+/// dependencies).
+fn synthetic_tree(root: &Path, files: usize) {
+    fs::create_dir_all(root).unwrap();
+    let original = root.join("original");
+    fs::write(&original, "x".repeat(64)).unwrap();
+    for i in 0..files {
+        let dir = root.join(format!("d{}", i / 100));
+        fs::create_dir_all(&dir).unwrap();
+        let file = dir.join(format!("f{i}"));
+        if i % 2 == 0 {
+            fs::hard_link(&original, file).unwrap();
+        } else {
+            fs::write(file, "x".repeat(64)).unwrap();
+        }
+    }
+}
+
+/// Size `root`, returning the size and the peak heap use above what was in
+/// use beforehand.
+fn sized_with_peak(root: &Path) -> (u64, usize) {
+    let before = IN_USE.load(Ordering::Relaxed);
+    PEAK.store(before, Ordering::Relaxed);
+    let size = dir_size_with(root, SizeMode::Apparent).unwrap();
+    (size, PEAK.load(Ordering::Relaxed).saturating_sub(before))
+}
+
+#[test]
+fn peak_memory_does_not_grow_with_file_count() {
+    let dir = test_dir("size_memory");
+    let (small, large) = (dir.join("small"), dir.join("large"));
+    synthetic_tree(&small, 1_000);
+    synthetic_tree(&large, 20_000);
+
+    let (small_size, small_peak) = sized_with_peak(&small);
+    let (large_size, large_peak) = sized_with_peak(&large);
+
+    // The hard-linked names count once; results are unchanged.
+    assert_eq!(small_size, 64 * (1 + 500));
+    assert_eq!(large_size, 64 * (1 + 10_000));
+    // Twenty times the files, but the peak stays about the same: only the
+    // walk's open directories and the one linked inode are held.
+    assert!(
+        large_peak < small_peak + 64 * 1024,
+        "peak grew from {small_peak} to {large_peak} bytes"
+    );
+
+    let _ = fs::remove_dir_all(&dir);
+}
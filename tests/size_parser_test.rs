@@ -0,0 +1,64 @@
+//! Tests for the size string parser (e.g. "500MB", "1.5GB", "2g") and the
+//! `confirm_threshold` check built on it.
+
+use dev_sweep::cli::commands::ConfirmPolicy;
+use dev_sweep::util::parse_size;
+
+const MB: u64 = 1024 * 1024;
+
+// ── valid inputs ────────────────────────────────────────────────────────────
+
+#[test]
+fn parse_plain_bytes() {
+    assert_eq!(parse_size("512").unwrap(), 512);
+    assert_eq!(parse_size("512B").unwrap(), 512);
+}
+
+#[test]
+fn parse_units() {
+    assert_eq!(parse_size("4KB").unwrap(), 4 * 1024);
+    assert_eq!(parse_size("500MB").unwrap(), 500 * MB);
+    assert_eq!(parse_size("2GB").unwrap(), 2048 * MB);
+    assert_eq!(parse_size("1TB").unwrap(), 1024 * 1024 * MB);
+}
+
+#[test]
+fn parse_short_units_and_case() {
+    assert_eq!(parse_size("2g").unwrap(), 2048 * MB);
+    assert_eq!(parse_size("100m").unwrap(), 100 * MB);
+    assert_eq!(parse_size("1Gb").unwrap(), 1024 * MB);
+}
+
+#[test]
+fn parse_fractions_and_spaces() {
+    assert_eq!(parse_size("1.5 GB").unwrap(), 1536 * MB);
+    assert_eq!(parse_size("  0.5MB ").unwrap(), MB / 2);
+}
+
+// ── invalid inputs ──────────────────────────────────────────────────────────
+
+#[test]
+fn invalid_unit() {
+    assert!(parse_size("5PB").is_err());
+    assert!(parse_size("lots").is_err());
+}
+
+#[test]
+fn invalid_number() {
+    assert!(parse_size("").is_err());
+    assert!(parse_size("MB").is_err());
+    assert!(parse_size("-5MB").is_err());
+    assert!(parse_size("1.2.3GB").is_err());
+}
+
+// ── confirm policy ──────────────────────────────────────────────────────────
+
+#[test]
+fn threshold_only_asks_above_it() {
+    let policy = ConfirmPolicy::Above(100 * MB);
+    assert!(!policy.asks_for(12 * MB));
+    assert!(!policy.asks_for(100 * MB));
+    assert!(policy.asks_for(100 * MB + 1));
+    assert!(ConfirmPolicy::Always.asks_for(0));
+    assert!(!ConfirmPolicy::Never.asks_for(u64::MAX));
+}